@@ -0,0 +1,49 @@
+// Prime gaps in a range
+//
+//      cargo run --release --example gap_explorer [LO] [HI] > gaps.csv
+//
+// Writes every gap between consecutive primes in [LO, HI) as CSV with
+// `export_gaps_csv`, then reports on stderr the longest one, whether it is a
+// record (`is_maximal_gap`), and the known maximal gaps that start in the
+// range. LO and HI may be expressions such as `1e12` or `2^40`.
+use prime::{export_gaps_csv, is_maximal_gap, max_gap_in_range, maximal_prime_gaps, parse_biguint_expr};
+
+use std::env;
+use std::io::{self, BufWriter};
+
+
+fn parse(arg: Option<String>, default: u64) -> u64 {
+    match arg {
+        Some(s) => {
+            let n = parse_biguint_expr(&s).expect("not a number");
+            n.to_u64_digits().first().copied().filter(|_| n.bits() <= 64).unwrap_or_else(|| panic!("{} is not below 2 ^ 64", s))
+        },
+        None => default,
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let lo = parse(args.next(), 0);
+    let hi = parse(args.next(), lo + 1_000_000);
+
+    let rows = export_gaps_csv(lo, hi, BufWriter::new(io::stdout().lock()))?;
+    eprintln!("{} gaps in [{}, {})", rows, lo, hi);
+
+    if let Some(record) = max_gap_in_range(lo, hi) {
+        let merit = record.gap as f64 / (record.start as f64).ln();
+        eprintln!(
+            "longest: {} after {} (merit {:.4}){}",
+            record.gap,
+            record.start,
+            merit,
+            if is_maximal_gap(record.start, record.gap) { ", a maximal gap" } else { "" },
+        );
+    }
+
+    for record in maximal_prime_gaps().iter().filter(|record| lo <= record.start && record.start < hi) {
+        eprintln!("maximal gap {} after {}", record.gap, record.start);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// RSA key generation
+//
+//      cargo run --release --example rsa_keygen [BITS]
+//
+// Draws two primes with `gen_rsa_primes`, derives the private exponent for
+// e = 65537 and checks the key by encrypting and decrypting one message. Each
+// prime is also run through `check_rsa_prime_quality`; random primes usually
+// miss the auxiliary-prime sizes of FIPS 186-4, which the report shows.
+use prime::{check_rsa_prime_quality, gen_rsa_primes, Bits, PaddedBytes};
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+
+fn main() {
+    let bits: u32 = env::args().nth(1).map(|s| s.parse().expect("BITS must be a number")).unwrap_or(1024);
+    let bits = Bits::rsa_modulus(bits).expect("BITS must be even and at least 16");
+
+    let e = BigUint::from(65537u32);
+    let one = BigUint::from(1u8);
+    let mut rng = rand::thread_rng();
+
+    let started = Instant::now();
+    let (p, q) = loop {
+        let (p, q) = gen_rsa_primes(bits, &mut rng);
+        if e.gcd(&(&p - 1u8)) == one && e.gcd(&(&q - 1u8)) == one {
+            break (p, q);
+        }
+    };
+    println!("generated in {:.2?}", started.elapsed());
+
+    let n = &p * &q;
+    // λ(n) = lcm(p − 1, q − 1)
+    let lambda = (&p - 1u8).lcm(&(&q - 1u8));
+    let d = inverse(&e, &lambda);
+
+    let half = bits.half();
+    println!("n = {}", n.to_hex_padded(bits.get() as usize));
+    println!("e = {}", e);
+    println!("d = {}", d.to_hex_padded(bits.get() as usize));
+    println!("p = {}", p.to_hex_padded(half.get() as usize));
+    println!("q = {}", q.to_hex_padded(half.get() as usize));
+
+    let message = BigUint::from_bytes_be(b"attack at dawn") % &n;
+    let ciphertext = message.modpow(&e, &n);
+    assert_eq!(ciphertext.modpow(&d, &n), message, "the key does not round-trip");
+    println!("encrypt / decrypt round trip: ok");
+
+    for (name, prime, other) in [("p", &p, &q), ("q", &q, &p)] {
+        let report = check_rsa_prime_quality(prime, Some(other), &e, half, Duration::from_secs(2));
+        println!(
+            "{}: largest factor found of p − 1 {} bits, of p + 1 {} bits (FIPS 186-4 wants {}); {}",
+            name,
+            report.p_minus_one.largest_prime_factor_bits,
+            report.p_plus_one.largest_prime_factor_bits,
+            report.min_auxiliary_bits,
+            if report.passed() { "passed" } else { "not passed" },
+        );
+    }
+}
+
+// a ^ −1 (mod m), for a coprime to m.
+fn inverse(a: &BigUint, m: &BigUint) -> BigUint {
+    let m = BigInt::from_biguint(Sign::Plus, m.clone());
+    let e = BigInt::from_biguint(Sign::Plus, a.clone()).extended_gcd(&m);
+    assert_eq!(e.gcd, BigInt::from(1u8), "not invertible");
+
+    e.x.mod_floor(&m).to_biguint().unwrap()
+}
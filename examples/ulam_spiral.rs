@@ -0,0 +1,51 @@
+// Ulam spiral data for a plotting tool
+//
+//      cargo run --release --example ulam_spiral [SIDE] [--pbm] > spiral.csv
+//
+// Places 1 ..= SIDE ^ 2 on the spiral with `ulam_spiral_coords` and writes
+// the primes as `n,x,y` rows, or with `--pbm` the whole square as a plain
+// PBM bitmap, one pixel per number and black for the primes.
+use prime::{primes_in_range, ulam_spiral_coords};
+
+use std::env;
+use std::io::{self, BufWriter, Write};
+
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let pbm = args.iter().any(|arg| arg == "--pbm");
+    let side: u64 = args.iter()
+        .find(|arg| !arg.starts_with("--"))
+        .map(|s| s.parse().expect("SIDE must be a number"))
+        .unwrap_or(201);
+    // NOTE: 边长取奇数，1 才能位于正中央。
+    let side = side | 1;
+    let k = (side / 2) as i64;
+
+    let primes = primes_in_range(0, side * side + 1);
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    if !pbm {
+        writeln!(out, "n,x,y")?;
+        for p in primes {
+            let (x, y) = ulam_spiral_coords(p);
+            writeln!(out, "{},{},{}", p, x, y)?;
+        }
+        return out.flush();
+    }
+
+    // Rows top to bottom, y from k down to −k.
+    let mut pixels = vec![0u8; (side * side) as usize];
+    for p in primes {
+        let (x, y) = ulam_spiral_coords(p);
+        pixels[((k - y) as u64 * side + (x + k) as u64) as usize] = 1;
+    }
+
+    writeln!(out, "P1")?;
+    writeln!(out, "{} {}", side, side)?;
+    for row in pixels.chunks(side as usize) {
+        let line: Vec<String> = row.iter().map(|pixel| pixel.to_string()).collect();
+        writeln!(out, "{}", line.join(" "))?;
+    }
+    out.flush()
+}
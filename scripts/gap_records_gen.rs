@@ -0,0 +1,79 @@
+// First occurrences of every prime gap below 2 ^ 32, for `src/gap_records.rs`.
+//
+//      rustc -O scripts/gap_records_gen.rs -o /tmp/gap_records_gen && /tmp/gap_records_gen
+const LIMIT: u64 = 1 << 32;
+const SEGMENT: u64 = 1 << 20;
+
+fn base_primes(limit: u64) -> Vec<u64> {
+    let mut composite = vec![false; limit as usize + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if !composite[n as usize] {
+            primes.push(n);
+            let mut m = n * n;
+            while m <= limit {
+                composite[m as usize] = true;
+                m += n;
+            }
+        }
+    }
+
+    primes
+}
+
+fn gap_records_gen() {
+    let base = base_primes(1 << 16);
+
+    // first[g / 2] = the first prime p followed by a gap of g
+    let mut first: Vec<u64> = Vec::new();
+    let mut prev = 2u64;
+    let mut lo = 3u64;
+    while lo < LIMIT {
+        let hi = (lo + SEGMENT).min(LIMIT);
+        let mut composite = vec![false; (hi - lo) as usize];
+        for p in base.iter().skip(1) {
+            if p * p >= hi {
+                break;
+            }
+            let mut m = ((lo + p - 1) / p * p).max(p * p);
+            while m < hi {
+                composite[(m - lo) as usize] = true;
+                m += p;
+            }
+        }
+
+        for (i, c) in composite.iter().enumerate() {
+            let n = lo + i as u64;
+            if *c || n % 2 == 0 {
+                continue;
+            }
+
+            let g = ((n - prev) / 2) as usize;
+            if first.len() <= g {
+                first.resize(g + 1, 0);
+            }
+            if first[g] == 0 {
+                first[g] = prev;
+            }
+            prev = n;
+        }
+
+        lo = hi;
+    }
+
+    // NOTE: 2 → 3 的差值 1 单独处理，表里只保留偶数差值。
+    let entries: Vec<(usize, u64)> = first.iter().enumerate().skip(1).filter(|(_, p)| **p != 0).map(|(g, p)| (2 * g, *p)).collect();
+    println!("static FIRST_OCCURRENCES: [(u16, u32); {}] = [", entries.len());
+    for line in entries.chunks(4) {
+        print!("   ");
+        for (g, p) in line.iter() {
+            print!(" ({}, {}),", g, p);
+        }
+        println!();
+    }
+    println!("];");
+}
+
+fn main() {
+    gap_records_gen()
+}
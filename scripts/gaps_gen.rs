@@ -0,0 +1,48 @@
+// Differences between the first 10000 primes, for `src/gaps.rs`.
+//
+//      rustc -O scripts/gaps_gen.rs -o /tmp/gaps_gen && /tmp/gaps_gen
+const COUNT: usize = 10_000;
+
+fn first_primes(count: usize) -> Vec<u32> {
+    let mut primes: Vec<u32> = Vec::with_capacity(count);
+    let mut n = 2u32;
+    while primes.len() < count {
+        if primes.iter().take_while(|p| *p * *p <= n).all(|p| n % p != 0) {
+            primes.push(n);
+        }
+        n += 1;
+    }
+
+    primes
+}
+
+fn gaps_gen() {
+    let primes = first_primes(COUNT);
+
+    // NOTE: 第一个差值是 2 − 0，之后都是相邻两个素数之差，全部小于 256。
+    let mut data: Vec<u8> = Vec::with_capacity(COUNT);
+    let mut prev = 0u32;
+    for p in primes.iter() {
+        let gap = p - prev;
+        assert!(gap <= u8::MAX as u32);
+        data.push(gap as u8);
+        prev = *p;
+    }
+
+    println!("static PRIME_GAPS: [u8; {}] = [", data.len());
+    for line in data.chunks(32) {
+        print!("    ");
+        for x in line.iter() {
+            print!("{}, ", x);
+        }
+        println!();
+    }
+    println!("];");
+
+    println!();
+    println!("LAST={:?} MAX-GAP={:?}", primes[COUNT - 1], data.iter().max());
+}
+
+fn main() {
+    gaps_gen()
+}
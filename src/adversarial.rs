@@ -0,0 +1,193 @@
+// Composites that fool fixed-base tests
+//
+// F. Arnault, "Constructing Carmichael numbers which are strong pseudoprimes
+// to several bases", J. Symbolic Computation 20 (1995).
+//
+// Take n = p1 · p2 · p3 with p_i = k_i · (p1 − 1) + 1 (k1 = 1) and every p_i
+// prime and ≡ 3 (mod 4). If p_i − 1 | n − 1 and (a | p_i) = −1 for every base
+// a and every i, then
+//
+//      a ^ ((n − 1) / 2) ≡ (a | p_i) = −1 (mod p_i)
+//
+// for all i, so n is a strong pseudoprime to every such base. The symbols only
+// depend on p1 modulo 4a, and the divisibility on p1 modulo k2 and k3, so
+// the conditions reduce to a single residue class for p1.
+//
+// Only meant for testing a configuration against adversarial inputs.
+use crate::Primality;
+use crate::Bits;
+use crate::factorize_u64;
+use crate::jacobi_symbol_i64;
+use crate::miller_rabin::{find_witness_biguint, is_prime_u64};
+use crate::baillie_psw_primality_test_biguint;
+
+use rand::Rng;
+use num_bigint::{BigUint, RandBigInt};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct StrongPseudoprime {
+    pub n: BigUint,
+    // p1 < p2 < p3, with n = p1 · p2 · p3.
+    pub factors: Vec<BigUint>,
+}
+
+// A composite that passes the strong (Miller–Rabin) test for every base in
+// `bases`, and therefore the Fermat test for them too. Its smallest factor
+// has at least `bits` bits.
+pub fn gen_strong_pseudoprime<R: Rng + ?Sized>(bases: &[u64], bits: Bits, rng: &mut R) -> StrongPseudoprime {
+    assert!(bases.iter().all(|a| *a >= 2));
+
+    // NOTE: 由 Jacobi 符号的积性，只要对底数的每个素因子成立即可。
+    let mut primes: Vec<u64> = bases.iter()
+        .flat_map(|a| factorize_u64(*a).into_iter().map(|(p, _)| p))
+        .collect();
+    primes.sort_unstable();
+    primes.dedup();
+
+    let largest = primes.last().copied().unwrap_or(2);
+    let mut ks = (largest + 1..).filter(|k| is_prime_u64(*k));
+
+    let mut k2 = ks.next().unwrap();
+    loop {
+        let k3 = ks.next().unwrap();
+        if let Some((residue, modulus)) = residue_class(&primes, k2, k3, rng) {
+            return search(bases, bits, k2, k3, &residue, &modulus, rng);
+        }
+        k2 = k3;
+    }
+}
+
+// The class of p1 satisfying every condition for the given k2 and k3, with
+// one residue picked at random for each base.
+fn residue_class<R: Rng + ?Sized>(primes: &[u64], k2: u64, k3: u64, rng: &mut R) -> Option<(BigUint, BigUint)> {
+    // p1 ≡ −k3 ^ −1 (mod k2) and p1 ≡ −k2 ^ −1 (mod k3) make n ≡ 1 modulo both.
+    let r2 = k2 - inverse(k3 % k2, k2)?;
+    let r3 = k3 - inverse(k2 % k3, k3)?;
+
+    // NOTE: 还需要 gcd(k_i, p1 − 1) = 1，才能保证 p_i − 1 | n − 1。
+    if r2 == 1 || r3 == 1 {
+        return None;
+    }
+
+    // NOTE: 底数 2 的条件是模 8 的，它取代了 p1 ≡ 3 (mod 4) 这个初始条件。
+    let mut residue = BigUint::from(3u8);
+    let mut modulus = BigUint::from(4u8);
+    let mut classes: Vec<(u64, u64)> = vec![(r2, k2), (r3, k3)];
+
+    for a in primes.iter().copied() {
+        // Candidates for p1 modulo 4a, all ≡ 3 (mod 4).
+        let m = 4 * a;
+        let allowed: Vec<u64> = (3..m).step_by(4)
+            .filter(|r| [1, k2, k3].iter().all(|k| jacobi_symbol_i64(a as i64, (k * (r - 1) + 1) % m) == -1))
+            .collect();
+        if allowed.is_empty() {
+            return None;
+        }
+
+        let r = allowed[rng.gen_range(0, allowed.len())];
+        if a == 2 {
+            residue = BigUint::from(r);
+            modulus = BigUint::from(m);
+        } else {
+            classes.push((r % a, a));
+        }
+    }
+
+    for (r, m) in classes {
+        crt(&mut residue, &mut modulus, r, m);
+    }
+
+    Some((residue, modulus))
+}
+
+fn search<R: Rng + ?Sized>(bases: &[u64], bits: Bits, k2: u64, k3: u64, residue: &BigUint, modulus: &BigUint, rng: &mut R) -> StrongPseudoprime {
+    let one = BigUint::from(1u8);
+    // NOTE: 开启 `proven-only` 时 BPSW 通过的数是 `Unknown`，只排除合数。
+    let is_prime = |p: &BigUint| -> bool { baillie_psw_primality_test_biguint(p) != Primality::Composite };
+
+    // Start at a random multiple of the modulus with p1 around 2 ^ bits.
+    let lo = (BigUint::from(1u8) << (bits.get() - 1) as usize) / modulus + 1u8;
+    let mut t = rng.gen_biguint_range(&lo, &(&lo * 2u8));
+
+    loop {
+        let p1 = residue + modulus * &t;
+        t += 1u8;
+
+        if !is_prime(&p1) {
+            continue;
+        }
+
+        let p2 = (&p1 - 1u8) * k2 + 1u8;
+        let p3 = (&p1 - 1u8) * k3 + 1u8;
+        if !is_prime(&p2) || !is_prime(&p3) {
+            continue;
+        }
+
+        let n = &p1 * &p2 * &p3;
+
+        // NOTE: 构造本身已经保证了结果，这里再用每个底数确认一次。
+        let fooled = bases.iter()
+            .map(|a| BigUint::from(*a) % &n)
+            .filter(|a| a > &one)
+            .all(|a| find_witness_biguint(&n, 1, |_| a.clone()).is_none());
+        if fooled {
+            return StrongPseudoprime { n, factors: vec![p1, p2, p3] };
+        }
+    }
+}
+
+// Fold x ≡ r (mod m) into x ≡ residue (mod modulus); m must be coprime to modulus.
+fn crt(residue: &mut BigUint, modulus: &mut BigUint, r: u64, m: u64) {
+    let current = (&*residue % m).to_u64_digits().first().copied().unwrap_or(0);
+    let step = (&*modulus % m).to_u64_digits().first().copied().unwrap_or(0);
+    let t = (r + m - current) % m * inverse(step, m).expect("moduli must be coprime") % m;
+
+    *residue += &*modulus * t;
+    *modulus *= m;
+}
+
+// a ^ −1 (mod m)
+fn inverse(a: u64, m: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (m as i128, a as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+
+    if r0 != 1 {
+        return None;
+    }
+
+    Some(t0.rem_euclid(m as i128) as u64)
+}
+
+
+#[test]
+fn test_gen_strong_pseudoprime() {
+    use crate::miller_rabin_primality_test_biguint;
+
+    let mut rng = crate::global().rng();
+
+    let bases = [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let spsp = gen_strong_pseudoprime(&bases, Bits::new(32).unwrap(), &mut rng);
+
+    let product = spsp.factors.iter().fold(BigUint::from(1u8), |acc, p| acc * p);
+    assert_eq!(spsp.n, product);
+    assert!(spsp.factors.iter().all(|p| baillie_psw_primality_test_biguint(p) != Primality::Composite));
+    assert!(spsp.factors[0].bits() >= 32);
+
+    // Passes the fixed bases, including composite ones built from them ...
+    for a in bases.iter().chain([6u64, 221, 1 << 20].iter()) {
+        assert_eq!(find_witness_biguint(&spsp.n, 1, |_| BigUint::from(*a)), None, "a={}", a);
+    }
+
+    // ... but not random ones, nor BPSW.
+    assert_eq!(miller_rabin_primality_test_biguint(&spsp.n, 20), Primality::Composite);
+    assert_eq!(baillie_psw_primality_test_biguint(&spsp.n), Primality::Composite);
+
+    assert_eq!(inverse(3, 8), Some(3));
+    assert_eq!(inverse(4, 8), None);
+}
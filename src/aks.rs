@@ -1,44 +1,164 @@
 // PRIMES is in P
 // https://www.cse.iitk.ac.in/users/manindra/algebra/primality_v6.pdf
-// 
+//
 // AKS primality test (wikipedia)
 // https://en.wikipedia.org/wiki/AKS_primality_test
+//
+// The test is deterministic and polynomial in log n, but the polynomial is
+// large: every step works with polynomials of degree r − 1 > log2(n) ^ 2. It
+// is here for its own sake; every other test of this crate is faster.
 use crate::Primality;
+use crate::perfect_power_u64;
+
+use num_integer::Integer;
 
 
-// AKS test for primes (Example Code)
-// https://rosettacode.org/wiki/AKS_test_for_primes#Rust
 pub fn aks_primality_test_usize(n: usize) -> Primality {
-    // Input:  integern > 1
-    debug_assert!(n > 1);
-    
-    let n1 = n + 1;
-    let n2 = n / 2 + 1;
-
-    let pp: i64 = core::convert::TryFrom::try_from(n).expect("oops ...");
-
-    // NOTE: 在计算大数时，系数的存储将变得几乎不可能实现。
-    let mut coefficients = vec![0i64; n1];
-    coefficients[0] = 1;
-    for i in 1..n1 {
-        let mut prev = coefficients[0];
-        for j in 1..i {
-            let old = coefficients[j];
-            // NOTE: 此处的减法会溢出，应该使用 BigInt。
-            // coefficients[j] = old.wrapping_sub(prev);
-            coefficients[j] = old - prev;
-            prev = old;
+    // Input:  integer n; 0 and 1 are neither prime nor composite
+    if n < 2 {
+        return Primality::ZeroOrOne;
+    }
+    let n = n as u64;
+
+    // 1. If n = a ^ b for integers a > 1 and b > 1, output composite.
+    if perfect_power_u64(n).is_some() {
+        return Primality::Composite;
+    }
+
+    // 2. Find the smallest r such that ord_r(n) > (log2 n) ^ 2.
+    let log2_n = (n as f64).log2();
+    let max_k = (log2_n * log2_n).floor() as u64;
+    let r = (2..).find(|r| n.gcd(r) == 1 && multiplicative_order(n, *r) > max_k).unwrap();
+
+    // 3. If 1 < gcd(a, n) < n for some a ≤ r, output composite.
+    for a in 2..=r.min(n - 1) {
+        if n.gcd(&a) > 1 {
+            return Primality::Composite;
         }
-        
-        coefficients[i] = -prev;
     }
 
-    for i in 1..n2 {
-        if coefficients[i] % pp != 0 {
+    // 4. If n ≤ r, output prime.
+    if n <= r {
+        return Primality::Prime;
+    }
+
+    // 5. For a = 1 to ⌊√φ(r) · log2 n⌋, if (X + a) ^ n ≠ X ^ (n mod r) + a
+    //    (mod X ^ r − 1, n), output composite.
+    let limit = ((totient(r) as f64).sqrt() * log2_n).floor() as u64;
+    let ring = Ring { n, r: r as usize };
+    for a in 1..=limit {
+        let lhs = ring.pow(&ring.x_plus(a), n);
+
+        let mut rhs = vec![0u64; ring.r];
+        rhs[0] = a % n;
+        rhs[(n % r) as usize] = (rhs[(n % r) as usize] + 1) % n;
+
+        if lhs != rhs {
             return Primality::Composite;
         }
     }
 
-    return Primality::Prime;
+    // 6. Output prime.
+    Primality::Prime
 }
 
+// The least k > 0 with n ^ k ≡ 1 (mod r), for gcd(n, r) = 1.
+fn multiplicative_order(n: u64, r: u64) -> u64 {
+    let n = n % r;
+    let mut x = n;
+    let mut k = 1;
+    while x != 1 % r {
+        x = (x as u128 * n as u128 % r as u128) as u64;
+        k += 1;
+    }
+
+    k
+}
+
+// Euler's totient of a small r, by trial division.
+fn totient(r: u64) -> u64 {
+    let mut phi = r;
+    let mut m = r;
+    let mut p = 2;
+    while p * p <= m {
+        if m.is_multiple_of(p) {
+            while m.is_multiple_of(p) {
+                m /= p;
+            }
+            phi -= phi / p;
+        }
+        p += 1;
+    }
+    if m > 1 {
+        phi -= phi / m;
+    }
+
+    phi
+}
+
+// Z_n[X] / (X ^ r − 1), polynomials as r coefficients, lowest degree first.
+struct Ring {
+    n: u64,
+    r: usize,
+}
+
+impl Ring {
+    fn x_plus(&self, a: u64) -> Vec<u64> {
+        let mut p = vec![0u64; self.r];
+        p[0] = a % self.n;
+        p[1 % self.r] = (p[1 % self.r] + 1) % self.n;
+        p
+    }
+
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = self.n as u128;
+        let nn = n * n;
+
+        // NOTE: 在 u128 中累加并保持小于 n^2，避免每个乘积都做一次除法。
+        let mut acc = vec![0u128; self.r];
+        for (i, x) in a.iter().enumerate().filter(|(_, x)| **x != 0) {
+            for (j, y) in b.iter().enumerate().filter(|(_, y)| **y != 0) {
+                let k = if i + j >= self.r { i + j - self.r } else { i + j };
+                let (t, overflow) = acc[k].overflowing_add(*x as u128 * *y as u128);
+                acc[k] = if overflow || t >= nn { t.wrapping_sub(nn) } else { t };
+            }
+        }
+
+        acc.into_iter().map(|c| (c % n) as u64).collect()
+    }
+
+    fn pow(&self, base: &[u64], exponent: u64) -> Vec<u64> {
+        let mut result = vec![0u64; self.r];
+        result[0] = 1 % self.n;
+
+        for i in (0..64 - exponent.leading_zeros()).rev() {
+            result = self.mul(&result, &result);
+            if (exponent >> i) & 1 == 1 {
+                result = self.mul(&result, base);
+            }
+        }
+
+        result
+    }
+}
+
+
+#[test]
+fn test_aks_primality_test_usize() {
+    use crate::table_query_u16;
+
+    for n in 0..256u16 {
+        assert_eq!(aks_primality_test_usize(n as usize), table_query_u16(n), "N={}", n);
+    }
+
+    // Beyond the reach of the old Pascal-triangle version, which overflowed
+    // its i64 coefficients above 66; these go through step 5.
+    for n in [257usize, 1009, 2047, 4093, 4097, 4141] {
+        assert_eq!(aks_primality_test_usize(n), table_query_u16(n as u16), "N={}", n);
+    }
+
+    assert_eq!(multiplicative_order(10, 7), 6);
+    assert_eq!(multiplicative_order(2, 7), 3);
+    assert_eq!(totient(36), 12);
+    assert_eq!(totient(97), 96);
+}
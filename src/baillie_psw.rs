@@ -0,0 +1,363 @@
+// Baillie–PSW primality test
+// https://en.wikipedia.org/wiki/Baillie%E2%80%93PSW_primality_test
+//
+// A strong probable prime test to base 2 followed by a strong Lucas probable
+// prime test with Selfridge's parameters. No composite is known to pass both.
+use crate::Primality;
+use crate::Provenance;
+use crate::AsBigUint;
+use crate::table_query_u16;
+use crate::miller_rabin::{find_witness_biguint, is_prime_u64, miller_rabin_u64_with};
+use crate::jacobi_symbol_i64;
+use crate::jacobi_symbol_bigint;
+use crate::is_perfect_square_u64;
+use crate::is_perfect_square_biguint;
+
+use rand::Rng;
+use num_bigint::{BigInt, BigUint, RandBigInt};
+
+
+// NOTE: GMP 的 mpz_probab_prime_p(n, reps) 在 BPSW 之后再做 reps − 24 轮随机底数的强测试，
+//       文档建议 reps 取 15 到 50；这里相当于 reps = 28。
+pub(crate) const AUTO_EXTRA_ROUNDS: usize = 4;
+
+// BPSW is exact below 2 ^ 64: every base-2 strong pseudoprime below 2 ^ 64
+// is known (Feitsma and Galway) and none of them is a strong Lucas probable
+// prime, so the answer is `Prime` rather than `ProbablyPrime`.
+pub fn baillie_psw_primality_test_u64(n: u64) -> Primality {
+    if n <= u16::MAX as u64 {
+        return table_query_u16(n as u16);
+    }
+
+    if n.is_multiple_of(2) {
+        return Primality::Composite;
+    }
+
+    if miller_rabin_u64_with(n, &[(u64::MAX, &[2])]) == Primality::Composite {
+        return Primality::Composite;
+    }
+
+    Primality::from_proof(strong_lucas_probable_prime_u64(n))
+}
+
+pub fn baillie_psw_primality_test_biguint(n: &(impl AsBigUint + ?Sized)) -> Primality {
+    baillie_psw_provenance(&n.as_biguint()).0
+}
+
+// `baillie_psw_primality_test_biguint` together with the step that decided it.
+fn baillie_psw_provenance(n: &BigUint) -> (Primality, Provenance) {
+    if n.bits() <= 16 {
+        let n = n.to_u32_digits().first().copied().unwrap_or(0) as u16;
+        return (table_query_u16(n), Provenance::Table);
+    }
+
+    if !n.bit(0) {
+        return (Primality::Composite, Provenance::TrialDivision);
+    }
+
+    if n.bits() <= 64 {
+        let n = n.to_u64_digits()[0];
+        return (Primality::from_proof(is_prime_u64(n)), Provenance::DeterministicMillerRabin);
+    }
+
+    if crate::global().has_small_factor(n) {
+        return (Primality::Composite, Provenance::TrialDivision);
+    }
+
+    if find_witness_biguint(n, 1, |_| BigUint::from(2u8)).is_some() {
+        return (Primality::Composite, Provenance::BailliePsw);
+    }
+
+    if !strong_lucas_probable_prime_biguint(n) {
+        return (Primality::Composite, Provenance::BailliePsw);
+    }
+
+    (Primality::PROBABLE, Provenance::BailliePsw)
+}
+
+// BPSW followed by `extra_rounds` strong tests to random bases, so that a
+// composite would have to fool both BPSW and the random rounds.
+pub fn baillie_psw_plus<R: Rng + ?Sized>(n: &(impl AsBigUint + ?Sized), extra_rounds: usize, rng: &mut R) -> Primality {
+    let two = BigUint::from(2u8);
+    baillie_psw_plus_provenance(&n.as_biguint(), extra_rounds, |hi| rng.gen_biguint_range(&two, hi)).0
+}
+
+// `baillie_psw_plus` together with the step that decided it, drawing the
+// base of each extra round in [2, hi) from `next_base(hi)`. A probable prime
+// is credited to BPSW, the random rounds after it only ever find composites.
+fn baillie_psw_plus_provenance<F>(n: &BigUint, extra_rounds: usize, mut next_base: F) -> (Primality, Provenance)
+where
+    F: FnMut(&BigUint) -> BigUint,
+{
+    let ret = baillie_psw_provenance(n);
+    if ret.0 != Primality::PROBABLE || extra_rounds == 0 {
+        return ret;
+    }
+
+    // a in the range [2, n − 2]
+    let n_minus_two: BigUint = n - 2u8;
+    match find_witness_biguint(n, extra_rounds, |_| next_base(&n_minus_two)) {
+        Some(_) => (Primality::Composite, Provenance::RandomMillerRabin { rounds: extra_rounds as u32 }),
+        None => ret,
+    }
+}
+
+// The recommended test for a BigUint of unknown origin: exact below 2 ^ 64,
+// BPSW plus a few random strong tests above.
+pub fn is_prime_biguint_auto(n: &(impl AsBigUint + ?Sized)) -> Primality {
+    is_prime_biguint_auto_provenance(&n.as_biguint()).0
+}
+
+// `is_prime_biguint_auto` together with the step that decided it and whether
+// the random rounds fell back to derived bases, for `check`.
+pub(crate) fn is_prime_biguint_auto_provenance(n: &BigUint) -> (Primality, Provenance, bool) {
+    // NOTE: 只有真正进行随机轮次时才取底数，免得 rng_fallbacks 把没有用到底数的测试也算进去。
+    let mut witnesses = None;
+    let (primality, provenance) = baillie_psw_plus_provenance(n, AUTO_EXTRA_ROUNDS, |hi| {
+        witnesses.get_or_insert_with(|| crate::global().witnesses(n)).next(hi)
+    });
+
+    (primality, provenance, witnesses.is_some_and(|witnesses| witnesses.is_derived()))
+}
+
+// Strong Lucas probable prime test
+// https://en.wikipedia.org/wiki/Lucas_pseudoprime#Strong_Lucas_pseudoprimes
+//
+// Selfridge's method A: D is the first of 5, −7, 9, −11, ... with (D | n) = −1,
+// P = 1 and Q = (1 − D) / 4. The input must be odd and free of small factors.
+pub(crate) fn strong_lucas_probable_prime_biguint(n: &BigUint) -> bool {
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+
+    // NOTE: 完全平方数找不到 (D | n) = −1 的 D，必须先排除。
+    if is_perfect_square_biguint(n) {
+        return false;
+    }
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi_symbol_bigint(&BigInt::from(d), n) {
+            -1 => break,
+            0 if BigUint::from(d.unsigned_abs()) != *n => return false,
+            _ => {},
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+
+    // D and Q as residues mod n
+    let residue = |v: i64| -> BigUint {
+        let r = BigUint::from(v.unsigned_abs()) % n;
+        if v < 0 && r != zero { n - r } else { r }
+    };
+    let d_mod = residue(d);
+    let q_mod = residue((1 - d) / 4);
+
+    // x / 2 (mod n), n odd
+    let half = |x: BigUint| -> BigUint {
+        if !x.bit(0) { x >> 1usize } else { (x + n) >> 1usize }
+    };
+
+    // write n + 1 as 2^s·k with k odd
+    let n_plus_one: BigUint = n + 1u8;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k: BigUint = &n_plus_one >> s as usize;
+
+    // U_k, V_k and Q^k by the binary method, starting from U_1 = 1, V_1 = P = 1
+    let mut u = one.clone();
+    let mut v = one.clone();
+    let mut qk = q_mod.clone();
+    for i in (0..k.bits() - 1).rev() {
+        // U_2m = U_m·V_m, V_2m = V_m ^ 2 − 2·Q^m
+        u = &u * &v % n;
+        v = (&v * &v + n * 2u8 - &qk * 2u8 % n) % n;
+        qk = &qk * &qk % n;
+
+        if k.bit(i) {
+            // U_m+1 = (P·U_m + V_m) / 2, V_m+1 = (D·U_m + P·V_m) / 2
+            let u_next = half((&u + &v) % n);
+            let v_next = half((&d_mod * &u + &v) % n);
+            u = u_next;
+            v = v_next;
+            qk = &qk * &q_mod % n;
+        }
+    }
+
+    if u == zero || v == zero {
+        return true;
+    }
+
+    // V_2^r·k for 0 < r < s
+    for _ in 1..s {
+        v = (&v * &v + n * 2u8 - &qk * 2u8 % n) % n;
+        if v == zero {
+            return true;
+        }
+        qk = &qk * &qk % n;
+    }
+
+    false
+}
+
+
+// `strong_lucas_probable_prime_biguint` for an odd n > 2, with the products
+// taken in u128.
+pub(crate) fn strong_lucas_probable_prime_u64(n: u64) -> bool {
+    if is_perfect_square_u64(n) {
+        return false;
+    }
+
+    let mut d: i64 = 5;
+    loop {
+        match jacobi_symbol_i64(d, n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => {},
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+
+    let residue = |v: i64| -> u64 {
+        let r = v.unsigned_abs() % n;
+        if v < 0 && r != 0 { n - r } else { r }
+    };
+    let d_mod = residue(d);
+    let q_mod = residue((1 - d) / 4);
+
+    let mul = |a: u64, b: u64| -> u64 { (a as u128 * b as u128 % n as u128) as u64 };
+    let add = |a: u64, b: u64| -> u64 { ((a as u128 + b as u128) % n as u128) as u64 };
+    // NOTE: x、n 都是奇数时 (x + n) / 2 = x / 2 + n / 2 + 1，避免 x + n 溢出。
+    let half = |x: u64| -> u64 { if x.is_multiple_of(2) { x >> 1 } else { (x >> 1) + (n >> 1) + 1 } };
+    // V_2m = V_m ^ 2 − 2·Q^m
+    let double_v = |v: u64, qk: u64| -> u64 { add(mul(v, v), n - add(qk, qk)) };
+
+    // NOTE: n = u64::MAX 时 n + 1 = 2^64 溢出，此时 k = 1、s = 64。
+    let (k, s) = match n.checked_add(1) {
+        Some(n_plus_one) => (n_plus_one >> n_plus_one.trailing_zeros(), n_plus_one.trailing_zeros()),
+        None => (1, 64),
+    };
+
+    let mut u = 1u64;
+    let mut v = 1u64;
+    let mut qk = q_mod;
+    for i in (0..63 - k.leading_zeros()).rev() {
+        u = mul(u, v);
+        v = double_v(v, qk);
+        qk = mul(qk, qk);
+
+        if (k >> i) & 1 == 1 {
+            let u_next = half(add(u, v));
+            let v_next = half(add(mul(d_mod, u), v));
+            u = u_next;
+            v = v_next;
+            qk = mul(qk, q_mod);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = double_v(v, qk);
+        if v == 0 {
+            return true;
+        }
+        qk = mul(qk, qk);
+    }
+
+    false
+}
+
+
+#[test]
+fn test_strong_lucas_probable_prime_biguint() {
+    use crate::table_query_u16;
+
+    for n in (7..u16::MAX).step_by(2) {
+        if n % 3 == 0 || n % 5 == 0 {
+            continue;
+        }
+
+        let ret = strong_lucas_probable_prime_biguint(&BigUint::from(n));
+        // Strong Lucas pseudoprimes below 2^16 (OEIS A217255).
+        let pseudoprime = [5459u16, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519].contains(&n);
+        assert_eq!(ret, table_query_u16(n) == Primality::Prime || pseudoprime, "N={}", n);
+    }
+}
+
+#[test]
+fn test_strong_lucas_probable_prime_u64() {
+    for n in (7..1 << 17).step_by(2) {
+        assert_eq!(strong_lucas_probable_prime_u64(n), strong_lucas_probable_prime_biguint(&BigUint::from(n)), "N={}", n);
+    }
+
+    let mut rng = crate::global().rng();
+    for _ in 0..2000 {
+        let n = rng.gen::<u64>() | 1;
+        assert_eq!(strong_lucas_probable_prime_u64(n), strong_lucas_probable_prime_biguint(&BigUint::from(n)), "N={}", n);
+    }
+}
+
+#[test]
+fn test_baillie_psw_primality_test_u64() {
+    assert_eq!(baillie_psw_primality_test_u64(0), Primality::ZeroOrOne);
+    assert_eq!(baillie_psw_primality_test_u64(2), Primality::Prime);
+    assert_eq!(baillie_psw_primality_test_u64(u64::MAX), Primality::Composite);
+    assert_eq!(baillie_psw_primality_test_u64(18446744073709551557), Primality::Prime);
+
+    for n in (0..u64::MAX).step_by(1 << 56).flat_map(|n| n..n + 500) {
+        assert_eq!(baillie_psw_primality_test_u64(n).is_prime(), is_prime_u64(n), "N={}", n);
+    }
+
+    // Strong pseudoprimes to base 2, and to the bases 2 through 23.
+    for n in [2047u64, 3277, 4033, 3215031751, 3825123056546413051] {
+        assert_eq!(baillie_psw_primality_test_u64(n), Primality::Composite, "N={}", n);
+    }
+}
+
+#[test]
+fn test_baillie_psw_primality_test_biguint() {
+    assert_eq!(baillie_psw_primality_test_biguint(&1u8), Primality::ZeroOrOne);
+    assert_eq!(baillie_psw_primality_test_biguint(&2u8), Primality::Prime);
+    assert_eq!(baillie_psw_primality_test_biguint(&18446744073709551557u64), Primality::Prime);
+
+    // 2 ^ 127 − 1, 2 ^ 521 − 1
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m521 = (BigUint::from(1u8) << 521usize) - 1u8;
+    assert_eq!(baillie_psw_primality_test_biguint(&m127), Primality::PROBABLE);
+    assert_eq!(baillie_psw_primality_test_biguint(&m521), Primality::PROBABLE);
+    assert_eq!(baillie_psw_primality_test_biguint(&(&m127 * &m521)), Primality::Composite);
+    assert_eq!(baillie_psw_primality_test_biguint(&(&m127 * &m127)), Primality::Composite);
+
+    // Strong pseudoprime to bases 2 through 37 (Arnault)
+    let n: BigUint = "3317044064679887385961981".parse().unwrap();
+    assert_eq!(baillie_psw_primality_test_biguint(&n), Primality::Composite);
+}
+
+#[test]
+fn test_baillie_psw_plus() {
+    let mut rng = crate::global().rng();
+
+    assert_eq!(baillie_psw_plus(&0u8, 4, &mut rng), Primality::ZeroOrOne);
+    assert_eq!(baillie_psw_plus(&65521u32, 4, &mut rng), Primality::Prime);
+    assert_eq!(is_prime_biguint_auto(&18446744073709551557u64), Primality::Prime);
+    assert_eq!(is_prime_biguint_auto(&18446744073709551615u64), Primality::Composite);
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    for extra_rounds in [0, 1, 10] {
+        assert_eq!(baillie_psw_plus(&m127, extra_rounds, &mut rng), Primality::PROBABLE);
+        assert_eq!(baillie_psw_plus(&(&m127 * &m89), extra_rounds, &mut rng), Primality::Composite);
+    }
+    assert_eq!(is_prime_biguint_auto(&m127), Primality::PROBABLE);
+
+    let n: BigUint = "3317044064679887385961981".parse().unwrap();
+    assert_eq!(is_prime_biguint_auto(&n), Primality::Composite);
+}
+
+#[bench]
+fn bench_baillie_psw_primality_test_u64(b: &mut test::Bencher) {
+    b.iter(|| {
+        baillie_psw_primality_test_u64(18446744073709551557)
+    })
+}
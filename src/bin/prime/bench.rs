@@ -0,0 +1,67 @@
+// prime bench N [--algorithms LIST] [--json]
+//
+// Times each algorithm once on N. LIST is comma separated, out of
+// trial-division, miller-rabin, solovay-strassen, baillie-psw and aks; the
+// default skips trial division and AKS once N has more than 64 bits.
+use crate::{envelope, primality_name, Args};
+use crate::json::Json;
+
+use prime::parse_biguint_expr;
+use prime::compare::{self, Algorithm};
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let n = match args.values() {
+        [n] => parse_biguint_expr(n).map_err(|e| format!("{}: {}", n, e))?,
+        _ => return Err("expected exactly one number".to_string()),
+    };
+
+    let algorithms = match args.option("algorithms") {
+        Some(list) => parse_algorithms(list)?,
+        None => Algorithm::ALL.iter()
+            .copied()
+            .filter(|a| n.bits() <= 64 || !matches!(a, Algorithm::TrialDivision | Algorithm::Aks))
+            .collect(),
+    };
+
+    let results = compare::run(&n, &algorithms);
+
+    if args.flag("json") {
+        let results = results.iter().map(|(algorithm, elapsed, primality)| Json::Object(vec![
+            ("algorithm", Json::string(algorithm.name())),
+            ("result", Json::string(primality_name(*primality))),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ])).collect();
+
+        println!("{}", envelope("bench", vec![
+            ("n", Json::string(&n)),
+            ("results", Json::Array(results)),
+        ]));
+    } else {
+        for (algorithm, elapsed, primality) in results.iter() {
+            println!("{:<18} {:>12.3?} {}", algorithm.name(), elapsed, primality_name(*primality));
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_algorithms(list: &str) -> Result<Vec<Algorithm>, String> {
+    list.split(',')
+        .map(|name| Algorithm::from_name(name.trim()).ok_or_else(|| format!("unknown algorithm: {}", name)))
+        .collect()
+}
+
+
+#[test]
+fn test_parse_algorithms() {
+    assert_eq!(parse_algorithms("miller-rabin"), Ok(vec![Algorithm::MillerRabin]));
+    assert_eq!(
+        parse_algorithms("baillie-psw, aks"),
+        Ok(vec![Algorithm::BailliePsw, Algorithm::Aks])
+    );
+    assert!(parse_algorithms("miller-rabin,fermat").is_err());
+    assert!(parse_algorithms("").is_err());
+}
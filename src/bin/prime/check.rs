@@ -0,0 +1,157 @@
+// prime check [--threads N] [--summary] [--json] (--file PATH | N...)
+//
+// Prints one `<input>\t<result>` line per candidate, in input order. Blank
+// lines and lines starting with `#` in the file are skipped.
+use crate::{envelope, primality_name, Args};
+use crate::json::Json;
+use crate::factor::certificate_to_json;
+use crate::count::parse_u64;
+
+use prime::Primality;
+use prime::Report;
+use prime::pratt_certificate_u64;
+use prime::parse_biguint_expr;
+use prime::check;
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+
+pub const FLAGS: &[&str] = &["summary", "json"];
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let threads = match args.option("threads") {
+        Some(s) => s.parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("invalid thread count: {}", s))?,
+        None => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+    };
+
+    let content;
+    let inputs: Vec<&str> = match args.option("file") {
+        Some(path) => {
+            content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            content.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect()
+        },
+        None => args.values().iter().map(|s| s.as_str()).collect(),
+    };
+
+    if inputs.is_empty() {
+        return Err("no numbers to check".to_string());
+    }
+
+    let start = Instant::now();
+    let results = check_all(&inputs, threads);
+    let elapsed = start.elapsed();
+
+    let count = |primality: Option<Primality>| {
+        results.iter().filter(|r| r.map(|(verdict, _)| verdict.primality) == primality).count()
+    };
+
+    if args.flag("json") {
+        let items = inputs.iter().zip(results.iter())
+            .map(|(input, result)| result_to_json(input, *result))
+            .collect();
+
+        let summary = Json::Object(vec![
+            ("total", Json::Int(results.len() as u64)),
+            ("prime", Json::Int(count(Some(Primality::Prime)) as u64)),
+            ("probably-prime", Json::Int(count(Some(Primality::ProbablyPrime)) as u64)),
+            ("composite", Json::Int(count(Some(Primality::Composite)) as u64)),
+            ("zero-or-one", Json::Int(count(Some(Primality::ZeroOrOne)) as u64)),
+            ("invalid", Json::Int(count(None) as u64)),
+        ]);
+
+        println!("{}", envelope("check", vec![
+            ("results", Json::Array(items)),
+            ("summary", summary),
+            ("threads", Json::Int(threads as u64)),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ]));
+
+        return Ok(());
+    }
+
+    for (input, result) in inputs.iter().zip(results.iter()) {
+        let name = result.map(|(verdict, _)| primality_name(verdict.primality)).unwrap_or("invalid");
+        println!("{}\t{}", input, name);
+    }
+
+    if args.flag("summary") {
+        eprintln!("total:          {}", results.len());
+        eprintln!("prime:          {}", count(Some(Primality::Prime)));
+        eprintln!("probably-prime: {}", count(Some(Primality::ProbablyPrime)));
+        eprintln!("composite:      {}", count(Some(Primality::Composite)));
+        eprintln!("zero-or-one:    {}", count(Some(Primality::ZeroOrOne)));
+        eprintln!("invalid:        {}", count(None));
+        eprintln!("threads:        {}", threads);
+        eprintln!("elapsed:        {:?}", elapsed);
+    }
+
+    Ok(())
+}
+
+fn result_to_json(input: &str, result: Option<(Report, Duration)>) -> Json {
+    let (verdict, elapsed) = match result {
+        Some(result) => result,
+        None => return Json::Object(vec![
+            ("input", Json::string(input)),
+            ("result", Json::string("invalid")),
+        ]),
+    };
+
+    // NOTE: 只有 u64 范围内的素数才能快速地给出 Pratt 证书。
+    let certificate = match parse_u64(input) {
+        Ok(n) if verdict.primality == Primality::Prime => {
+            pratt_certificate_u64(n).as_ref().map(certificate_to_json).unwrap_or(Json::Null)
+        },
+        _ => Json::Null,
+    };
+
+    Json::Object(vec![
+        ("input", Json::string(input)),
+        ("result", Json::string(primality_name(verdict.primality))),
+        ("provenance", Json::string(verdict.provenance)),
+        ("rounds", verdict.rounds().map(|k| Json::Int(k as u64)).unwrap_or(Json::Null)),
+        ("certificate", certificate),
+        ("elapsed", Json::Float(elapsed.as_secs_f64())),
+    ])
+}
+
+// `None` marks an input that is not a number expression.
+fn check_all(inputs: &[&str], threads: usize) -> Vec<Option<(Report, Duration)>> {
+    let check_one = |input: &&str| {
+        let start = Instant::now();
+        parse_biguint_expr(input).ok().map(|n| (check::<Report>(&n), start.elapsed()))
+    };
+
+    let chunk_size = inputs.len().div_ceil(threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = inputs.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(check_one).collect::<Vec<_>>()))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+
+#[test]
+fn test_result_to_json() {
+    let json = |input: &str| result_to_json(input, check_all(&[input], 1)[0]).to_string();
+
+    assert!(json("7").contains(r#""provenance":"table""#));
+    assert!(json("1_000_000_007").contains(r#""provenance":"deterministic-miller-rabin""#));
+    assert!(json("3*(2^127-1)").contains(r#""provenance":"trial-division""#));
+    assert!(json("3*(2^127-1)").contains(r#""rounds":null"#));
+    assert!(json("2^127-1").contains(r#""provenance":"baillie-psw""#));
+    assert!(json("x").contains(r#""result":"invalid""#));
+}
@@ -0,0 +1,97 @@
+// prime pi X [--json]
+// prime nth N [--json]
+//
+// Both accept `1000000`, `1_000_000`, `1e6`, `10^6` and sums such as `1e9+7`.
+use crate::{envelope, Args};
+use crate::json::Json;
+
+use prime::{prime_pi, nth_prime};
+use prime::parse_biguint_expr;
+
+use core::convert::TryFrom;
+
+use std::time::Instant;
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+pub fn run_pi(args: &Args) -> Result<(), String> {
+    let x = single_number(args)?;
+
+    let start = Instant::now();
+    let pi = prime_pi(x);
+    let elapsed = start.elapsed();
+
+    if args.flag("json") {
+        println!("{}", envelope("pi", vec![
+            ("x", Json::string(x)),
+            ("result", Json::string(pi)),
+            ("method", Json::string("lucy-hedgehog")),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ]));
+    } else {
+        println!("{}", pi);
+    }
+
+    Ok(())
+}
+
+pub fn run_nth(args: &Args) -> Result<(), String> {
+    let n = single_number(args)?;
+    if n == 0 {
+        return Err("primes are counted from 1".to_string());
+    }
+
+    let start = Instant::now();
+    let p = nth_prime(n);
+    let elapsed = start.elapsed();
+
+    if args.flag("json") {
+        println!("{}", envelope("nth", vec![
+            ("n", Json::string(n)),
+            ("result", Json::string(p)),
+            ("method", Json::string("segmented-sieve")),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ]));
+    } else {
+        println!("{}", p);
+    }
+
+    Ok(())
+}
+
+fn single_number(args: &Args) -> Result<u64, String> {
+    match args.values() {
+        [s] => parse_u64(s),
+        _ => Err("expected exactly one number".to_string()),
+    }
+}
+
+// Parse a number expression such as `1000000`, `1_000_000`, `1e6`,
+// `2.5e6`, `10^6` or `2^32+15` that fits in a u64.
+pub fn parse_u64(s: &str) -> Result<u64, String> {
+    let invalid = || format!("not a number below 2^64: {}", s);
+
+    parse_biguint_expr(s)
+        .ok()
+        .and_then(|n| u64::try_from(&n).ok())
+        .ok_or_else(invalid)
+}
+
+
+#[test]
+fn test_parse_u64() {
+    assert_eq!(parse_u64("1000000"), Ok(1_000_000));
+    assert_eq!(parse_u64("1_000_000"), Ok(1_000_000));
+    assert_eq!(parse_u64("1e12"), Ok(1_000_000_000_000));
+    assert_eq!(parse_u64("1E3"), Ok(1000));
+    assert_eq!(parse_u64("2.5e6"), Ok(2_500_000));
+    assert_eq!(parse_u64("10^6"), Ok(1_000_000));
+    assert!(parse_u64("2^64").is_err());
+    assert!(parse_u64("1e20").is_err());
+    assert!(parse_u64("2.55e1").is_err());
+    assert!(parse_u64("e5").is_err());
+    assert!(parse_u64("-1").is_err());
+    assert_eq!(parse_u64("1e18+9"), Ok(1_000_000_000_000_000_009));
+    assert_eq!(parse_u64("2^64-1"), Ok(u64::MAX));
+}
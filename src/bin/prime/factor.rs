@@ -0,0 +1,170 @@
+// prime factor N [--timeout 60s] [--json]
+//
+// Prints the prime factorization of N together with a Pratt certificate for
+// every prime factor. Rho progress is reported on stderr about once a second.
+use crate::{envelope, Args};
+use crate::json::Json;
+use crate::count::parse_u64;
+
+use prime::{Factorizer, FactorState, Method};
+use prime::{pratt_certificate_u64, PrattCertificate};
+
+use core::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+// NOTE: 每次 resume 之间的 rho 迭代次数，决定了检查超时和输出进度的粒度。
+const RHO_CHUNK: u64 = 1 << 20;
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let n = match args.values() {
+        [n] => parse_u64(n)?,
+        _ => return Err("expected exactly one number".to_string()),
+    };
+
+    if n == 0 {
+        return Err("0 has no factorization".to_string());
+    }
+
+    let timeout = args.option("timeout").map(parse_duration).transpose()?;
+    let json = args.flag("json");
+
+    let start = Instant::now();
+    let mut last_report = start;
+
+    let mut state = Factorizer::new().with(Method::TrialTo(1 << 16)).factor(n);
+    let mut iterations = 0u64;
+    while !state.is_complete() && timeout.map(|t| start.elapsed() < t).unwrap_or(true) {
+        state = Factorizer::new().with(Method::Rho { max_iterations: RHO_CHUNK }).resume(state);
+        iterations += RHO_CHUNK;
+
+        if !json && !state.is_complete() && last_report.elapsed() >= Duration::from_secs(1) {
+            eprintln!("rho: {} iterations, {} cofactor(s) pending, {:?}", iterations, state.pending().len(), start.elapsed());
+            last_report = Instant::now();
+        }
+    }
+
+    let elapsed = start.elapsed();
+
+    if json {
+        println!("{}", to_json(n, &state, elapsed));
+    } else {
+        print_text(n, &state);
+    }
+
+    if state.is_complete() {
+        Ok(())
+    } else {
+        Err(format!("timed out after {:?}", elapsed))
+    }
+}
+
+// Accepts `90`, `90s`, `1500ms` and `2m`.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration: {}", s);
+
+    let (value, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "s" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        "h" => Ok(Duration::from_secs(value * 3600)),
+        _ => Err(invalid()),
+    }
+}
+
+fn print_text(n: u64, state: &FactorState) {
+    // NOTE: 分解完成时的输出可以直接被 `Factorization::from_str` 读回。
+    match state.factorization() {
+        Some(factorization) => println!("{}: {}", n, factorization),
+        None => {
+            let mut parts: Vec<String> = state.factors().iter()
+                .map(|(p, e)| if *e == 1 { p.to_string() } else { format!("{}^{}", p, e) })
+                .collect();
+            parts.extend(state.pending().iter().map(|item| format!("({})", item.n)));
+
+            println!("{}: {}", n, parts.join(" · "));
+        },
+    }
+
+    let mut certs: Vec<&PrattCertificate> = Vec::new();
+    let roots: Vec<PrattCertificate> = state.factors().iter()
+        .filter_map(|(p, _)| u64::try_from(p).ok().and_then(pratt_certificate_u64))
+        .collect();
+    for cert in roots.iter() {
+        flatten(cert, &mut certs);
+    }
+
+    certs.sort_by_key(|cert| core::cmp::Reverse(cert.p));
+    certs.dedup_by_key(|cert| cert.p);
+
+    if let Some(width) = certs.first().map(|cert| cert.p.to_string().len()) {
+        println!("certificates:");
+        for cert in certs.iter() {
+            if cert.factors.is_empty() {
+                println!("    {:>w$}", cert.p, w = width);
+            } else {
+                let qs: Vec<String> = cert.factors.iter().map(|q| q.p.to_string()).collect();
+                println!("    {:>w$}  witness {:<w$}  {} = {}", cert.p, cert.witness, cert.p - 1, qs.join(" · "), w = width);
+            }
+        }
+    }
+
+    for item in state.pending() {
+        println!("unfactored: {}", item.n);
+    }
+}
+
+// Every certificate in the tree, parents before children.
+fn flatten<'a>(cert: &'a PrattCertificate, out: &mut Vec<&'a PrattCertificate>) {
+    out.push(cert);
+    for q in cert.factors.iter() {
+        flatten(q, out);
+    }
+}
+
+pub fn certificate_to_json(cert: &PrattCertificate) -> Json {
+    Json::Object(vec![
+        ("type", Json::string("pratt")),
+        ("p", Json::string(cert.p)),
+        ("witness", Json::string(cert.witness)),
+        ("factors", Json::Array(cert.factors.iter().map(certificate_to_json).collect())),
+    ])
+}
+
+fn to_json(n: u64, state: &FactorState, elapsed: Duration) -> Json {
+    let factors = state.factors().iter()
+        .map(|(p, e)| Json::Object(vec![
+            ("prime", Json::string(p)),
+            ("exponent", Json::Int(*e as u64)),
+            ("certificate", u64::try_from(p).ok().and_then(pratt_certificate_u64).as_ref().map(certificate_to_json).unwrap_or(Json::Null)),
+        ]))
+        .collect();
+
+    envelope("factor", vec![
+        ("n", Json::string(n)),
+        ("complete", Json::Bool(state.is_complete())),
+        ("factors", Json::Array(factors)),
+        ("unfactored", Json::Array(state.pending().iter().map(|item| Json::string(&item.n)).collect())),
+        ("elapsed", Json::Float(elapsed.as_secs_f64())),
+    ])
+}
+
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_duration("60s"), Ok(Duration::from_secs(60)));
+    assert_eq!(parse_duration("1500ms"), Ok(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+    assert!(parse_duration("s").is_err());
+    assert!(parse_duration("10 s").is_err());
+    assert!(parse_duration("10d").is_err());
+}
@@ -0,0 +1,88 @@
+// Minimal JSON writer for the `--json` output modes
+//
+// NOTE: 大整数一律以字符串的形式输出，避免被其它语言的 JSON 解析器截断成 f64。
+use std::fmt;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    pub fn string(s: impl ToString) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(v) => write!(f, "{}", v),
+            Json::Int(v) => write!(f, "{}", v),
+            Json::Float(v) if v.is_finite() => write!(f, "{}", v),
+            Json::Float(_) => f.write_str("null"),
+            Json::String(s) => write_str(f, s),
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            },
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            },
+        }
+    }
+}
+
+
+#[test]
+fn test_json_display() {
+    let value = Json::Object(vec![
+        ("n", Json::string(18446744073709551557u64)),
+        ("complete", Json::Bool(true)),
+        ("factors", Json::Array(vec![Json::Int(2), Json::Float(0.5), Json::Null])),
+        ("note", Json::string("a \"quoted\"\n\u{1}")),
+    ]);
+
+    assert_eq!(
+        value.to_string(),
+        r#"{"n":"18446744073709551557","complete":true,"factors":[2,0.5,null],"note":"a \"quoted\"\n\u0001"}"#
+    );
+}
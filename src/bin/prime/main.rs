@@ -0,0 +1,163 @@
+// Command line interface
+//
+//      prime check [--threads N] [--summary] [--json] (--file PATH | N...)
+//      prime factor N [--timeout 60s] [--json]
+//      prime prove N [--timeout 60s] [--checkpoint PATH] [--json]
+//      prime verify PATH [--json]
+//      prime pi X [--json]
+//      prime nth N [--json]
+//      prime bench N [--algorithms LIST] [--json]
+//      prime table [--json]
+//
+// Numbers may be written as expressions such as `1_000_003`, `1e18+9` or
+// `2^127-1`.
+//
+// With `--json` every subcommand prints a single JSON object carrying
+// `version` (bumped on incompatible changes) and `command`; failures are
+// reported as `{"version": .., "command": .., "error": ".."}`.
+mod json;
+mod check;
+mod count;
+mod factor;
+mod prove;
+mod verify;
+mod bench;
+mod table;
+
+use prime::Primality;
+
+use json::Json;
+
+use std::env;
+use std::process;
+
+
+const USAGE: &str = "\
+USAGE:
+    prime check [--threads N] [--summary] [--json] (--file PATH | N...)
+    prime factor N [--timeout 60s] [--json]
+    prime prove N [--timeout 60s] [--checkpoint PATH] [--json]
+    prime verify PATH [--json]
+    prime pi X [--json]
+    prime nth N [--json]
+    prime bench N [--algorithms LIST] [--json]
+    prime table [--json]
+
+Numbers may be written as expressions such as 1_000_003, 1e18+9 or 2^127-1.
+";
+
+pub const JSON_VERSION: u64 = 2;
+
+// Command line arguments: positional values plus `--name [value]` options.
+pub struct Args {
+    values: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    // `flags` lists the options that take no value.
+    fn parse(args: &[String], flags: &[&str]) -> Result<Self, String> {
+        let mut values = Vec::new();
+        let mut options = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if flags.contains(&name) {
+                    options.push((name.to_string(), None));
+                } else {
+                    match iter.next() {
+                        Some(value) => options.push((name.to_string(), Some(value.clone()))),
+                        None => return Err(format!("missing value for --{}", name)),
+                    }
+                }
+            } else {
+                values.push(arg.clone());
+            }
+        }
+
+        Ok(Args { values, options })
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(k, _)| k == name)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter()
+            .rev()
+            .find(|(k, _)| k == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+}
+
+// `{"version": .., "command": .., fields...}`
+pub fn envelope(command: &'static str, fields: Vec<(&'static str, Json)>) -> Json {
+    let mut all = vec![
+        ("version", Json::Int(JSON_VERSION)),
+        ("command", Json::string(command)),
+    ];
+    all.extend(fields);
+
+    Json::Object(all)
+}
+
+pub fn primality_name(primality: Primality) -> &'static str {
+    match primality {
+        Primality::ZeroOrOne => "zero-or-one",
+        Primality::Prime => "prime",
+        Primality::Composite => "composite",
+        Primality::ProbablyPrime => "probably-prime",
+        Primality::Unknown => "unknown",
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = args.first().map(|s| s.as_str()).unwrap_or("");
+    let ret = match command {
+        "check" => Args::parse(&args[1..], check::FLAGS).and_then(|args| check::run(&args)),
+        "factor" => Args::parse(&args[1..], factor::FLAGS).and_then(|args| factor::run(&args)),
+        "prove" => Args::parse(&args[1..], prove::FLAGS).and_then(|args| prove::run(&args)),
+        "verify" => Args::parse(&args[1..], verify::FLAGS).and_then(|args| verify::run(&args)),
+        "pi" => Args::parse(&args[1..], count::FLAGS).and_then(|args| count::run_pi(&args)),
+        "nth" => Args::parse(&args[1..], count::FLAGS).and_then(|args| count::run_nth(&args)),
+        "bench" => Args::parse(&args[1..], bench::FLAGS).and_then(|args| bench::run(&args)),
+        "table" => Args::parse(&args[1..], table::FLAGS).and_then(|args| table::run(&args)),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(e) = ret {
+        if args.iter().any(|arg| arg == "--json") {
+            println!("{}", Json::Object(vec![
+                ("version", Json::Int(JSON_VERSION)),
+                ("command", Json::string(command)),
+                ("error", Json::string(e.trim_end())),
+            ]));
+        } else {
+            eprintln!("{}", e.trim_end());
+        }
+        process::exit(2);
+    }
+}
+
+
+#[test]
+fn test_args_parse() {
+    let args: Vec<String> = ["7", "--summary", "--threads", "4", "9"].iter().map(|s| s.to_string()).collect();
+    let args = Args::parse(&args, &["summary"]).unwrap();
+
+    assert_eq!(args.values(), ["7", "9"]);
+    assert!(args.flag("summary"));
+    assert!(!args.flag("json"));
+    assert_eq!(args.option("threads"), Some("4"));
+    assert_eq!(args.option("file"), None);
+
+    let args: Vec<String> = ["--threads"].iter().map(|s| s.to_string()).collect();
+    assert!(Args::parse(&args, &[]).is_err());
+}
@@ -0,0 +1,154 @@
+// prime prove N [--timeout 60s] [--checkpoint PATH] [--json]
+//
+// Certifies N with an n − 1 (Pocklington) proof. Progress is written to the
+// checkpoint file every few seconds and picked up again on the next run, so a
+// proof can be spread over several runs; the file is removed once the proof
+// completes. When the timeout expires first, N is reported as probably prime
+// on the strength of Baillie–PSW.
+use crate::{envelope, primality_name, Args};
+use crate::json::Json;
+use crate::factor::{certificate_to_json as pratt_to_json, parse_duration};
+
+use prime::Primality;
+use prime::{Certificate, Evidence, Proof, ProofState};
+use prime::parse_biguint_expr;
+
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+// NOTE: 两次写入 checkpoint 之间的最长时间。
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let n = match args.values() {
+        [n] => parse_biguint_expr(n).map_err(|e| format!("{}: {}", n, e))?,
+        _ => return Err("expected exactly one number".to_string()),
+    };
+
+    let timeout = args.option("timeout").map(parse_duration).transpose()?;
+    let checkpoint = args.option("checkpoint");
+    let json = args.flag("json");
+
+    let mut state = match checkpoint.map(fs::read_to_string) {
+        Some(Ok(saved)) => {
+            let path = checkpoint.unwrap_or_default();
+            let state: ProofState = saved.parse().map_err(|e| format!("{}: {}", path, e))?;
+            if state.n() != &n {
+                return Err(format!("{}: checkpoint is for {}", path, state.n()));
+            }
+            state
+        },
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => {
+            return Err(format!("{}: {}", checkpoint.unwrap_or_default(), e));
+        },
+        _ => ProofState::new(&n),
+    };
+
+    let start = Instant::now();
+    let proof = loop {
+        let left = timeout.map(|t| t.saturating_sub(start.elapsed())).unwrap_or(CHECKPOINT_INTERVAL);
+        state = match state.resume(left.min(CHECKPOINT_INTERVAL)) {
+            Ok(proof) => {
+                if let Some(path) = checkpoint {
+                    let _ = fs::remove_file(path);
+                }
+                break proof;
+            },
+            Err(state) => state,
+        };
+
+        if let Some(path) = checkpoint {
+            fs::write(path, state.to_string()).map_err(|e| format!("{}: {}", path, e))?;
+        }
+
+        if !json {
+            eprintln!("prove: {} prime factor(s) of n − 1 found, {:?}", state.primes().len(), start.elapsed());
+        }
+
+        if timeout.map(|t| start.elapsed() >= t).unwrap_or(false) {
+            break Proof { primality: Primality::from_probable(true), evidence: Evidence::BailliePsw { tried: vec!["pocklington"] } };
+        }
+    };
+
+    let elapsed = start.elapsed();
+
+    if json {
+        let certificate = match &proof.evidence {
+            Evidence::Certificate(cert) => certificate_to_json(cert),
+            _ => Json::Null,
+        };
+        let tried = match &proof.evidence {
+            Evidence::BailliePsw { tried } => Json::Array(tried.iter().map(Json::string).collect()),
+            _ => Json::Null,
+        };
+
+        println!("{}", envelope("prove", vec![
+            ("n", Json::string(&n)),
+            ("result", Json::string(primality_name(proof.primality))),
+            ("evidence", Json::string(evidence_name(&proof.evidence))),
+            ("certificate", certificate),
+            ("tried", tried),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ]));
+    } else {
+        println!("{}\t{}\t{}", n, primality_name(proof.primality), evidence_name(&proof.evidence));
+    }
+
+    Ok(())
+}
+
+fn evidence_name(evidence: &Evidence) -> &'static str {
+    match evidence {
+        Evidence::None => "none",
+        Evidence::BailliePsw { .. } => "baillie-psw",
+        Evidence::Certificate(cert) => cert.kind(),
+    }
+}
+
+pub fn certificate_to_json(cert: &Certificate) -> Json {
+    match cert {
+        Certificate::Pratt(cert) => pratt_to_json(cert),
+        Certificate::Pocklington { n, factors } => Json::Object(vec![
+            ("type", Json::string("pocklington")),
+            ("n", Json::string(n)),
+            ("factors", Json::Array(factors.iter()
+                .map(|factor| Json::Object(vec![
+                    ("witness", Json::string(&factor.witness)),
+                    ("certificate", certificate_to_json(&factor.certificate)),
+                ]))
+                .collect())),
+        ]),
+        Certificate::Ecpp { step, next } => Json::Object(vec![
+            ("type", Json::string("ecpp")),
+            ("n", Json::string(&step.n)),
+            ("a", Json::string(&step.a)),
+            ("b", Json::string(&step.b)),
+            ("m", Json::string(&step.m)),
+            ("q", Json::string(&step.q)),
+            ("x", Json::string(&step.x)),
+            ("y", Json::string(&step.y)),
+            ("next", certificate_to_json(next)),
+        ]),
+        Certificate::Bls { n, d, minus, plus } => Json::Object(vec![
+            ("type", Json::string("bls")),
+            ("n", Json::string(n)),
+            ("d", Json::string(d)),
+            ("minus", Json::Array(minus.iter()
+                .map(|factor| Json::Object(vec![
+                    ("witness", Json::string(&factor.witness)),
+                    ("certificate", certificate_to_json(&factor.certificate)),
+                ]))
+                .collect())),
+            ("plus", Json::Array(plus.iter()
+                .map(|factor| Json::Object(vec![
+                    ("p", Json::Int(factor.p)),
+                    ("certificate", certificate_to_json(&factor.certificate)),
+                ]))
+                .collect())),
+        ]),
+    }
+}
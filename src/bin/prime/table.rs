@@ -0,0 +1,44 @@
+// prime table [--json]
+//
+// Summary of the embedded table of the primes below 2 ^ 16: how many there
+// are per decade and per bit size, and the smallest and largest gaps.
+use crate::{envelope, Args};
+use crate::json::Json;
+
+use prime::table_stats;
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+pub fn run(args: &Args) -> Result<(), String> {
+    if !args.values().is_empty() {
+        return Err("expected no arguments".to_string());
+    }
+
+    let stats = table_stats();
+
+    if args.flag("json") {
+        let counts = |v: &[u32]| Json::Array(v.iter().map(|c| Json::Int(*c as u64)).collect());
+
+        println!("{}", envelope("table", vec![
+            ("count", Json::Int(stats.count as u64)),
+            ("largest", Json::Int(stats.largest as u64)),
+            ("per_decade", counts(&stats.per_decade)),
+            ("per_bit_size", counts(&stats.per_bit_size)),
+            ("min_gap", Json::Int(stats.min_gap as u64)),
+            ("max_gap", Json::Int(stats.max_gap as u64)),
+            ("max_gap_start", Json::Int(stats.max_gap_start as u64)),
+        ]));
+    } else {
+        println!("{} primes, the largest {}", stats.count, stats.largest);
+        println!("gaps from {} to {} (after {})", stats.min_gap, stats.max_gap, stats.max_gap_start);
+        for (k, count) in stats.per_decade.iter().enumerate() {
+            println!("  10^{}  {:>5}", k, count);
+        }
+        for (bits, count) in stats.per_bit_size.iter().enumerate().filter(|(_, c)| **c > 0) {
+            println!("  {:>2} bits  {:>5}", bits, count);
+        }
+    }
+
+    Ok(())
+}
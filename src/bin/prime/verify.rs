@@ -0,0 +1,45 @@
+// prime verify PATH [--json]
+//
+// Checks a Primo (format 4) certificate file. Exits with an error when the
+// file cannot be read or the certificate does not hold.
+use crate::{envelope, Args};
+use crate::json::Json;
+
+use prime::parse_primo_certificate;
+
+use std::fs;
+use std::time::Instant;
+
+
+pub const FLAGS: &[&str] = &["json"];
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let path = match args.values() {
+        [path] => path,
+        _ => return Err("expected exactly one certificate file".to_string()),
+    };
+
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let cert = parse_primo_certificate(&content).map_err(|e| format!("{}: {}", path, e))?;
+
+    let start = Instant::now();
+    let valid = cert.verify();
+    let elapsed = start.elapsed();
+
+    if args.flag("json") {
+        println!("{}", envelope("verify", vec![
+            ("n", Json::string(cert.n())),
+            ("valid", Json::Bool(valid)),
+            ("format", Json::string("primo")),
+            ("elapsed", Json::Float(elapsed.as_secs_f64())),
+        ]));
+    } else if valid {
+        println!("{}\tprime", cert.n());
+    }
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{}: certificate does not verify", path))
+    }
+}
@@ -0,0 +1,449 @@
+// Combined n − 1 / n + 1 primality proof
+// https://en.wikipedia.org/wiki/Primality_certificate
+//
+// J. Brillhart, D. H. Lehmer and J. L. Selfridge, "New primality criteria and
+// factorizations of 2 ^ m ± 1", Math. Comp. 29 (1975).
+//
+// Write n − 1 = F1·R1 and n + 1 = F2·R2 with the prime factors of F1 and F2
+// known.
+//
+//   * Pocklington: if for every prime q | F1 some a has a ^ (n − 1) ≡ 1 and
+//     gcd(a ^ ((n − 1) / q) − 1, n) = 1, every prime p | n is 1 (mod F1).
+//   * Morrison: if (D | n) = −1 and for every prime q | F2 a Lucas sequence
+//     U(P, Q) with P ^ 2 − 4Q = D and gcd(n, Q) = 1 has n | U_(n+1) and
+//     gcd(U_((n+1)/q), n) = 1, every prime p | n is (D | p) (mod F2).
+//
+// So every prime factor of n is 1 or r modulo F = lcm(F1, F2), where r is 1
+// (mod F1) and −1 (mod F2). When F > √n, a composite n would need a prime
+// factor below √n < F, which can only be r itself: n is prime unless r
+// divides it. F1·F2 / 2 > √n is met, for instance, once both n − 1 and n + 1
+// are factored beyond n ^ (1/3), and often with one side alone.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::factor::factorize_u64_inline;
+use crate::jacobi_symbol_bigint;
+use crate::baillie_psw_primality_test_biguint;
+use crate::pratt_certificate_u64;
+use crate::factor::pollard_brent_rho_biguint;
+use crate::prove::pocklington_part;
+use crate::{Certificate, Evidence, PocklingtonFactor, Proof};
+
+use num_integer::Integer;
+use num_bigint::{BigInt, BigUint, Sign};
+
+use std::time::{Duration, Instant};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct LucasFactor {
+    // P of the Lucas sequence U(P, Q), with Q = (P ^ 2 − D) / 4.
+    pub p: u64,
+    // Certificate for the prime factor q of n + 1 itself.
+    pub certificate: Certificate,
+}
+
+// Like `prove_prime`, but factoring n − 1 and n + 1 together.
+pub fn prove_prime_bls(n: &(impl AsBigUint + ?Sized), budget: Duration) -> Proof {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+    let deadline = Instant::now() + budget;
+
+    let primality = baillie_psw_primality_test_biguint(n);
+    if primality == Primality::ZeroOrOne || primality == Primality::Composite {
+        return Proof { primality, evidence: Evidence::None };
+    }
+
+    match certify(n, deadline) {
+        Some(cert) => Proof { primality: Primality::Prime, evidence: Evidence::Certificate(cert) },
+        None => Proof { primality: Primality::PROBABLE, evidence: Evidence::BailliePsw { tried: vec!["bls"] } },
+    }
+}
+
+pub(crate) fn verify(n: &BigUint, d: i64, minus: &[PocklingtonFactor], plus: &[LucasFactor]) -> bool {
+    let f1 = match pocklington_part(n, minus) {
+        Some(f1) => f1,
+        None => return false,
+    };
+    let f2 = match lucas_part(n, d, plus) {
+        Some(f2) => f2,
+        None => return false,
+    };
+
+    let f = f1.lcm(&f2);
+    if &f * &f <= *n {
+        return false;
+    }
+
+    // NOTE: r 是唯一可能小于 √n 的素因子候选。
+    let r = crt_one_minus_one(&f1, &f2);
+    r <= BigUint::from(1u8) || &r >= n || n % &r != BigUint::from(0u8)
+}
+
+// The part F2 of n + 1 that `plus` accounts for, if every factor checks out.
+fn lucas_part(n: &BigUint, d: i64, plus: &[LucasFactor]) -> Option<BigUint> {
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+
+    if d.rem_euclid(4) != 1 || jacobi_symbol_bigint(&BigInt::from(d), n) != -1 {
+        return None;
+    }
+
+    let n_plus_one: BigUint = n + 1u8;
+    let mut f = one.clone();
+    let mut last = one.clone();
+    for factor in plus.iter() {
+        let q = factor.certificate.n();
+
+        // NOTE: 与 Pocklington 部分相同，q 必须严格递增。
+        if q <= last || &n_plus_one % &q != zero || factor.p.is_multiple_of(2) {
+            return None;
+        }
+
+        let (p, q_lucas) = lucas_parameters(n, d, factor.p)?;
+        let d_mod = residue(d as i128, n);
+        if lucas_u(&n_plus_one, &p, &q_lucas, &d_mod, n) != zero {
+            return None;
+        }
+        if lucas_u(&(&n_plus_one / &q), &p, &q_lucas, &d_mod, n).gcd(n) != one || !factor.certificate.verify() {
+            return None;
+        }
+
+        let mut rest = n_plus_one.clone();
+        while &rest % &q == zero {
+            rest /= &q;
+            f *= &q;
+        }
+
+        last = q;
+    }
+
+    Some(f)
+}
+
+// v as a residue mod n.
+fn residue(v: i128, n: &BigUint) -> BigUint {
+    let r = BigUint::from(v.unsigned_abs()) % n;
+    if v < 0 && r != BigUint::from(0u8) { n - r } else { r }
+}
+
+// P and Q = (P ^ 2 − D) / 4 as residues mod n, for an odd P, or `None` when
+// Q shares a factor with n.
+fn lucas_parameters(n: &BigUint, d: i64, p: u64) -> Option<(BigUint, BigUint)> {
+    let q = ((p as i128) * (p as i128) - d as i128) / 4;
+    let q = residue(q, n);
+    if q.gcd(n) != BigUint::from(1u8) {
+        return None;
+    }
+
+    Some((BigUint::from(p) % n, q))
+}
+
+// U_k of the Lucas sequence U(P, Q) mod the odd n, with D = P ^ 2 − 4Q.
+fn lucas_u(k: &BigUint, p: &BigUint, q: &BigUint, d: &BigUint, n: &BigUint) -> BigUint {
+    if k.bits() == 0 {
+        return BigUint::from(0u8);
+    }
+
+    // x / 2 (mod n), n odd
+    let half = |x: BigUint| -> BigUint {
+        if !x.bit(0) { x >> 1usize } else { (x + n) >> 1usize }
+    };
+
+    // U_1 = 1, V_1 = P
+    let mut u = BigUint::from(1u8);
+    let mut v = p.clone();
+    let mut qk = q.clone();
+    for i in (0..k.bits() - 1).rev() {
+        // U_2m = U_m·V_m, V_2m = V_m ^ 2 − 2·Q^m
+        u = &u * &v % n;
+        v = (&v * &v + n * 2u8 - &qk * 2u8 % n) % n;
+        qk = &qk * &qk % n;
+
+        if k.bit(i) {
+            // U_m+1 = (P·U_m + V_m) / 2, V_m+1 = (D·U_m + P·V_m) / 2
+            let u_next = half((p * &u + &v) % n);
+            let v_next = half((d * &u + p * &v) % n);
+            u = u_next;
+            v = v_next;
+            qk = &qk * q % n;
+        }
+    }
+
+    u
+}
+
+// r in [0, lcm(f1, f2)) with r ≡ 1 (mod f1) and r ≡ −1 (mod f2); f1 and f2 are
+// divisors of n − 1 and n + 1, so gcd(f1, f2) divides 2 and r exists.
+fn crt_one_minus_one(f1: &BigUint, f2: &BigUint) -> BigUint {
+    let g = f1.gcd(f2);
+    let f = f1.lcm(f2);
+
+    // r = 1 + f1·t with f1·t ≡ −2 (mod f2), i.e. (f1 / g)·t ≡ −2 / g (mod f2 / g)
+    let m = BigInt::from_biguint(Sign::Plus, f2 / &g);
+    let a = BigInt::from_biguint(Sign::Plus, f1 / &g);
+    let e = a.extended_gcd(&m);
+    let minus_two = BigInt::from(-2) / BigInt::from_biguint(Sign::Plus, g);
+    let t = (e.x * minus_two).mod_floor(&m);
+
+    (BigUint::from(1u8) + f1 * t.to_biguint().unwrap_or_default()) % f
+}
+
+// Prime factors found so far of one side, n − 1 or n + 1.
+struct Side {
+    m: BigUint,
+    // The factored part, every prime to its full power in m.
+    f: BigUint,
+    primes: Vec<BigUint>,
+    pending: Vec<BigUint>,
+}
+
+impl Side {
+    fn new(m: BigUint) -> Self {
+        let mut side = Side { m: m.clone(), f: BigUint::from(1u8), primes: Vec::new(), pending: Vec::new() };
+
+        let mut rest = m;
+        for p in crate::global().small_primes().iter() {
+            if (&rest % *p).bits() == 0 {
+                side.take(BigUint::from(*p));
+                while (&rest % *p).bits() == 0 {
+                    rest /= *p;
+                }
+            }
+        }
+        if rest > BigUint::from(1u8) {
+            side.pending.push(rest);
+        }
+
+        side
+    }
+
+    fn take(&mut self, q: BigUint) {
+        if let Err(i) = self.primes.binary_search(&q) {
+            let mut rest = self.m.clone();
+            while (&rest % &q).bits() == 0 {
+                rest /= &q;
+                self.f *= &q;
+            }
+            self.primes.insert(i, q);
+        }
+    }
+
+    // The smallest cofactor still to split.
+    fn smallest(&self) -> Option<&BigUint> {
+        self.pending.iter().min()
+    }
+
+    // Split or classify the smallest cofactor; `false` when there is none.
+    fn step(&mut self, rho_seed: &mut u64, deadline: Instant) -> bool {
+        self.pending.sort_unstable_by(|a, b| b.cmp(a));
+        let m = match self.pending.pop() {
+            Some(m) => m,
+            None => return false,
+        };
+
+        if m.bits() <= 64 {
+            for (q, _) in factorize_u64_inline(m.to_u64_digits()[0]).iter() {
+                self.take(BigUint::from(*q));
+            }
+        } else if baillie_psw_primality_test_biguint(&m) == Primality::PROBABLE {
+            self.take(m);
+        } else {
+            match pollard_brent_rho_biguint(&m, *rho_seed, deadline) {
+                Some(d) => {
+                    self.pending.push(&m / &d);
+                    self.pending.push(d);
+                },
+                None => {
+                    if Instant::now() < deadline {
+                        *rho_seed += 1;
+                    }
+                    self.pending.push(m);
+                },
+            }
+        }
+
+        true
+    }
+}
+
+fn certify(n: &BigUint, deadline: Instant) -> Option<Certificate> {
+    if n.bits() <= 64 {
+        return pratt_certificate_u64(n.to_u64_digits()[0]).map(Certificate::Pratt);
+    }
+
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+
+    let mut minus = Side::new(n - 1u8);
+    let mut plus = Side::new(n + 1u8);
+    let mut rho_seed = 1u64;
+
+    loop {
+        let f = minus.f.lcm(&plus.f);
+        if &f * &f > *n {
+            break;
+        }
+
+        if Instant::now() >= deadline {
+            return None;
+        }
+
+        // NOTE: 两边一起，优先分解较小的余因子。
+        let side = match (minus.smallest(), plus.smallest()) {
+            (Some(a), Some(b)) if b < a => &mut plus,
+            (Some(_), _) => &mut minus,
+            (None, _) => &mut plus,
+        };
+        if !side.step(&mut rho_seed, deadline) {
+            return None;
+        }
+    }
+
+    // n − 1 side: a Pocklington witness for every q.
+    let n_minus_one: BigUint = n - 1u8;
+    let mut pocklington = Vec::with_capacity(minus.primes.len());
+    for q in minus.primes.iter() {
+        let exp = &n_minus_one / q;
+
+        let mut a = BigUint::from(2u8);
+        let witness = loop {
+            if Instant::now() >= deadline || a.modpow(&n_minus_one, n) != one {
+                return None;
+            }
+
+            let y = a.modpow(&exp, n);
+            if y != zero && (y - 1u8).gcd(n) == one {
+                break a;
+            }
+
+            a += 1u8;
+        };
+
+        pocklington.push(PocklingtonFactor { witness, certificate: certify(q, deadline)? });
+    }
+
+    // n + 1 side: Selfridge's D, then an odd P for every q.
+    let mut d: i64 = 5;
+    while jacobi_symbol_bigint(&BigInt::from(d), n) != -1 {
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+    let d_mod = residue(d as i128, n);
+
+    let n_plus_one: BigUint = n + 1u8;
+    let mut lucas = Vec::with_capacity(plus.primes.len());
+    for q in plus.primes.iter() {
+        let mut p = 1u64;
+        let found = loop {
+            if Instant::now() >= deadline {
+                return None;
+            }
+
+            if let Some((p_mod, q_mod)) = lucas_parameters(n, d, p) {
+                // NOTE: n 通过了 BPSW，却不满足 n | U_(n+1)。
+                if lucas_u(&n_plus_one, &p_mod, &q_mod, &d_mod, n) != zero {
+                    return None;
+                }
+                if lucas_u(&(&n_plus_one / q), &p_mod, &q_mod, &d_mod, n).gcd(n) == one {
+                    break p;
+                }
+            }
+
+            p += 2;
+        };
+
+        lucas.push(LucasFactor { p: found, certificate: certify(q, deadline)? });
+    }
+
+    Some(Certificate::Bls { n: n.clone(), d, minus: pocklington, plus: lucas })
+}
+
+
+#[test]
+fn test_lucas_u() {
+    // U(1, −1) is the Fibonacci sequence.
+    let n = BigUint::from(1_000_000_007u32);
+    let (p, q) = (BigUint::from(1u8), &n - 1u8);
+    let d = BigUint::from(5u8);
+    let fib = [0u32, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+    for (k, f) in fib.iter().enumerate() {
+        assert_eq!(lucas_u(&BigUint::from(k), &p, &q, &d, &n), BigUint::from(*f), "k={}", k);
+    }
+    assert_eq!(lucas_u(&BigUint::from(90u8), &p, &q, &d, &n), BigUint::from(2880067194370816120u64 % 1_000_000_007));
+}
+
+#[test]
+fn test_crt_one_minus_one() {
+    for (f1, f2) in [(12u32, 10u32), (4, 6), (1, 8), (6, 1), (30, 14), (2, 2)] {
+        let r = crt_one_minus_one(&BigUint::from(f1), &BigUint::from(f2));
+        let r: u32 = r.to_u32_digits().first().copied().unwrap_or(0);
+        assert_eq!(r % f1, 1 % f1, "{} {}", f1, f2);
+        assert_eq!((r + 1) % f2, 0, "{} {}", f1, f2);
+    }
+}
+
+#[test]
+fn test_prove_prime_bls() {
+    let budget = Duration::from_secs(60);
+
+    assert_eq!(prove_prime_bls(&1u8, budget).primality, Primality::ZeroOrOne);
+    assert_eq!(prove_prime_bls(&91u8, budget).primality, Primality::Composite);
+    assert!(matches!(prove_prime_bls(&97u8, budget).evidence, Evidence::Certificate(Certificate::Pratt(_))));
+
+    let check = |n: &BigUint| -> (Vec<PocklingtonFactor>, Vec<LucasFactor>) {
+        let proof = prove_prime_bls(n, budget);
+        assert_eq!(proof.primality, Primality::Prime, "N={}", n);
+        match proof.evidence {
+            Evidence::Certificate(cert @ Certificate::Bls { .. }) => {
+                assert_eq!(&cert.n(), n);
+                assert!(cert.verify(), "N={}", n);
+                match cert {
+                    Certificate::Bls { minus, plus, .. } => (minus, plus),
+                    _ => unreachable!(),
+                }
+            },
+            evidence => panic!("N={} {:?}", n, evidence),
+        }
+    };
+
+    // k·2 ^ 200 − 1, where n + 1 is all but fully factored and n − 1 is not
+    let n = (3u32..)
+        .step_by(2)
+        .map(|k| (BigUint::from(k) << 200usize) - 1u8)
+        .find(|n| baillie_psw_primality_test_biguint(n) == Primality::PROBABLE)
+        .unwrap();
+    let (_, plus) = check(&n);
+    assert!(plus.iter().any(|factor| factor.certificate.n() == BigUint::from(2u8)));
+
+    // 2 ^ 127 − 1 and 2 ^ 521 − 1
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    check(&m127);
+    check(&((BigUint::from(1u8) << 521usize) - 1u8));
+
+    let m61 = (1u64 << 61) - 1;
+    assert_eq!(prove_prime_bls(&(&m127 * m61), budget).primality, Primality::Composite);
+}
+
+#[test]
+fn test_bls_certificate_verify() {
+    let n = (3u32..)
+        .step_by(2)
+        .map(|k| (BigUint::from(k) << 200usize) - 1u8)
+        .find(|n| baillie_psw_primality_test_biguint(n) == Primality::PROBABLE)
+        .unwrap();
+    let (d, minus, plus) = match prove_prime_bls(&n, Duration::from_secs(60)).evidence {
+        Evidence::Certificate(Certificate::Bls { d, minus, plus, .. }) => (d, minus, plus),
+        evidence => panic!("{:?}", evidence),
+    };
+    assert!(verify(&n, d, &minus, &plus));
+
+    // Without the n + 1 side F no longer exceeds √n.
+    assert!(!verify(&n, d, &minus, &[]));
+    // A discriminant that is a square mod n
+    assert!(!verify(&n, 1, &minus, &plus));
+    // An even P
+    let mut even = plus.clone();
+    even[0].p = 2;
+    assert!(!verify(&n, d, &minus, &even));
+    // The same certificate for another number
+    assert!(!verify(&(&n + 2u8), d, &minus, &plus));
+}
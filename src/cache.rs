@@ -0,0 +1,169 @@
+// Bounded LRU cache of test results
+//
+// Services that validate certificates see the same moduli over and over; with
+// `PrimalityTester::with_cache` they are tested once and answered from memory
+// afterwards. Entries are keyed by the test and the full n, hashed, so that a
+// collision can never return the answer for another number. A cached
+// `ProbablyPrime` answers requests for at most as many rounds as it was
+// computed with; `Composite` is a proof and answers any request.
+//
+// Clones of a tester share its cache.
+use crate::Primality;
+use crate::tester::Test;
+
+use num_bigint::BigUint;
+
+use core::hash::{Hash, Hasher};
+
+use std::sync::Mutex;
+use std::collections::{BTreeMap, HashMap};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    // Entries currently held.
+    pub len: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct ResultCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    // (result, rounds, last use)
+    entries: HashMap<(Test, BigUint), (Primality, usize, u64)>,
+    // last use -> key, oldest first
+    order: BTreeMap<u64, (Test, BigUint)>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+// NOTE: 两个测试器只有共享同一个缓存时才相等。
+impl PartialEq for ResultCache {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl Eq for ResultCache {}
+
+impl Hash for ResultCache {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.capacity.hash(state)
+    }
+}
+
+impl ResultCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        ResultCache { capacity, inner: Mutex::new(Inner::default()) }
+    }
+
+    // The cached answer for `k` rounds of `test` on `n`, or else the answer of
+    // `f`, remembered for next time.
+    pub(crate) fn get_or_insert_with<F>(&self, test: Test, n: &BigUint, k: usize, f: F) -> Primality
+    where
+        F: FnOnce() -> Primality,
+    {
+        let key = (test, n.clone());
+        if let Some(primality) = self.get(&key, k) {
+            return primality;
+        }
+
+        // NOTE: 计算期间不持有锁，两个线程同时测试同一个 n 时各算一次。
+        let primality = f();
+        self.insert(key, primality, k);
+        primality
+    }
+
+    fn get(&self, key: &(Test, BigUint), k: usize) -> Option<Primality> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *inner;
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        match inner.entries.get_mut(key) {
+            Some((primality, rounds, last)) if *primality == Primality::Composite || *rounds >= k => {
+                let key = inner.order.remove(last).unwrap_or_else(|| key.clone());
+                inner.order.insert(tick, key);
+                *last = tick;
+                inner.hits += 1;
+                Some(*primality)
+            },
+            _ => {
+                inner.misses += 1;
+                None
+            },
+        }
+    }
+
+    fn insert(&self, key: (Test, BigUint), primality: Primality, k: usize) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let inner = &mut *inner;
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        if let Some((_, _, last)) = inner.entries.insert(key.clone(), (primality, k, tick)) {
+            inner.order.remove(&last);
+        }
+        inner.order.insert(tick, key);
+
+        while inner.entries.len() > self.capacity {
+            match inner.order.pop_first() {
+                Some((_, oldest)) => inner.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        CacheStats { hits: inner.hits, misses: inner.misses, len: inner.entries.len(), capacity: self.capacity }
+    }
+}
+
+
+#[test]
+fn test_result_cache() {
+    let cache = ResultCache::new(2);
+    let (a, b, c) = (BigUint::from(101u8), BigUint::from(103u8), BigUint::from(107u8));
+
+    let mut calls = 0;
+    let mut test = |n: &BigUint, k: usize| cache.get_or_insert_with(Test::MillerRabin, n, k, || { calls += 1; Primality::ProbablyPrime });
+    test(&a, 10);
+    test(&a, 10);
+    test(&a, 5);
+    // More rounds than cached are computed again.
+    test(&a, 20);
+    test(&b, 10);
+    // a was used last, so c evicts b.
+    test(&a, 10);
+    test(&c, 10);
+    test(&a, 10);
+    test(&b, 10);
+    assert_eq!(calls, 5);
+
+    let stats = cache.stats();
+    assert_eq!(stats, CacheStats { hits: 4, misses: 5, len: 2, capacity: 2 });
+
+    // Composite answers any number of rounds and any other test stays apart.
+    let n = BigUint::from(1000003u64 * 1000033);
+    assert_eq!(cache.get_or_insert_with(Test::SolovayStrassen, &n, 1, || Primality::Composite), Primality::Composite);
+    assert_eq!(cache.get_or_insert_with(Test::SolovayStrassen, &n, 64, || unreachable!()), Primality::Composite);
+    assert_eq!(cache.get_or_insert_with(Test::MillerRabin, &n, 1, || Primality::Unknown), Primality::Unknown);
+
+    let disabled = ResultCache::new(0);
+    disabled.get_or_insert_with(Test::MillerRabin, &a, 1, || Primality::ProbablyPrime);
+    assert_eq!(disabled.stats().len, 0);
+}
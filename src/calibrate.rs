@@ -0,0 +1,133 @@
+// One-time calibration of the crossover thresholds
+//
+// The defaults are tuned for a typical x86-64 desktop. `calibrate` times a
+// 1024-bit modular exponentiation, a single-word division of a 1024-bit number
+// and the segmented sieve on the host, and derives from them
+//
+//   * the trial-division bound of `gen_prime`: keep dividing by p while one
+//     division costs less than the 1 / p chance of saving a Miller–Rabin round;
+//   * the prefilter bound of the BigUint tests: the largest power of two whose
+//     primorial still reduces modulo a 1024-bit n faster than one modpow;
+//   * the segment size of the sieve: the fastest of 2 ^ 15 .. 2 ^ 20.
+//
+// The result is kept for the rest of the process. Without a call every
+// threshold keeps its static default.
+//
+// NOTE: 默认的 `PrimalityTester` 在第一次使用时构造，之后再校准不会改变它的预筛选上界，
+//       所以应当在程序开始时调用 `calibrate`。
+use crate::sieve::for_each_segment_with;
+use crate::tester::DEFAULT_PREFILTER_BOUND;
+
+use num_bigint::BigUint;
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+
+static CALIBRATED: OnceLock<Thresholds> = OnceLock::new();
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Thresholds {
+    // Primes below this bound are tried before a Miller–Rabin round in
+    // `gen_prime` and its variants.
+    pub trial_division_bound: u16,
+    // The bound of the default `PrimalityTester`.
+    pub prefilter_bound: u32,
+    // Numbers per segment of the sieve.
+    pub segment_size: u64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            trial_division_bound: 1 << 12,
+            prefilter_bound: DEFAULT_PREFILTER_BOUND,
+            segment_size: 1 << 18,
+        }
+    }
+}
+
+// Measure the host once and use the result from then on. Later calls return
+// the same thresholds without measuring again.
+pub fn calibrate() -> &'static Thresholds {
+    CALIBRATED.get_or_init(measure)
+}
+
+// The thresholds in effect: calibrated, or the defaults.
+pub fn thresholds() -> Thresholds {
+    CALIBRATED.get().copied().unwrap_or_default()
+}
+
+// The fastest of `repeat` runs of `f`.
+fn best_of<T, F: FnMut() -> T>(repeat: usize, mut f: F) -> Duration {
+    (0..repeat)
+        .map(|_| {
+            let start = Instant::now();
+            core::hint::black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn measure() -> Thresholds {
+    // A fixed odd 1024-bit modulus; only the timings matter.
+    let n = (BigUint::from(1u8) << 1023usize) + BigUint::from(0x9e37_79b9_7f4a_7c15u64);
+    let a = BigUint::from(3u8);
+    let exponent: BigUint = &n - 1u8;
+
+    let modpow = best_of(5, || a.modpow(&exponent, &n)).as_nanos().max(1);
+
+    // 1024 single-word divisions, as in `rem_u32` of the prefilter
+    let digits = n.to_u32_digits();
+    let division = best_of(5, || {
+        (1..=1024u64).fold(0u64, |acc, m| acc ^ digits.iter().rev().fold(0u64, |r, d| ((r << 32) | *d as u64) % (m | 1)))
+    }).as_nanos().max(1) / 1024;
+
+    // NOTE: 只有当 p 是候选数的最小因子（概率约 1/p）时，试除 p 才能省下一次 Miller–Rabin。
+    let trial_division_bound = (modpow / division.max(1))
+        .clamp(1 << 8, u16::MAX as u128) as u16;
+
+    // n · 2 ^ (2 ^ 16) mod n, to estimate the reduction cost per bit.
+    let wide: BigUint = &n << (1usize << 16);
+    let per_bit = best_of(5, || &wide % &n).as_nanos().max(1) as f64 / (1u64 << 16) as f64;
+
+    // NOTE: 小于 B 的素数之积约有 B · log2(e) bits。
+    let prefilter_bound = (12..=24)
+        .map(|k| 1u32 << k)
+        .take_while(|b| per_bit * *b as f64 * core::f64::consts::LOG2_E < modpow as f64)
+        .last()
+        .unwrap_or(1 << 12);
+
+    let lo = 1u64 << 32;
+    let segment_size = (15..=20)
+        .map(|k| 1u64 << k)
+        .min_by_key(|size| {
+            best_of(3, || {
+                let mut count = 0usize;
+                for_each_segment_with(lo, lo + (1 << 22), *size, |_, composite| {
+                    count += composite.iter().filter(|c| !**c).count();
+                    true
+                });
+                count
+            })
+        })
+        .unwrap_or(1 << 18);
+
+    Thresholds { trial_division_bound, prefilter_bound, segment_size }
+}
+
+
+#[test]
+fn test_thresholds() {
+    let defaults = Thresholds::default();
+    assert_eq!(defaults.prefilter_bound, DEFAULT_PREFILTER_BOUND);
+
+    // NOTE: 不调用 `calibrate`，以免改变同一进程里其它测试看到的阈值。
+    let measured = measure();
+    assert!(measured.trial_division_bound >= 1 << 8);
+    assert!(((1 << 12)..=(1 << 24)).contains(&measured.prefilter_bound));
+    assert!(measured.prefilter_bound.is_power_of_two());
+    assert!(((1 << 15)..=(1 << 20)).contains(&measured.segment_size));
+    assert!(measured.segment_size.is_power_of_two());
+}
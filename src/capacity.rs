@@ -0,0 +1,77 @@
+// Prime capacities for hash tables
+//
+// Open-addressing tables with double hashing, and tables that reduce hashes
+// modulo their size, spread keys best over a prime number of slots. Like the
+// bucket counts of libstdc++'s unordered containers, the capacities here grow
+// by a factor of about two: the k-th one is the least prime >= 2 ^ k, so a
+// table that doubles moves one step along the sequence and a requested size is
+// rounded up by a table lookup instead of a next-prime search.
+
+
+// The least prime >= 2 ^ k, for k = 1 ..= 63.
+static PRIME_CAPACITIES: [u64; 63] = [
+    2, 5, 11,
+    17, 37, 67,
+    131, 257, 521,
+    1031, 2053, 4099,
+    8209, 16411, 32771,
+    65537, 131101, 262147,
+    524309, 1048583, 2097169,
+    4194319, 8388617, 16777259,
+    33554467, 67108879, 134217757,
+    268435459, 536870923, 1073741827,
+    2147483659, 4294967311, 8589934609,
+    17179869209, 34359738421, 68719476767,
+    137438953481, 274877906951, 549755813911,
+    1099511627791, 2199023255579, 4398046511119,
+    8796093022237, 17592186044423, 35184372088891,
+    70368744177679, 140737488355333, 281474976710677,
+    562949953421381, 1125899906842679, 2251799813685269,
+    4503599627370517, 9007199254740997, 18014398509482143,
+    36028797018963971, 72057594037928017, 144115188075855881,
+    288230376151711813, 576460752303423619, 1152921504606847009,
+    2305843009213693967, 4611686018427388039, 9223372036854775837,
+];
+
+// The growth sequence itself, ascending.
+pub fn prime_capacities() -> &'static [u64] {
+    &PRIME_CAPACITIES
+}
+
+// The least capacity of the sequence that holds n, at most about 2n; `None`
+// above 2 ^ 63 + 29.
+pub fn next_prime_capacity(n: u64) -> Option<u64> {
+    // NOTE: 2^(k−1) < n <= 2^k 时，答案是 2^(k−1) 或 2^k 之后的第一个素数。
+    let k = (u64::BITS - n.saturating_sub(1).leading_zeros()) as usize;
+    PRIME_CAPACITIES.iter()
+        .skip(k.saturating_sub(2))
+        .take(2)
+        .find(|p| **p >= n)
+        .copied()
+}
+
+
+#[test]
+fn test_next_prime_capacity() {
+    use crate::next_primes;
+
+    let powers: Vec<u64> = (1..64).map(|k| 1u64 << k).collect();
+    assert_eq!(next_primes(&powers), PRIME_CAPACITIES);
+
+    assert_eq!(next_prime_capacity(0), Some(2));
+    assert_eq!(next_prime_capacity(2), Some(2));
+    assert_eq!(next_prime_capacity(3), Some(5));
+    assert_eq!(next_prime_capacity(1000), Some(1031));
+    assert_eq!(next_prime_capacity(1031), Some(1031));
+    assert_eq!(next_prime_capacity(1032), Some(2053));
+    assert_eq!(next_prime_capacity(65537), Some(65537));
+    assert_eq!(next_prime_capacity(9223372036854775837), Some(9223372036854775837));
+    assert_eq!(next_prime_capacity(9223372036854775838), None);
+    assert_eq!(next_prime_capacity(u64::MAX), None);
+
+    // The same as searching the sequence from the start
+    let search = |n: u64| PRIME_CAPACITIES.iter().copied().find(|p| *p >= n);
+    for n in (0..5000).chain(powers.iter().flat_map(|p| [p - 1, *p, p + 1, p + 200])) {
+        assert_eq!(next_prime_capacity(n), search(n), "N={}", n);
+    }
+}
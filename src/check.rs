@@ -0,0 +1,174 @@
+// One entry point, several output types
+//
+//      let ok: bool = check(&n);
+//      let primality = check::<Primality>(&n);
+//      let report = check::<Report>(&n);
+//
+// All of them run the same test as `is_prime_biguint_auto`: deterministic
+// Miller–Rabin below 2 ^ 64, BPSW plus a few random strong tests above. The
+// output type only decides how much of the outcome is handed back.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::Assessment;
+use crate::baillie_psw::{is_prime_biguint_auto_provenance, AUTO_EXTRA_ROUNDS};
+
+use num_bigint::BigUint;
+
+use core::fmt;
+use std::time::{Duration, Instant};
+
+
+// The step that decided an answer, for logs and audits that must say why a
+// number was declared prime without re-deriving the dispatch.
+//
+// NOTE: `Method` 已经是分解算法的名字，所以这里叫 `Provenance`。
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Provenance {
+    // n < 2 ^ 16, looked up.
+    Table,
+    // A prime below the small-prime bound divides n.
+    TrialDivision,
+    // Miller–Rabin with a witness set that is exact below 2 ^ 64.
+    DeterministicMillerRabin,
+    // Baillie–PSW: a base-2 strong test and a strong Lucas test.
+    BailliePsw,
+    // A witness among `rounds` random strong tests, run after BPSW passed.
+    RandomMillerRabin { rounds: u32 },
+    // A primality certificate of the given `Certificate::kind`.
+    Certificate(&'static str),
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Table => write!(f, "table"),
+            Provenance::TrialDivision => write!(f, "trial-division"),
+            Provenance::DeterministicMillerRabin => write!(f, "deterministic-miller-rabin"),
+            Provenance::BailliePsw => write!(f, "baillie-psw"),
+            Provenance::RandomMillerRabin { rounds } => write!(f, "random-miller-rabin({})", rounds),
+            Provenance::Certificate(kind) => write!(f, "certificate({})", kind),
+        }
+    }
+}
+
+
+// Everything `check` knows about its answer.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Report {
+    pub primality: Primality,
+    // The step that decided `primality`.
+    pub provenance: Provenance,
+    // Upper bound on the probability that `primality` is wrong.
+    pub error: f64,
+    // Whether the random rounds drew their bases from SHA-256(n, i) because
+    // the OS offered no entropy (the `rng-fallback` feature). `error` then
+    // only holds for an n that was not chosen with those bases in mind.
+    pub derived_bases: bool,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    // Random strong tests run after BPSW, `None` when BPSW never passed.
+    pub fn rounds(&self) -> Option<u32> {
+        match self.provenance {
+            Provenance::RandomMillerRabin { rounds } => Some(rounds),
+            Provenance::BailliePsw if self.primality == Primality::PROBABLE => Some(AUTO_EXTRA_ROUNDS as u32),
+            _ => None,
+        }
+    }
+
+    pub fn assessment(&self) -> Assessment {
+        Assessment { primality: self.primality, error: self.error }
+    }
+}
+
+// An output type for `check`.
+pub trait CheckOutput {
+    fn from_report(report: Report) -> Self;
+}
+
+// Proven or probable prime.
+impl CheckOutput for bool {
+    fn from_report(report: Report) -> Self {
+        report.primality.is_probably_prime()
+    }
+}
+
+impl CheckOutput for Primality {
+    fn from_report(report: Report) -> Self {
+        report.primality
+    }
+}
+
+impl CheckOutput for Assessment {
+    fn from_report(report: Report) -> Self {
+        report.assessment()
+    }
+}
+
+impl CheckOutput for Report {
+    fn from_report(report: Report) -> Self {
+        report
+    }
+}
+
+pub fn check<T: CheckOutput>(n: &(impl AsBigUint + ?Sized)) -> T {
+    let start = Instant::now();
+
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    let (primality, provenance, derived_bases) = is_prime_biguint_auto_provenance(n);
+    let mut report = Report { primality, provenance, error: 0.0, derived_bases, elapsed: start.elapsed() };
+    // NOTE: BPSW 目前没有已知的反例，误差上界只计算其后的随机底数轮次。
+    if let Some(rounds) = report.rounds() {
+        report.error = Assessment::miller_rabin(primality, rounds as usize).error;
+    }
+
+    T::from_report(report)
+}
+
+#[test]
+fn test_check() {
+    assert!(check::<bool>(&65537u32));
+    assert!(!check::<bool>(&1u8));
+    assert_eq!(check::<Primality>(&0u8), Primality::ZeroOrOne);
+    assert_eq!(check::<Primality>(&u64::MAX), Primality::Composite);
+    assert_eq!(check::<Primality>(&18446744073709551557u64), Primality::Prime);
+
+    let report: Report = check(&1_000_000_007u64);
+    assert_eq!(report.primality, Primality::Prime);
+    assert_eq!((report.provenance, report.rounds(), report.error), (Provenance::DeterministicMillerRabin, None, 0.0));
+    assert_eq!(check::<Report>(&65521u16).provenance, Provenance::Table);
+    assert_eq!(check::<Report>(&7u8).provenance, Provenance::Table);
+    assert_eq!(check::<Report>(&(1u64 << 40)).provenance, Provenance::TrialDivision);
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let report: Report = check(&m127);
+    assert_eq!(report.primality, Primality::PROBABLE);
+    assert_eq!((report.provenance, report.rounds()), (Provenance::BailliePsw, Some(AUTO_EXTRA_ROUNDS as u32)));
+    // NOTE: 开启 `proven-only` 时结果是 `Unknown`，不给出误差上界。
+    #[cfg(not(feature = "proven-only"))]
+    assert!(report.error > 0.0 && report.error < 0.01);
+    assert_eq!(check::<Assessment>(&m127), report.assessment());
+    assert!(!report.derived_bases);
+    assert_eq!(check::<bool>(&m127), !cfg!(feature = "proven-only"));
+
+    let composite: Report = check(&(&m127 * 3u8));
+    assert_eq!(composite.primality, Primality::Composite);
+    assert_eq!(composite.error, 0.0);
+    assert_eq!((composite.provenance, composite.rounds()), (Provenance::TrialDivision, None));
+
+    // 3 · (2 ^ 127 − 1) is caught by trial division, (2 ^ 61 − 1)(2 ^ 89 − 1)
+    // only by BPSW.
+    let m61 = (BigUint::from(1u8) << 61usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    let report: Report = check(&(&m61 * &m89));
+    assert_eq!((report.primality, report.provenance), (Primality::Composite, Provenance::BailliePsw));
+    assert_eq!(report.rounds(), None);
+    assert_eq!(check::<Report>(&m127).provenance, Provenance::BailliePsw);
+
+    assert_eq!(Provenance::RandomMillerRabin { rounds: 5 }.to_string(), "random-miller-rabin(5)");
+    assert_eq!(Provenance::Certificate("pratt").to_string(), "certificate(pratt)");
+    assert_eq!(Provenance::Table.to_string(), "table");
+}
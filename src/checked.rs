@@ -0,0 +1,218 @@
+// Panic-free entry points
+//
+// Each `try_*` function checks the preconditions its counterpart asserts and
+// reports a violation as a `TryError` instead, so that none of them panics for
+// any input: requests whose answer would not fit (an index past the last
+// prime below 2 ^ 64) or could not be computed in memory (primes of more than
+// `MAX_GEN_BITS` bits) are errors too. The u64 / u128 routines they rely on —
+// the table, `trial_division_u64`, `trial_division_u128` and the deterministic
+// Miller–Rabin test — are defined for every input, carry no `debug_assert!`
+// and do all their arithmetic without overflow, in debug and release builds
+// alike. `test_try_apis_at_boundaries` feeds every `try_*` function the
+// boundary values and checks what comes back.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::gen_prime;
+use crate::Bits;
+use crate::nth_prime_nonzero;
+use crate::jacobi_symbol_i64;
+use crate::miller_rabin_primality_test_biguint;
+use crate::miller_rabin_with_witnesses_u64;
+use crate::solovay_strassen_primality_test_u64;
+use crate::solovay_strassen_primality_test_biguint;
+
+use rand::Rng;
+use num_bigint::BigUint;
+
+use core::num::NonZeroU64;
+
+use core::fmt;
+
+
+// π(2 ^ 64 − 1): the index of 18446744073709551557, the last prime below 2 ^ 64.
+pub const MAX_PRIME_INDEX: u64 = 425_656_284_035_217_743;
+
+// NOTE: 与 `parse_biguint_expr` 的上限相同。
+pub const MAX_GEN_BITS: usize = 1 << 20;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TryError {
+    // A probabilistic test was asked for 0 rounds.
+    ZeroRounds,
+    // Fewer bits than the smallest prime with the requested shape needs.
+    TooFewBits { bits: usize, min: usize },
+    // More bits than `MAX_GEN_BITS`.
+    TooManyBits { bits: usize, max: usize },
+    // An odd length where the shape needs an even one (an RSA modulus).
+    OddBits { bits: usize },
+    // The Jacobi symbol is only defined for an odd modulus.
+    EvenModulus,
+    // nth_prime counts from 1.
+    ZeroIndex,
+    // The n-th prime does not fit in a u64.
+    IndexTooLarge { n: u64, max: u64 },
+    // A range lo <= n < hi with lo >= hi.
+    EmptyRange { lo: u64, hi: u64 },
+    // A range holding neither an odd number nor 2.
+    NoOddNumber { lo: u64, hi: u64 },
+    // A deterministic Miller–Rabin test was given an empty set of bases.
+    NoWitnesses,
+}
+
+impl fmt::Display for TryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryError::ZeroRounds => write!(f, "at least one round is required"),
+            TryError::TooFewBits { bits, min } => write!(f, "{} bits requested, at least {} are required", bits, min),
+            TryError::TooManyBits { bits, max } => write!(f, "{} bits requested, at most {} are supported", bits, max),
+            TryError::OddBits { bits } => write!(f, "{} bits requested, an even number is required", bits),
+            TryError::EvenModulus => write!(f, "the modulus must be odd"),
+            TryError::ZeroIndex => write!(f, "primes are counted from 1"),
+            TryError::IndexTooLarge { n, max } => write!(f, "prime #{} exceeds u64, the last one is #{}", n, max),
+            TryError::EmptyRange { lo, hi } => write!(f, "the range [{}, {}) is empty", lo, hi),
+            TryError::NoOddNumber { lo, hi } => write!(f, "the range [{}, {}) holds no odd number", lo, hi),
+            TryError::NoWitnesses => write!(f, "at least one witness is required"),
+        }
+    }
+}
+
+pub fn try_miller_rabin_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Result<Primality, TryError> {
+    if k == 0 {
+        return Err(TryError::ZeroRounds);
+    }
+
+    Ok(miller_rabin_primality_test_biguint(n, k))
+}
+
+pub fn try_miller_rabin_with_witnesses_u64(n: u64, witnesses: &[u64]) -> Result<Primality, TryError> {
+    if witnesses.is_empty() {
+        return Err(TryError::NoWitnesses);
+    }
+
+    Ok(miller_rabin_with_witnesses_u64(n, witnesses))
+}
+
+pub fn try_solovay_strassen_primality_test_u64(n: u64, k: usize) -> Result<Primality, TryError> {
+    if k == 0 {
+        return Err(TryError::ZeroRounds);
+    }
+
+    Ok(solovay_strassen_primality_test_u64(n, k))
+}
+
+pub fn try_solovay_strassen_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Result<Primality, TryError> {
+    if k == 0 {
+        return Err(TryError::ZeroRounds);
+    }
+
+    Ok(solovay_strassen_primality_test_biguint(n, k))
+}
+
+pub fn try_gen_prime<R: Rng + ?Sized>(bits: Bits, rng: &mut R) -> Result<BigUint, TryError> {
+    if bits.get() as usize > MAX_GEN_BITS {
+        return Err(TryError::TooManyBits { bits: bits.get() as usize, max: MAX_GEN_BITS });
+    }
+
+    Ok(gen_prime(bits, rng))
+}
+
+pub fn try_jacobi_symbol_i64(a: i64, n: u64) -> Result<i8, TryError> {
+    if n.is_multiple_of(2) {
+        return Err(TryError::EvenModulus);
+    }
+
+    Ok(jacobi_symbol_i64(a, n))
+}
+
+pub fn try_nth_prime(n: u64) -> Result<u64, TryError> {
+    if n > MAX_PRIME_INDEX {
+        return Err(TryError::IndexTooLarge { n, max: MAX_PRIME_INDEX });
+    }
+
+    NonZeroU64::new(n).map(nth_prime_nonzero).ok_or(TryError::ZeroIndex)
+}
+
+
+#[test]
+fn test_try_apis() {
+    use crate::table_query_u16;
+    use crate::trial_division_u64;
+    use crate::trial_division_u128;
+    use crate::miller_rabin_primality_test_u64;
+
+    // Every precondition violation is an error, not a panic.
+    assert_eq!(try_miller_rabin_primality_test_biguint(&1000003u32, 0), Err(TryError::ZeroRounds));
+    assert_eq!(try_solovay_strassen_primality_test_u64(1000003, 0), Err(TryError::ZeroRounds));
+    assert_eq!(try_solovay_strassen_primality_test_biguint(&1000003u32, 0), Err(TryError::ZeroRounds));
+    assert_eq!(try_jacobi_symbol_i64(3, 10), Err(TryError::EvenModulus));
+    assert_eq!(try_nth_prime(0), Err(TryError::ZeroIndex));
+    assert_eq!(try_miller_rabin_with_witnesses_u64(2047, &[]), Err(TryError::NoWitnesses));
+
+    assert_eq!(try_miller_rabin_primality_test_biguint(&1000003u32, 1), Ok(Primality::Prime));
+    assert_eq!(try_gen_prime(Bits::new(2).unwrap(), &mut crate::global().rng()).map(|p| p.bits()), Ok(2));
+    assert_eq!(try_jacobi_symbol_i64(-1, 7), Ok(-1));
+    assert_eq!(try_nth_prime(1), Ok(2));
+    assert_eq!(try_miller_rabin_with_witnesses_u64(2047, &[2, 3]), Ok(Primality::Composite));
+
+    // Edge inputs of the u64 / u128 routines, including the even ones.
+    let edges = [0u64, 1, 2, 3, 4, 65535, 65536, 65537, 1 << 32, (1 << 32) + 1, u64::MAX - 1, u64::MAX];
+    for n in edges {
+        let _ = miller_rabin_primality_test_u64(n);
+        let _ = try_solovay_strassen_primality_test_u64(n, 1);
+        let _ = try_miller_rabin_primality_test_biguint(&n, 1);
+        let _ = try_solovay_strassen_primality_test_biguint(&n, 1);
+    }
+    for n in 0..=u16::MAX {
+        assert_eq!(trial_division_u64(n as u64), table_query_u16(n));
+    }
+
+    assert_eq!(miller_rabin_primality_test_u64(u64::MAX - 1), Primality::Composite);
+    assert_eq!(trial_division_u128(u128::MAX), Primality::Composite);
+    assert_eq!(try_solovay_strassen_primality_test_u64((1 << 32) + 2, 1), Ok(Primality::Composite));
+}
+
+#[test]
+fn test_try_apis_at_boundaries() {
+    use Primality::*;
+
+    let edges = [0u64, 1, 2, u64::MAX];
+    // u64::MAX = 3 · 5 · 17 · 257 · 641 · 65537 · 6700417
+    let expected = [ZeroOrOne, ZeroOrOne, Prime, Composite];
+
+    for (n, expected) in edges.iter().zip(expected.iter()) {
+        for k in [1, 2, usize::MAX] {
+            assert_eq!(try_miller_rabin_primality_test_biguint(n, k), Ok(*expected), "N={} k={}", n, k);
+            assert_eq!(try_solovay_strassen_primality_test_u64(*n, k), Ok(*expected), "N={} k={}", n, k);
+            assert_eq!(try_solovay_strassen_primality_test_biguint(n, k), Ok(*expected), "N={} k={}", n, k);
+        }
+        assert_eq!(try_miller_rabin_primality_test_biguint(n, 0), Err(TryError::ZeroRounds));
+        assert_eq!(try_solovay_strassen_primality_test_u64(*n, 0), Err(TryError::ZeroRounds));
+        assert_eq!(try_solovay_strassen_primality_test_biguint(n, 0), Err(TryError::ZeroRounds));
+
+        assert_eq!(try_miller_rabin_with_witnesses_u64(*n, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]), Ok(*expected), "N={}", n);
+        assert_eq!(try_miller_rabin_with_witnesses_u64(*n, &[0, 1, 2, u64::MAX]), Ok(*expected), "N={}", n);
+        assert_eq!(try_miller_rabin_with_witnesses_u64(*n, &[]), Err(TryError::NoWitnesses));
+    }
+
+    let mut rng = crate::global().rng();
+    assert_eq!(Bits::new(0).and_then(|bits| try_gen_prime(bits, &mut rng)), Err(TryError::TooFewBits { bits: 0, min: 2 }));
+    assert_eq!(Bits::new(1).and_then(|bits| try_gen_prime(bits, &mut rng)), Err(TryError::TooFewBits { bits: 1, min: 2 }));
+    assert_eq!(Bits::new(2).and_then(|bits| try_gen_prime(bits, &mut rng)).map(|p| p.bits()), Ok(2));
+    assert_eq!(Bits::new(u32::MAX).and_then(|bits| try_gen_prime(bits, &mut rng)), Err(TryError::TooManyBits { bits: u32::MAX as usize, max: MAX_GEN_BITS }));
+
+    for a in [i64::MIN, -1, 0, 1, 2, i64::MAX] {
+        assert_eq!(try_jacobi_symbol_i64(a, 0), Err(TryError::EvenModulus));
+        assert_eq!(try_jacobi_symbol_i64(a, 1), Ok(1), "a={}", a);
+        assert_eq!(try_jacobi_symbol_i64(a, 2), Err(TryError::EvenModulus));
+    }
+    // u64::MAX ≡ 3 (mod 4) and ≡ 7 (mod 8): (−1 | n) = −1 and (2 | n) = 1.
+    for (a, expected) in [(i64::MIN, -1), (-1, -1), (0, 0), (1, 1), (2, 1), (i64::MAX, -1)] {
+        assert_eq!(try_jacobi_symbol_i64(a, u64::MAX), Ok(expected), "a={}", a);
+    }
+
+    assert_eq!(try_nth_prime(0), Err(TryError::ZeroIndex));
+    assert_eq!(try_nth_prime(1), Ok(2));
+    assert_eq!(try_nth_prime(2), Ok(3));
+    assert_eq!(try_nth_prime(MAX_PRIME_INDEX + 1), Err(TryError::IndexTooLarge { n: MAX_PRIME_INDEX + 1, max: MAX_PRIME_INDEX }));
+    assert_eq!(try_nth_prime(u64::MAX), Err(TryError::IndexTooLarge { n: u64::MAX, max: MAX_PRIME_INDEX }));
+}
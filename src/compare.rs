@@ -0,0 +1,115 @@
+// Side-by-side timing of the primality tests
+//
+// `run` times each requested algorithm on the same input, so that users can
+// see on their own numbers and hardware where trial division stops being
+// viable and what the probabilistic tests cost compared with BPSW.
+//
+// Trial division takes time exponential in the size of n and is run on
+// whatever it is given. AKS is polynomial, but already takes about a second
+// at 2 ^ 16 and several at 2 ^ 20; it is only run below 2 ^ 16 and reports
+// `Unknown` above that.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::SecurityLevel;
+use crate::recommended_rounds;
+use crate::trial_division_biguint;
+use crate::aks_primality_test_usize;
+use crate::miller_rabin_primality_test_biguint;
+use crate::solovay_strassen_primality_test_biguint;
+use crate::baillie_psw_primality_test_biguint;
+
+use core::convert::TryFrom;
+
+use std::time::{Duration, Instant};
+
+
+// NOTE: r 随 log2(n)^2 增长，每一步都是 r 次多项式的乘法。
+const AKS_MAX: u64 = u16::MAX as u64;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Algorithm {
+    TrialDivision,
+    // With `recommended_rounds(bits, SecurityLevel::Adversarial)` random bases.
+    MillerRabin,
+    // With twice as many rounds as Miller–Rabin, for the same error bound.
+    SolovayStrassen,
+    BailliePsw,
+    Aks,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::TrialDivision,
+        Algorithm::MillerRabin,
+        Algorithm::SolovayStrassen,
+        Algorithm::BailliePsw,
+        Algorithm::Aks,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::TrialDivision => "trial-division",
+            Algorithm::MillerRabin => "miller-rabin",
+            Algorithm::SolovayStrassen => "solovay-strassen",
+            Algorithm::BailliePsw => "baillie-psw",
+            Algorithm::Aks => "aks",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Algorithm::ALL.iter().copied().find(|a| a.name() == name)
+    }
+}
+
+// Run each of `algorithms` once on `n`, in the given order.
+pub fn run(n: &(impl AsBigUint + ?Sized), algorithms: &[Algorithm]) -> Vec<(Algorithm, Duration, Primality)> {
+    let n = n.as_biguint();
+
+    let k = recommended_rounds(n.bits() as usize, SecurityLevel::Adversarial) as usize;
+
+    // NOTE: 预筛选用的素数积在第一次使用时才构造，不应计入第一个算法的耗时。
+    let _ = crate::global().tester();
+
+    algorithms.iter().map(|algorithm| {
+        let start = Instant::now();
+        let primality = match algorithm {
+            Algorithm::TrialDivision => trial_division_biguint(&*n),
+            Algorithm::MillerRabin => miller_rabin_primality_test_biguint(&*n, k),
+            Algorithm::SolovayStrassen => solovay_strassen_primality_test_biguint(&*n, 2 * k),
+            Algorithm::BailliePsw => baillie_psw_primality_test_biguint(&*n),
+            Algorithm::Aks => match u64::try_from(&*n) {
+                Ok(0) | Ok(1) => Primality::ZeroOrOne,
+                Ok(small) if small <= AKS_MAX => aks_primality_test_usize(small as usize),
+                _ => Primality::Unknown,
+            },
+        };
+
+        (*algorithm, start.elapsed(), primality)
+    }).collect()
+}
+
+
+#[test]
+fn test_compare_run() {
+    for a in Algorithm::ALL.iter() {
+        assert_eq!(Algorithm::from_name(a.name()), Some(*a));
+    }
+    assert_eq!(Algorithm::from_name("fermat"), None);
+
+    let results = run(&65537u32, &Algorithm::ALL);
+    assert_eq!(results.iter().map(|r| r.0).collect::<Vec<_>>(), Algorithm::ALL.to_vec());
+    assert_eq!(
+        results.iter().map(|r| r.2).collect::<Vec<_>>(),
+        [Primality::Prime, Primality::Prime, Primality::Prime, Primality::Prime, Primality::Unknown]
+    );
+
+    for n in (0..=64u64).chain([4093, 4097]) {
+        let results = run(&n, &Algorithm::ALL);
+        assert!(results.iter().all(|r| r.2 == results[0].2), "N={} {:?}", n, results);
+    }
+
+    let n = (1u64 << 61) - 1;
+    let results = run(&n, &[Algorithm::MillerRabin, Algorithm::BailliePsw, Algorithm::MillerRabin]);
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.2.is_accepted()));
+}
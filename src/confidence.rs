@@ -0,0 +1,124 @@
+// Results together with their error bounds
+//
+// A `Primality` says what a test concluded; an `Assessment` also says how
+// likely that conclusion is to be wrong, so that callers can compare results
+// from different tests and apply a threshold. A bare `Primality` can still be
+// scored with `Primality::confidence`, which has to assume an error bound.
+use crate::Primality;
+
+use core::cmp::Ordering;
+
+
+// The error bound `Primality::confidence` assumes for a `ProbablyPrime` whose
+// test is not known: that of `is_prime_biguint_auto`, whose four random strong
+// tests after BPSW each let a composite through with probability at most 1/4.
+pub const DEFAULT_PROBABLE_ERROR: f64 = 1.0 / 256.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Assessment {
+    pub primality: Primality,
+    // Upper bound on the probability that `primality` is wrong; 0 for
+    // everything but `ProbablyPrime`.
+    pub error: f64,
+}
+
+impl Assessment {
+    // A result that needs no error bound: a proof, or no answer at all.
+    pub fn proven(primality: Primality) -> Self {
+        let error = match primality {
+            Primality::ProbablyPrime => 1.0,
+            _ => 0.0,
+        };
+
+        Assessment { primality, error }
+    }
+
+    pub fn unknown() -> Self {
+        Assessment::proven(Primality::Unknown)
+    }
+
+    // `k` rounds of Miller–Rabin with random bases: each round lets a
+    // composite through with probability at most 1/4.
+    pub fn miller_rabin(primality: Primality, k: usize) -> Self {
+        Assessment::with_rounds(primality, k, 2)
+    }
+
+    // `k` rounds of Solovay–Strassen: at most 1/2 per round.
+    pub fn solovay_strassen(primality: Primality, k: usize) -> Self {
+        Assessment::with_rounds(primality, k, 1)
+    }
+
+    fn with_rounds(primality: Primality, k: usize, bits_per_round: usize) -> Self {
+        match primality {
+            Primality::ProbablyPrime => {
+                let exp = k.saturating_mul(bits_per_round).min(i32::MAX as usize) as i32;
+                Assessment { primality, error: 0.5f64.powi(exp) }
+            },
+            _ => Assessment::proven(primality),
+        }
+    }
+
+    // How strongly this result says "prime", in [0, 1]: 1 for a proven
+    // prime, 1 − error for a probable prime and 0 for everything else.
+    //
+    // NOTE: Unknown 与 Composite 的分数都是 0，用阈值判断时二者都不会被当作素数。
+    pub fn confidence(&self) -> f64 {
+        match self.primality {
+            Primality::Prime => 1.0,
+            Primality::ProbablyPrime => 1.0 - self.error,
+            _ => 0.0,
+        }
+    }
+}
+
+// Ordered like `Primality`, with probable primes further ordered by their
+// error bound, smallest first.
+impl PartialOrd for Assessment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.primality.partial_cmp(&other.primality)? {
+            Ordering::Equal => other.error.partial_cmp(&self.error),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+
+#[test]
+fn test_assessment() {
+    let prime = Assessment::proven(Primality::Prime);
+    let mr = Assessment::miller_rabin(Primality::ProbablyPrime, 10);
+    let ss = Assessment::solovay_strassen(Primality::ProbablyPrime, 10);
+    let unknown = Assessment::unknown();
+    let composite = Assessment::miller_rabin(Primality::Composite, 10);
+    let zero_or_one = Assessment::proven(Primality::ZeroOrOne);
+
+    assert_eq!(mr.error, 1.0 / (1u64 << 20) as f64);
+    assert_eq!(ss.error, 1.0 / (1u64 << 10) as f64);
+    assert_eq!(composite.error, 0.0);
+
+    assert!(prime > mr && mr > ss && ss > unknown && unknown > composite);
+    assert!(unknown > zero_or_one);
+    assert_eq!(composite.partial_cmp(&zero_or_one), None);
+
+    assert_eq!(prime.confidence(), 1.0);
+    assert!(mr.confidence() > 0.999999 && mr.confidence() < 1.0);
+    assert_eq!(unknown.confidence(), 0.0);
+    assert_eq!(composite.confidence(), 0.0);
+
+    // A bare result assumes the error bound of `is_prime_biguint_auto`.
+    let auto = Assessment::miller_rabin(Primality::ProbablyPrime, crate::baillie_psw::AUTO_EXTRA_ROUNDS);
+    assert_eq!(auto.error, DEFAULT_PROBABLE_ERROR);
+    assert_eq!(Primality::ProbablyPrime.confidence(), auto.confidence());
+    assert_eq!(Primality::Prime.confidence(), 1.0);
+    assert_eq!(Primality::Unknown.confidence(), 0.0);
+    assert_eq!(Primality::Composite.confidence(), 0.0);
+
+    // Huge round counts saturate instead of overflowing.
+    assert_eq!(Assessment::miller_rabin(Primality::ProbablyPrime, usize::MAX).confidence(), 1.0);
+
+    assert!(Primality::Prime > Primality::ProbablyPrime);
+    assert!(Primality::ProbablyPrime > Primality::Unknown);
+    assert!(Primality::Unknown > Primality::Composite);
+    assert_eq!(Primality::ZeroOrOne.partial_cmp(&Primality::Composite), None);
+    assert!(Primality::Composite <= Primality::Composite);
+}
@@ -0,0 +1,127 @@
+// Conformance of every public test on the smallest inputs
+//
+// 0 and 1 are `ZeroOrOne`, 2 and 3 are `Prime` and every composite is
+// `Composite` for every test, however it is configured; other primes may come
+// back as `ProbablyPrime` from the probabilistic tests (`Unknown` under the
+// `proven-only` feature). The smallest inputs are
+// where the special cases of the algorithms live — n − 1 has no odd part, no
+// base lies strictly between 1 and n − 1 — so a custom witness ladder or
+// prefilter bound is worth running through `check_small_inputs_with` before it
+// goes into production.
+use crate::Primality;
+use crate::PrimalityTester;
+use crate::table_query_u16;
+use crate::U256;
+
+use num_bigint::BigUint;
+
+use core::fmt;
+
+
+// Every input from 0 up to this bound is checked.
+pub const SMALL_INPUT_BOUND: u64 = 64;
+
+// NOTE: 概率性测试只跑少量轮次，这里只关心边界情况是否正确。
+const ROUNDS: usize = 8;
+
+type Algorithm = (&'static str, fn(&PrimalityTester, u64) -> Primality);
+
+const ALGORITHMS: &[Algorithm] = &[
+    ("trial_division_u64", |_, n| crate::trial_division_u64(n)),
+    ("trial_division_u128", |_, n| crate::trial_division_u128(n as u128)),
+    ("trial_division_biguint", |_, n| crate::trial_division_biguint(&n)),
+    ("aks_primality_test_usize", |_, n| crate::aks_primality_test_usize(n as usize)),
+    ("wilson_test", |_, n| crate::wilson_test(&n)),
+    ("is_prime_u8", |_, n| crate::is_prime_u8(n as u8)),
+    ("is_prime_u16", |_, n| crate::is_prime_u16(n as u16)),
+    ("is_prime_u32", |_, n| crate::is_prime_u32(n as u32)),
+    ("miller_rabin_primality_test_u64", |_, n| crate::miller_rabin_primality_test_u64(n)),
+    ("miller_rabin_primality_test_biguint", |_, n| crate::miller_rabin_primality_test_biguint(&n, ROUNDS)),
+    ("miller_rabin_primality_test_limbs", |_, n| crate::miller_rabin_primality_test_limbs(&[n], ROUNDS)),
+    ("solovay_strassen_primality_test_u64", |_, n| crate::solovay_strassen_primality_test_u64(n, ROUNDS)),
+    ("solovay_strassen_primality_test_biguint", |_, n| crate::solovay_strassen_primality_test_biguint(&n, ROUNDS)),
+    ("solovay_strassen_primality_test_limbs", |_, n| crate::solovay_strassen_primality_test_limbs(&[n], ROUNDS)),
+    ("fermat_primality_test_u64", |_, n| crate::fermat_primality_test_u64(n, ROUNDS)),
+    ("fermat_primality_test_biguint", |_, n| crate::fermat_primality_test_biguint(&n, ROUNDS)),
+    ("fibonacci_primality_test_u64", |_, n| crate::fibonacci_primality_test_u64(n)),
+    ("fibonacci_primality_test_biguint", |_, n| crate::fibonacci_primality_test_biguint(&n)),
+    ("fermat_fibonacci_test_u64", |_, n| crate::fermat_fibonacci_test_u64(n)),
+    ("frobenius_primality_test_biguint", |_, n| crate::frobenius_primality_test_biguint(&n, ROUNDS)),
+    ("euler_plus_strong_test", |_, n| crate::euler_plus_strong_test(&n, ROUNDS)),
+    ("baillie_psw_primality_test_u64", |_, n| crate::baillie_psw_primality_test_u64(n)),
+    ("baillie_psw_primality_test_biguint", |_, n| crate::baillie_psw_primality_test_biguint(&n)),
+    ("baillie_psw_primality_test_u256", |_, n| crate::baillie_psw_primality_test_u256(&U256::from(n))),
+    ("baillie_psw_plus", |_, n| crate::baillie_psw_plus(&n, ROUNDS, &mut crate::global().rng())),
+    ("is_prime_biguint_auto", |_, n| crate::is_prime_biguint_auto(&n)),
+    #[cfg(feature = "derandomized")]
+    ("miller_rabin_primality_test_biguint_derandomized", |_, n| crate::miller_rabin_primality_test_biguint_derandomized(&n, ROUNDS)),
+    ("PrimalityTester::miller_rabin_u64", |tester, n| tester.miller_rabin_u64(n)),
+    ("PrimalityTester::miller_rabin_biguint", |tester, n| tester.miller_rabin_biguint(&BigUint::from(n), ROUNDS)),
+    ("PrimalityTester::solovay_strassen_biguint", |tester, n| tester.solovay_strassen_biguint(&BigUint::from(n), ROUNDS)),
+    ("PrimalityTester::euler_plus_strong_biguint", |tester, n| tester.euler_plus_strong_biguint(&BigUint::from(n), ROUNDS)),
+];
+
+// A test that answered `actual` for n where `expected` was required.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Mismatch {
+    pub algorithm: &'static str,
+    pub n: u64,
+    pub expected: Primality,
+    pub actual: Primality,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}) = {:?}, expected {:?}", self.algorithm, self.n, self.actual, self.expected)
+    }
+}
+
+// Run every public test on 0 ..= SMALL_INPUT_BOUND with the default
+// configuration.
+pub fn check_small_inputs() -> Result<(), Vec<Mismatch>> {
+    check_small_inputs_with(&PrimalityTester::default())
+}
+
+// Like `check_small_inputs`, with `tester` for the `PrimalityTester` methods.
+pub fn check_small_inputs_with(tester: &PrimalityTester) -> Result<(), Vec<Mismatch>> {
+    let mut mismatches = Vec::new();
+    for n in 0..=SMALL_INPUT_BOUND {
+        let expected = table_query_u16(n as u16);
+        for (algorithm, test) in ALGORITHMS.iter() {
+            let actual = test(tester, n);
+            let ok = match expected {
+                Primality::Prime if n > 3 => actual.is_accepted(),
+                _ => actual == expected,
+            };
+            if !ok {
+                mismatches.push(Mismatch { algorithm, n, expected, actual });
+            }
+        }
+    }
+
+    match mismatches.is_empty() {
+        true => Ok(()),
+        false => Err(mismatches),
+    }
+}
+
+
+#[test]
+fn test_check_small_inputs() {
+    let report = |result: Result<(), Vec<Mismatch>>| {
+        result.map_err(|mismatches| mismatches.iter().map(|m| m.to_string()).collect::<Vec<_>>())
+    };
+
+    assert_eq!(report(check_small_inputs()), Ok(()));
+    assert_eq!(report(check_small_inputs_with(&PrimalityTester::new().with_prefilter_bound(0))), Ok(()));
+    assert_eq!(report(check_small_inputs_with(&PrimalityTester::new().with_prefilter_bound(5))), Ok(()));
+
+    // A ladder that stops short of the inputs is caught.
+    static SHORT: &[(u64, &[u64])] = &[(3, &[2])];
+    let mismatches = check_small_inputs_with(&PrimalityTester::new().with_witness_ladder(SHORT)).unwrap_err();
+    assert!(mismatches.iter().all(|m| m.algorithm == "PrimalityTester::miller_rabin_u64" && m.n > 3));
+    // NOTE: 开启 `proven-only` 时素数得到 `Unknown` 是允许的，只有合数会被报告。
+    #[cfg(not(feature = "proven-only"))]
+    assert_eq!(mismatches[0].to_string(), "PrimalityTester::miller_rabin_u64(5) = Unknown, expected Prime");
+    assert!(mismatches.iter().any(|m| m.to_string() == "PrimalityTester::miller_rabin_u64(9) = Unknown, expected Composite"));
+}
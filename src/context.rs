@@ -0,0 +1,229 @@
+// Process-wide constants shared by the primality tests
+//
+// The constants are built once, on first use, and then shared by every thread;
+// randomness comes from `rand::thread_rng`, which is thread-local and never locks.
+//
+// `thread_rng` panics where the OS offers no entropy (some WASM targets and
+// sandboxes). With the `rng-fallback` feature, the random rounds of the
+// Miller–Rabin, Solovay–Strassen and Euler-plus-strong tests then draw their
+// bases from SHA-256(n, i) as in `miller_rabin_primality_test_biguint_derandomized`
+// instead, and `Context::rng_fallbacks` counts how often that happened. Such
+// bases are fixed by n, so the error bounds only hold for inputs that were not
+// chosen with the bases in mind; `check` marks the results that relied on them
+// with `Report::derived_bases`.
+//
+// The fallback is for std targets without entropy, such as
+// wasm32-unknown-unknown: the crate itself needs `std`, for `OnceLock` here
+// and for `thread_rng`, and does not build for `no_std`.
+use crate::Primality;
+use crate::table_query_u16;
+use crate::PrimalityTester;
+use crate::SmallPrimeDivisor;
+
+use num_integer::Integer;
+use num_bigint::{BigUint, RandBigInt};
+
+use std::sync::OnceLock;
+#[cfg(feature = "rng-fallback")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+
+// NOTE: 小于 2^10 的素数之积（约 1400 bits），用来通过一次 gcd 排除带有小因子的合数。
+const PRIMORIAL_BOUND: u16 = 1 << 10;
+
+static GLOBAL: OnceLock<Context> = OnceLock::new();
+
+#[derive(Debug)]
+pub struct Context {
+    small_primes: Vec<u16>,
+    small_prime_divisors: Vec<SmallPrimeDivisor>,
+    primorial: BigUint,
+    // NOTE: 默认的预筛选素数积较大，只在第一次用到时才构造。
+    tester: OnceLock<PrimalityTester>,
+    entropy: OnceLock<bool>,
+    #[cfg(feature = "rng-fallback")]
+    rng_fallbacks: AtomicU64,
+}
+
+// Bases for the random rounds of a test.
+pub(crate) enum Witnesses {
+    Random(rand::rngs::ThreadRng),
+    #[cfg(feature = "rng-fallback")]
+    Hashed { n: BigUint, round: usize },
+}
+
+impl Witnesses {
+    // Whether the bases are derived from n instead of drawn at random.
+    pub(crate) fn is_derived(&self) -> bool {
+        !matches!(self, Witnesses::Random(_))
+    }
+
+    // The next base in [2, hi), where hi is n − 2 or n − 1.
+    pub(crate) fn next(&mut self, hi: &BigUint) -> BigUint {
+        match self {
+            Witnesses::Random(rng) => rng.gen_biguint_range(&BigUint::from(2u8), hi),
+            // NOTE: 派生的底数落在 [2, n − 2] 中，对两种上界都成立。
+            #[cfg(feature = "rng-fallback")]
+            Witnesses::Hashed { n, round } => {
+                *round += 1;
+                crate::derandomize::hash_witness(n, *round - 1)
+            },
+        }
+    }
+}
+
+impl Context {
+    fn new() -> Self {
+        let small_primes: Vec<u16> = (0..=u16::MAX)
+            .filter(|n| table_query_u16(*n) == Primality::Prime)
+            .collect();
+
+        let small_prime_divisors = small_primes.iter()
+            .skip(1)
+            .map(|p| SmallPrimeDivisor::new(*p as u64))
+            .collect();
+
+        let primorial = small_primes.iter()
+            .take_while(|p| **p < PRIMORIAL_BOUND)
+            .fold(BigUint::from(1u8), |acc, p| acc * *p);
+
+        Context {
+            small_primes,
+            small_prime_divisors,
+            primorial,
+            tester: OnceLock::new(),
+            entropy: OnceLock::new(),
+            #[cfg(feature = "rng-fallback")]
+            rng_fallbacks: AtomicU64::new(0),
+        }
+    }
+
+    // Every prime below 2 ^ 16, in ascending order.
+    pub fn small_primes(&self) -> &[u16] {
+        &self.small_primes
+    }
+
+    // Every odd prime below 2 ^ 16 with its precomputed inverse, in ascending
+    // order.
+    pub fn small_prime_divisors(&self) -> &[SmallPrimeDivisor] {
+        &self.small_prime_divisors
+    }
+
+    // Product of every prime below 2 ^ 10.
+    pub fn primorial(&self) -> &BigUint {
+        &self.primorial
+    }
+
+    // The tester behind `miller_rabin_primality_test_biguint` and
+    // `solovay_strassen_primality_test_biguint`, with the prefilter bound in
+    // effect when it is first used.
+    pub fn tester(&self) -> &PrimalityTester {
+        self.tester.get_or_init(|| PrimalityTester::with_bound(crate::thresholds().prefilter_bound))
+    }
+
+    pub fn rng(&self) -> rand::rngs::ThreadRng {
+        rand::thread_rng()
+    }
+
+    // Whether the OS provides the entropy `rng` is seeded from, checked once.
+    pub fn entropy_available(&self) -> bool {
+        *self.entropy.get_or_init(|| {
+            use rand::RngCore;
+
+            rand::rngs::OsRng.try_fill_bytes(&mut [0u8; 16]).is_ok()
+        })
+    }
+
+    // Number of tests that ran on derived rather than random bases.
+    #[cfg(feature = "rng-fallback")]
+    pub fn rng_fallbacks(&self) -> u64 {
+        self.rng_fallbacks.load(Ordering::Relaxed)
+    }
+
+    // The bases for the random rounds of a test of `n`.
+    #[cfg_attr(not(feature = "rng-fallback"), allow(unused_variables))]
+    pub(crate) fn witnesses(&self, n: &BigUint) -> Witnesses {
+        #[cfg(feature = "rng-fallback")]
+        if !self.entropy_available() {
+            self.rng_fallbacks.fetch_add(1, Ordering::Relaxed);
+            return Witnesses::Hashed { n: n.clone(), round: 0 };
+        }
+
+        Witnesses::Random(self.rng())
+    }
+
+    // Whether `n` is a proper multiple of a prime below 2 ^ 10.
+    pub(crate) fn has_small_factor(&self, n: &BigUint) -> bool {
+        // NOTE: 先做一次取模，避免两个长度相差很大的数直接进行 gcd 运算。
+        let g = if n > &self.primorial {
+            (n % &self.primorial).gcd(&self.primorial)
+        } else {
+            (&self.primorial % n).gcd(n)
+        };
+        if g == BigUint::from(1u8) {
+            return false;
+        }
+
+        // NOTE: n 本身就是一个小素数的情况。
+        &g != n
+            || n.bits() > 16
+            || self.small_primes.binary_search(&(n.to_u32_digits()[0] as u16)).is_err()
+    }
+}
+
+pub fn global() -> &'static Context {
+    GLOBAL.get_or_init(Context::new)
+}
+
+
+#[test]
+fn test_global_context() {
+    let ctx = global();
+    assert!(core::ptr::eq(ctx, std::thread::spawn(global).join().unwrap()));
+
+    assert_eq!(ctx.small_primes().len(), 6542);
+    assert_eq!(ctx.small_primes()[..5], [2, 3, 5, 7, 11]);
+    assert_eq!(*ctx.small_primes().last().unwrap(), 65521);
+    assert_eq!(ctx.small_prime_divisors().len(), 6541);
+    assert_eq!(ctx.small_prime_divisors()[0], SmallPrimeDivisor::new(3));
+
+    let primorial = ctx.small_primes().iter()
+        .take_while(|p| **p < 1024)
+        .fold(BigUint::from(1u8), |acc, p| acc * *p);
+    assert_eq!(ctx.primorial(), &primorial);
+
+    assert!(!ctx.has_small_factor(&BigUint::from(7u8)));
+    assert!(!ctx.has_small_factor(&BigUint::from(1021u16)));
+    assert!(!ctx.has_small_factor(&BigUint::from(1031u32 * 1033)));
+    assert!(ctx.has_small_factor(&BigUint::from(15u8)));
+    assert!(ctx.has_small_factor(&BigUint::from(1021u32 * 1021u32)));
+    assert!(ctx.has_small_factor(&(BigUint::from(u128::MAX) * 7u8)));
+}
+
+#[test]
+fn test_witnesses() {
+    let ctx = global();
+    assert!(ctx.entropy_available());
+
+    let n = BigUint::from(1_000_000_007u32);
+    let n_minus_two: BigUint = &n - 2u8;
+    let mut witnesses = ctx.witnesses(&n);
+    assert!(!witnesses.is_derived());
+    for _ in 0..64 {
+        let a = witnesses.next(&n_minus_two);
+        assert!(a >= BigUint::from(2u8) && a < n_minus_two);
+    }
+
+    #[cfg(feature = "rng-fallback")]
+    {
+        use crate::derandomize::hash_witness;
+
+        assert_eq!(ctx.rng_fallbacks(), 0);
+
+        let mut hashed = Witnesses::Hashed { n: n.clone(), round: 0 };
+        assert!(hashed.is_derived());
+        for i in 0..8 {
+            assert_eq!(hashed.next(&n_minus_two), hash_witness(&n, i));
+        }
+    }
+}
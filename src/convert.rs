@@ -0,0 +1,96 @@
+// Input conversions for the BigUint-flavored entry points
+use crate::U256;
+use crate::{parse_biguint_expr, ParseExprError};
+
+use num_bigint::BigUint;
+
+use std::borrow::Cow;
+
+
+// Anything that can be viewed as a `BigUint` without the caller converting it first.
+//
+// NOTE: `BigUint` 本身只借用，不会发生拷贝；原生整数类型则会构造一个新的 `BigUint`。
+pub trait AsBigUint {
+    fn as_biguint(&self) -> Cow<'_, BigUint>;
+}
+
+impl AsBigUint for BigUint {
+    fn as_biguint(&self) -> Cow<'_, BigUint> {
+        Cow::Borrowed(self)
+    }
+}
+
+macro_rules! impl_as_biguint {
+    ($($t:ty),*) => {
+        $(
+            impl AsBigUint for $t {
+                fn as_biguint(&self) -> Cow<'_, BigUint> {
+                    Cow::Owned(BigUint::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_as_biguint!(u8, u16, u32, u64, u128, usize);
+
+// Little-endian `u64` limbs, as produced by most other bignum libraries.
+impl AsBigUint for [u64] {
+    fn as_biguint(&self) -> Cow<'_, BigUint> {
+        let mut digits: Vec<u32> = Vec::with_capacity(self.len() * 2);
+        for limb in self.iter() {
+            digits.push(*limb as u32);
+            digits.push((*limb >> 32) as u32);
+        }
+
+        Cow::Owned(BigUint::new(digits))
+    }
+}
+
+impl AsBigUint for U256 {
+    fn as_biguint(&self) -> Cow<'_, BigUint> {
+        Cow::Owned(self.to_biguint())
+    }
+}
+
+// The `&str` counterpart of `AsBigUint`, which cannot fail: a decimal number
+// or an expression such as `2^127-1` or `1e18+9` (see `parse_biguint_expr`),
+// ready to be passed by reference to any of the BigUint entry points.
+pub fn try_as_biguint(s: &str) -> Result<BigUint, ParseExprError> {
+    parse_biguint_expr(s)
+}
+
+
+#[test]
+fn test_as_biguint() {
+    let n = BigUint::from(97u8);
+    assert!(matches!(n.as_biguint(), Cow::Borrowed(_)));
+    assert_eq!(n.as_biguint().as_ref(), &n);
+
+    assert_eq!(97u8.as_biguint().as_ref(), &n);
+    assert_eq!(97u16.as_biguint().as_ref(), &n);
+    assert_eq!(97u32.as_biguint().as_ref(), &n);
+    assert_eq!(97u64.as_biguint().as_ref(), &n);
+    assert_eq!(97u128.as_biguint().as_ref(), &n);
+    assert_eq!(97usize.as_biguint().as_ref(), &n);
+
+    assert_eq!([97u64][..].as_biguint().as_ref(), &n);
+    assert_eq!([97u64, 0, 0][..].as_biguint().as_ref(), &n);
+
+    let m = (BigUint::from(3u8) << 128u32) + (BigUint::from(2u8) << 64u32) + 1u8;
+    assert_eq!([1u64, 2, 3][..].as_biguint().as_ref(), &m);
+    assert_eq!(U256::from_limbs([1, 2, 3, 0]).as_biguint().as_ref(), &m);
+}
+
+#[test]
+fn test_try_as_biguint() {
+    use crate::{miller_rabin_primality_test_biguint, Primality};
+
+    let m127 = try_as_biguint("2^127-1").unwrap();
+    assert_eq!(m127, (BigUint::from(1u8) << 127u32) - 1u8);
+    assert_eq!(miller_rabin_primality_test_biguint(&m127, 8), Primality::PROBABLE);
+    assert_eq!(miller_rabin_primality_test_biguint(&try_as_biguint("1_000_003").unwrap(), 8), Primality::Prime);
+
+    assert_eq!(try_as_biguint("12a"), Err(ParseExprError { position: 2 }));
+    assert!(try_as_biguint("").is_err());
+}
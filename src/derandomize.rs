@@ -0,0 +1,93 @@
+// Derandomized Miller–Rabin
+//
+// Instead of sampling bases from `thread_rng`, the base for round i is derived
+// from SHA-256(n, i). The result is reproducible across machines and runs,
+// while the bases remain (heuristically) independent of how n was chosen.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::tester::small;
+use crate::miller_rabin::miller_rabin_rounds_biguint;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+
+const DOMAIN: &[u8] = b"prime/miller-rabin/witness";
+
+// A base in the range [2, n − 2] derived from (n, round).
+pub(crate) fn hash_witness(n: &BigUint, round: usize) -> BigUint {
+    let n_bytes = n.to_bytes_le();
+
+    // NOTE: 多取 128 bits，使得取模带来的偏差可以忽略不计。
+    let wanted = n_bytes.len() + 16;
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(wanted + 32);
+    let mut block = 0u32;
+    while bytes.len() < wanted {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN);
+        hasher.update((n_bytes.len() as u64).to_le_bytes());
+        hasher.update(&n_bytes);
+        hasher.update((round as u64).to_le_bytes());
+        hasher.update(block.to_le_bytes());
+        bytes.extend_from_slice(&hasher.finalize());
+
+        block += 1;
+    }
+
+    BigUint::from_bytes_le(&bytes) % (n - 3u8) + 2u8
+}
+
+pub fn miller_rabin_primality_test_biguint_derandomized(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+    // Input #1: n, any integer; n < 2 ^ 32 (2 and 3 included) and even n are answered exactly
+    // Input #2: k, the number of rounds of testing to perform
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    // NOTE: n ≤ 4 时 [2, n − 2] 为空，取模会除以零。
+    if let Some(primality) = small(n) {
+        return primality;
+    }
+
+    miller_rabin_rounds_biguint(n, k, |i| hash_witness(n, i))
+}
+
+
+#[test]
+fn test_hash_witness() {
+    let n = BigUint::from(1_000_000_007u32);
+    for i in 0..64 {
+        let a = hash_witness(&n, i);
+        assert_eq!(a, hash_witness(&n, i));
+        assert!(a >= BigUint::from(2u8) && a <= &n - 2u8);
+    }
+
+    assert_ne!(hash_witness(&n, 0), hash_witness(&n, 1));
+    assert_ne!(hash_witness(&n, 0), hash_witness(&(&n + 2u8), 0));
+
+    let five = BigUint::from(5u8);
+    for i in 0..16 {
+        let a = hash_witness(&five, i);
+        assert!(a == BigUint::from(2u8) || a == BigUint::from(3u8));
+    }
+}
+
+#[test]
+fn test_miller_rabin_primality_test_biguint_derandomized() {
+    use crate::table_query_u16;
+
+    for n in 0..4096u16 {
+        let a = miller_rabin_primality_test_biguint_derandomized(&n, 8).is_accepted();
+        let b = table_query_u16(n).is_prime();
+        assert_eq!(a, b, "N={}", n);
+    }
+
+    // Carmichael numbers
+    for n in [561u64, 1105, 1729, 2465, 2821, 6601, 8911, 3215031751].iter() {
+        assert_eq!(miller_rabin_primality_test_biguint_derandomized(n, 8), Primality::Composite, "N={}", n);
+    }
+
+    // 2 ^ 127 - 1
+    let m127 = (BigUint::from(1u8) << 127u32) - 1u8;
+    assert_eq!(miller_rabin_primality_test_biguint_derandomized(&m127, 8), Primality::PROBABLE);
+}
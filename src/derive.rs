@@ -0,0 +1,141 @@
+// Deterministic prime derivation
+//
+// The seed goes through HKDF-SHA256 (RFC 5869): one extract step, then one
+// expand step per candidate with the candidate index in the info string.
+// Candidates are tested with the derandomized Miller–Rabin rounds, so the same
+// (seed, bits) always yields the same prime, on every machine.
+use crate::Primality;
+use crate::Bits;
+use crate::SecurityLevel;
+use crate::recommended_rounds;
+use crate::miller_rabin::is_prime_u64;
+use crate::miller_rabin_primality_test_biguint_derandomized;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+
+const SALT: &[u8] = b"prime/derive-prime";
+
+// NOTE: HKDF-Expand 最多输出 255 个 SHA-256 分组。
+const MAX_BITS: usize = 255 * 32 * 8;
+
+// The first probable prime of exactly `bits` bits in the candidate stream
+// expanded from `seed`.
+pub fn derive_prime(seed: &[u8], bits: Bits) -> BigUint {
+    let bits = bits.get() as usize;
+    assert!(bits <= MAX_BITS);
+
+    let prk = hmac_sha256(SALT, seed);
+    let rounds = recommended_rounds(bits, SecurityLevel::Bits128) as usize;
+
+    (0u64..)
+        .map(|i| candidate(&prk, bits, i))
+        .find(|n| is_probable_prime(n, rounds))
+        .unwrap()
+}
+
+fn candidate(prk: &[u8; 32], bits: usize, index: u64) -> BigUint {
+    let mut info = b"candidate".to_vec();
+    info.extend_from_slice(&(bits as u64).to_le_bytes());
+    info.extend_from_slice(&index.to_le_bytes());
+
+    let bytes = hkdf_expand(prk, &info, bits.div_ceil(8));
+
+    // Keep the low `bits` bits, then force the top bit and oddness.
+    let one = BigUint::from(1u8);
+    let n = BigUint::from_bytes_be(&bytes) % (&one << bits);
+    n | (&one << (bits - 1)) | one
+}
+
+fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    if n.bits() <= 64 {
+        return is_prime_u64(n.to_u64_digits()[0]);
+    }
+
+    // NOTE: 开启 `proven-only` 时通过的数是 `Unknown` 而不是 `ProbablyPrime`，只排除合数。
+    miller_rabin_primality_test_biguint_derandomized(n, rounds) != Primality::Composite
+}
+
+// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+
+    let mut k = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        k[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(k.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+    inner.update(message);
+
+    let mut outer = Sha256::new();
+    outer.update(k.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+    outer.update(inner.finalize());
+
+    outer.finalize().into()
+}
+
+// HKDF-Expand (RFC 5869, section 2.3)
+fn hkdf_expand(prk: &[u8; 32], info: &[u8], len: usize) -> Vec<u8> {
+    debug_assert!(len <= 255 * 32);
+
+    let mut okm: Vec<u8> = Vec::with_capacity(len + 32);
+    let mut t: Vec<u8> = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        let mut message = t.clone();
+        message.extend_from_slice(info);
+        message.push(counter);
+
+        t = hmac_sha256(prk, &message).to_vec();
+        okm.extend_from_slice(&t);
+        counter = counter.wrapping_add(1);
+    }
+
+    okm.truncate(len);
+    okm
+}
+
+
+#[test]
+fn test_hkdf_sha256() {
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+    // RFC 4231, test case 2
+    assert_eq!(
+        hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+
+    // RFC 5869, test case 1
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let prk = hmac_sha256(&salt, &[0x0b; 22]);
+    assert_eq!(hex(&prk), "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5");
+    assert_eq!(
+        hex(&hkdf_expand(&prk, &info, 42)),
+        "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+    );
+}
+
+#[test]
+fn test_derive_prime() {
+    use crate::baillie_psw_primality_test_biguint;
+
+    let bits = |bits: u32| Bits::new(bits).unwrap();
+    for b in [2, 3, 16, 64, 65, 256] {
+        let p = derive_prime(b"seed", bits(b));
+        assert_eq!(p.bits(), b as u64);
+        assert_ne!(baillie_psw_primality_test_biguint(&p), Primality::Composite, "P={}", p);
+        assert_eq!(p, derive_prime(b"seed", bits(b)));
+    }
+
+    assert_ne!(derive_prime(b"seed", bits(256)), derive_prime(b"seed!", bits(256)));
+
+    // Pinned, so that a change to the derivation cannot go unnoticed.
+    assert_eq!(derive_prime(b"seed", bits(64)), BigUint::from(16134002059038700979u64));
+}
@@ -0,0 +1,131 @@
+// One entry point for every integer type
+//
+//      assert_eq!(is_prime(65521u16), Primality::Prime);
+//      assert_eq!(is_prime(18446744073709551557u64), Primality::Prime);
+//      assert_eq!(is_prime(&((BigUint::from(1u8) << 127usize) - 1u8)), Primality::ProbablyPrime);
+//
+// `is_prime` picks the test by the type and size of n:
+//
+//   * n < 2 ^ 16: the table;
+//   * n < 2 ^ 32: one hashed strong test (`is_prime_u32`);
+//   * n < 2 ^ 64: deterministic Miller–Rabin, so still exact;
+//   * above: BPSW plus a few random strong tests (`is_prime_biguint_auto`),
+//     a probable prime at best.
+//
+// `U256` goes through its own BPSW without leaving the fixed width.
+use crate::Primality;
+use crate::U256;
+use crate::is_prime_u32;
+use crate::is_prime_biguint_auto;
+use crate::table_query_u16;
+use crate::miller_rabin_primality_test_u64;
+use crate::baillie_psw_primality_test_u256;
+
+use num_bigint::BigUint;
+
+
+// Integers `is_prime` accepts, by value or by reference.
+pub trait IsPrime {
+    fn primality(&self) -> Primality;
+}
+
+pub fn is_prime<T: IsPrime>(n: T) -> Primality {
+    n.primality()
+}
+
+impl<T: IsPrime + ?Sized> IsPrime for &T {
+    fn primality(&self) -> Primality {
+        (**self).primality()
+    }
+}
+
+impl IsPrime for u8 {
+    fn primality(&self) -> Primality {
+        table_query_u16(*self as u16)
+    }
+}
+
+impl IsPrime for u16 {
+    fn primality(&self) -> Primality {
+        table_query_u16(*self)
+    }
+}
+
+impl IsPrime for u32 {
+    fn primality(&self) -> Primality {
+        is_prime_u32(*self)
+    }
+}
+
+impl IsPrime for u64 {
+    fn primality(&self) -> Primality {
+        match *self <= u32::MAX as u64 {
+            true => is_prime_u32(*self as u32),
+            false => miller_rabin_primality_test_u64(*self),
+        }
+    }
+}
+
+impl IsPrime for usize {
+    fn primality(&self) -> Primality {
+        (*self as u64).primality()
+    }
+}
+
+impl IsPrime for u128 {
+    fn primality(&self) -> Primality {
+        match *self <= u64::MAX as u128 {
+            true => (*self as u64).primality(),
+            false => is_prime_biguint_auto(self),
+        }
+    }
+}
+
+impl IsPrime for BigUint {
+    fn primality(&self) -> Primality {
+        match self.bits() <= 64 {
+            true => self.to_u64_digits().first().copied().unwrap_or(0).primality(),
+            false => is_prime_biguint_auto(self),
+        }
+    }
+}
+
+impl IsPrime for U256 {
+    fn primality(&self) -> Primality {
+        baillie_psw_primality_test_u256(self)
+    }
+}
+
+
+#[test]
+fn test_is_prime() {
+    use crate::miller_rabin::is_prime_u64;
+
+    for n in 0..=u16::MAX {
+        assert_eq!(is_prime(n), table_query_u16(n), "N={}", n);
+        assert_eq!(is_prime(n as u64), is_prime(n as u128), "N={}", n);
+    }
+    assert_eq!(is_prime(97u8), Primality::Prime);
+
+    // Every size class of u64 and u128 below 2 ^ 64 is exact.
+    for n in [65537u64, 4_294_967_291, 4_294_967_297, 3_215_031_751, 3_825_123_056_546_413_051, 18446744073709551557, u64::MAX] {
+        let expected = Primality::from_proof(is_prime_u64(n));
+        assert_eq!(is_prime(n), expected, "N={}", n);
+        assert_eq!(is_prime(n as u128), expected, "N={}", n);
+        assert_eq!(is_prime(n as usize), expected, "N={}", n);
+        assert_eq!(is_prime(BigUint::from(n)), expected, "N={}", n);
+    }
+
+    // Above 2 ^ 64 the answer is probable.
+    let m127 = (1u128 << 127) - 1;
+    assert_eq!(is_prime(m127), Primality::PROBABLE);
+    assert_eq!(is_prime(m127 - 2), Primality::Composite);
+    assert_eq!(is_prime(u64::MAX as u128 * 3), Primality::Composite);
+
+    let m521 = (BigUint::from(1u8) << 521usize) - 1u8;
+    assert_eq!(is_prime(&m521), Primality::PROBABLE);
+    assert_eq!(is_prime(&m521 * &m521), Primality::Composite);
+    assert_eq!(is_prime(BigUint::from(0u8)), Primality::ZeroOrOne);
+
+    assert_eq!(is_prime(U256::from_limbs([u64::MAX, u64::MAX - 1, 0, 0])), is_prime(u128::MAX - (1 << 64)));
+}
@@ -0,0 +1,80 @@
+// Divisibility by multiplication with the modular inverse
+//
+// T. Granlund, P. Montgomery, "Division by invariant integers using
+// multiplication", PLDI 1994, section 9.
+//
+// For an odd p, multiplication by p ^ −1 (mod 2 ^ 64) permutes the u64 values
+// and maps the multiples 0, p, 2p, ..., ⌊(2 ^ 64 − 1) / p⌋ · p onto
+// 0, 1, 2, ..., ⌊(2 ^ 64 − 1) / p⌋. Hence
+//
+//      p | n  ⟺  n · p ^ −1 (mod 2 ^ 64) ≤ ⌊(2 ^ 64 − 1) / p⌋
+//
+// which costs one multiplication and one comparison instead of a division.
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SmallPrimeDivisor {
+    pub p: u64,
+    // p ^ −1 (mod 2 ^ 64)
+    pub inv: u64,
+    // ⌊(2 ^ 64 − 1) / p⌋
+    pub limit: u64,
+}
+
+impl SmallPrimeDivisor {
+    // Panics when `p` is even.
+    pub const fn new(p: u64) -> Self {
+        assert!(p % 2 == 1, "the divisor must be odd");
+
+        // NOTE: Newton 迭代，每一步正确的低位比特数翻倍：3 → 6 → 12 → 24 → 48 → 96。
+        let mut inv = p;
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+            i += 1;
+        }
+
+        SmallPrimeDivisor { p, inv, limit: u64::MAX / p }
+    }
+
+    #[inline]
+    pub fn divides(&self, n: u64) -> bool {
+        n.wrapping_mul(self.inv) <= self.limit
+    }
+}
+
+
+#[test]
+fn test_small_prime_divisor() {
+    for p in [1u64, 3, 5, 7, 65521, 4294967291, u64::MAX] {
+        let d = SmallPrimeDivisor::new(p);
+        assert_eq!(p.wrapping_mul(d.inv), 1, "P={}", p);
+
+        for n in (0..1000u64).chain(u64::MAX - 1000..=u64::MAX) {
+            assert_eq!(d.divides(n), n.is_multiple_of(p), "N={} P={}", n, p);
+        }
+        assert!(d.divides(d.limit * p));
+    }
+}
+
+#[bench]
+fn bench_small_prime_divisor(b: &mut test::Bencher) {
+    let divisors: Vec<SmallPrimeDivisor> = [3u64, 5, 7, 11, 13, 17, 19, 23, 29, 31].iter()
+        .map(|p| SmallPrimeDivisor::new(*p))
+        .collect();
+
+    b.iter(|| {
+        let n = test::black_box(4294967291u64);
+        divisors.iter().any(|d| d.divides(n))
+    })
+}
+
+#[bench]
+fn bench_remainder(b: &mut test::Bencher) {
+    let primes = [3u64, 5, 7, 11, 13, 17, 19, 23, 29, 31];
+
+    b.iter(|| {
+        let n = test::black_box(4294967291u64);
+        primes.iter().any(|p| n.is_multiple_of(test::black_box(*p)))
+    })
+}
@@ -0,0 +1,296 @@
+// Lenstra elliptic-curve factorization, stage 1
+// https://en.wikipedia.org/wiki/Lenstra_elliptic-curve_factorization
+//
+// Montgomery curves
+// https://en.wikipedia.org/wiki/Montgomery_curve
+//
+//      B·y ^ 2 = x ^ 3 + A·x ^ 2 + x
+//
+// Points are kept as projective (X : Z) pairs without y, so a sum P + Q can
+// only be formed when the difference P − Q is known. Scalar multiples [k]P are
+// built with Lucas chains, found by Montgomery's PRAC algorithm, which need
+// about 9.3 multiplications per bit of k against about 11 for the ladder.
+//
+// Stage 1 multiplies a random point by every prime power up to B1; the
+// computation breaks down, and Z shares a factor with n, modulo every prime
+// p | n for which the order of the curve mod p is B1-smooth.
+use crate::primes_up_to;
+
+use num_bigint::BigUint;
+
+
+// NOTE: PRAC 的步长比例取黄金分割 (√5 − 1) / 2，所得链长接近最优。
+const PRAC_RATIO: f64 = 0.6180339887498949;
+
+// A point (X : Z) without its y coordinate; Z = 0 is the point at infinity.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct XzPoint {
+    pub x: BigUint,
+    pub z: BigUint,
+}
+
+// A Montgomery curve modulo n, given by (A + 2) / 4 as the fraction
+// `a24_num / a24_den` so that no modular inverse is ever needed.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MontgomeryCurve {
+    n: BigUint,
+    a24_num: BigUint,
+    a24_den: BigUint,
+}
+
+impl MontgomeryCurve {
+    pub fn new(n: &BigUint, a24_num: &BigUint, a24_den: &BigUint) -> Self {
+        assert!(n.bit(0));
+
+        MontgomeryCurve { n: n.clone(), a24_num: a24_num % n, a24_den: a24_den % n }
+    }
+
+    // Suyama's parametrization, whose curves have a group order divisible by
+    // 12: with u = σ ^ 2 − 5 and v = 4σ,
+    //
+    //      (A + 2) / 4 = (v − u) ^ 3 · (3u + v) / (16 · u ^ 3 · v),  P = (u ^ 3 : v ^ 3)
+    //
+    // `sigma` must be at least 6.
+    pub fn suyama(n: &BigUint, sigma: u64) -> (Self, XzPoint) {
+        assert!(sigma >= 6);
+
+        let sigma = BigUint::from(sigma);
+        let u: BigUint = (&sigma * &sigma - 5u8) % n;
+        let v: BigUint = (sigma * 4u8) % n;
+
+        let curve = MontgomeryCurve { n: n.clone(), a24_num: BigUint::from(0u8), a24_den: BigUint::from(1u8) };
+        let u3 = curve.mul(&curve.mul(&u, &u), &u);
+        let v_minus_u = curve.sub(&v, &u);
+        let a24_num = curve.mul(&curve.mul(&curve.mul(&v_minus_u, &v_minus_u), &v_minus_u), &((&u * 3u8 + &v) % n));
+        let a24_den = curve.mul(&(&u3 * 16u8 % n), &v);
+
+        let p = XzPoint { x: u3, z: curve.mul(&curve.mul(&v, &v), &v) };
+
+        (MontgomeryCurve { n: n.clone(), a24_num, a24_den }, p)
+    }
+
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % &self.n
+    }
+
+    fn add_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        let s = a + b;
+        if s >= self.n { s - &self.n } else { s }
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        if a >= b { a - b } else { a + &self.n - b }
+    }
+
+    // [2]P
+    pub fn double(&self, p: &XzPoint) -> XzPoint {
+        let t1 = self.add_mod(&p.x, &p.z);
+        let t1 = self.mul(&t1, &t1);
+        let t2 = self.sub(&p.x, &p.z);
+        let t2 = self.mul(&t2, &t2);
+        // t3 = 4XZ
+        let t3 = self.sub(&t1, &t2);
+
+        let x = self.mul(&self.mul(&t1, &t2), &self.a24_den);
+        let z = self.mul(&t3, &self.add_mod(&self.mul(&t2, &self.a24_den), &self.mul(&t3, &self.a24_num)));
+
+        XzPoint { x, z }
+    }
+
+    // P + Q, given the difference P − Q.
+    pub fn add(&self, p: &XzPoint, q: &XzPoint, diff: &XzPoint) -> XzPoint {
+        let u = self.mul(&self.sub(&p.x, &p.z), &self.add_mod(&q.x, &q.z));
+        let v = self.mul(&self.add_mod(&p.x, &p.z), &self.sub(&q.x, &q.z));
+
+        let s = self.add_mod(&u, &v);
+        let d = self.sub(&u, &v);
+
+        XzPoint {
+            x: self.mul(&diff.z, &self.mul(&s, &s)),
+            z: self.mul(&diff.x, &self.mul(&d, &d)),
+        }
+    }
+
+    // [k]P by the Montgomery ladder.
+    pub fn ladder(&self, k: u64, p: &XzPoint) -> XzPoint {
+        if k == 0 {
+            return XzPoint { x: BigUint::from(1u8), z: BigUint::from(0u8) };
+        }
+
+        // NOTE: 始终保持 r1 − r0 = P。
+        let mut r0 = p.clone();
+        let mut r1 = self.double(p);
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+
+        r0
+    }
+
+    // [k]P by a Lucas chain from Montgomery's PRAC algorithm.
+    //
+    // Peter L. Montgomery, "Evaluating recurrences of form X_{m+n} =
+    // f(X_m, X_n, X_{m−n}) via Lucas chains", 1983.
+    pub fn prac(&self, k: u64, p: &XzPoint) -> XzPoint {
+        if k < 3 {
+            return self.ladder(k, p);
+        }
+
+        if k.is_multiple_of(2) {
+            return self.double(&self.prac(k / 2, p));
+        }
+
+        let r = (k as f64 * PRAC_RATIO).round() as u64;
+        // NOTE: 只有 gcd(k, r) = 1 时 (d, e) 才会收敛到 (1, 1)，否则退回到 ladder。
+        if num_integer::gcd(r, k) != 1 {
+            return self.ladder(k, p);
+        }
+
+        // Invariants: A = [a]P, B = [b]P, C = [a − b]P with d·a + e·b = k.
+        // NOTE: k 接近 u64::MAX 时 4d、5e 会溢出，所以在 u128 上计算。
+        let mut d = (k - r) as u128;
+        let mut e = 2 * r as u128 - k as u128;
+        let mut a = self.double(p);
+        let mut b = p.clone();
+        let mut c = p.clone();
+
+        while d != e {
+            if d < e {
+                core::mem::swap(&mut d, &mut e);
+                core::mem::swap(&mut a, &mut b);
+            }
+
+            if 4 * d <= 5 * e && (d + e).is_multiple_of(3) {
+                d = (2 * d - e) / 3;
+                e = (e - d) / 2;
+                let t = self.add(&a, &b, &c);
+                let t2 = self.add(&t, &a, &b);
+                b = self.add(&b, &t, &a);
+                a = t2;
+            } else if 4 * d <= 5 * e && (d - e).is_multiple_of(6) {
+                d = (d - e) / 2;
+                b = self.add(&a, &b, &c);
+                a = self.double(&a);
+            } else if d <= 4 * e {
+                d -= e;
+                let t = self.add(&b, &a, &c);
+                c = core::mem::replace(&mut b, t);
+            } else if (d + e).is_multiple_of(2) {
+                d = (d - e) / 2;
+                b = self.add(&b, &a, &c);
+                a = self.double(&a);
+            } else if d.is_multiple_of(2) {
+                d /= 2;
+                c = self.add(&c, &a, &b);
+                a = self.double(&a);
+            } else if d.is_multiple_of(3) {
+                d = d / 3 - e;
+                let t = self.double(&a);
+                let t2 = self.add(&a, &b, &c);
+                a = self.add(&t, &a, &a);
+                let t = self.add(&t, &t2, &c);
+                c = core::mem::replace(&mut b, t);
+            } else if (d + e).is_multiple_of(3) {
+                d = (d - 2 * e) / 3;
+                let t = self.add(&a, &b, &c);
+                b = self.add(&t, &a, &b);
+                let t = self.double(&a);
+                a = self.add(&a, &t, &a);
+            } else if (d - e).is_multiple_of(3) {
+                d = (d - e) / 3;
+                let t = self.add(&a, &b, &c);
+                c = self.add(&c, &a, &b);
+                b = t;
+                let t = self.double(&a);
+                a = self.add(&a, &t, &a);
+            } else {
+                // NOTE: 此时 d 为奇数、e 为偶数，一定满足这个条件。
+                e /= 2;
+                c = self.add(&c, &b, &a);
+                b = self.double(&b);
+            }
+        }
+
+        self.add(&a, &b, &c)
+    }
+}
+
+// Stage 1 of ECM on the curve with Suyama parameter `sigma` >= 6: a proper
+// factor of the odd n, or `None` when this curve does not split it below `b1`.
+pub fn ecm_stage1_biguint(n: &BigUint, b1: u64, sigma: u64) -> Option<BigUint> {
+    let (curve, mut p) = MontgomeryCurve::suyama(n, sigma);
+
+    for q in primes_up_to(b1) {
+        let mut power = q;
+        loop {
+            p = curve.prac(q, &p);
+            // NOTE: power <= b1 / q 等价于 power * q <= b1，且不会溢出。
+            if power > b1 / q {
+                break;
+            }
+            power *= q;
+        }
+    }
+
+    let g = num_integer::Integer::gcd(&p.z, n);
+    if g > BigUint::from(1u8) && &g < n {
+        Some(g)
+    } else {
+        None
+    }
+}
+
+
+#[test]
+fn test_montgomery_curve_prac() {
+    // 2 ^ 127 − 1 is prime, so every step stays on the curve.
+    let n = (BigUint::from(1u8) << 127usize) - 1u8;
+    let (curve, p) = MontgomeryCurve::suyama(&n, 11);
+
+    // (X : Z) and (X' : Z') are the same point when X·Z' = X'·Z.
+    let same = |a: &XzPoint, b: &XzPoint| (&a.x * &b.z) % &n == (&b.x * &a.z) % &n;
+
+    assert!(same(&curve.ladder(2, &p), &curve.double(&p)));
+    let p3 = curve.add(&curve.double(&p), &p, &p);
+    assert!(same(&curve.ladder(3, &p), &p3));
+
+    for k in 1..1000u64 {
+        assert!(same(&curve.prac(k, &p), &curve.ladder(k, &p)), "K={}", k);
+    }
+    for k in [65537u64, 1_000_003, 4294967291, u64::MAX] {
+        assert!(same(&curve.prac(k, &p), &curve.ladder(k, &p)), "K={}", k);
+    }
+
+    // [6]([7]P) = [42]P
+    assert!(same(&curve.prac(6, &curve.prac(7, &p)), &curve.ladder(42, &p)));
+}
+
+#[test]
+fn test_ecm_stage1_biguint() {
+    let n = BigUint::from(1_000_003u64 * 1_000_000_007);
+    let factor = (6..200).find_map(|sigma| ecm_stage1_biguint(&n, 2000, sigma));
+    assert_eq!(factor, Some(BigUint::from(1_000_003u64)));
+
+    // A prime is never split.
+    let p = (BigUint::from(1u8) << 89usize) - 1u8;
+    assert_eq!(ecm_stage1_biguint(&p, 1000, 6), None);
+}
+
+#[bench]
+fn bench_ecm_stage1_biguint(b: &mut test::Bencher) {
+    let n = (BigUint::from(1u8) << 127usize) - 1u8;
+
+    b.iter(|| {
+        ecm_stage1_biguint(&n, 1000, 7)
+    })
+}
@@ -0,0 +1,284 @@
+// Elliptic curve primality certificates
+// https://en.wikipedia.org/wiki/Elliptic_curve_primality
+//
+// Goldwasser–Kilian / Atkin–Morain: let E be the curve y ^ 2 = x ^ 3 + ax + b
+// over Z/nZ with gcd(n, 6) = 1 and gcd(4a ^ 3 + 27b ^ 2, n) = 1, let m = k·q
+// with q > (n ^ (1/4) + 1) ^ 2, and let P be a point on E with
+//
+//      [k]P ≠ O
+//      [m]P = O
+//
+// where every slope along the way has an invertible denominator. If q is
+// prime, so is n. A certificate chains such steps, each proving the q of the
+// step before it, down to a q below 2 ^ 64 that is checked directly.
+//
+// Verification only needs a few scalar multiplications per step, so this
+// module never produces certificates, it only checks them.
+use crate::miller_rabin::is_prime_u64;
+
+use num_integer::Integer;
+use num_bigint::{BigInt, BigUint, Sign};
+
+
+// One link of an ECPP certificate.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct EcppStep {
+    pub n: BigUint,
+    // The curve y ^ 2 = x ^ 3 + ax + b (mod n)
+    pub a: BigUint,
+    pub b: BigUint,
+    // The order m = k·q claimed for the point.
+    pub m: BigUint,
+    pub q: BigUint,
+    // The point P = (x, y)
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct EcppCertificate {
+    // From n downward; the q of every step is the n of the next one.
+    pub steps: Vec<EcppStep>,
+}
+
+impl EcppCertificate {
+    // The number this certificate proves prime.
+    pub fn n(&self) -> Option<&BigUint> {
+        self.steps.first().map(|step| &step.n)
+    }
+
+    pub fn verify(&self) -> bool {
+        let last = match self.steps.last() {
+            Some(last) => last,
+            None => return false,
+        };
+
+        let linked = self.steps.windows(2).all(|w| w[0].q == w[1].n);
+        if !linked || !self.steps.iter().all(EcppStep::verify) {
+            return false;
+        }
+
+        // NOTE: 链条的最后一个 q 必须足够小，能够直接确定性地判定。
+        last.q.bits() <= 64 && is_prime_u64(last.q.to_u64_digits()[0])
+    }
+}
+
+impl EcppStep {
+    // Whether this step proves that n is prime provided q is.
+    pub fn verify(&self) -> bool {
+        let n = &self.n;
+        let zero = BigUint::from(0u8);
+        let one  = BigUint::from(1u8);
+
+        if n.bits() < 3 || n.gcd(&BigUint::from(6u8)) != one {
+            return false;
+        }
+
+        if [&self.a, &self.b, &self.x, &self.y].iter().any(|v| *v >= n) {
+            return false;
+        }
+
+        // q > (n ^ (1/4) + 1) ^ 2, checked against ⌈n ^ (1/4)⌉ so that no
+        // fractional root is needed.
+        let mut r = n.nth_root(4);
+        if r.pow(4u32) < *n {
+            r += 1u8;
+        }
+        let bound = (&r + 1u8) * (&r + 1u8);
+        if self.q <= bound || &self.m % &self.q != zero {
+            return false;
+        }
+
+        let k = &self.m / &self.q;
+
+        // 4a ^ 3 + 27b ^ 2
+        let disc = (&self.a * &self.a * &self.a * 4u8 + &self.b * &self.b * 27u8) % n;
+        if disc.gcd(n) != one {
+            return false;
+        }
+
+        // y ^ 2 = x ^ 3 + ax + b
+        let (x, y) = (&self.x, &self.y);
+        if (y * y) % n != (x * x * x + &self.a * x + &self.b) % n {
+            return false;
+        }
+
+        let curve = Curve::new(n, &self.a);
+        let p = Point::Affine(x.clone(), y.clone());
+
+        let kp = match curve.mul(&k, &p) {
+            Some(Point::Affine(x, y)) => Point::Affine(x, y),
+            _ => return false,
+        };
+
+        curve.mul(&self.q, &kp) == Some(Point::Infinity)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Point {
+    Infinity,
+    Affine(BigUint, BigUint),
+}
+
+struct Curve<'a> {
+    n: &'a BigUint,
+    a: &'a BigUint,
+    // n as a signed integer, converted once instead of on every inversion
+    n_signed: BigInt,
+}
+
+impl<'a> Curve<'a> {
+    fn new(n: &'a BigUint, a: &'a BigUint) -> Self {
+        Curve { n, a, n_signed: BigInt::from_biguint(Sign::Plus, n.clone()) }
+    }
+
+    // 1 / v (mod n), or `None` when v shares a factor with n.
+    fn inverse(&self, v: &BigUint) -> Option<BigUint> {
+        let v = BigInt::from_biguint(Sign::Plus, v % self.n);
+
+        let e = v.extended_gcd(&self.n_signed);
+        if e.gcd != BigInt::from(1u8) {
+            return None;
+        }
+
+        e.x.mod_floor(&self.n_signed).to_biguint()
+    }
+
+    // P + Q, or `None` when a denominator is not invertible, which exposes n
+    // as composite.
+    fn add(&self, p: &Point, q: &Point) -> Option<Point> {
+        let n = self.n;
+        let (x1, y1, x2, y2) = match (p, q) {
+            (Point::Infinity, _) => return Some(q.clone()),
+            (_, Point::Infinity) => return Some(p.clone()),
+            (Point::Affine(x1, y1), Point::Affine(x2, y2)) => (x1, y1, x2, y2),
+        };
+
+        let lambda = if x1 == x2 {
+            if (y1 + y2) % n == BigUint::from(0u8) {
+                return Some(Point::Infinity);
+            }
+
+            // NOTE: 模素数时 x 相同意味着 y = ±y'，否则 n 必然是合数。
+            if y1 != y2 {
+                return None;
+            }
+
+            // (3x ^ 2 + a) / 2y
+            (x1 * x1 * 3u8 + self.a) * self.inverse(&(y1 * 2u8))? % n
+        } else {
+            // (y2 − y1) / (x2 − x1)
+            (y2 + n - y1) * self.inverse(&(x2 + n - x1))? % n
+        };
+
+        let x3 = (&lambda * &lambda + n * 2u8 - x1 - x2) % n;
+        let y3 = (lambda * (x1 + n - &x3) + n - y1) % n;
+
+        Some(Point::Affine(x3, y3))
+    }
+
+    // [k]P, by double-and-add.
+    fn mul(&self, k: &BigUint, p: &Point) -> Option<Point> {
+        let mut ret = Point::Infinity;
+        for i in (0..k.bits()).rev() {
+            ret = self.add(&ret, &ret)?;
+            if k.bit(i) {
+                ret = self.add(&ret, p)?;
+            }
+        }
+
+        Some(ret)
+    }
+}
+
+
+#[test]
+fn test_ecpp_certificate_verify() {
+    let step = |n: &str, a: &str, b: &str, m: &str, q: &str, x: &str, y: &str| EcppStep {
+        n: n.parse().unwrap(),
+        a: a.parse().unwrap(),
+        b: b.parse().unwrap(),
+        m: m.parse().unwrap(),
+        q: q.parse().unwrap(),
+        x: x.parse().unwrap(),
+        y: y.parse().unwrap(),
+    };
+
+    // A 128-bit prime, built with CM curves of discriminant −3 and −4.
+    let cert = EcppCertificate { steps: vec![
+        step(
+            "304272993624995748841678725082160714249",
+            "192632971434353656168060735799590948918",
+            "0",
+            "304272993624995748829171878260993688436",
+            "214990767665709319704859150715329",
+            "281695882817430079087483752239506483468",
+            "119492866839383003289010246906460814683",
+        ),
+        step(
+            "214990767665709319704859150715329",
+            "0",
+            "48169441910356679833982573811292",
+            "214990767665709293514142654270153",
+            "169684899499375922268463026259",
+            "96557999367360338837276534359130",
+            "98272596015258703820179272693764",
+        ),
+        step(
+            "169684899499375922268463026259",
+            "0",
+            "15543847239156614030362615095",
+            "169684899499376746052503756323",
+            "18593361993340133323",
+            "87695253515551540256600929973",
+            "61679360796738881134179188819",
+        ),
+        step(
+            "18593361993340133323",
+            "0",
+            "15761736697162528370",
+            "18593361992094905124",
+            "6537750348837871",
+            "16709170487662013578",
+            "5259747177875694068",
+        ),
+    ] };
+
+    assert_eq!(cert.n(), Some(&"304272993624995748841678725082160714249".parse().unwrap()));
+    assert!(cert.verify());
+    assert!(cert.steps.iter().all(EcppStep::verify));
+
+    // The tail of a chain is a certificate for its own n.
+    assert!(EcppCertificate { steps: cert.steps[1..].to_vec() }.verify());
+
+    // The chain must reach a q below 2 ^ 64.
+    assert!(!EcppCertificate { steps: cert.steps[..2].to_vec() }.verify());
+    assert!(!EcppCertificate { steps: Vec::new() }.verify());
+
+    // Broken links
+    let mut steps = cert.steps.clone();
+    steps.remove(1);
+    assert!(!EcppCertificate { steps }.verify());
+
+    // P is not on the curve.
+    let mut bad = cert.steps[0].clone();
+    bad.y += 1u8;
+    assert!(!bad.verify());
+
+    // m is not the order of P.
+    let mut bad = cert.steps[0].clone();
+    bad.m = &bad.q * 2u8;
+    assert!(!bad.verify());
+
+    // q is too small for the theorem to apply.
+    let mut bad = cert.steps[3].clone();
+    bad.q = BigUint::from(7u8);
+    bad.m = BigUint::from(7u8);
+    assert!(!bad.verify());
+
+    // The same curve and point over a composite modulus.
+    let mut bad = cert.steps[0].clone();
+    bad.n = &bad.n * &cert.steps[1].n;
+    assert!(!bad.verify());
+}
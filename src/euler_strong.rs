@@ -0,0 +1,105 @@
+// Combined Euler–Jacobi and strong probable prime test
+//
+// Both tests raise the same base to (a divisor of) n − 1. Writing n − 1 = 2 ^ s · d,
+// the strong test looks at the chain
+//
+//      a ^ d, a ^ 2d, ..., a ^ (2 ^ (s − 1) · d) = a ^ ((n − 1) / 2)
+//
+// and the last link is exactly what the Euler–Jacobi test compares with the
+// Jacobi symbol (a | n). One exponentiation per base therefore feeds both
+// checks, at the cost of one extra Jacobi symbol.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::PrimalityTester;
+use crate::jacobi_symbol_biguint;
+use crate::miller_rabin::decompose_n_minus_one_biguint;
+
+use num_bigint::BigUint;
+
+
+pub fn euler_plus_strong_test(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+    // Input #1: n, any integer; n < 2 ^ 32 (2 and 3 included) and even n are answered exactly
+    // Input #2: k, the number of random bases to try
+    crate::global().tester().euler_plus_strong_biguint(n, k)
+}
+
+pub(crate) fn euler_plus_strong_with(n: &BigUint, k: usize, tester: &PrimalityTester) -> Primality {
+    debug_assert!(k > 0);
+
+    let one   = BigUint::from(1u8);
+    let three = BigUint::from(3u8);
+
+    if cfg!(debug_assertions) {
+        debug_assert!(n > &three && n.bit(0));
+    }
+
+    if tester.has_small_factor(n) {
+        return Primality::Composite;
+    }
+
+    let n_minus_one = n - 1u8;
+    let (d, s) = decompose_n_minus_one_biguint(n);
+
+    let mut witnesses = crate::global().witnesses(n);
+
+    for _ in 0..k {
+        // a in the range [2, n − 2]
+        let a = witnesses.next(&n_minus_one);
+
+        // x = a ^ d, then squared until it reaches a ^ ((n − 1) / 2).
+        let mut x = a.modpow(&d, n);
+        let mut strong = x == one || x == n_minus_one;
+        for _ in 1..s {
+            x = &x * &x % n;
+            // NOTE: 在出现 n − 1 之前先出现 1，说明找到了 1 的非平凡平方根。
+            if !strong && x == one {
+                return Primality::Composite;
+            }
+            strong |= x == n_minus_one;
+        }
+
+        if !strong {
+            return Primality::Composite;
+        }
+
+        // NOTE: 理论上通过强测试的底数一定也通过 Euler 测试，这里的 Jacobi 符号只是一次廉价的交叉验证。
+        let euler = match jacobi_symbol_biguint(&a, n) {
+            1 => x == one,
+            -1 => x == n_minus_one,
+            _ => false,
+        };
+
+        if !euler {
+            return Primality::Composite;
+        }
+    }
+
+    Primality::PROBABLE
+}
+
+
+#[test]
+fn test_euler_plus_strong_test() {
+    use crate::table_query_u16;
+
+    for n in (5..u16::MAX).step_by(2) {
+        let b = table_query_u16(n).is_prime();
+        let a = euler_plus_strong_test(&n, 8).is_accepted();
+        assert_eq!(a, b, "N={}", n);
+    }
+
+    // Carmichael numbers and strong pseudoprimes to small bases.
+    for n in [561u64, 1105, 1729, 2047, 3215031751, 3825123056546413051] {
+        assert_eq!(euler_plus_strong_test(&n, 20), Primality::Composite, "N={}", n);
+    }
+
+    // 2 ^ 127 − 1 and 2 ^ 89 − 1
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    assert_eq!(euler_plus_strong_test(&m127, 10), Primality::PROBABLE);
+    assert_eq!(euler_plus_strong_test(&(&m127 * &m89), 10), Primality::Composite);
+
+    let tester = PrimalityTester::new().with_prefilter_bound(0);
+    assert_eq!(tester.euler_plus_strong_biguint(&1105u16, 10), Primality::Composite);
+    assert_eq!(tester.euler_plus_strong_biguint(&m89, 10), Primality::PROBABLE);
+}
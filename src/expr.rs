@@ -0,0 +1,227 @@
+// Integer expressions, as typed on a command line
+//
+//      expr   = term (('+' | '-') term)*
+//      term   = power ('*' power)*
+//      power  = atom ('^' power)?
+//      atom   = number | '(' expr ')'
+//      number = digits ('.' digits)? (('e' | 'E') digits)?
+//
+// so that `1_000_003`, `1e18+9`, `2^127-1` and `(2^61-1)*(2^89-1)` all work.
+// Digits may be grouped with `_` and whitespace is ignored. A decimal point is
+// only allowed when the exponent moves it out of the way (`2.5e6`, but not
+// `2.55e1`), and no intermediate result may go negative.
+use num_bigint::BigUint;
+
+use std::fmt;
+
+
+// NOTE: 限制结果的大小，避免 `10^10^10` 这样的输入耗尽内存。
+const MAX_BITS: u64 = 1 << 20;
+
+// The byte offset in the input where parsing failed.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ParseExprError {
+    pub position: usize,
+}
+
+impl fmt::Display for ParseExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid number expression at offset {}", self.position)
+    }
+}
+
+pub fn parse_biguint_expr(s: &str) -> Result<BigUint, ParseExprError> {
+    let mut parser = Parser { s: s.as_bytes(), pos: 0 };
+
+    let n = parser.expr()?;
+    match parser.peek() {
+        None => Ok(n),
+        Some(_) => Err(parser.error()),
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> ParseExprError {
+        ParseExprError { position: self.pos }
+    }
+
+    // The next byte that is not whitespace.
+    fn peek(&mut self) -> Option<u8> {
+        while self.pos < self.s.len() && self.s[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+
+        self.s.get(self.pos).copied()
+    }
+
+    fn expr(&mut self) -> Result<BigUint, ParseExprError> {
+        let mut acc = self.term()?;
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.pos += 1;
+                    acc += self.term()?;
+                },
+                Some(b'-') => {
+                    let at = self.pos;
+                    self.pos += 1;
+                    let rhs = self.term()?;
+                    if rhs > acc {
+                        return Err(ParseExprError { position: at });
+                    }
+                    acc -= rhs;
+                },
+                _ => return Ok(acc),
+            }
+
+            if acc.bits() > MAX_BITS {
+                return Err(self.error());
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<BigUint, ParseExprError> {
+        let mut acc = self.power()?;
+        while self.peek() == Some(b'*') {
+            self.pos += 1;
+            acc *= self.power()?;
+
+            if acc.bits() > MAX_BITS {
+                return Err(self.error());
+            }
+        }
+
+        Ok(acc)
+    }
+
+    fn power(&mut self) -> Result<BigUint, ParseExprError> {
+        let base = self.atom()?;
+        if self.peek() != Some(b'^') {
+            return Ok(base);
+        }
+
+        let at = self.pos;
+        self.pos += 1;
+
+        // NOTE: 右结合，2^3^2 = 2^9。
+        let exp = self.power()?;
+        pow(&base, &exp).ok_or(ParseExprError { position: at })
+    }
+
+    fn atom(&mut self) -> Result<BigUint, ParseExprError> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let n = self.expr()?;
+                if self.peek() != Some(b')') {
+                    return Err(self.error());
+                }
+                self.pos += 1;
+                Ok(n)
+            },
+            Some(b) if b.is_ascii_digit() => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<BigUint, ParseExprError> {
+        let start = self.pos;
+
+        let mut digits = self.digits();
+        let mut scale = 0usize;
+        if self.s.get(self.pos) == Some(&b'.') {
+            self.pos += 1;
+            let frac = self.digits();
+            if frac.is_empty() {
+                return Err(self.error());
+            }
+            scale = frac.len();
+            digits.push_str(&frac);
+        }
+
+        let mut exp = 0usize;
+        if matches!(self.s.get(self.pos), Some(b'e') | Some(b'E')) {
+            self.pos += 1;
+            exp = self.digits().parse().map_err(|_| self.error())?;
+        }
+
+        // 2.5e6 = 25e5
+        let exp = match exp.checked_sub(scale) {
+            Some(exp) => exp,
+            None => return Err(ParseExprError { position: start }),
+        };
+
+        let mantissa: BigUint = digits.parse().map_err(|_| ParseExprError { position: start })?;
+        pow(&BigUint::from(10u8), &BigUint::from(exp))
+            .map(|scale| mantissa * scale)
+            .filter(|n| n.bits() <= MAX_BITS)
+            .ok_or(ParseExprError { position: start })
+    }
+
+    // A run of digits and `_`, with the underscores dropped.
+    fn digits(&mut self) -> String {
+        let mut out = String::new();
+        while let Some(b) = self.s.get(self.pos) {
+            match b {
+                b'0'..=b'9' => out.push(*b as char),
+                b'_' => {},
+                _ => break,
+            }
+            self.pos += 1;
+        }
+
+        out
+    }
+}
+
+// base ^ exp, or `None` when the result would exceed `MAX_BITS`.
+fn pow(base: &BigUint, exp: &BigUint) -> Option<BigUint> {
+    let one = BigUint::from(1u8);
+    if base <= &one || exp.bits() == 0 {
+        return Some(if exp.bits() == 0 { one } else { base.clone() });
+    }
+
+    let exp: u32 = exp.to_u32_digits().first().copied().filter(|_| exp.bits() <= 32)?;
+    if (base.bits() - 1).saturating_mul(exp as u64) > MAX_BITS {
+        return None;
+    }
+
+    Some(base.pow(exp))
+}
+
+
+#[test]
+fn test_parse_biguint_expr() {
+    let parse = |s: &str| parse_biguint_expr(s).map(|n| n.to_string());
+    let err = |s: &str| parse_biguint_expr(s).unwrap_err().position;
+
+    assert_eq!(parse("1000003"), Ok("1000003".to_string()));
+    assert_eq!(parse("1_000_003"), Ok("1000003".to_string()));
+    assert_eq!(parse("1e18+9"), Ok("1000000000000000009".to_string()));
+    assert_eq!(parse("1E3 + 9"), Ok("1009".to_string()));
+    assert_eq!(parse("2.5e6"), Ok("2500000".to_string()));
+    assert_eq!(parse("2^127-1"), Ok("170141183460469231731687303715884105727".to_string()));
+    assert_eq!(parse("(2^61-1)*(2^31-1)"), Ok("4951760154835678088235319297".to_string()));
+    assert_eq!(parse("2^3^2"), Ok("512".to_string()));
+    assert_eq!(parse("2*3+4*5"), Ok("26".to_string()));
+    assert_eq!(parse("10-10"), Ok("0".to_string()));
+    assert_eq!(parse("0^0"), Ok("1".to_string()));
+    assert_eq!(parse("1^99999999999999999999"), Ok("1".to_string()));
+
+    assert_eq!(err(""), 0);
+    assert_eq!(err("-1"), 0);
+    assert_eq!(err("e5"), 0);
+    assert_eq!(err("1-2"), 1);
+    assert_eq!(err("2.55e1"), 0);
+    assert_eq!(err("2."), 2);
+    assert_eq!(err("(1+2"), 4);
+    assert_eq!(err("1+2)"), 3);
+    assert_eq!(err("12 34"), 3);
+    assert_eq!(err("10^10^10"), 2);
+    assert_eq!(err("1e99999999"), 0);
+}
@@ -0,0 +1,872 @@
+// Integer factorization
+// https://en.wikipedia.org/wiki/Integer_factorization
+//
+// Pollard's rho algorithm (Brent's variant)
+// https://en.wikipedia.org/wiki/Pollard%27s_rho_algorithm#Variants
+//
+// Pollard's p − 1 algorithm, stage 1 only
+// https://en.wikipedia.org/wiki/Pollard%27s_p_%E2%88%92_1_algorithm
+//
+// Lenstra's elliptic-curve method, stage 1 only (see ecm.rs)
+use crate::miller_rabin::is_prime_u64;
+use crate::lcm_of_primes_powers_up_to;
+use crate::montgomery::Montgomery128;
+use crate::{ecm_stage1_biguint, is_prime_biguint_auto};
+use crate::{AsBigUint, Factorization, Primality};
+use crate::small_vec::SmallVec;
+
+use num_integer::Integer;
+use num_bigint::BigUint;
+
+use core::convert::TryFrom;
+use core::num::NonZeroU64;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Method {
+    // Divide by every candidate (2, 3 and 6k ± 1) up to the given bound.
+    TrialTo(u64),
+    // Pollard's rho with Brent's cycle detection, giving up on a cofactor
+    // after `max_iterations` polynomial evaluations.
+    Rho { max_iterations: u64 },
+    // Pollard's p − 1 with base 2, finding the prime factors p for which
+    // p − 1 divides lcm(1, 2, ..., b1).
+    PMinusOne { b1: u64 },
+    // ECM stage 1 on `curves` Suyama curves with bound `b1`, finding the prime
+    // factors p for which the order of one of the curves mod p is b1-smooth.
+    Ecm { b1: u64, curves: u64 },
+    // Run `walks` rho walks with different polynomials concurrently, each with
+    // its own `max_iterations` budget; the first factor found wins.
+    #[cfg(feature = "parallel")]
+    ParallelRho { max_iterations: u64, walks: u64 },
+    // `Ecm` with the curves spread over the thread pool.
+    #[cfg(feature = "parallel")]
+    ParallelEcm { b1: u64, curves: u64 },
+}
+
+// A cofactor that could not be split yet.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Pending {
+    pub n: BigUint,
+    // The next rho polynomial `x ^ 2 + c` to try, so that resuming does not
+    // repeat walks that already failed.
+    pub rho_seed: u64,
+    // The next ECM curve to try, as its Suyama parameter σ (at least 6).
+    pub sigma: u64,
+}
+
+impl Pending {
+    fn new(n: BigUint) -> Self {
+        Pending { n, rho_seed: 1, sigma: 6 }
+    }
+}
+
+// Partial (or complete) factorization, returned by `Factorizer::factor` and
+// accepted again by `Factorizer::resume`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct FactorState {
+    primes: Vec<BigUint>,
+    pending: Vec<Pending>,
+}
+
+impl FactorState {
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // Prime factors found so far, as (prime, exponent) pairs in ascending order.
+    pub fn factors(&self) -> Vec<(BigUint, u32)> {
+        let mut primes = self.primes.clone();
+        primes.sort_unstable();
+
+        let mut factors: Vec<(BigUint, u32)> = Vec::new();
+        for p in primes.into_iter() {
+            match factors.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+
+        factors
+    }
+
+    // The factorization, once complete, of a number that fits in a u64.
+    pub fn factorization(&self) -> Option<Factorization> {
+        if !self.is_complete() || self.primes.iter().product::<BigUint>().bits() > 64 {
+            return None;
+        }
+
+        let factors = self.factors().iter()
+            .map(|(p, e)| u64::try_from(p).map(|p| (p, *e)))
+            .collect::<Result<Vec<(u64, u32)>, _>>()
+            .ok()?;
+
+        Some(Factorization::from_factors(factors))
+    }
+
+    // Composite cofactors no method has managed to split.
+    pub fn pending(&self) -> &[Pending] {
+        &self.pending
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Factorizer {
+    methods: Vec<Method>,
+}
+
+impl Factorizer {
+    pub fn new() -> Self {
+        Factorizer { methods: Vec::new() }
+    }
+
+    // Methods run in the order they were added.
+    pub fn with(mut self, method: Method) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    pub fn factor(&self, n: u64) -> FactorState {
+        debug_assert!(n > 0);
+
+        match NonZeroU64::new(n) {
+            Some(n) => self.factor_nonzero(n),
+            None => FactorState::default(),
+        }
+    }
+
+    pub fn factor_nonzero(&self, n: NonZeroU64) -> FactorState {
+        self.factor_biguint(&n.get())
+    }
+
+    // Like `factor`, for numbers of any size.
+    pub fn factor_biguint(&self, n: &(impl AsBigUint + ?Sized)) -> FactorState {
+        let n = n.as_biguint();
+        debug_assert!(n.bits() > 0);
+
+        let mut state = FactorState::default();
+        let twos = match n.trailing_zeros() {
+            Some(twos) => twos,
+            None => return state,
+        };
+
+        // NOTE: rho 和 ECM 只能处理奇数，所以无论选择了哪些方法，先把因子 2 全部除掉。
+        for _ in 0..twos {
+            state.primes.push(BigUint::from(2u8));
+        }
+
+        let n = n.as_ref() >> twos;
+        if n.bits() > 1 {
+            state.pending.push(Pending::new(n));
+        }
+
+        self.resume(state)
+    }
+
+    pub fn resume(&self, state: FactorState) -> FactorState {
+        let FactorState { mut primes, pending } = state;
+
+        let mut todo: Vec<Pending> = Vec::new();
+        for item in pending.into_iter() {
+            if is_prime(&item.n) {
+                primes.push(item.n);
+            } else {
+                todo.push(item);
+            }
+        }
+
+        for method in self.methods.iter() {
+            let mut failed: Vec<Pending> = Vec::new();
+
+            while let Some(item) = todo.pop() {
+                match run(*method, item) {
+                    Ok(mut parts) => {
+                        while let Some(part) = parts.pop() {
+                            // NOTE: bits() > 1 即 n >= 2，试除剩下的 1 在这里丢掉。
+                            if part.n.bits() <= 1 {
+                                continue;
+                            }
+
+                            if is_prime(&part.n) {
+                                primes.push(part.n);
+                            } else {
+                                todo.push(part);
+                            }
+                        }
+                    },
+                    Err(item) => failed.push(item),
+                }
+            }
+
+            todo = failed;
+        }
+
+        FactorState { primes, pending: todo }
+    }
+}
+
+
+// Prime factorization of `n` as (prime, exponent) pairs in ascending order.
+pub fn factorize_u64(n: u64) -> Vec<(u64, u32)> {
+    debug_assert!(n > 0);
+
+    factorize_u64_inline(n).to_vec()
+}
+
+pub fn factorize_nonzero_u64(n: NonZeroU64) -> Vec<(u64, u32)> {
+    factorize_u64_inline(n.get()).to_vec()
+}
+
+// `factorize_u64` without touching the heap: trial division up to 2 ^ 10,
+// then rho on whatever is left. An empty list for 0 and 1.
+//
+// NOTE: 前 16 个素数之积已经超过 2 ^ 64，所以不同的素因子最多 15 个；
+//       计重数最多 63 个。两个缓冲区都不会搬到堆上。
+pub(crate) fn factorize_u64_inline(n: u64) -> SmallVec<(u64, u32), 16> {
+    let mut primes: SmallVec<u64, 64> = SmallVec::new();
+    let mut todo: SmallVec<u64, 64> = SmallVec::new();
+
+    if n > 0 {
+        match trial_divide_u64(n, 1 << 10, |p| primes.push(p)) {
+            1 => {},
+            m => todo.push(m),
+        }
+    }
+
+    let stop = AtomicBool::new(false);
+    while let Some(m) = todo.pop() {
+        if is_prime_u64(m) {
+            primes.push(m);
+            continue;
+        }
+
+        let mut c = 1;
+        loop {
+            let mut budget = u64::MAX;
+            if let Some(d) = pollard_brent_rho_u64(m, c, &mut budget, &stop) {
+                todo.push(d);
+                todo.push(m / d);
+                break;
+            }
+            c += 1;
+        }
+    }
+
+    primes.sort_unstable();
+
+    let mut factors: SmallVec<(u64, u32), 16> = SmallVec::new();
+    for p in primes.iter().copied() {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+
+    factors
+}
+
+// NOTE: 64 bits 以内的判定是确定的；更大的余因子按概率素数处理。
+fn is_prime(n: &BigUint) -> bool {
+    match u64::try_from(n) {
+        Ok(n) => is_prime_u64(n),
+        Err(_) => is_prime_biguint_auto(n) != Primality::Composite,
+    }
+}
+
+// The two parts of `n` after a proper factor `d` was found.
+fn split(n: &BigUint, d: BigUint) -> SmallVec<Pending, 2> {
+    let cofactor = n / &d;
+    SmallVec::Inline([Pending::new(d), Pending::new(cofactor)], 2)
+}
+
+// Split `item.n`, returning the parts on success or the updated item on failure.
+//
+// NOTE: 除了试除，每个方法成功时都恰好分成两部分，所以 N = 2。
+fn run(method: Method, item: Pending) -> Result<SmallVec<Pending, 2>, Pending> {
+    match method {
+        Method::TrialTo(bound) => {
+            let mut parts: SmallVec<Pending, 2> = SmallVec::new();
+            let n = match u64::try_from(&item.n) {
+                Ok(n) => BigUint::from(trial_divide_u64(n, bound, |p| parts.push(Pending::new(BigUint::from(p))))),
+                Err(_) => trial_divide_biguint(&item.n, bound, &mut parts),
+            };
+
+            if parts.is_empty() {
+                return Err(item);
+            }
+
+            parts.push(Pending { n, ..item });
+            Ok(parts)
+        },
+        Method::PMinusOne { b1 } => {
+            let x = BigUint::from(2u8).modpow(&lcm_of_primes_powers_up_to(b1), &item.n);
+
+            // NOTE: g = n 说明 n 的所有素因子都被同时找到，这个界对 n 来说太大了。
+            let g = (x + &item.n - 1u8).gcd(&item.n);
+            if g.bits() <= 1 || g == item.n {
+                return Err(item);
+            }
+
+            let cofactor = &item.n / &g;
+            Ok(SmallVec::Inline([Pending::new(g), Pending { n: cofactor, ..item }], 2))
+        },
+        Method::Rho { max_iterations } => {
+            let mut budget = max_iterations;
+            let mut c = item.rho_seed;
+            let stop = AtomicBool::new(false);
+
+            while budget > 0 {
+                if let Some(d) = pollard_brent_rho(&item.n, c, &mut budget, &stop) {
+                    return Ok(split(&item.n, d));
+                }
+
+                // NOTE: 预算用完时，当前的多项式还没有走完，下次从这里继续。
+                if budget > 0 {
+                    c += 1;
+                }
+            }
+
+            Err(Pending { rho_seed: c, ..item })
+        },
+        Method::Ecm { b1, curves } => {
+            let first = item.sigma.max(6);
+            let sigmas = first..first.saturating_add(curves);
+
+            for sigma in sigmas.clone() {
+                if let Some(d) = ecm_stage1_biguint(&item.n, b1, sigma) {
+                    return Ok(split(&item.n, d));
+                }
+            }
+
+            Err(Pending { sigma: sigmas.end, ..item })
+        },
+        #[cfg(feature = "parallel")]
+        Method::ParallelRho { max_iterations, walks } => {
+            use rayon::prelude::*;
+
+            // NOTE: 任意一个 walk 找到因子之后，通知其它 walk 尽快退出。
+            let stop = AtomicBool::new(false);
+            let seeds = item.rho_seed..item.rho_seed.saturating_add(walks);
+
+            let found = seeds.clone().into_par_iter().find_map_any(|c| {
+                let mut budget = max_iterations;
+                let d = pollard_brent_rho(&item.n, c, &mut budget, &stop);
+                if d.is_some() {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                d
+            });
+
+            match found {
+                Some(d) => Ok(split(&item.n, d)),
+                None => Err(Pending { rho_seed: seeds.end, ..item }),
+            }
+        },
+        #[cfg(feature = "parallel")]
+        Method::ParallelEcm { b1, curves } => {
+            use rayon::prelude::*;
+
+            let first = item.sigma.max(6);
+            let sigmas = first..first.saturating_add(curves);
+
+            // NOTE: 一条曲线一旦开始就会跑完 stage 1，find_map_any 只是不再启动新的曲线。
+            let found = sigmas.clone().into_par_iter().find_map_any(|sigma| ecm_stage1_biguint(&item.n, b1, sigma));
+
+            match found {
+                Some(d) => Ok(split(&item.n, d)),
+                None => Err(Pending { sigma: sigmas.end, ..item }),
+            }
+        },
+    }
+}
+
+// Divide `n` by every candidate up to `bound` (and up to √n), pushing the
+// prime factors found to `push`; returns what is left.
+fn trial_divide_u64(mut n: u64, bound: u64, mut push: impl FnMut(u64)) -> u64 {
+    let mut divide = |p: u64, n: &mut u64| {
+        while n.is_multiple_of(p) {
+            *n /= p;
+            push(p);
+        }
+    };
+
+    divide(2, &mut n);
+    divide(3, &mut n);
+
+    let mut i = 5u64;
+    while i <= bound && i.saturating_mul(i) <= n {
+        divide(i, &mut n);
+        divide(i + 2, &mut n);
+        i += 6;
+    }
+
+    n
+}
+
+fn trial_divide_biguint(n: &BigUint, bound: u64, parts: &mut SmallVec<Pending, 2>) -> BigUint {
+    let mut n = n.clone();
+    let mut divide = |p: u64, n: &mut BigUint| {
+        while (&*n % p).bits() == 0 {
+            *n /= p;
+            parts.push(Pending::new(BigUint::from(p)));
+        }
+    };
+
+    divide(2, &mut n);
+    divide(3, &mut n);
+
+    // NOTE: 只有在 n 变小之后才需要重新计算 √n。
+    let mut i = 5u64;
+    let mut limit = u64::try_from(n.sqrt()).unwrap_or(u64::MAX);
+    while i <= bound && i <= limit {
+        let before = n.bits();
+        divide(i, &mut n);
+        divide(i + 2, &mut n);
+        if n.bits() < before {
+            limit = u64::try_from(n.sqrt()).unwrap_or(u64::MAX);
+        }
+        i += 6;
+    }
+
+    n
+}
+
+// One budgeted rho walk on the odd composite `n`, in the narrowest arithmetic
+// that holds it.
+fn pollard_brent_rho(n: &BigUint, c: u64, budget: &mut u64, stop: &AtomicBool) -> Option<BigUint> {
+    match u64::try_from(n) {
+        Ok(n) => pollard_brent_rho_u64(n, c, budget, stop).map(BigUint::from),
+        Err(_) => pollard_brent_rho_biguint_with(n, c, budget, &|| stop.load(Ordering::Relaxed)),
+    }
+}
+
+fn modmul_u64(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128) * (b as u128) % (m as u128)) as u64
+}
+// One rho walk on f(x) = x ^ 2 + c (mod n), spending at most `budget`
+// evaluations of f. Returns a non-trivial factor of the odd composite `n`.
+// The walk is abandoned as soon as `stop` is set.
+fn pollard_brent_rho_u64(n: u64, c: u64, budget: &mut u64, stop: &AtomicBool) -> Option<u64> {
+    // NOTE: 每次调用 gcd 之前，先累乘 M 个差值，以减少 gcd 的调用次数。
+    const M: u64 = 128;
+
+    let f = |x: u64| ((modmul_u64(x, x, n) as u128 + c as u128) % n as u128) as u64;
+
+    let mut y = 2 % n;
+    let mut x = y;
+    let mut ys = y;
+    let mut q = 1u64;
+    let mut g = 1u64;
+    let mut r = 1u64;
+
+    while g == 1 {
+        if *budget < r {
+            *budget = 0;
+            return None;
+        }
+        *budget -= r;
+
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0u64;
+        while k < r && g == 1 {
+            ys = y;
+            let steps = M.min(r - k);
+            if *budget < steps {
+                *budget = 0;
+                return None;
+            }
+            *budget -= steps;
+
+            for _ in 0..steps {
+                y = f(y);
+                q = modmul_u64(q, x.max(y) - x.min(y), n);
+            }
+
+            g = q.gcd(&n);
+            k += M;
+
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+        }
+
+        r *= 2;
+    }
+
+    if g == n {
+        // NOTE: 累乘的结果恰好包含了 n 的所有因子，退回到逐步计算 gcd。
+        loop {
+            ys = f(ys);
+            g = (x.max(ys) - x.min(ys)).gcd(&n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    if g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+
+// One rho walk for an odd composite `n` below 2 ^ 128, abandoned once
+// `deadline` has passed.
+pub(crate) fn pollard_brent_rho_u128(n: u128, c: u64, deadline: Instant) -> Option<u128> {
+    let mut budget = u64::MAX;
+    pollard_brent_rho_u128_with(n, c, &mut budget, &|| Instant::now() >= deadline)
+}
+
+// As `pollard_brent_rho_u128`, spending at most `budget` evaluations of f and
+// abandoned as soon as `stop` returns true.
+//
+// The walk runs in Montgomery form: x ↦ x·x·R ^ −1 + c·R is the map
+// x ↦ x ^ 2 + c in disguise, and x·R − y·R shares its factors with x − y, so
+// neither the steps nor the accumulated product ever need a division.
+fn pollard_brent_rho_u128_with(n: u128, c: u64, budget: &mut u64, stop: &dyn Fn() -> bool) -> Option<u128> {
+    const M: u64 = 128;
+
+    let ctx = Montgomery128::new(n);
+    let c = ctx.encode(c as u128);
+    let f = |x: u128| ctx.add(ctx.mul(x, x), c);
+
+    let mut y = ctx.encode(2);
+    let mut x = y;
+    let mut ys = y;
+    let mut q = ctx.one();
+    let mut g = 1u128;
+    let mut r = 1u64;
+
+    while g == 1 {
+        if *budget < r {
+            *budget = 0;
+            return None;
+        }
+        *budget -= r;
+
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0u64;
+        while k < r && g == 1 {
+            ys = y;
+            let steps = M.min(r - k);
+            if *budget < steps {
+                *budget = 0;
+                return None;
+            }
+            *budget -= steps;
+
+            for _ in 0..steps {
+                y = f(y);
+                q = ctx.mul(q, x.max(y) - x.min(y));
+            }
+
+            g = q.gcd(&n);
+            k += M;
+
+            if stop() {
+                return None;
+            }
+        }
+
+        r *= 2;
+    }
+
+    if g == n {
+        loop {
+            ys = f(ys);
+            g = (x.max(ys) - x.min(ys)).gcd(&n);
+            if g > 1 {
+                break;
+            }
+        }
+    }
+
+    if g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+// One rho walk on f(x) = x ^ 2 + c (mod n) for an odd composite `n` of any
+// size, abandoned once `deadline` has passed.
+pub(crate) fn pollard_brent_rho_biguint(n: &BigUint, c: u64, deadline: Instant) -> Option<BigUint> {
+    let mut budget = u64::MAX;
+    pollard_brent_rho_biguint_with(n, c, &mut budget, &|| Instant::now() >= deadline)
+}
+
+// As `pollard_brent_rho_biguint`, spending at most `budget` evaluations of f
+// and abandoned as soon as `stop` returns true.
+fn pollard_brent_rho_biguint_with(n: &BigUint, c: u64, budget: &mut u64, stop: &dyn Fn() -> bool) -> Option<BigUint> {
+    const M: u64 = 128;
+
+    // NOTE: 128 bits 以内改用 Montgomery 形式的 u128 运算，不再分配内存。
+    if let Ok(n) = u128::try_from(n) {
+        return pollard_brent_rho_u128_with(n, c, budget, stop).map(BigUint::from);
+    }
+
+    let one = BigUint::from(1u8);
+    let f = |x: &BigUint| (x * x + c) % n;
+    let diff = |a: &BigUint, b: &BigUint| if a > b { a - b } else { b - a };
+
+    let mut y = BigUint::from(2u8) % n;
+    let mut x = y.clone();
+    let mut ys = y.clone();
+    let mut q = one.clone();
+    let mut g = one.clone();
+    let mut r = 1u64;
+
+    while g == one {
+        if *budget < r {
+            *budget = 0;
+            return None;
+        }
+        *budget -= r;
+
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+
+        let mut k = 0u64;
+        while k < r && g == one {
+            ys = y.clone();
+            let steps = M.min(r - k);
+            if *budget < steps {
+                *budget = 0;
+                return None;
+            }
+            *budget -= steps;
+
+            for _ in 0..steps {
+                y = f(&y);
+                q = q * diff(&x, &y) % n;
+            }
+
+            g = q.gcd(n);
+            k += M;
+
+            if stop() {
+                return None;
+            }
+        }
+
+        r *= 2;
+    }
+
+    if &g == n {
+        loop {
+            ys = f(&ys);
+            g = diff(&x, &ys).gcd(n);
+            if g > one {
+                break;
+            }
+        }
+    }
+
+    if &g == n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+#[test]
+fn test_factorize_u64() {
+    fn product(factors: &[(u64, u32)]) -> u64 {
+        factors.iter().map(|(p, e)| p.pow(*e)).product()
+    }
+
+    for n in 1..20_000u64 {
+        let factors = factorize_u64(n);
+        assert_eq!(product(&factors), n, "N={}", n);
+        assert!(factors.iter().all(|(p, _)| is_prime_u64(*p)), "N={} {:?}", n, factors);
+    }
+
+    assert_eq!(factorize_u64(1 << 63), vec![(2, 63)]);
+    assert_eq!(factorize_nonzero_u64(NonZeroU64::new(1).unwrap()), vec![]);
+    assert_eq!(factorize_nonzero_u64(NonZeroU64::new(360).unwrap()), factorize_u64(360));
+    assert_eq!(Factorizer::new().with(Method::TrialTo(10)).factor(360).factorization().map(|f| f.to_string()), Some("2^3 · 3^2 · 5".to_string()));
+    assert_eq!(Factorizer::new().factor(360).factorization(), None);
+    assert_eq!(factorize_u64(600851475143), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+    // 4294967291 * 4294967279
+    assert_eq!(factorize_u64(18446743979220271189), vec![(4294967279, 1), (4294967291, 1)]);
+    assert_eq!(factorize_u64(18446744073709551557), vec![(18446744073709551557, 1)]);
+    assert_eq!(factorize_u64(u64::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6700417, 1)]);
+
+    // The most distinct primes, and the most primes counted with multiplicity,
+    // a u64 can have both fit inline.
+    let primorial = [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47].iter().product();
+    assert_eq!(factorize_u64_inline(primorial).len(), 15);
+    assert!(!factorize_u64_inline(primorial).spilled());
+    assert!(!factorize_u64_inline(1 << 63).spilled());
+    assert!(factorize_u64_inline(0).is_empty());
+}
+
+#[test]
+fn test_factorizer_p_minus_one() {
+    // The order of 2 is 1232 = 2^4·7·11 modulo 110881 and 1000002 modulo 1000003.
+    let n = 110881 * 1000003;
+    let state = Factorizer::new().with(Method::PMinusOne { b1: 16 }).factor(n);
+    assert!(state.is_complete());
+    assert_eq!(state.factorization().unwrap().factors(), [(110881, 1), (1000003, 1)]);
+
+    // 2^4 does not divide lcm(1, ..., 15).
+    let state = Factorizer::new().with(Method::PMinusOne { b1: 15 }).factor(n);
+    assert_eq!(state.pending(), [Pending { n: BigUint::from(n), rho_seed: 1, sigma: 6 }]);
+
+    // The order of 2 modulo 2^31 − 1 is 31: with both orders below the bound
+    // the gcd is n itself.
+    let n = 110881 * 2147483647;
+    let state = Factorizer::new().with(Method::PMinusOne { b1: 1000 }).factor(n);
+    assert_eq!(state.pending(), [Pending { n: BigUint::from(n), rho_seed: 1, sigma: 6 }]);
+}
+
+#[test]
+fn test_factorizer_resume() {
+    // 4294967291 * 4294967279, out of reach for trial division alone.
+    let n = 18446743979220271189u64;
+
+    let state = Factorizer::new().with(Method::TrialTo(1 << 20)).factor(n);
+    assert!(!state.is_complete());
+    assert_eq!(state.pending().len(), 1);
+    assert_eq!(state.pending()[0].n, BigUint::from(n));
+
+    let state = Factorizer::new().with(Method::Rho { max_iterations: 1 }).resume(state);
+    assert!(!state.is_complete());
+
+    let state = Factorizer::new().with(Method::Rho { max_iterations: u64::MAX }).resume(state);
+    assert!(state.is_complete());
+    assert_eq!(state.factorization().unwrap().factors(), [(4294967279, 1), (4294967291, 1)]);
+}
+
+#[test]
+fn test_factorizer_biguint() {
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+
+    // Trial division leaves a prime cofactor well beyond 64 bits.
+    let n = (BigUint::from(1u8) << 64usize) * 243u32 * 1_000_003u32 * &m89;
+    let state = Factorizer::new().with(Method::TrialTo(1 << 20)).factor_biguint(&n);
+    assert!(state.is_complete());
+    assert_eq!(state.factors(), vec![
+        (BigUint::from(2u8), 64),
+        (BigUint::from(3u8), 5),
+        (BigUint::from(1_000_003u32), 1),
+        (m89.clone(), 1),
+    ]);
+    assert_eq!(state.factorization(), None);
+
+    // Rho in u128 and in BigUint arithmetic.
+    for n in [&m89 * 4294967291u32, &m127 * 1_000_003u32] {
+        let state = Factorizer::new().with(Method::Rho { max_iterations: u64::MAX }).factor_biguint(&n);
+        assert!(state.is_complete(), "N={}", n);
+        assert_eq!(state.factors().len(), 2, "N={}", n);
+    }
+}
+
+#[test]
+fn test_factorizer_ecm() {
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let n = &m127 * 1_000_003u32;
+
+    let state = Factorizer::new().with(Method::Ecm { b1: 2000, curves: 200 }).factor_biguint(&n);
+    assert!(state.is_complete());
+    assert_eq!(state.factors(), vec![(BigUint::from(1_000_003u32), 1), (m127.clone(), 1)]);
+
+    // Curves that failed are not tried again on resume.
+    let sigma = (6..206).find(|sigma| ecm_stage1_biguint(&n, 2000, *sigma).is_some()).unwrap();
+    let state = Factorizer::new().with(Method::Ecm { b1: 2000, curves: sigma - 6 }).factor_biguint(&n);
+    assert!(!state.is_complete());
+    assert_eq!(state.pending(), [Pending { n: n.clone(), rho_seed: 1, sigma }]);
+
+    let state = Factorizer::new().with(Method::Ecm { b1: 2000, curves: 1 }).resume(state);
+    assert!(state.is_complete());
+
+    // Without a curve of smooth order nothing is found.
+    let state = Factorizer::new().with(Method::Ecm { b1: 10, curves: 4 }).factor_biguint(&n);
+    assert_eq!(state.pending(), [Pending { n, rho_seed: 1, sigma: 10 }]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_factorizer_parallel_rho() {
+    let n = 18446743979220271189u64;
+
+    let state = Factorizer::new().with(Method::ParallelRho { max_iterations: u64::MAX, walks: 8 }).factor(n);
+    assert!(state.is_complete());
+    assert_eq!(state.factorization().unwrap().factors(), [(4294967279, 1), (4294967291, 1)]);
+
+    let state = Factorizer::new().with(Method::ParallelRho { max_iterations: 1, walks: 8 }).factor(n);
+    assert!(!state.is_complete());
+    assert_eq!(state.pending()[0].rho_seed, 9);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_factorizer_parallel_biguint() {
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let n = &m127 * 1_000_003u32;
+
+    let state = Factorizer::new().with(Method::ParallelRho { max_iterations: u64::MAX, walks: 4 }).factor_biguint(&n);
+    assert_eq!(state.factors(), vec![(BigUint::from(1_000_003u32), 1), (m127.clone(), 1)]);
+
+    let state = Factorizer::new().with(Method::ParallelEcm { b1: 2000, curves: 200 }).factor_biguint(&n);
+    assert_eq!(state.factors(), vec![(BigUint::from(1_000_003u32), 1), (m127.clone(), 1)]);
+
+    let state = Factorizer::new().with(Method::ParallelEcm { b1: 10, curves: 8 }).factor_biguint(&n);
+    assert_eq!(state.pending(), [Pending { n, rho_seed: 1, sigma: 14 }]);
+}
+
+#[test]
+fn test_pollard_brent_rho_u128() {
+    use std::time::Duration;
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let next_prime = |n: u64| (n..).find(|n| is_prime_u64(*n)).unwrap();
+
+    // 40-bit × 88-bit, 37-bit × 88-bit and 32-bit × 96-bit, all just below 2 ^ 128
+    let p88 = (1u128 << 88) - 299;
+    for (p, q) in [
+        (next_prime((1 << 40) - (1 << 20)) as u128, p88),
+        (next_prime((1 << 36) + 12345) as u128, p88),
+        (4294967291, (1u128 << 96) - 17),
+    ] {
+        let n = p * q;
+        let d = (1..).find_map(|c| pollard_brent_rho_u128(n, c, deadline)).unwrap();
+        assert!(d == p || d == q, "N={} d={}", n, d);
+
+        let d = (1..).find_map(|c| pollard_brent_rho_biguint(&BigUint::from(n), c, deadline)).unwrap();
+        assert!(d == BigUint::from(p) || d == BigUint::from(q), "N={} d={}", n, d);
+    }
+}
+
+#[bench]
+fn bench_pollard_brent_rho_u128(b: &mut test::Bencher) {
+    let deadline = Instant::now() + std::time::Duration::from_secs(3600);
+    // 4294967291 · (2 ^ 96 − 17)
+    let n = 4294967291u128 * ((1u128 << 96) - 17);
+    b.iter(|| {
+        pollard_brent_rho_u128(test::black_box(n), 1, deadline)
+    })
+}
+
+#[bench]
+fn bench_factorize_u64(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(18446743979220271189u64);
+        factorize_u64(n)
+    })
+}
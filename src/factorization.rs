@@ -0,0 +1,150 @@
+// Complete prime factorizations as text
+//
+// A `Factorization` prints as `2^3 · 5 · 17^2`: the prime factors in
+// ascending order, separated by " · ", with the exponent after a caret when
+// it is not 1. The factorization of 1 is written `1`. Parsing accepts the same
+// format and, for fixtures typed by hand, also `*` as the separator and
+// factors in any order or repeated; the result is always normalized.
+use crate::factorize_u64;
+use crate::miller_rabin::is_prime_u64;
+
+use core::fmt;
+use core::str::FromStr;
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Factorization {
+    // (prime, exponent) pairs in ascending order, empty for 1.
+    factors: Vec<(u64, u32)>,
+}
+
+impl Factorization {
+    // `factors` must be (prime, exponent) pairs in ascending order.
+    pub(crate) fn from_factors(factors: Vec<(u64, u32)>) -> Self {
+        Factorization { factors }
+    }
+
+    pub fn factors(&self) -> &[(u64, u32)] {
+        &self.factors
+    }
+
+    // The number factored.
+    pub fn value(&self) -> u64 {
+        // NOTE: 构造时已经检查过乘积不会溢出。
+        self.factors.iter().map(|(p, e)| p.pow(*e)).product()
+    }
+}
+
+// The prime factorization of `n`, which must not be 0.
+pub fn factorize(n: u64) -> Factorization {
+    assert!(n > 0, "0 has no factorization");
+
+    Factorization::from_factors(factorize_u64(n))
+}
+
+impl fmt::Display for Factorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+
+        for (i, (p, e)) in self.factors.iter().enumerate() {
+            if i > 0 {
+                write!(f, " · ")?;
+            }
+            match e {
+                1 => write!(f, "{}", p)?,
+                _ => write!(f, "{}^{}", p, e)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The term of a factorization that could not be understood, counting from 1:
+// not a prime power, or one that makes the product overflow a u64.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ParseFactorizationError {
+    pub term: usize,
+}
+
+impl fmt::Display for ParseFactorizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid factorization at term {}", self.term)
+    }
+}
+
+impl FromStr for Factorization {
+    type Err = ParseFactorizationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "1" {
+            return Ok(Factorization::default());
+        }
+
+        let mut factors: Vec<(u64, u32)> = Vec::new();
+        let mut product = 1u64;
+        for (i, term) in s.split(['·', '*']).enumerate() {
+            let invalid = ParseFactorizationError { term: i + 1 };
+
+            let (p, e) = match term.trim().split_once('^') {
+                Some((p, e)) => (p.trim(), e.trim().parse::<u32>().map_err(|_| invalid)?),
+                None => (term.trim(), 1),
+            };
+            let p: u64 = p.parse().map_err(|_| invalid)?;
+            if e == 0 || !is_prime_u64(p) {
+                return Err(invalid);
+            }
+
+            product = p.checked_pow(e).and_then(|q| product.checked_mul(q)).ok_or(invalid)?;
+            factors.push((p, e));
+        }
+
+        factors.sort_unstable();
+        let mut merged: Vec<(u64, u32)> = Vec::with_capacity(factors.len());
+        for (p, e) in factors {
+            match merged.last_mut() {
+                Some((q, f)) if *q == p => *f += e,
+                _ => merged.push((p, e)),
+            }
+        }
+
+        Ok(Factorization { factors: merged })
+    }
+}
+
+
+#[test]
+fn test_factorization_display() {
+    assert_eq!(factorize(1).to_string(), "1");
+    assert_eq!(factorize(2).to_string(), "2");
+    assert_eq!(factorize(11560).to_string(), "2^3 · 5 · 17^2");
+    assert_eq!(factorize(1 << 63).to_string(), "2^63");
+    assert_eq!(factorize(u64::MAX).to_string(), "3 · 5 · 17 · 257 · 641 · 65537 · 6700417");
+    assert_eq!(factorize(11560).value(), 11560);
+
+    for n in (1..10_000u64).chain(u64::MAX - 10_000..=u64::MAX) {
+        let f = factorize(n);
+        assert_eq!(f.to_string().parse::<Factorization>(), Ok(f.clone()), "N={}", n);
+        assert_eq!(f.value(), n);
+    }
+}
+
+#[test]
+fn test_factorization_from_str() {
+    let f: Factorization = "17^2 * 2 · 5 * 2^2".parse().unwrap();
+    assert_eq!(f, factorize(11560));
+    assert_eq!(f.factors(), [(2, 3), (5, 1), (17, 2)]);
+    assert_eq!(" 1 ".parse::<Factorization>(), Ok(Factorization::default()));
+
+    let err = |term| Err(ParseFactorizationError { term });
+    assert_eq!("".parse::<Factorization>(), err(1));
+    assert_eq!("4".parse::<Factorization>(), err(1));
+    assert_eq!("2 · 9".parse::<Factorization>(), err(2));
+    assert_eq!("2 · 3^0".parse::<Factorization>(), err(2));
+    assert_eq!("2 · 3^".parse::<Factorization>(), err(2));
+    assert_eq!("2^63 · 2".parse::<Factorization>(), err(2));
+    assert_eq!("2^64".parse::<Factorization>(), err(1));
+    assert_eq!("1 · 2".parse::<Factorization>(), err(1));
+}
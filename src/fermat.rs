@@ -0,0 +1,122 @@
+// Fermat primality test
+// https://en.wikipedia.org/wiki/Fermat_primality_test
+//
+// n is a probable prime to base a when a ^ (n − 1) ≡ 1 (mod n). Every
+// Carmichael number passes for all bases coprime to it, so unlike the other
+// tests in this crate the error does not shrink with k on such inputs. Kept
+// for teaching, as a cheap prefilter, and to compare pseudoprimes against the
+// strong tests.
+//
+// NOTE: 为了能观察到伪素数，这里不对小的 n 直接给出确定的结果。
+use crate::Primality;
+use crate::AsBigUint;
+use crate::miller_rabin::modpow;
+
+use rand::Rng;
+use num_bigint::{BigUint, RandBigInt};
+
+
+pub fn fermat_primality_test_u64(n: u64, k: usize) -> Primality {
+    match n {
+        0 | 1 => return Primality::ZeroOrOne,
+        2 | 3 => return Primality::Prime,
+        _ if n.is_multiple_of(2) => return Primality::Composite,
+        _ => {},
+    }
+
+    debug_assert!(k > 0);
+
+    let mut rng = crate::global().rng();
+    for _ in 0..k {
+        // a in the range [2, n − 2]
+        let a: u64 = rng.gen_range(2, n - 1);
+        if modpow(a, n - 1, n) != 1 {
+            return Primality::Composite;
+        }
+    }
+
+    Primality::PROBABLE
+}
+
+pub fn fermat_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    if n.bits() <= 64 {
+        return fermat_primality_test_u64(n.to_u64_digits().first().copied().unwrap_or(0), k);
+    }
+
+    if !n.bit(0) {
+        return Primality::Composite;
+    }
+
+    debug_assert!(k > 0);
+
+    let one = BigUint::from(1u8);
+    let two = BigUint::from(2u8);
+    let n_minus_one: BigUint = n - 1u8;
+
+    let mut rng = crate::global().rng();
+    for _ in 0..k {
+        let a = rng.gen_biguint_range(&two, &n_minus_one);
+        if a.modpow(&n_minus_one, n) != one {
+            return Primality::Composite;
+        }
+    }
+
+    Primality::PROBABLE
+}
+
+
+#[test]
+fn test_fermat_primality_test_u64() {
+    use crate::table_query_u16;
+
+    assert_eq!(fermat_primality_test_u64(0, 10), Primality::ZeroOrOne);
+    assert_eq!(fermat_primality_test_u64(2, 10), Primality::Prime);
+    assert_eq!(fermat_primality_test_u64(4, 10), Primality::Composite);
+    assert_eq!(fermat_primality_test_u64(18446744073709551557, 10), Primality::PROBABLE);
+
+    // Primes always pass; composites with few Fermat liars are caught.
+    for n in 5..u16::MAX as u64 {
+        let ret = fermat_primality_test_u64(n, 20);
+        if table_query_u16(n as u16).is_prime() {
+            assert_eq!(ret, Primality::PROBABLE, "N={}", n);
+        }
+    }
+    for n in [91u64, 341, 4033, 1_000_000_007 * 998_244_353] {
+        assert_eq!(fermat_primality_test_u64(n, 20), Primality::Composite, "N={}", n);
+    }
+
+    // A Carmichael number with large factors fools every round, but not
+    // Miller–Rabin: (6k + 1)(12k + 1)(18k + 1) with k = 200116.
+    let carmichael = 10386066643795453969u64;
+    assert_eq!(fermat_primality_test_u64(carmichael, 20), Primality::PROBABLE);
+    assert_eq!(crate::miller_rabin_primality_test_u64(carmichael), Primality::Composite);
+}
+
+#[test]
+fn test_fermat_primality_test_biguint() {
+    assert_eq!(fermat_primality_test_biguint(&97u8, 10), Primality::PROBABLE);
+    assert_eq!(fermat_primality_test_biguint(&100u8, 10), Primality::Composite);
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    assert_eq!(fermat_primality_test_biguint(&m127, 10), Primality::PROBABLE);
+    assert_eq!(fermat_primality_test_biguint(&(&m127 * &m89), 10), Primality::Composite);
+    assert_eq!(fermat_primality_test_biguint(&(&m127 << 1usize), 10), Primality::Composite);
+
+    // The Carmichael number (6k + 1)(12k + 1)(18k + 1) with k = 100000131
+    let n: BigUint = "1296005097246682578520326409".parse().unwrap();
+    assert_eq!(fermat_primality_test_biguint(&n, 20), Primality::PROBABLE);
+    assert_eq!(crate::miller_rabin_primality_test_biguint(&n, 20), Primality::Composite);
+}
+
+#[bench]
+fn bench_fermat_primality_test_biguint(b: &mut test::Bencher) {
+    let n = (BigUint::from(1u8) << 521usize) - 1u8;
+
+    b.iter(|| {
+        fermat_primality_test_biguint(&n, 1)
+    })
+}
@@ -0,0 +1,175 @@
+// Fibonacci probable prime test
+// https://en.wikipedia.org/wiki/Fibonacci_pseudoprime
+//
+// For a prime n other than 5, F(n − (n / 5)) ≡ 0 (mod n), where (n / 5) is
+// the Legendre symbol: +1 when n ≡ ±1 (mod 5), −1 when n ≡ ±2. F(m) mod n
+// comes from fast doubling,
+//
+//      F(2k)     = F(k)·(2F(k + 1) − F(k))
+//      F(2k + 1) = F(k) ^ 2 + F(k + 1) ^ 2,
+//
+// about as cheap as one modular exponentiation.
+//
+// Together with a base-2 Fermat test this is the "PSW" test of Pomerance,
+// Selfridge and Wagstaff: no composite n ≡ ±2 (mod 5) is known to pass both,
+// and none below 2 ^ 64 does (checked against Feitsma's list of base-2
+// pseudoprimes). For n ≡ ±1 (mod 5) there are composites passing both, the
+// first being the Carmichael number 6601 = 7 · 23 · 41.
+//
+// NOTE: 与 Fermat 测试一样，这里不对小的 n 直接给出确定的结果，以便观察伪素数。
+use crate::Primality;
+use crate::AsBigUint;
+use crate::ModCtx;
+use crate::miller_rabin::modpow;
+
+use num_bigint::BigUint;
+
+
+pub fn fibonacci_primality_test_u64(n: u64) -> Primality {
+    match n {
+        0 | 1 => return Primality::ZeroOrOne,
+        2 | 3 | 5 => return Primality::Prime,
+        _ if n.is_multiple_of(2) || n.is_multiple_of(5) => return Primality::Composite,
+        _ => {},
+    }
+
+    // NOTE: u64::MAX 是 5 的倍数，所以 n + 1 不会溢出。
+    let m = match n % 5 {
+        1 | 4 => n - 1,
+        _ => n + 1,
+    };
+
+    let ctx = ModCtx::new(n);
+    let (mut f, mut g) = (ctx.zero(), ctx.one());
+    for i in (0..u64::BITS - m.leading_zeros()).rev() {
+        let f2 = f * (g + g - f);
+        let g2 = f * f + g * g;
+        (f, g) = match (m >> i) & 1 {
+            1 => (g2, f2 + g2),
+            _ => (f2, g2),
+        };
+    }
+
+    match f.is_zero() {
+        true => Primality::PROBABLE,
+        false => Primality::Composite,
+    }
+}
+
+pub fn fibonacci_primality_test_biguint(n: &(impl AsBigUint + ?Sized)) -> Primality {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    if n.bits() <= 64 {
+        return fibonacci_primality_test_u64(n.to_u64_digits().first().copied().unwrap_or(0));
+    }
+
+    let r5 = (n % 5u8).to_u64_digits().first().copied().unwrap_or(0);
+    if !n.bit(0) || r5 == 0 {
+        return Primality::Composite;
+    }
+
+    let m: BigUint = match r5 {
+        1 | 4 => n - 1u8,
+        _ => n + 1u8,
+    };
+
+    let (mut f, mut g) = (BigUint::from(0u8), BigUint::from(1u8));
+    for i in (0..m.bits()).rev() {
+        // NOTE: 2g − f 可能为负，先加上 n。
+        let f2 = &f * ((&g << 1usize) + n - &f) % n;
+        let g2 = (&f * &f + &g * &g) % n;
+        (f, g) = match m.bit(i) {
+            true => { let s = (&f2 + &g2) % n; (g2, s) },
+            false => (f2, g2),
+        };
+    }
+
+    match f.bits() == 0 {
+        true => Primality::PROBABLE,
+        false => Primality::Composite,
+    }
+}
+
+// A base-2 Fermat test followed by the Fibonacci test: exact for n < 2 ^ 64
+// with n ≡ ±2 (mod 5), a probable prime otherwise.
+pub fn fermat_fibonacci_test_u64(n: u64) -> Primality {
+    match n {
+        0 | 1 => return Primality::ZeroOrOne,
+        2 | 3 | 5 => return Primality::Prime,
+        _ if n.is_multiple_of(2) => return Primality::Composite,
+        _ => {},
+    }
+
+    if modpow(2, n - 1, n) != 1 {
+        return Primality::Composite;
+    }
+
+    match (fibonacci_primality_test_u64(n), n % 5) {
+        (Primality::PROBABLE, 2 | 3) => Primality::Prime,
+        (ret, _) => ret,
+    }
+}
+
+
+#[test]
+fn test_fibonacci_primality_test_u64() {
+    use crate::table_query_u16;
+
+    assert_eq!(fibonacci_primality_test_u64(1), Primality::ZeroOrOne);
+    assert_eq!(fibonacci_primality_test_u64(5), Primality::Prime);
+    assert_eq!(fibonacci_primality_test_u64(25), Primality::Composite);
+    assert_eq!(fibonacci_primality_test_u64(18446744073709551557), Primality::PROBABLE);
+    assert_eq!(fibonacci_primality_test_u64(u64::MAX), Primality::Composite);
+
+    // OEIS A081264, the composites passing below 30000
+    let mut pseudoprimes = Vec::new();
+    for n in 7..30_000u64 {
+        let ret = fibonacci_primality_test_u64(n);
+        if n <= u16::MAX as u64 && table_query_u16(n as u16).is_prime() {
+            assert_eq!(ret, Primality::PROBABLE, "N={}", n);
+        } else if ret == Primality::PROBABLE {
+            pseudoprimes.push(n);
+        }
+    }
+    assert_eq!(pseudoprimes, [
+        323, 377, 1891, 3827, 4181, 5777, 6601, 6721, 8149, 10877, 11663, 13201, 13981, 15251, 17119,
+        17711, 18407, 19043, 23407, 25877, 27323,
+    ]);
+
+    // Only the two ≡ 1 (mod 5) are base-2 Fermat pseudoprimes as well.
+    let both: Vec<u64> = pseudoprimes.iter().copied().filter(|n| fermat_fibonacci_test_u64(*n).is_accepted()).collect();
+    assert_eq!(both, [6601, 13981]);
+    assert_eq!(fermat_fibonacci_test_u64(6601), Primality::PROBABLE);
+    assert_eq!(fermat_fibonacci_test_u64(1_000_000_007), Primality::Prime);
+    assert_eq!(fermat_fibonacci_test_u64(1_000_000_009), Primality::PROBABLE);
+}
+
+#[test]
+fn test_fibonacci_primality_test_biguint() {
+    assert_eq!(fibonacci_primality_test_biguint(&323u16), Primality::PROBABLE);
+    assert_eq!(fibonacci_primality_test_biguint(&97u8), Primality::PROBABLE);
+
+    // Both residues of n mod 5 among the primes
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m521 = (BigUint::from(1u8) << 521usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    assert_eq!((&m127 % 5u8, &m521 % 5u8), (BigUint::from(2u8), BigUint::from(1u8)));
+    assert_eq!(fibonacci_primality_test_biguint(&m127), Primality::PROBABLE);
+    assert_eq!(fibonacci_primality_test_biguint(&m521), Primality::PROBABLE);
+    assert_eq!(fibonacci_primality_test_biguint(&(&m127 * &m89)), Primality::Composite);
+    assert_eq!(fibonacci_primality_test_biguint(&(&m127 * 5u8)), Primality::Composite);
+
+    // Agrees with the u64 version on the boundary
+    for n in [18446744073709551557u64, 18446744073709551559, 4181, 6601] {
+        assert_eq!(fibonacci_primality_test_biguint(&n), fibonacci_primality_test_u64(n), "N={}", n);
+    }
+}
+
+#[bench]
+fn bench_fibonacci_primality_test_u64(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(18446744073709551557u64);
+        fibonacci_primality_test_u64(n)
+    })
+}
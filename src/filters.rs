@@ -0,0 +1,235 @@
+// Divisibility by small primes read off the digits
+// https://en.wikipedia.org/wiki/Divisibility_rule
+//
+// In base b,
+//
+//   * for p | b, n ≡ (last digit) (mod p);
+//   * for p | b − 1, n ≡ (sum of the digits) (mod p), since b ≡ 1;
+//   * for p | b + 1, n ≡ (alternating sum from the last digit) (mod p), since
+//     b ≡ −1.
+//
+// Searches for primes of a special decimal (or other base) form — palindromic,
+// truncatable, repdigit-like — build candidates digit by digit, so the digits
+// are at hand and these rules reject a candidate without converting it or
+// dividing it.
+//
+// Digits are most significant first, as `BigUint::to_radix_be` returns them.
+use num_bigint::BigUint;
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Rule {
+    LastDigit,
+    DigitSum,
+    AlternatingSum,
+}
+
+// Divisibility by one prime p, in one base.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DigitFilter {
+    rule: Rule,
+    base: u32,
+    p: u32,
+}
+
+impl DigitFilter {
+    // The rule for a prime p dividing `base`, if p is one.
+    pub fn last_digit(base: u32, p: u32) -> Option<Self> {
+        Self::new(Rule::LastDigit, base, p, base)
+    }
+
+    // The rule for a prime p dividing `base` − 1, if p is one.
+    pub fn digit_sum(base: u32, p: u32) -> Option<Self> {
+        Self::new(Rule::DigitSum, base, p, base - 1)
+    }
+
+    // The rule for a prime p dividing `base` + 1, if p is one.
+    pub fn alternating_sum(base: u32, p: u32) -> Option<Self> {
+        Self::new(Rule::AlternatingSum, base, p, base + 1)
+    }
+
+    fn new(rule: Rule, base: u32, p: u32, m: u32) -> Option<Self> {
+        assert!((2..=256).contains(&base), "base must be in 2 ..= 256");
+
+        match crate::is_prime_u32(p).is_prime() && m.is_multiple_of(p) {
+            true => Some(DigitFilter { rule, base, p }),
+            false => None,
+        }
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    pub fn p(&self) -> u32 {
+        self.p
+    }
+
+    // n mod p for the number with these digits.
+    pub fn residue(&self, digits: &[u8]) -> u32 {
+        let p = self.p as u64;
+        match self.rule {
+            Rule::LastDigit => digits.last().map_or(0, |d| (*d as u64 % p) as u32),
+            Rule::DigitSum => (digits.iter().map(|d| *d as u64).sum::<u64>() % p) as u32,
+            Rule::AlternatingSum => {
+                let (even, odd) = digits.iter().rev().enumerate()
+                    .fold((0u64, 0u64), |(even, odd), (i, d)| match i % 2 {
+                        0 => (even + *d as u64, odd),
+                        _ => (even, odd + *d as u64),
+                    });
+                ((even % p + p - odd % p) % p) as u32
+            },
+        }
+    }
+
+    pub fn divides(&self, digits: &[u8]) -> bool {
+        self.residue(digits) == 0
+    }
+
+    // Whether the number is a proper multiple of p, hence not prime. p itself
+    // is not rejected.
+    pub fn rejects(&self, digits: &[u8]) -> bool {
+        self.divides(digits) && !is_value(digits, self.base, self.p)
+    }
+}
+
+// The filters of every rule that applies in one base.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct DigitFilters {
+    base: u32,
+    filters: Vec<DigitFilter>,
+}
+
+impl DigitFilters {
+    // Every prime dividing `base`, `base` − 1 or `base` + 1, tested by the
+    // cheapest rule that applies: for base 10, 2 and 5 by the last digit, 3 by
+    // the digit sum and 11 by the alternating sum.
+    pub fn for_base(base: u32) -> Self {
+        assert!((2..=256).contains(&base), "base must be in 2 ..= 256");
+
+        let mut filters = Vec::new();
+        for p in (2..=base + 1).filter(|p| crate::is_prime_u32(*p).is_prime()) {
+            let filter = DigitFilter::last_digit(base, p)
+                .or_else(|| DigitFilter::digit_sum(base, p))
+                .or_else(|| DigitFilter::alternating_sum(base, p));
+            filters.extend(filter);
+        }
+
+        DigitFilters { base, filters }
+    }
+
+    // No filter at all, to be built up with `with`.
+    pub fn empty(base: u32) -> Self {
+        assert!((2..=256).contains(&base), "base must be in 2 ..= 256");
+
+        DigitFilters { base, filters: Vec::new() }
+    }
+
+    pub fn with(mut self, filter: DigitFilter) -> Self {
+        assert_eq!(filter.base, self.base, "filter for another base");
+
+        if !self.filters.contains(&filter) {
+            self.filters.push(filter);
+        }
+        self
+    }
+
+    pub fn filters(&self) -> &[DigitFilter] {
+        &self.filters
+    }
+
+    // The first filter that proves the number composite, if any.
+    pub fn rejected_by(&self, digits: &[u8]) -> Option<&DigitFilter> {
+        self.filters.iter().find(|filter| filter.rejects(digits))
+    }
+
+    pub fn rejects(&self, digits: &[u8]) -> bool {
+        self.rejected_by(digits).is_some()
+    }
+
+    // `rejects` for a BigUint, converted to digits first.
+    pub fn rejects_biguint(&self, n: &BigUint) -> bool {
+        self.rejects(&n.to_radix_be(self.base))
+    }
+}
+
+// Whether the digits spell exactly v.
+fn is_value(digits: &[u8], base: u32, v: u32) -> bool {
+    // NOTE: p ≤ base + 1 < base ^ 2，所以只需要看最后两位，前面的位必须全为 0。
+    let n = digits.len();
+    if n > 2 && digits[..n - 2].iter().any(|d| *d != 0) {
+        return false;
+    }
+
+    let value = digits.iter().rev().take(2).rev().fold(0u32, |acc, d| acc * base + *d as u32);
+    value == v
+}
+
+
+#[test]
+fn test_digit_filters() {
+    use crate::table_query_u16;
+
+    let ten = DigitFilters::for_base(10);
+    let ps: Vec<u32> = ten.filters().iter().map(|f| f.p()).collect();
+    assert_eq!(ps, [2, 3, 5, 11]);
+    assert_eq!(DigitFilter::digit_sum(10, 3), Some(ten.filters()[1]));
+    assert_eq!(DigitFilter::digit_sum(10, 5), None);
+    assert_eq!(DigitFilter::alternating_sum(10, 7), None);
+    assert_eq!(DigitFilter::last_digit(10, 10), None);
+
+    for base in [2u32, 3, 7, 10, 16, 36, 256] {
+        let filters = DigitFilters::for_base(base);
+        for n in 0..=u16::MAX {
+            let digits = BigUint::from(n).to_radix_be(base);
+            for filter in filters.filters() {
+                assert_eq!(filter.residue(&digits), n as u32 % filter.p(), "base={} N={} p={}", base, n, filter.p());
+            }
+
+            // Every rejected number is composite, 0 or 1; the primes p themselves pass.
+            if filters.rejects(&digits) {
+                assert!(!table_query_u16(n).is_prime(), "base={} N={}", base, n);
+            }
+        }
+
+        // Leading zeros do not change anything.
+        for filter in filters.filters() {
+            let mut digits = vec![0, 0, 0];
+            digits.extend(BigUint::from(filter.p()).to_radix_be(base));
+            assert!(!filter.rejects(&digits), "base={} p={}", base, filter.p());
+        }
+    }
+
+    // 11 and 3 themselves are not rejected, 121 and 33 are.
+    let digits = |n: u32| BigUint::from(n).to_radix_be(10);
+    assert!(!ten.rejects(&digits(11)) && !ten.rejects(&[0, 1, 1]) && !ten.rejects(&digits(3)));
+    assert_eq!(ten.rejected_by(&digits(121)).map(|f| f.p()), Some(11));
+    assert_eq!(ten.rejected_by(&digits(33)).map(|f| f.p()), Some(3));
+    assert!(ten.rejects_biguint(&BigUint::from(1234567u32 * 3)));
+
+    let custom = DigitFilters::empty(10).with(DigitFilter::digit_sum(10, 3).unwrap());
+    assert!(custom.rejects(&digits(21)) && !custom.rejects(&digits(25)));
+}
+
+#[test]
+fn test_digit_filters_palindromes() {
+    use crate::miller_rabin_primality_test_u64;
+
+    // Palindromic primes below 10 ^ 6: every even-length palindrome is a
+    // multiple of 11, which the filters catch before any primality test.
+    let filters = DigitFilters::for_base(10);
+    let mut count = 0;
+    for n in 1..1_000_000u64 {
+        let digits = BigUint::from(n).to_radix_be(10);
+        if digits.iter().ne(digits.iter().rev()) {
+            continue;
+        }
+        if digits.len() % 2 == 0 && n != 11 {
+            assert!(filters.rejects(&digits), "N={}", n);
+        }
+        if !filters.rejects(&digits) && miller_rabin_primality_test_u64(n).is_prime() {
+            count += 1;
+        }
+    }
+    assert_eq!(count, 113);
+}
@@ -0,0 +1,224 @@
+// Quadratic Frobenius primality test
+// https://en.wikipedia.org/wiki/Quadratic_Frobenius_test
+//
+// J. Grantham, "A probable prime test with high confidence", J. Number Theory
+// 72 (1998).
+//
+// For n prime and f(x) = x ^ 2 − bx − c irreducible mod n, the ring
+// Z[x] / (n, f(x)) is the field of n ^ 2 elements and x ^ n is the conjugate of
+// x. With ((b ^ 2 + 4c) | n) = −1 and (−c | n) = 1, the QFT checks
+//
+//   1. x ^ ((n + 1) / 2) ∈ Z / nZ;
+//   2. x ^ (n + 1) ≡ −c;
+//   3. with n ^ 2 − 1 = 2 ^ r · s, s odd: x ^ s ≡ 1 or x ^ (2 ^ j · s) ≡ −1
+//      for some 0 <= j <= r − 2.
+//
+// A round with (b, c) drawn at random (RQFT) passes a composite without prime
+// factors below 50000 with probability less than 1 / 7710, against 1 / 4 for
+// a Miller–Rabin round, at the cost of about three of them.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::jacobi_symbol_biguint;
+use crate::is_perfect_square_biguint;
+use crate::miller_rabin::is_prime_u64;
+
+use rand::Rng;
+use num_bigint::{BigUint, RandBigInt};
+
+
+// NOTE: Grantham 的误差上界要求 n 没有小于 50000 的素因子。
+const TRIAL_DIVISION_BOUND: u32 = 50_000;
+
+// u + v·x in Z[x] / (n, x ^ 2 − bx − c)
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Element {
+    u: BigUint,
+    v: BigUint,
+}
+
+struct Ring<'a> {
+    n: &'a BigUint,
+    b: BigUint,
+    c: BigUint,
+}
+
+impl<'a> Ring<'a> {
+    // (u1 + v1·x)(u2 + v2·x) = u1·u2 + c·v1·v2 + (u1·v2 + u2·v1 + b·v1·v2)·x
+    fn mul(&self, x: &Element, y: &Element) -> Element {
+        let vv = &x.v * &y.v % self.n;
+
+        Element {
+            u: (&x.u * &y.u + &self.c * &vv) % self.n,
+            v: (&x.u * &y.v + &y.u * &x.v + &self.b * vv) % self.n,
+        }
+    }
+
+    fn pow(&self, base: &Element, exponent: &BigUint) -> Element {
+        let mut result = Element { u: BigUint::from(1u8), v: BigUint::from(0u8) };
+        for i in (0..exponent.bits()).rev() {
+            result = self.mul(&result, &result);
+            if exponent.bit(i) {
+                result = self.mul(&result, base);
+            }
+        }
+
+        result
+    }
+}
+
+// `k` rounds of RQFT.
+pub fn frobenius_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    if n.bits() <= 64 {
+        let digits = n.to_u64_digits();
+        return match digits.first() {
+            None | Some(1) => Primality::ZeroOrOne,
+            Some(n) => Primality::from_proof(is_prime_u64(*n)),
+        };
+    }
+
+    debug_assert!(k > 0);
+
+    let digits = n.to_u32_digits();
+    let has_small_factor = crate::global().small_primes().iter()
+        .map(|p| *p as u32)
+        .take_while(|p| *p < TRIAL_DIVISION_BOUND)
+        .any(|p| digits.iter().rev().fold(0u64, |r, d| ((r << 32) | *d as u64) % p as u64) == 0);
+    if has_small_factor || is_perfect_square_biguint(n) {
+        return Primality::Composite;
+    }
+
+    let mut rng = crate::global().rng();
+    for _ in 0..k {
+        let passed = match random_parameters(n, &mut rng) {
+            Some((b, c)) => quadratic_frobenius(n, b, c),
+            None => false,
+        };
+        if !passed {
+            return Primality::Composite;
+        }
+    }
+
+    Primality::PROBABLE
+}
+
+// A random (b, c) with ((b ^ 2 + 4c) | n) = −1 and (−c | n) = 1, or `None`
+// when a symbol of 0 reveals a factor of n. n must not be a square.
+fn random_parameters<R: Rng + ?Sized>(n: &BigUint, rng: &mut R) -> Option<(BigUint, BigUint)> {
+    let one = BigUint::from(1u8);
+
+    loop {
+        let b = rng.gen_biguint_below(n);
+        let c = rng.gen_biguint_range(&one, n);
+
+        let delta = (&b * &b + &c * 4u8) % n;
+        let minus_c = n - &c;
+        match (jacobi_symbol_biguint(&delta, n), jacobi_symbol_biguint(&minus_c, n)) {
+            (-1, 1) => return Some((b, c)),
+            // NOTE: 判别式为 0 时 gcd(Δ, n) = n，不能说明 n 是合数，只能重新选取。
+            (0, _) if delta.bits() == 0 => continue,
+            (0, _) | (_, 0) => return None,
+            _ => continue,
+        }
+    }
+}
+
+// The QFT with the parameters (b, c), for an odd n.
+fn quadratic_frobenius(n: &BigUint, b: BigUint, c: BigUint) -> bool {
+    let zero = BigUint::from(0u8);
+    let one = BigUint::from(1u8);
+    let n_minus_one: BigUint = n - 1u8;
+
+    let minus_c = n - &c;
+    let ring = Ring { n, b, c };
+    let x = Element { u: zero.clone(), v: one.clone() };
+
+    // 1. x ^ ((n + 1) / 2) ∈ Z / nZ
+    let half = ring.pow(&x, &((n + 1u8) >> 1usize));
+    if half.v != zero {
+        return false;
+    }
+
+    // 2. x ^ (n + 1) ≡ −c
+    if &half.u * &half.u % n != minus_c {
+        return false;
+    }
+
+    // 3. n ^ 2 − 1 = 2 ^ r · s
+    let n2_minus_one: BigUint = n * n - 1u8;
+    let r = n2_minus_one.trailing_zeros().unwrap_or(0);
+    let s: BigUint = &n2_minus_one >> r as usize;
+
+    let mut y = ring.pow(&x, &s);
+    if y.u == one && y.v == zero {
+        return true;
+    }
+    for _ in 0..r - 1 {
+        if y.u == n_minus_one && y.v == zero {
+            return true;
+        }
+        y = ring.mul(&y, &y);
+    }
+
+    false
+}
+
+
+#[test]
+fn test_quadratic_frobenius() {
+    use crate::table_query_u16;
+
+    let mut rng = crate::global().rng();
+
+    // Every prime passes with any valid parameters.
+    for p in (7..u16::MAX).step_by(2).filter(|n| table_query_u16(*n).is_prime()) {
+        let n = BigUint::from(p);
+        let (b, c) = random_parameters(&n, &mut rng).unwrap();
+        assert!(quadratic_frobenius(&n, b, c), "N={}", p);
+    }
+
+    // Without the trial division the error bound does not hold, but a single
+    // round still catches almost every small composite.
+    let mut liars = 0;
+    for n in (9..u16::MAX).step_by(2).filter(|n| table_query_u16(*n) == Primality::Composite) {
+        let n = BigUint::from(n);
+        if is_perfect_square_biguint(&n) {
+            continue;
+        }
+        if let Some((b, c)) = random_parameters(&n, &mut rng) {
+            liars += quadratic_frobenius(&n, b, c) as u32;
+        }
+    }
+    assert!(liars < 10, "{}", liars);
+}
+
+#[test]
+fn test_frobenius_primality_test_biguint() {
+    assert_eq!(frobenius_primality_test_biguint(&1u8, 2), Primality::ZeroOrOne);
+    assert_eq!(frobenius_primality_test_biguint(&65537u32, 2), Primality::Prime);
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m521 = (BigUint::from(1u8) << 521usize) - 1u8;
+    assert_eq!(frobenius_primality_test_biguint(&m127, 2), Primality::PROBABLE);
+    assert_eq!(frobenius_primality_test_biguint(&m521, 2), Primality::PROBABLE);
+    assert_eq!(frobenius_primality_test_biguint(&(&m127 * &m521), 2), Primality::Composite);
+    assert_eq!(frobenius_primality_test_biguint(&(&m127 * &m127), 2), Primality::Composite);
+
+    // A strong pseudoprime to the bases up to 37 (Arnault), and a Carmichael
+    // number
+    for n in ["3317044064679887385961981", "1296005097246682578520326409"] {
+        let n: BigUint = n.parse().unwrap();
+        assert_eq!(frobenius_primality_test_biguint(&n, 1), Primality::Composite, "N={}", n);
+    }
+}
+
+#[bench]
+fn bench_frobenius_primality_test_biguint(b: &mut test::Bencher) {
+    let n = (BigUint::from(1u8) << 521usize) - 1u8;
+
+    b.iter(|| {
+        frobenius_primality_test_biguint(&n, 1)
+    })
+}
@@ -0,0 +1,311 @@
+// Prime gap records
+// https://en.wikipedia.org/wiki/Prime_gap#Numerical_results
+//
+// A gap of g after the prime p means that p + g is the next prime. It is a
+// maximal gap when every gap before p is shorter (OEIS A005250, A002386), and
+// a first occurrence when no earlier gap has the same length (OEIS A000230).
+// The maximal gaps below 2 ^ 64 are all known (Nicely, Oliveira e Silva,
+// Herzog and Pardi), so `is_maximal_gap` is exact; first occurrences are
+// tabulated for the gaps that end below 2 ^ 32 only.
+use crate::sieve::for_each_segment;
+
+use std::io::{self, Write};
+
+
+// NOTE: 该表由 `scripts/gap_records_gen.rs` 脚本生成。
+static FIRST_OCCURRENCES: [(u16, u32); 151] = [
+    (2, 3), (4, 7), (6, 23), (8, 89),
+    (10, 139), (12, 199), (14, 113), (16, 1831),
+    (18, 523), (20, 887), (22, 1129), (24, 1669),
+    (26, 2477), (28, 2971), (30, 4297), (32, 5591),
+    (34, 1327), (36, 9551), (38, 30593), (40, 19333),
+    (42, 16141), (44, 15683), (46, 81463), (48, 28229),
+    (50, 31907), (52, 19609), (54, 35617), (56, 82073),
+    (58, 44293), (60, 43331), (62, 34061), (64, 89689),
+    (66, 162143), (68, 134513), (70, 173359), (72, 31397),
+    (74, 404597), (76, 212701), (78, 188029), (80, 542603),
+    (82, 265621), (84, 461717), (86, 155921), (88, 544279),
+    (90, 404851), (92, 927869), (94, 1100977), (96, 360653),
+    (98, 604073), (100, 396733), (102, 1444309), (104, 1388483),
+    (106, 1098847), (108, 2238823), (110, 1468277), (112, 370261),
+    (114, 492113), (116, 5845193), (118, 1349533), (120, 1895359),
+    (122, 3117299), (124, 6752623), (126, 1671781), (128, 3851459),
+    (130, 5518687), (132, 1357201), (134, 6958667), (136, 6371401),
+    (138, 3826019), (140, 7621259), (142, 10343761), (144, 11981443),
+    (146, 6034247), (148, 2010733), (150, 13626257), (152, 8421251),
+    (154, 4652353), (156, 17983717), (158, 49269581), (160, 33803689),
+    (162, 39175217), (164, 20285099), (166, 83751121), (168, 37305713),
+    (170, 27915737), (172, 38394127), (174, 52721113), (176, 38089277),
+    (178, 39389989), (180, 17051707), (182, 36271601), (184, 79167733),
+    (186, 147684137), (188, 134065829), (190, 142414669), (192, 123454691),
+    (194, 166726367), (196, 70396393), (198, 46006769), (200, 378043979),
+    (202, 107534587), (204, 112098817), (206, 232423823), (208, 192983851),
+    (210, 20831323), (212, 215949407), (214, 253878403), (216, 202551667),
+    (218, 327966101), (220, 47326693), (222, 122164747), (224, 409866323),
+    (226, 519653371), (228, 895858039), (230, 607010093), (232, 525436489),
+    (234, 189695659), (236, 216668603), (238, 673919143), (240, 391995431),
+    (242, 367876529), (244, 693103639), (246, 555142061), (248, 191912783),
+    (250, 387096133), (252, 630045137), (254, 1202442089), (256, 1872851947),
+    (258, 1316355323), (260, 944192807), (262, 1649328997), (264, 2357881993),
+    (266, 1438779821), (268, 1579306789), (270, 1391048047), (272, 1851255191),
+    (274, 1282463269), (276, 649580171), (278, 4260928601), (280, 1855047163),
+    (282, 436273009), (284, 1667186459), (286, 2842739311), (288, 1294268491),
+    (290, 1948819133), (292, 1453168141), (304, 2433630109), (306, 3917587237),
+    (310, 4024713661), (320, 2300942549), (336, 3842610773),
+];
+
+// NOTE: 2^32 以上的首次出现差值只有极大差值是已知的。
+const FIRST_OCCURRENCE_BOUND: u64 = 1 << 32;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct GapRecord {
+    pub gap: u32,
+    // The prime before the gap
+    pub start: u64,
+}
+
+// Every maximal prime gap below 2 ^ 64, by increasing start; the gaps grow
+// along with it.
+static MAXIMAL_PRIME_GAPS: [GapRecord; 80] = [
+    GapRecord { gap: 1, start: 2 }, GapRecord { gap: 2, start: 3 },
+    GapRecord { gap: 4, start: 7 }, GapRecord { gap: 6, start: 23 },
+    GapRecord { gap: 8, start: 89 }, GapRecord { gap: 14, start: 113 },
+    GapRecord { gap: 18, start: 523 }, GapRecord { gap: 20, start: 887 },
+    GapRecord { gap: 22, start: 1129 }, GapRecord { gap: 34, start: 1327 },
+    GapRecord { gap: 36, start: 9551 }, GapRecord { gap: 44, start: 15683 },
+    GapRecord { gap: 52, start: 19609 }, GapRecord { gap: 72, start: 31397 },
+    GapRecord { gap: 86, start: 155921 }, GapRecord { gap: 96, start: 360653 },
+    GapRecord { gap: 112, start: 370261 }, GapRecord { gap: 114, start: 492113 },
+    GapRecord { gap: 118, start: 1349533 }, GapRecord { gap: 132, start: 1357201 },
+    GapRecord { gap: 148, start: 2010733 }, GapRecord { gap: 154, start: 4652353 },
+    GapRecord { gap: 180, start: 17051707 }, GapRecord { gap: 210, start: 20831323 },
+    GapRecord { gap: 220, start: 47326693 }, GapRecord { gap: 222, start: 122164747 },
+    GapRecord { gap: 234, start: 189695659 }, GapRecord { gap: 248, start: 191912783 },
+    GapRecord { gap: 250, start: 387096133 }, GapRecord { gap: 282, start: 436273009 },
+    GapRecord { gap: 288, start: 1294268491 }, GapRecord { gap: 292, start: 1453168141 },
+    GapRecord { gap: 320, start: 2300942549 }, GapRecord { gap: 336, start: 3842610773 },
+    GapRecord { gap: 354, start: 4302407359 }, GapRecord { gap: 382, start: 10726904659 },
+    GapRecord { gap: 384, start: 20678048297 }, GapRecord { gap: 394, start: 22367084959 },
+    GapRecord { gap: 456, start: 25056082087 }, GapRecord { gap: 464, start: 42652618343 },
+    GapRecord { gap: 468, start: 127976334671 }, GapRecord { gap: 474, start: 182226896239 },
+    GapRecord { gap: 486, start: 241160624143 }, GapRecord { gap: 490, start: 297501075799 },
+    GapRecord { gap: 500, start: 303371455241 }, GapRecord { gap: 514, start: 304599508537 },
+    GapRecord { gap: 516, start: 416608695821 }, GapRecord { gap: 532, start: 461690510011 },
+    GapRecord { gap: 534, start: 614487453523 }, GapRecord { gap: 540, start: 738832927927 },
+    GapRecord { gap: 582, start: 1346294310749 }, GapRecord { gap: 588, start: 1408695493609 },
+    GapRecord { gap: 602, start: 1968188556461 }, GapRecord { gap: 652, start: 2614941710599 },
+    GapRecord { gap: 674, start: 7177162611713 }, GapRecord { gap: 716, start: 13829048559701 },
+    GapRecord { gap: 766, start: 19581334192423 }, GapRecord { gap: 778, start: 42842283925351 },
+    GapRecord { gap: 804, start: 90874329411493 }, GapRecord { gap: 806, start: 171231342420521 },
+    GapRecord { gap: 906, start: 218209405436543 }, GapRecord { gap: 916, start: 1189459969825483 },
+    GapRecord { gap: 924, start: 1686994940955803 }, GapRecord { gap: 1132, start: 1693182318746371 },
+    GapRecord { gap: 1184, start: 43841547845541059 }, GapRecord { gap: 1198, start: 55350776431903243 },
+    GapRecord { gap: 1220, start: 80873624627234849 }, GapRecord { gap: 1224, start: 203986478517455989 },
+    GapRecord { gap: 1248, start: 218034721194214273 }, GapRecord { gap: 1272, start: 305405826521087869 },
+    GapRecord { gap: 1328, start: 352521223451364323 }, GapRecord { gap: 1356, start: 401429925999153707 },
+    GapRecord { gap: 1370, start: 418032645936712127 }, GapRecord { gap: 1442, start: 804212830686677669 },
+    GapRecord { gap: 1476, start: 1425172824437699411 }, GapRecord { gap: 1488, start: 5733241593241196731 },
+    GapRecord { gap: 1510, start: 6787988999657777797 }, GapRecord { gap: 1526, start: 15570628755536096243 },
+    GapRecord { gap: 1530, start: 17678654157568189057 }, GapRecord { gap: 1550, start: 18361375334787046697 },
+];
+
+pub fn maximal_prime_gaps() -> &'static [GapRecord] {
+    &MAXIMAL_PRIME_GAPS
+}
+
+// Whether the gap of `gap` after the prime `start` is longer than every gap
+// before it.
+pub fn is_maximal_gap(start: u64, gap: u32) -> bool {
+    MAXIMAL_PRIME_GAPS.binary_search_by_key(&start, |record| record.start)
+        .map(|i| MAXIMAL_PRIME_GAPS[i].gap == gap)
+        .unwrap_or(false)
+}
+
+// Whether the gap of `gap` after the prime `start` is the first gap of that
+// length, or `None` when the first occurrence of `gap` is not known.
+pub fn is_first_occurrence_gap(start: u64, gap: u32) -> Option<bool> {
+    if is_maximal_gap(start, gap) {
+        return Some(true);
+    }
+
+    if gap == 1 {
+        return Some(start == 2);
+    }
+
+    if let Ok(i) = FIRST_OCCURRENCES.binary_search_by_key(&gap, |(g, _)| *g as u32) {
+        return Some(start == FIRST_OCCURRENCES[i].1 as u64);
+    }
+
+    // NOTE: 2^32 以内没有出现过这个差值，说明 (start, start + gap) 不是相邻素数。
+    match start.checked_add(gap as u64) {
+        Some(end) if end < FIRST_OCCURRENCE_BOUND => Some(false),
+        _ => None,
+    }
+}
+
+// The longest gap between consecutive primes p < q in [lo, hi), the first
+// one on ties, or `None` when the range holds fewer than two primes. A gap
+// found by a search over [lo, hi) is locally maximal when it equals this.
+pub fn max_gap_in_range(lo: u64, hi: u64) -> Option<GapRecord> {
+    let mut prev: Option<u64> = None;
+    let mut best: Option<GapRecord> = None;
+    for_each_segment(lo, hi, |base, composite| {
+        for (i, is_composite) in composite.iter().enumerate() {
+            if *is_composite {
+                continue;
+            }
+
+            let p = base + i as u64;
+            if let Some(q) = prev {
+                let gap = (p - q) as u32;
+                if best.is_none_or(|record| gap > record.gap) {
+                    best = Some(GapRecord { gap, start: q });
+                }
+            }
+            prev = Some(p);
+        }
+
+        true
+    });
+
+    best
+}
+
+// Every gap between consecutive primes p < q in [lo, hi) as a CSV row
+//
+//      start,gap,end,merit,maximal,first_occurrence
+//      31397,72,31469,6.9898,true,true
+//
+// where the merit is gap / ln(start) and `first_occurrence` is empty where it
+// is not known. Returns the number of rows written.
+pub fn export_gaps_csv<W: Write>(lo: u64, hi: u64, mut out: W) -> io::Result<u64> {
+    writeln!(out, "start,gap,end,merit,maximal,first_occurrence")?;
+
+    let mut prev: Option<u64> = None;
+    let mut rows = 0u64;
+    let mut result = Ok(());
+    for_each_segment(lo, hi, |base, composite| {
+        for (i, is_composite) in composite.iter().enumerate() {
+            if *is_composite {
+                continue;
+            }
+
+            let p = base + i as u64;
+            if let Some(q) = prev {
+                let gap = (p - q) as u32;
+                let first = match is_first_occurrence_gap(q, gap) {
+                    Some(first) => first.to_string(),
+                    None => String::new(),
+                };
+                let merit = gap as f64 / (q as f64).ln();
+                result = writeln!(out, "{},{},{},{:.4},{},{}", q, gap, p, merit, is_maximal_gap(q, gap), first);
+                if result.is_err() {
+                    return false;
+                }
+                rows += 1;
+            }
+            prev = Some(p);
+        }
+
+        true
+    });
+
+    result.and(out.flush()).map(|_| rows)
+}
+
+
+#[test]
+fn test_maximal_prime_gaps() {
+    use crate::miller_rabin::is_prime_u64;
+
+    for (i, record) in MAXIMAL_PRIME_GAPS.iter().enumerate() {
+        let end = record.start + record.gap as u64;
+        assert!(is_prime_u64(record.start) && is_prime_u64(end), "{:?}", record);
+        assert!((record.start + 1..end).all(|n| !is_prime_u64(n)), "{:?}", record);
+        if i > 0 {
+            assert!(record.gap > MAXIMAL_PRIME_GAPS[i - 1].gap && record.start > MAXIMAL_PRIME_GAPS[i - 1].start);
+        }
+    }
+
+    // Every record below 2 ^ 22 against a sieve
+    let records: Vec<GapRecord> = MAXIMAL_PRIME_GAPS.iter().copied().take_while(|r| r.start < 1 << 22).collect();
+    let mut found: Vec<GapRecord> = Vec::new();
+    let primes = crate::primes_in_range(0, 1 << 22);
+    for w in primes.windows(2) {
+        let gap = (w[1] - w[0]) as u32;
+        if found.last().is_none_or(|r| gap > r.gap) {
+            found.push(GapRecord { gap, start: w[0] });
+        }
+    }
+    assert_eq!(found, records);
+
+    assert!(is_maximal_gap(31397, 72));
+    assert!(is_maximal_gap(18361375334787046697, 1550));
+    assert!(!is_maximal_gap(139, 10));
+    assert!(!is_maximal_gap(31397, 70));
+}
+
+#[test]
+fn test_is_first_occurrence_gap() {
+    assert_eq!(is_first_occurrence_gap(2, 1), Some(true));
+    assert_eq!(is_first_occurrence_gap(3, 2), Some(true));
+    assert_eq!(is_first_occurrence_gap(5, 2), Some(false));
+    assert_eq!(is_first_occurrence_gap(139, 10), Some(true));
+    assert_eq!(is_first_occurrence_gap(181, 10), Some(false));
+    assert_eq!(is_first_occurrence_gap(4024713661, 310), Some(true));
+    assert_eq!(is_first_occurrence_gap(1693182318746371, 1132), Some(true));
+    // Gap 300 first occurs above 2 ^ 32.
+    assert_eq!(is_first_occurrence_gap(5 << 32, 300), None);
+    assert_eq!(is_first_occurrence_gap(1000, 300), Some(false));
+
+    // Against a sieve below 2 ^ 20
+    let primes = crate::primes_in_range(0, 1 << 20);
+    let mut seen = std::collections::HashSet::new();
+    for w in primes.windows(2) {
+        let gap = (w[1] - w[0]) as u32;
+        assert_eq!(is_first_occurrence_gap(w[0], gap), Some(seen.insert(gap)), "P={}", w[0]);
+    }
+}
+
+#[test]
+fn test_max_gap_in_range() {
+    assert_eq!(max_gap_in_range(0, 3), None);
+    assert_eq!(max_gap_in_range(0, 4), Some(GapRecord { gap: 1, start: 2 }));
+    assert_eq!(max_gap_in_range(24, 30), None);
+    assert_eq!(max_gap_in_range(0, 1 << 16), Some(GapRecord { gap: 72, start: 31397 }));
+    // Not a maximal gap, but the longest one in the window
+    let record = max_gap_in_range(1 << 40, (1 << 40) + (1 << 20)).unwrap();
+    assert!(!is_maximal_gap(record.start, record.gap));
+    assert_eq!(max_gap_in_range(record.start, record.start + record.gap as u64 + 1), Some(record));
+}
+
+#[test]
+fn test_export_gaps_csv() {
+    let mut out = Vec::new();
+    assert_eq!(export_gaps_csv(0, 30, &mut out).unwrap(), 9);
+    let csv = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "start,gap,end,merit,maximal,first_occurrence");
+    assert_eq!(lines[1], "2,1,3,1.4427,true,true");
+    assert_eq!(lines[2], "3,2,5,1.8205,true,true");
+    assert_eq!(lines[3], "5,2,7,1.2427,false,false");
+    assert_eq!(lines[9], "23,6,29,1.9136,true,true");
+
+    // The rows match the sieve, and the maximal ones the table.
+    let mut out = Vec::new();
+    let rows = export_gaps_csv(1 << 16, 1 << 18, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let primes = crate::primes_in_range(1 << 16, 1 << 18);
+    assert_eq!(rows as usize, primes.len() - 1);
+    for (line, w) in csv.lines().skip(1).zip(primes.windows(2)) {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!((fields[0], fields[2]), (w[0].to_string().as_str(), w[1].to_string().as_str()));
+        assert_eq!(fields[4] == "true", is_maximal_gap(w[0], (w[1] - w[0]) as u32));
+    }
+    assert!(csv.contains("\n155921,86,156007,7.1924,true,true\n"));
+
+    // Short gaps this far up all occurred long before.
+    let mut out = Vec::new();
+    export_gaps_csv(1 << 40, (1 << 40) + 1000, &mut out).unwrap();
+    assert!(String::from_utf8(out).unwrap().lines().skip(1).all(|line| line.ends_with(",false,false")));
+}
@@ -0,0 +1,624 @@
+// Random prime generation
+//
+// Every candidate is screened by a mod-30 wheel and by trial division with the
+// primes below 2 ^ 12 (or the bound set by `calibrate`) before any Miller–Rabin
+// round is spent on it. The
+// `_with_stats` variants report why each rejected candidate was thrown away,
+// which is what to look at when tuning the prefilter.
+//
+// Primes of more than 24 bits are searched incrementally: from a random odd
+// n0, the candidates n0, n0 + 2, n0 + 4, ... are sieved together with the
+// residues of n0 modulo the small primes, so that only survivors need a
+// multi-precision operation. The prime found is the first after n0, which
+// slightly favours primes after long gaps; FIPS 186-5 (B.3.3) and most
+// libraries accept that for the speed.
+//
+// `gen_prime_avoiding` rules out whole residue classes, e.g. p ≡ 1 (mod 8)
+// for protocols that need a square root of −1 to be missing. The classes are
+// struck from the sieve along with the multiples of the small primes, so they
+// cost no primality test.
+use crate::{Bits, RsaModulusBits, SafePrimeBits};
+use crate::SecurityLevel;
+use crate::recommended_rounds;
+use crate::miller_rabin::find_witness_biguint;
+
+use rand::Rng;
+use num_bigint::BigUint;
+use num_bigint::RandBigInt;
+use num_integer::Integer;
+
+use std::collections::BTreeMap;
+
+
+// How many of the most significant bits of a generated prime are forced to 1.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TopBits {
+    // Exactly `bits` bits.
+    One,
+    // The top two bits set, so that the product of two such primes has
+    // exactly twice as many bits, as RSA key generation requires.
+    Two,
+}
+
+// NOTE: 每个随机起点最多向后筛这么多个奇数，走完或超出位数时重新选取起点。
+const SIEVE_STEPS: usize = 1 << 12;
+
+// Residues mod 30 that are coprime to 2, 3 and 5.
+const WHEEL_30: [u32; 8] = [1, 7, 11, 13, 17, 19, 23, 29];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GenStats {
+    // Number of candidates drawn, including the one that was accepted.
+    pub candidates: u64,
+    // Candidates divisible by 2, 3 or 5.
+    pub rejected_by_wheel: u64,
+    // Candidates that passed the wheel, keyed by the smallest prime factor found.
+    pub rejected_by_small_factor: BTreeMap<u32, u64>,
+    // Candidates in one of the residue classes to avoid.
+    pub rejected_by_residue: u64,
+    // The Miller–Rabin base that exposed each remaining composite.
+    pub rejected_by_witness: Vec<BigUint>,
+}
+
+impl GenStats {
+    pub fn rejected(&self) -> u64 {
+        self.rejected_by_wheel
+            + self.rejected_by_residue
+            + self.rejected_by_small_factor.values().sum::<u64>()
+            + self.rejected_by_witness.len() as u64
+    }
+}
+
+enum Rejection {
+    Wheel,
+    Residue,
+    SmallFactor(u32),
+    Witness(BigUint),
+}
+
+impl GenStats {
+    fn record(&mut self, rejection: Rejection) {
+        match rejection {
+            Rejection::Wheel => self.rejected_by_wheel += 1,
+            Rejection::Residue => self.rejected_by_residue += 1,
+            Rejection::SmallFactor(p) => *self.rejected_by_small_factor.entry(p).or_insert(0) += 1,
+            Rejection::Witness(a) => self.rejected_by_witness.push(a),
+        }
+    }
+}
+
+// A random prime of exactly `bits` bits.
+pub fn gen_prime<R: Rng + ?Sized>(bits: Bits, rng: &mut R) -> BigUint {
+    gen_prime_with_stats(bits, rng).0
+}
+
+pub fn gen_prime_with_top_bits<R: Rng + ?Sized>(bits: Bits, top: TopBits, rng: &mut R) -> BigUint {
+    gen_prime_inner(bits.get() as usize, top, &[], rng).0
+}
+
+// A random prime p of exactly `bits` bits with p ≢ r (mod m) for every
+// (r, m) in `avoid`, 2 ≤ m ≤ 2 ^ 16. Panics when that leaves no class of
+// some m coprime to m to draw from; for short primes it is up to the caller
+// that some prime of `bits` bits is left (none of 11, 13 is for
+// [(1, 8), (2, 3), (3, 10)]).
+pub fn gen_prime_avoiding<R: Rng + ?Sized>(bits: Bits, avoid: &[(u32, u32)], rng: &mut R) -> BigUint {
+    for (r, m) in avoid {
+        assert!((2..=1 << 16).contains(m), "modulus must be in 2 ..= 65536");
+        assert!(r < m, "residue must be below its modulus");
+    }
+    for (_, m) in avoid {
+        let open = (1..*m).filter(|r| r.gcd(m) == 1).any(|r| !avoid.contains(&(r, *m)));
+        assert!(open, "every class coprime to {} is avoided", m);
+    }
+
+    gen_prime_inner(bits.get() as usize, TopBits::One, avoid, rng).0
+}
+
+// Two distinct primes p, q of `bits / 2` bits each, with the top two bits
+// set, such that p · q has exactly `bits` bits.
+//
+// As in FIPS 186-5 (A.1.3), |p − q| > 2 ^ (bits / 2 − 100) whenever the
+// primes are long enough for that to matter.
+pub fn gen_rsa_primes<R: Rng + ?Sized>(bits: RsaModulusBits, rng: &mut R) -> (BigUint, BigUint) {
+    let half = bits.half().get() as usize;
+    let p = gen_prime_inner(half, TopBits::Two, &[], rng).0;
+    loop {
+        let q = gen_prime_inner(half, TopBits::Two, &[], rng).0;
+
+        let diff = if p > q { &p - &q } else { &q - &p };
+        if diff.bits() as usize > half.saturating_sub(100) {
+            return (p, q);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Semiprime {
+    pub n: BigUint,
+    // p <= q, with n = p · q.
+    pub p: BigUint,
+    pub q: BigUint,
+}
+
+// A random semiprime n = p · q of exactly `bits` bits whose smaller factor p
+// has `balance · bits` bits, rounded: 0.5 gives RSA-like moduli, smaller
+// values increasingly unbalanced ones.
+//
+// p is uniform among the primes of its length, and q among the primes that
+// keep n at `bits` bits. Every candidate is drawn afresh rather than walked
+// up to from a random start as in `gen_prime`, so no prime is favoured for
+// following a long gap.
+pub fn gen_semiprime<R: Rng + ?Sized>(bits: Bits, balance: f64, rng: &mut R) -> Semiprime {
+    let bits = bits.get() as usize;
+    assert!(bits >= 4);
+    assert!(balance > 0.0 && balance <= 0.5);
+
+    let p_bits = ((bits as f64 * balance).round() as usize).clamp(2, bits / 2);
+    let rounds = recommended_rounds(bits - p_bits, SecurityLevel::Bits128) as usize;
+    let is_prime = |n: &BigUint, rng: &mut R| prefilter(n).and_then(|_| miller_rabin(n, rounds, rng)).is_ok();
+
+    let p = loop {
+        let p = gen_odd(p_bits, TopBits::One, rng);
+        if is_prime(&p, rng) {
+            break p;
+        }
+    };
+
+    // q in [⌈2 ^ (bits − 1) / p⌉, ⌊(2 ^ bits − 1) / p⌋]
+    let one = BigUint::from(1u8);
+    let lo: BigUint = ((&one << (bits - 1)) + &p - 1u8) / &p;
+    let hi: BigUint = ((&one << bits) - 1u8) / &p + 1u8;
+    loop {
+        let q = rng.gen_biguint_range(&lo, &hi);
+        // NOTE: 平衡的情况下 q 可能小于 p，交换即可；但两个因子不能相同。
+        if q != p && is_prime(&q, rng) {
+            let (p, q) = if p < q { (p, q) } else { (q, p) };
+            return Semiprime { n: &p * &q, p, q };
+        }
+    }
+}
+
+// A random safe prime p = 2q + 1 (q prime) of exactly `bits` bits.
+pub fn gen_safe_prime<R: Rng + ?Sized>(bits: SafePrimeBits, rng: &mut R) -> BigUint {
+    gen_safe_prime_with_stats(bits, rng).0
+}
+
+pub fn gen_prime_with_stats<R: Rng + ?Sized>(bits: Bits, rng: &mut R) -> (BigUint, GenStats) {
+    gen_prime_inner(bits.get() as usize, TopBits::One, &[], rng)
+}
+
+fn gen_prime_inner<R: Rng + ?Sized>(bits: usize, top: TopBits, avoid: &[(u32, u32)], rng: &mut R) -> (BigUint, GenStats) {
+    let rounds = recommended_rounds(bits, SecurityLevel::Bits128) as usize;
+    let mut stats = GenStats::default();
+
+    // NOTE: 超过 24 bits 的候选数大于所有试除用的素数，整除即说明是合数，可以直接筛。
+    if bits > 24 {
+        loop {
+            if let Some(p) = sieve_search(bits, top, avoid, rounds, &mut stats, rng) {
+                return (p, stats);
+            }
+        }
+    }
+
+    loop {
+        stats.candidates += 1;
+
+        let n = gen_odd(bits, top, rng);
+        match avoided(&n, avoid).and_then(|_| prefilter(&n)).and_then(|_| miller_rabin(&n, rounds, rng)) {
+            Ok(()) => return (n, stats),
+            Err(rejection) => stats.record(rejection),
+        }
+    }
+}
+
+pub fn gen_safe_prime_with_stats<R: Rng + ?Sized>(bits: SafePrimeBits, rng: &mut R) -> (BigUint, GenStats) {
+    let bits = bits.get() as usize;
+
+    let rounds = recommended_rounds(bits, SecurityLevel::Bits128) as usize;
+    let mut stats = GenStats::default();
+    loop {
+        stats.candidates += 1;
+
+        let q = gen_odd(bits - 1, TopBits::One, rng);
+        let p: BigUint = &q * 2u8 + 1u8;
+
+        // NOTE: 先对 q 和 p 都做完廉价的筛选，再进行 Miller–Rabin 测试。
+        let result = prefilter(&q)
+            .and_then(|_| prefilter(&p))
+            .and_then(|_| miller_rabin(&q, rounds, rng))
+            .and_then(|_| miller_rabin(&p, rounds, rng));
+        match result {
+            Ok(()) => return (p, stats),
+            Err(rejection) => stats.record(rejection),
+        }
+    }
+}
+
+// Walk from a random odd start through the following odd numbers of `bits`
+// bits, giving up after `SIEVE_STEPS` of them.
+fn sieve_search<R: Rng + ?Sized>(bits: usize, top: TopBits, avoid: &[(u32, u32)], rounds: usize, stats: &mut GenStats, rng: &mut R) -> Option<BigUint> {
+    let n0 = gen_odd(bits, top, rng);
+
+    // Candidates n0 + 2k stay below 2 ^ bits for k < (2 ^ bits − n0 + 1) / 2.
+    let room: BigUint = ((BigUint::from(1u8) << bits) - &n0 + 1u8) / 2u8;
+    let steps = room.to_u64_digits().first().copied()
+        .filter(|_| room.bits() <= 64)
+        .map_or(SIEVE_STEPS, |room| SIEVE_STEPS.min(room as usize));
+
+    // smallest[k] is the smallest prime below the bound dividing n0 + 2k, or 0.
+    let digits = n0.to_u32_digits();
+    let mut smallest = vec![0u32; steps];
+    let bound = crate::thresholds().trial_division_bound as u32;
+    for p in crate::global().small_primes().iter().skip(1) {
+        let p = *p as u32;
+        if p >= bound {
+            break;
+        }
+
+        // n0 + 2k ≡ 0 (mod p)  ⟺  k ≡ −n0 · 2 ^ −1 (mod p), with 2 ^ −1 = (p + 1) / 2.
+        let r = rem_u32(&digits, p) as u64;
+        let first = (p as u64 - r) * (p as u64 / 2 + 1) % p as u64;
+        for k in (first as usize..steps).step_by(p as usize) {
+            if smallest[k] == 0 {
+                smallest[k] = p;
+            }
+        }
+    }
+
+    // NOTE: n0 + 2k ≡ r (mod m) 的 k 以 m 为周期（m 为偶数时也是），只需在第一个周期里找。
+    let mut excluded = vec![false; steps];
+    for (r, m) in avoid {
+        let r0 = rem_u32(&digits, *m) as usize;
+        let (r, m) = (*r as usize, *m as usize);
+        for first in (0..m.min(steps)).filter(|k| (r0 + 2 * k) % m == r) {
+            for k in (first..steps).step_by(m) {
+                excluded[k] = true;
+            }
+        }
+    }
+
+    for (k, p) in smallest.iter().enumerate() {
+        stats.candidates += 1;
+        if excluded[k] {
+            stats.record(Rejection::Residue);
+            continue;
+        }
+        match p {
+            0 => {
+                let n = &n0 + 2 * k as u64;
+                match miller_rabin(&n, rounds, rng) {
+                    Ok(()) => return Some(n),
+                    Err(rejection) => stats.record(rejection),
+                }
+            },
+            3 | 5 => stats.record(Rejection::Wheel),
+            p => stats.record(Rejection::SmallFactor(*p)),
+        }
+    }
+
+    None
+}
+
+// A random odd number with exactly `bits` bits.
+fn gen_odd<R: Rng + ?Sized>(bits: usize, top: TopBits, rng: &mut R) -> BigUint {
+    let one = BigUint::from(1u8);
+    let n = rng.gen_biguint(bits as u64) | (&one << (bits - 1)) | &one;
+    match top {
+        TopBits::One => n,
+        TopBits::Two => n | (one << (bits - 2)),
+    }
+}
+
+fn avoided(n: &BigUint, avoid: &[(u32, u32)]) -> Result<(), Rejection> {
+    let digits = n.to_u32_digits();
+    match avoid.iter().any(|(r, m)| rem_u32(&digits, *m) == *r) {
+        true => Err(Rejection::Residue),
+        false => Ok(()),
+    }
+}
+
+// n % m
+fn rem_u32(digits: &[u32], m: u32) -> u32 {
+    digits.iter().rev().fold(0u64, |r, d| ((r << 32) | *d as u64) % m as u64) as u32
+}
+
+fn prefilter(n: &BigUint) -> Result<(), Rejection> {
+    prefilter_with(n, crate::thresholds().trial_division_bound as u32)
+}
+
+fn prefilter_with(n: &BigUint, bound: u32) -> Result<(), Rejection> {
+    let digits = n.to_u32_digits();
+    let is_small = |p: u32| digits.len() == 1 && digits[0] == p;
+
+    let r = rem_u32(&digits, 30);
+    if WHEEL_30.binary_search(&r).is_err() && !is_small(2) && !is_small(3) && !is_small(5) {
+        return Err(Rejection::Wheel);
+    }
+
+    // NOTE: 试除所用小素数的上界。`calibrate` 可能把它降到 2^8，
+    //       但不超过 24 bits 的数要试除到 2^12，`miller_rabin` 才能直接接受它们。
+    let bound = match n.bits() <= 24 {
+        true => bound.max(1 << 12),
+        false => bound,
+    };
+    for p in crate::global().small_primes().iter().skip(3) {
+        let p = *p as u32;
+        if p >= bound || (digits.len() == 1 && p * p > digits[0]) {
+            break;
+        }
+        if rem_u32(&digits, p) == 0 && !is_small(p) {
+            return Err(Rejection::SmallFactor(p));
+        }
+    }
+
+    Ok(())
+}
+
+fn miller_rabin<R: Rng + ?Sized>(n: &BigUint, rounds: usize, rng: &mut R) -> Result<(), Rejection> {
+    // NOTE: 没有小于 2^12 的因子且小于 2^24 的数一定是素数（`prefilter` 对它们总是试除到 2^12），
+    //       Miller–Rabin 也要求 n > 4。
+    if n.bits() <= 24 {
+        return Ok(());
+    }
+
+    let two = BigUint::from(2u8);
+    let n_minus_two: BigUint = n - 2u8;
+    match find_witness_biguint(n, rounds, |_| rng.gen_biguint_range(&two, &n_minus_two)) {
+        Some(a) => Err(Rejection::Witness(a)),
+        None => Ok(()),
+    }
+}
+
+// Fixed-width encodings for primes that go into protocol fields, e.g. the
+// 256-byte primes of an RSA-4096 key. The width is `bits` rounded up to whole
+// bytes; the value must fit in it.
+pub trait PaddedBytes {
+    fn to_padded_be_bytes(&self, bits: usize) -> Vec<u8>;
+    fn to_padded_le_bytes(&self, bits: usize) -> Vec<u8>;
+    // Lowercase hex of `to_padded_be_bytes`, two digits per byte.
+    fn to_hex_padded(&self, bits: usize) -> String;
+}
+
+impl PaddedBytes for BigUint {
+    fn to_padded_be_bytes(&self, bits: usize) -> Vec<u8> {
+        let mut bytes = self.to_padded_le_bytes(bits);
+        bytes.reverse();
+        bytes
+    }
+
+    fn to_padded_le_bytes(&self, bits: usize) -> Vec<u8> {
+        assert!(self.bits() as usize <= bits, "{} does not fit in {} bits", self, bits);
+
+        // NOTE: 0 的 to_bytes_le 结果是 [0]，所以这里按位宽截断而不是直接补齐。
+        let mut bytes = self.to_bytes_le();
+        bytes.resize(bits.div_ceil(8), 0);
+        bytes
+    }
+
+    fn to_hex_padded(&self, bits: usize) -> String {
+        self.to_padded_be_bytes(bits).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+
+#[test]
+fn test_gen_prime() {
+    use crate::miller_rabin::is_prime_u64;
+    use crate::miller_rabin_primality_test_biguint;
+    use crate::Primality;
+
+    let mut rng = rand::thread_rng();
+    for bits in 2..=64 {
+        let p = gen_prime(Bits::new(bits).unwrap(), &mut rng);
+        assert_eq!(p.bits(), bits as u64);
+        assert!(is_prime_u64(p.to_u64_digits()[0]), "P={}", p);
+    }
+
+    let p = gen_prime(Bits::new(256).unwrap(), &mut rng);
+    assert_eq!(p.bits(), 256);
+    assert_eq!(miller_rabin_primality_test_biguint(&p, 20), Primality::PROBABLE);
+
+    for bits in 3..=64 {
+        let p = gen_safe_prime(Bits::safe_prime(bits).unwrap(), &mut rng);
+        let q: BigUint = &p >> 1;
+        assert_eq!(p.bits(), bits as u64);
+        assert!(is_prime_u64(p.to_u64_digits()[0]), "P={}", p);
+        assert!(is_prime_u64(q.to_u64_digits()[0]), "Q={}", q);
+    }
+}
+
+#[test]
+fn test_gen_rsa_primes() {
+    let mut rng = rand::thread_rng();
+
+    for bits in 2..=64 {
+        let p = gen_prime_with_top_bits(Bits::new(bits as u32).unwrap(), TopBits::Two, &mut rng);
+        assert_eq!(p.bits() as usize, bits);
+        assert!(bits == 2 || (&p >> (bits - 2)) == BigUint::from(3u8), "P={}", p);
+    }
+
+    for bits in [16, 64, 512] {
+        let (p, q) = gen_rsa_primes(Bits::rsa_modulus(bits as u32).unwrap(), &mut rng);
+        assert_ne!(p, q);
+        assert_eq!(p.bits() as usize, bits / 2);
+        assert_eq!(q.bits() as usize, bits / 2);
+        assert_eq!((&p * &q).bits() as usize, bits);
+    }
+
+    // The smallest product with the top two bits set still has full length.
+    for bits in [8usize, 16, 100] {
+        let min = BigUint::from(3u8) << (bits / 2 - 2);
+        assert_eq!((&min * &min).bits() as usize, bits);
+    }
+}
+
+#[test]
+fn test_gen_semiprime() {
+    use crate::miller_rabin::is_prime_u64;
+    use crate::baillie_psw_primality_test_biguint;
+
+    let mut rng = rand::thread_rng();
+
+    for bits in 4..=64 {
+        for balance in [0.5, 0.3, 0.1] {
+            let s = gen_semiprime(Bits::new(bits as u32).unwrap(), balance, &mut rng);
+            let p_bits = ((bits as f64 * balance).round() as usize).clamp(2, bits / 2);
+            assert_eq!(s.n.bits() as usize, bits, "{:?}", s);
+            assert_eq!(&s.p * &s.q, s.n);
+            assert!(s.p < s.q);
+            assert!(is_prime_u64(s.p.to_u64_digits()[0]) && is_prime_u64(s.q.to_u64_digits()[0]), "{:?}", s);
+            // NOTE: 平衡时两个因子可能交换，较小的一个至多少一位。
+            assert!(s.p.bits() as usize == p_bits || (balance == 0.5 && s.p.bits() as usize == p_bits - 1), "{:?}", s);
+        }
+    }
+
+    let s = gen_semiprime(Bits::new(512).unwrap(), 0.25, &mut rng);
+    assert_eq!(s.n.bits(), 512);
+    assert_eq!(s.p.bits(), 128);
+    assert!(baillie_psw_primality_test_biguint(&s.q).is_accepted());
+
+    // Both 4-bit primes, 11 and 13, show up as the smaller factor.
+    let seen: std::collections::BTreeSet<BigUint> = (0..200).map(|_| gen_semiprime(Bits::new(8).unwrap(), 0.5, &mut rng).p).collect();
+    assert!(seen.contains(&BigUint::from(11u8)) && seen.contains(&BigUint::from(13u8)), "{:?}", seen);
+}
+
+#[test]
+fn test_gen_stats() {
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let (_, stats) = gen_prime_with_stats(Bits::new(512).unwrap(), &mut rng);
+        assert_eq!(stats.rejected() + 1, stats.candidates);
+        assert!(stats.rejected_by_small_factor.keys().all(|p| *p > 5 && *p < crate::thresholds().trial_division_bound as u32));
+    }
+
+    let (_, stats) = gen_safe_prime_with_stats(Bits::safe_prime(128).unwrap(), &mut rng);
+    assert_eq!(stats.rejected() + 1, stats.candidates);
+    assert!(stats.rejected_by_wheel > 0);
+}
+
+#[test]
+fn test_sieve_search() {
+    use crate::baillie_psw_primality_test_biguint;
+
+    let mut rng = rand::thread_rng();
+
+    // Near the top of the range the walk stops at 2 ^ bits.
+    for bits in [25usize, 26, 40, 64, 65, 300] {
+        for top in [TopBits::One, TopBits::Two] {
+            let (p, stats) = gen_prime_inner(bits, top, &[], &mut rng);
+            assert_eq!(p.bits() as usize, bits);
+            assert!(top == TopBits::One || (&p >> (bits - 2)) == BigUint::from(3u8));
+            assert!(baillie_psw_primality_test_biguint(&p).is_accepted(), "P={}", p);
+            assert_eq!(stats.rejected() + 1, stats.candidates);
+        }
+    }
+
+    // The sieve records the same smallest factor as trial division would.
+    let (p, stats) = loop {
+        let mut stats = GenStats::default();
+        if let Some(p) = sieve_search(512, TopBits::One, &[], 1, &mut stats, &mut rng) {
+            break (p, stats);
+        }
+    };
+    let start: BigUint = &p - 2 * (stats.candidates - 1);
+    let mut expected = GenStats::default();
+    for k in 0..stats.candidates - 1 {
+        match prefilter(&(&start + 2 * k)) {
+            Err(rejection) => expected.record(rejection),
+            Ok(()) => expected.rejected_by_witness.push(BigUint::from(0u8)),
+        }
+    }
+    assert_eq!(stats.rejected_by_wheel, expected.rejected_by_wheel);
+    assert_eq!(stats.rejected_by_small_factor, expected.rejected_by_small_factor);
+    assert_eq!(stats.rejected_by_witness.len(), expected.rejected_by_witness.len());
+}
+
+#[test]
+fn test_gen_prime_avoiding() {
+    use crate::miller_rabin::is_prime_u64;
+
+    let mut rng = rand::thread_rng();
+
+    // Both the small-number path and the sieve
+    let avoid = [(1, 8), (2, 3), (3, 10)];
+    for bits in [10usize, 24, 25, 64, 256] {
+        for _ in 0..20 {
+            let p = gen_prime_avoiding(Bits::new(bits as u32).unwrap(), &avoid, &mut rng);
+            assert_eq!(p.bits() as usize, bits);
+            assert!(bits > 64 || is_prime_u64(p.to_u64_digits()[0]), "P={}", p);
+            for (r, m) in avoid {
+                assert_ne!(&p % m, BigUint::from(r), "P={} m={}", p, m);
+            }
+        }
+    }
+
+    // Only p ≡ 3 (mod 4) is left, so −1 is never a square mod p.
+    for _ in 0..20 {
+        let p = gen_prime_avoiding(Bits::new(128).unwrap(), &[(1, 4)], &mut rng);
+        assert_eq!(&p % 4u8, BigUint::from(3u8));
+    }
+
+    // A sieve walk rejects a class without any primality test.
+    let mut stats = GenStats::default();
+    while sieve_search(512, TopBits::One, &[(1, 4)], 1, &mut stats, &mut rng).is_none() {}
+    assert!(stats.rejected_by_residue > 0);
+    assert_eq!(stats.rejected() + 1, stats.candidates);
+}
+
+#[test]
+#[should_panic]
+fn test_gen_prime_avoiding_everything() {
+    gen_prime_avoiding(Bits::new(64).unwrap(), &[(1, 6), (5, 6)], &mut rand::thread_rng());
+}
+
+#[test]
+fn test_small_candidates_with_low_bound() {
+    use crate::miller_rabin::is_prime_u64;
+
+    // The lowest trial-division bound `calibrate` can choose still decides
+    // every candidate of 24 bits or fewer, 257 · 263 and 4093 · 4099 included.
+    let mut rng = rand::thread_rng();
+    let candidates = (3..1u64 << 16).step_by(2).chain([257 * 263, 4093 * 4099, (1 << 24) - 3]);
+    for n in candidates {
+        let accepted = prefilter_with(&BigUint::from(n), 1 << 8)
+            .and_then(|_| miller_rabin(&BigUint::from(n), 1, &mut rng))
+            .is_ok();
+        assert_eq!(accepted, is_prime_u64(n), "N={}", n);
+    }
+}
+
+#[test]
+fn test_padded_bytes() {
+    let n = BigUint::from(0x0102u16);
+    assert_eq!(n.to_padded_be_bytes(32), [0, 0, 1, 2]);
+    assert_eq!(n.to_padded_le_bytes(32), [2, 1, 0, 0]);
+    assert_eq!(n.to_hex_padded(32), "00000102");
+
+    // Widths round up to whole bytes.
+    assert_eq!(n.to_padded_be_bytes(12), [1, 2]);
+    assert_eq!(BigUint::from(0u8).to_padded_be_bytes(0), Vec::<u8>::new());
+    assert_eq!(BigUint::from(0u8).to_hex_padded(16), "0000");
+
+    // A 1023-bit prime still takes the full 128 bytes.
+    let mut rng = rand::thread_rng();
+    let p = gen_prime(Bits::new(1023).unwrap(), &mut rng);
+    let bytes = p.to_padded_be_bytes(1024);
+    assert_eq!(bytes.len(), 128);
+    assert_eq!(bytes[0] >> 7, 0);
+    assert_eq!(BigUint::from_bytes_be(&bytes), p);
+    assert_eq!(p.to_hex_padded(1024).len(), 256);
+    assert_eq!(BigUint::parse_bytes(p.to_hex_padded(1024).as_bytes(), 16), Some(p));
+}
+
+#[test]
+#[should_panic]
+fn test_padded_bytes_overflow() {
+    BigUint::from(0x100u16).to_padded_be_bytes(8);
+}
+
+
+#[bench]
+fn bench_gen_prime(b: &mut test::Bencher) {
+    let mut rng = rand::thread_rng();
+    b.iter(|| gen_prime(Bits::new(test::black_box(512)).unwrap(), &mut rng))
+}
@@ -0,0 +1,388 @@
+// Segmented sieve on the GPU
+// https://en.wikipedia.org/wiki/Sieve_of_Eratosthenes#Segmented_sieve
+//
+// `GpuSieve` walks [lo, hi) in segments like `primes_in_range`, but crosses
+// out the multiples in each segment with wgpu compute shaders, one bit per
+// number. The sieving primes p <= √(hi − 1) and the offset of the next
+// multiple of each in the segment stay on the GPU; the host only appends the
+// primes whose square enters the segment, and reads back the bits.
+//
+// Three passes run per segment:
+//
+//      mark_small  one invocation per 8192 numbers, crossing out the multiples
+//                  of every prime below 8192 in them
+//      mark_large  one invocation per larger prime, which hits the segment at
+//                  most len / 8192 times
+//      advance     moves every offset on to the next segment
+//
+// WGSL has no 64-bit integers, so everything the shaders see is relative to
+// the segment start and fits in a u32; ranges anywhere below 2 ^ 64 work, as
+// long as the sieving primes fit in one storage buffer (√hi up to about 5·10 ^ 8
+// with the 128 MiB most adapters allow), and fall back to the CPU sieve
+// otherwise. The CPU sieve remains the reference the results are tested
+// against.
+use crate::isqrt_u64;
+use crate::primes_up_to;
+use crate::sieve::for_each_segment;
+
+
+// NOTE: 与着色器中的 CHUNK 相同，也是分段长度的最小单位。
+const CHUNK: u32 = 8192;
+const WORKGROUP: u32 = 64;
+const MAX_WORKGROUPS: u32 = 65535;
+
+const SHADER: &str = r#"
+struct Params {
+    len: u32,
+    small: u32,
+    count: u32,
+    pad: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> primes: array<u32>;
+@group(0) @binding(2) var<storage, read_write> offsets: array<u32>;
+@group(0) @binding(3) var<storage, read_write> bits: array<atomic<u32>>;
+
+const CHUNK: u32 = 8192u;
+
+fn mark(j: u32) {
+    atomicOr(&bits[j / 32u], 1u << (j % 32u));
+}
+
+fn index(id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return id.x + id.y * groups.x * 64u;
+}
+
+@compute @workgroup_size(64)
+fn mark_small(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let start = index(id, groups) * CHUNK;
+    if (start >= params.len) {
+        return;
+    }
+    let end = min(start + CHUNK, params.len);
+
+    for (var i = 0u; i < params.small; i++) {
+        let p = primes[i];
+        let first = offsets[i];
+        var j = first;
+        if (first < start) {
+            j = start + (p - (start - first) % p) % p;
+        }
+        for (; j < end; j += p) {
+            mark(j);
+        }
+    }
+}
+
+@compute @workgroup_size(64)
+fn mark_large(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let i = params.small + index(id, groups);
+    if (i >= params.count) {
+        return;
+    }
+
+    let p = primes[i];
+    var j = offsets[i];
+    loop {
+        if (j >= params.len) {
+            break;
+        }
+        mark(j);
+        if (params.len - j <= p) {
+            break;
+        }
+        j += p;
+    }
+}
+
+@compute @workgroup_size(64)
+fn advance(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let i = index(id, groups);
+    if (i >= params.count) {
+        return;
+    }
+
+    let p = primes[i];
+    let first = offsets[i];
+    if (first >= params.len) {
+        offsets[i] = first - params.len;
+    } else {
+        offsets[i] = (p - (params.len - first) % p) % p;
+    }
+}
+"#;
+
+pub struct GpuSieve {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    mark_small: wgpu::ComputePipeline,
+    mark_large: wgpu::ComputePipeline,
+    advance: wgpu::ComputePipeline,
+    // Numbers per segment, a multiple of `CHUNK`.
+    segment_size: u32,
+}
+
+// The buffers of one walk over a range.
+struct Buffers {
+    params: wgpu::Buffer,
+    primes: wgpu::Buffer,
+    offsets: wgpu::Buffer,
+    bits: wgpu::Buffer,
+    staging: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl GpuSieve {
+    // A sieve on the first adapter wgpu finds, or `None` when there is none.
+    pub fn new() -> Option<Self> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await.ok()?;
+            let descriptor = wgpu::DeviceDescriptor { required_limits: adapter.limits(), ..Default::default() };
+            let (device, queue) = adapter.request_device(&descriptor).await.ok()?;
+
+            Some(GpuSieve::with_device(device, queue))
+        })
+    }
+
+    fn with_device(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sieve"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer { ty, has_dynamic_offset: false, min_binding_size: None },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sieve"),
+            entries: &[
+                entry(0, wgpu::BufferBindingType::Uniform),
+                entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                entry(2, wgpu::BufferBindingType::Storage { read_only: false }),
+                entry(3, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("sieve"),
+            bind_group_layouts: &[Some(&layout)],
+            immediate_size: 0,
+        });
+
+        let pipeline = |entry_point: &str| device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(entry_point),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        GpuSieve {
+            mark_small: pipeline("mark_small"),
+            mark_large: pipeline("mark_large"),
+            advance: pipeline("advance"),
+            device,
+            queue,
+            layout,
+            segment_size: 1 << 24,
+        }
+    }
+
+    // Every prime p with lo <= p < hi, in ascending order, as `primes_in_range`.
+    pub fn primes_in_range(&self, lo: u64, hi: u64) -> Vec<u64> {
+        let mut primes = Vec::new();
+        self.for_each_segment(lo, hi, |base, len, words| {
+            for (i, word) in words.iter().enumerate() {
+                let mut word = !word;
+                while word != 0 {
+                    let j = i as u32 * 32 + word.trailing_zeros();
+                    if j >= len {
+                        break;
+                    }
+                    primes.push(base + j as u64);
+                    word &= word - 1;
+                }
+            }
+        });
+
+        primes
+    }
+
+    // The number of primes p with lo <= p < hi.
+    pub fn count_primes(&self, lo: u64, hi: u64) -> u64 {
+        let mut count = 0;
+        self.for_each_segment(lo, hi, |_, len, words| {
+            let whole = len as usize / 32;
+            count += words[..whole].iter().map(|word| word.count_zeros() as u64).sum::<u64>();
+            if len % 32 != 0 {
+                let mask = (1u32 << (len % 32)) - 1;
+                count += (!words[whole] & mask).count_ones() as u64;
+            }
+        });
+
+        count
+    }
+
+    // Sieve [lo, hi) segment by segment. `f(base, len, words)` receives one
+    // segment at a time, where bit j of `words[j / 32]` tells whether
+    // `base + j` is NOT prime, for j < len.
+    fn for_each_segment<F>(&self, lo: u64, hi: u64, mut f: F)
+    where
+        F: FnMut(u64, u32, &[u32]),
+    {
+        if lo >= hi {
+            return;
+        }
+
+        let base_primes = primes_up_to(isqrt_u64(hi - 1));
+        let size = (base_primes.len().max(1) * 4) as u64;
+        if size > self.device.limits().max_storage_buffer_binding_size {
+            // NOTE: 筛选用的素数放不进一个 storage buffer，退回到 CPU 的实现。
+            return for_each_segment(lo, hi, |base, composite| {
+                let mut words = vec![0u32; composite.len().div_ceil(32)];
+                for (j, is_composite) in composite.iter().enumerate() {
+                    words[j / 32] |= (*is_composite as u32) << (j % 32);
+                }
+                f(base, composite.len() as u32, &words);
+                true
+            });
+        }
+
+        let buffers = self.buffers(size);
+        let small = base_primes.partition_point(|p| *p < CHUNK as u64);
+
+        let mut active = 0;
+        let mut start = lo;
+        while start < hi {
+            let end = hi.min(start.saturating_add(self.segment_size as u64));
+            let len = (end - start) as u32;
+
+            // NOTE: p² 进入当前分段的素数才开始划掉倍数，第一个倍数是 max(p², ⌈start / p⌉·p)。
+            let first = active;
+            let mut offsets = Vec::new();
+            while active < base_primes.len() && base_primes[active] * base_primes[active] < end {
+                let p = base_primes[active];
+                // NOTE: 靠近 u64::MAX 时倍数可能溢出，此时剩下的范围里已经没有 p 的倍数了。
+                let m = start.div_ceil(p).saturating_mul(p).max(p * p);
+                offsets.push((m - start).min(u32::MAX as u64) as u32);
+                active += 1;
+            }
+            if active > first {
+                let primes: Vec<u32> = base_primes[first..active].iter().map(|p| *p as u32).collect();
+                self.queue.write_buffer(&buffers.primes, first as u64 * 4, &bytes(&primes));
+                self.queue.write_buffer(&buffers.offsets, first as u64 * 4, &bytes(&offsets));
+            }
+
+            let params = [len, small.min(active) as u32, active as u32, 0];
+            self.queue.write_buffer(&buffers.params, 0, &bytes(&params));
+
+            let words = len.div_ceil(32) as usize;
+            let mut encoder = self.device.create_command_encoder(&Default::default());
+            encoder.clear_buffer(&buffers.bits, 0, None);
+            for (pipeline, invocations) in [
+                (&self.mark_small, len.div_ceil(CHUNK)),
+                (&self.mark_large, (active - small.min(active)) as u32),
+                (&self.advance, active as u32),
+            ] {
+                if invocations == 0 {
+                    continue;
+                }
+
+                let groups = invocations.div_ceil(WORKGROUP);
+                let x = groups.min(MAX_WORKGROUPS);
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, &buffers.bind_group, &[]);
+                pass.dispatch_workgroups(x, groups.div_ceil(x), 1);
+            }
+            encoder.copy_buffer_to_buffer(&buffers.bits, 0, &buffers.staging, 0, Some(words as u64 * 4));
+            self.queue.submit([encoder.finish()]);
+
+            let slice = buffers.staging.slice(..words as u64 * 4);
+            slice.map_async(wgpu::MapMode::Read, |result| result.expect("mapping the sieve segment"));
+            self.device.poll(wgpu::PollType::wait_indefinitely()).expect("waiting for the sieve segment");
+            {
+                let view = slice.get_mapped_range().expect("reading the sieve segment");
+                let mut bits: Vec<u32> = view.chunks_exact(4)
+                    .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+                    .collect();
+                // NOTE: 0 和 1 不是素数，着色器不处理它们。
+                for n in start..end.min(2) {
+                    bits[0] |= 1 << (n - start);
+                }
+                f(start, len, &bits);
+            }
+            buffers.staging.unmap();
+
+            start = end;
+        }
+    }
+
+    fn buffers(&self, size: u64) -> Buffers {
+        use wgpu::BufferUsages as Usages;
+
+        let buffer = |label: &str, size: u64, usage: Usages| self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage,
+            mapped_at_creation: false,
+        });
+
+        let bits_size = (self.segment_size / 8) as u64;
+        let params = buffer("params", 16, Usages::UNIFORM | Usages::COPY_DST);
+        let primes = buffer("primes", size, Usages::STORAGE | Usages::COPY_DST);
+        let offsets = buffer("offsets", size, Usages::STORAGE | Usages::COPY_DST);
+        let bits = buffer("bits", bits_size, Usages::STORAGE | Usages::COPY_SRC | Usages::COPY_DST);
+        let staging = buffer("staging", bits_size, Usages::MAP_READ | Usages::COPY_DST);
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sieve"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: primes.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: offsets.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: bits.as_entire_binding() },
+            ],
+        });
+
+        Buffers { params, primes, offsets, bits, staging, bind_group }
+    }
+}
+
+fn bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+
+#[test]
+fn test_gpu_sieve() {
+    use crate::primes_in_range;
+
+    let mut sieve = match GpuSieve::new() {
+        Some(sieve) => sieve,
+        None => {
+            eprintln!("no GPU adapter, skipping the GPU sieve test");
+            return;
+        },
+    };
+
+    // Small segments, so that the ranges cross several of them and primes
+    // become active part of the way through.
+    sieve.segment_size = 3 * CHUNK;
+    for (lo, hi) in [(0u64, 100_000u64), (1, 3), (99_990, 100_000), (1 << 32, (1 << 32) + 200_000), (1_000_000_000_000 - 100_000, 1_000_000_000_000)] {
+        let expected = primes_in_range(lo, hi);
+        assert_eq!(sieve.primes_in_range(lo, hi), expected, "lo={} hi={}", lo, hi);
+        assert_eq!(sieve.count_primes(lo, hi), expected.len() as u64, "lo={} hi={}", lo, hi);
+    }
+
+    sieve.segment_size = 1 << 24;
+    assert_eq!(sieve.count_primes(0, 100_000_000), 5_761_455);
+    assert!(sieve.primes_in_range(10, 10).is_empty());
+}
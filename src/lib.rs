@@ -1,4 +1,5 @@
 #![feature(test)]
+#![cfg_attr(feature = "async", feature(async_iterator))]
 #![allow(dead_code)]
 
 #[cfg(test)]
@@ -8,20 +9,287 @@ extern crate num_bigint;
 extern crate num_integer;
 
 
+mod convert;
 mod table;
+mod gaps;
+mod gap_records;
+mod spiral;
 mod trial_division;
 mod aks;
+mod wilson;
 mod miller_rabin;
 mod solovay_strassen;
+mod fermat;
+mod frobenius;
+mod fibonacci;
+mod rounds;
+mod montgomery;
+mod modular;
+mod roots;
+mod limbs;
+mod small_vec;
+mod u256;
+mod factor;
+mod factorization;
+mod stage1;
+mod ecm;
+mod context;
+mod pratt;
+mod sieve;
+mod capacity;
+mod totient;
+mod generate;
+mod baillie_psw;
+mod proth;
+mod prove;
+mod bls;
+mod ecpp;
+mod primo;
+mod spsp;
+mod tester;
+mod euler_strong;
+mod confidence;
+mod expr;
+mod divisor;
+mod checked;
+mod params;
+mod calibrate;
+pub mod compare;
+pub mod conformance;
+pub mod math;
+pub mod filters;
+mod sample;
+mod quality;
+mod small;
+mod dispatch;
+mod safe_prime;
+mod check;
+#[cfg(feature = "derandomized")]
+mod derandomize;
+#[cfg(feature = "derandomized")]
+mod derive;
+#[cfg(feature = "testing")]
+mod adversarial;
+#[cfg(feature = "testing")]
+mod vectors;
+#[cfg(feature = "witness-stats")]
+mod stats;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "roaring")]
+mod roaring;
+#[cfg(feature = "gpu")]
+mod gpu;
 
+pub use self::convert::AsBigUint;
+pub use self::convert::try_as_biguint;
 pub use self::table::table_query_u16;
+pub use self::table::table_query_u32_partial;
+pub use self::table::table_stats;
+pub use self::table::TableStats;
+pub use self::gaps::first_primes;
+pub use self::gaps::prime_gaps;
+pub use self::gaps::FirstPrimes;
+pub use self::gaps::FIRST_PRIMES_COUNT;
+pub use self::gap_records::maximal_prime_gaps;
+pub use self::gap_records::is_maximal_gap;
+pub use self::gap_records::is_first_occurrence_gap;
+pub use self::gap_records::max_gap_in_range;
+pub use self::gap_records::GapRecord;
+pub use self::gap_records::export_gaps_csv;
+pub use self::spiral::ulam_spiral_coords;
+pub use self::small::is_prime_u8;
+pub use self::small::is_prime_u16;
+pub use self::small::is_prime_u32;
+pub use self::dispatch::is_prime;
+pub use self::dispatch::IsPrime;
+pub use self::divisor::SmallPrimeDivisor;
+pub use self::checked::TryError;
+pub use self::checked::{MAX_GEN_BITS, MAX_PRIME_INDEX};
+pub use self::params::Bits;
+pub use self::params::PrimeRange;
+pub use self::params::{RsaModulusBits, SafePrimeBits};
+pub use self::checked::try_miller_rabin_with_witnesses_u64;
+pub use self::checked::try_miller_rabin_primality_test_biguint;
+pub use self::checked::try_solovay_strassen_primality_test_u64;
+pub use self::checked::try_solovay_strassen_primality_test_biguint;
+pub use self::checked::try_gen_prime;
+pub use self::checked::try_jacobi_symbol_i64;
+pub use self::checked::try_nth_prime;
 pub use self::aks::aks_primality_test_usize;
+pub use self::wilson::wilson_test;
 pub use self::trial_division::trial_division_u64;
 pub use self::trial_division::trial_division_u128;
 pub use self::trial_division::trial_division_biguint;
 pub use self::miller_rabin::miller_rabin_primality_test_u64;
+pub use self::miller_rabin::miller_rabin_with_witnesses_u64;
+pub use self::miller_rabin::is_prime_const;
+pub use self::miller_rabin::decompose_n_minus_one;
+pub use self::miller_rabin::decompose_n_minus_one_biguint;
+pub use self::miller_rabin::MILLER_RABIN_WITNESSES_U64;
 pub use self::miller_rabin::miller_rabin_primality_test_biguint;
+pub use self::miller_rabin::miller_test_grh_biguint;
+pub use self::miller_rabin::is_strong_prp_u64;
+pub use self::miller_rabin::is_strong_prp_biguint;
 pub use self::solovay_strassen::solovay_strassen_primality_test_u64;
+pub use self::solovay_strassen::solovay_strassen_primality_test_biguint;
+pub use self::solovay_strassen::is_euler_jacobi_prp;
+pub use self::solovay_strassen::jacobi_symbol_i64;
+pub use self::solovay_strassen::jacobi_symbol_bigint;
+pub use self::solovay_strassen::jacobi_symbol_biguint;
+pub use self::solovay_strassen::quadratic_residues_mod_p;
+pub use self::fermat::fermat_primality_test_u64;
+pub use self::fermat::fermat_primality_test_biguint;
+pub use self::frobenius::frobenius_primality_test_biguint;
+pub use self::fibonacci::fibonacci_primality_test_u64;
+pub use self::fibonacci::fibonacci_primality_test_biguint;
+pub use self::fibonacci::fermat_fibonacci_test_u64;
+pub use self::rounds::recommended_rounds;
+pub use self::rounds::SecurityLevel;
+pub use self::roots::isqrt_u64;
+pub use self::roots::isqrt_u128;
+pub use self::roots::isqrt_biguint;
+pub use self::roots::icbrt_u64;
+pub use self::roots::icbrt_u128;
+pub use self::roots::icbrt_biguint;
+pub use self::roots::iroot_u64;
+pub use self::roots::iroot_u128;
+pub use self::roots::iroot_biguint;
+pub use self::roots::is_perfect_square_u64;
+pub use self::roots::is_perfect_square_biguint;
+pub use self::roots::perfect_power_u64;
+pub use self::roots::perfect_power_biguint;
+pub use self::limbs::miller_rabin_primality_test_limbs;
+pub use self::limbs::solovay_strassen_primality_test_limbs;
+pub use self::u256::U256;
+pub use self::u256::baillie_psw_primality_test_u256;
+pub use self::factor::factorize_u64;
+pub use self::factor::factorize_nonzero_u64;
+pub use self::factor::Factorizer;
+pub use self::factor::FactorState;
+pub use self::factor::Method;
+pub use self::factor::Pending;
+pub use self::factorization::factorize;
+pub use self::factorization::Factorization;
+pub use self::factorization::ParseFactorizationError;
+pub use self::stage1::primes_powers_up_to;
+pub use self::stage1::lcm_of_primes_powers_up_to;
+pub use self::ecm::ecm_stage1_biguint;
+pub use self::ecm::MontgomeryCurve;
+pub use self::ecm::XzPoint;
+pub use self::context::global;
+pub use self::context::Context;
+pub use self::pratt::pratt_certificate_u64;
+pub use self::pratt::PrattCertificate;
+pub use self::sieve::primes_up_to;
+pub use self::sieve::primes_in_range;
+pub use self::sieve::primes;
+pub use self::sieve::Primes;
+pub use self::sieve::prime_pi;
+pub use self::sieve::nth_prime;
+pub use self::sieve::nth_prime_nonzero;
+pub use self::sieve::next_primes;
+pub use self::capacity::next_prime_capacity;
+pub use self::capacity::prime_capacities;
+pub use self::totient::totient_sum;
+pub use self::totient::count_coprime_pairs;
+pub use self::modular::ModCtx;
+pub use self::modular::Mod;
+pub use self::sieve::least_prime_factor_table;
+pub use self::sieve::least_prime_factors_in_range;
+pub use self::sieve::composites_in_range;
+pub use self::sieve::Composites;
+pub use self::sieve::factorize_with_table;
+pub use self::sieve::factorize_range;
+pub use self::sieve::FactorizeRange;
+pub use self::sample::sample_primes;
+pub use self::quality::check_rsa_prime_quality;
+pub use self::quality::FactorCheck;
+pub use self::quality::RsaPrimeReport;
+pub use self::generate::gen_prime;
+pub use self::generate::gen_safe_prime;
+pub use self::safe_prime::SafePrimeTester;
+pub use self::generate::gen_prime_with_top_bits;
+pub use self::generate::gen_prime_avoiding;
+pub use self::generate::gen_rsa_primes;
+pub use self::generate::TopBits;
+pub use self::generate::gen_semiprime;
+pub use self::generate::Semiprime;
+pub use self::generate::gen_prime_with_stats;
+pub use self::generate::gen_safe_prime_with_stats;
+pub use self::generate::GenStats;
+pub use self::generate::PaddedBytes;
+pub use self::baillie_psw::baillie_psw_primality_test_u64;
+pub use self::baillie_psw::baillie_psw_primality_test_biguint;
+pub use self::baillie_psw::baillie_psw_plus;
+pub use self::baillie_psw::is_prime_biguint_auto;
+pub use self::proth::proth_primality_test;
+pub use self::proth::proth_witness;
+pub use self::proth::proth_decompose;
+pub use self::check::check;
+pub use self::check::CheckOutput;
+pub use self::check::Report;
+pub use self::check::Provenance;
+pub use self::prove::prove_prime;
+pub use self::prove::Proof;
+pub use self::prove::ProofState;
+pub use self::prove::ParseProofStateError;
+pub use self::prove::Evidence;
+pub use self::prove::Certificate;
+pub use self::prove::PocklingtonFactor;
+pub use self::bls::prove_prime_bls;
+pub use self::bls::LucasFactor;
+pub use self::ecpp::EcppCertificate;
+pub use self::ecpp::EcppStep;
+pub use self::primo::parse_primo_certificate;
+pub use self::primo::ParsePrimoError;
+pub use self::spsp::parse_spsp_list;
+pub use self::spsp::read_spsp_list;
+pub use self::spsp::audit_witness_ladder;
+pub use self::spsp::LadderAudit;
+pub use self::spsp::ParseSpspError;
+pub use self::tester::PrimalityTester;
+pub use self::tester::DEFAULT_PREFILTER_BOUND;
+pub use self::calibrate::calibrate;
+pub use self::calibrate::thresholds;
+pub use self::calibrate::Thresholds;
+pub use self::euler_strong::euler_plus_strong_test;
+pub use self::confidence::Assessment;
+pub use self::confidence::DEFAULT_PROBABLE_ERROR;
+pub use self::expr::parse_biguint_expr;
+pub use self::expr::ParseExprError;
+#[cfg(feature = "derandomized")]
+pub use self::derandomize::miller_rabin_primality_test_biguint_derandomized;
+#[cfg(feature = "derandomized")]
+pub use self::derive::derive_prime;
+#[cfg(feature = "testing")]
+pub use self::adversarial::gen_strong_pseudoprime;
+#[cfg(feature = "testing")]
+pub use self::adversarial::StrongPseudoprime;
+#[cfg(feature = "testing")]
+pub use self::vectors::gen_test_vectors;
+#[cfg(feature = "testing")]
+pub use self::vectors::TestVector;
+#[cfg(feature = "testing")]
+pub use self::vectors::VectorKind;
+#[cfg(feature = "testing")]
+pub use self::vectors::MIN_TEST_VECTOR_BITS;
+#[cfg(feature = "witness-stats")]
+pub use self::stats::witness_stats;
+#[cfg(feature = "witness-stats")]
+pub use self::stats::reset_witness_stats;
+#[cfg(feature = "witness-stats")]
+pub use self::stats::WitnessStats;
+#[cfg(feature = "cache")]
+pub use self::cache::CacheStats;
+#[cfg(feature = "roaring")]
+pub use self::roaring::primes_roaring;
+#[cfg(feature = "roaring")]
+pub use self::roaring::RoaringSet;
+#[cfg(feature = "gpu")]
+pub use self::gpu::GpuSieve;
 
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -30,8 +298,176 @@ pub enum Primality {
     Prime,
     Composite,
     ProbablyPrime,
+    // Nothing was decided, e.g. because a time budget ran out.
+    Unknown,
 }
 
+impl Primality {
+    // The answer of a deterministic test.
+    pub const fn from_proof(is_prime: bool) -> Primality {
+        if is_prime { Primality::Prime } else { Primality::Composite }
+    }
+
+    // What a probabilistic test answers when it finds no witness:
+    // `ProbablyPrime`, or `Unknown` under the `proven-only` feature.
+    #[cfg(not(feature = "proven-only"))]
+    pub(crate) const PROBABLE: Primality = Primality::ProbablyPrime;
+    #[cfg(feature = "proven-only")]
+    pub(crate) const PROBABLE: Primality = Primality::Unknown;
+
+    // The answer of a probabilistic test, which can only prove compositeness.
+    pub fn from_probable(is_prime: bool) -> Primality {
+        if is_prime { Primality::PROBABLE } else { Primality::Composite }
+    }
+
+    // Whether primality was proven.
+    pub fn is_prime(self) -> bool {
+        self == Primality::Prime
+    }
+
+    // Whether this is a proven or a probable prime.
+    pub fn is_probably_prime(self) -> bool {
+        matches!(self, Primality::Prime | Primality::ProbablyPrime)
+    }
+
+    // `Assessment::confidence` for a result that came without its error
+    // bound, taking `DEFAULT_PROBABLE_ERROR` for `ProbablyPrime`. A test run
+    // with fewer rounds than `is_prime_biguint_auto` is scored too high.
+    pub fn confidence(self) -> f64 {
+        let error = match self {
+            Primality::ProbablyPrime => DEFAULT_PROBABLE_ERROR,
+            _ => 0.0,
+        };
+
+        Assessment { primality: self, error }.confidence()
+    }
+
+    // Whether a test let n through: a proof, or `PROBABLE`, which is `Unknown`
+    // under `proven-only` and not a probable prime by `is_probably_prime`.
+    pub(crate) fn is_accepted(self) -> bool {
+        self.is_probably_prime() || self == Primality::PROBABLE
+    }
+
+    // `Ok` with how firmly n is known to be prime, or `Err` with why it is
+    // not, so that a check can end with `?`.
+    pub fn ok_or_composite(self) -> Result<ProofLevel, CompositeInfo> {
+        match self {
+            Primality::Prime => Ok(ProofLevel::Proven),
+            Primality::ProbablyPrime => Ok(ProofLevel::Probable),
+            Primality::ZeroOrOne => Err(CompositeInfo::ZeroOrOne),
+            Primality::Composite => Err(CompositeInfo::Composite),
+            Primality::Unknown => Err(CompositeInfo::Unknown),
+        }
+    }
+
+    pub fn into_option(self) -> Option<ProofLevel> {
+        self.ok_or_composite().ok()
+    }
+
+    // `true` only for a proven prime and `false` only for a proven composite;
+    // whatever else `policy` does not explicitly settle is an error.
+    pub fn to_bool(self, policy: BoolPolicy) -> Result<bool, BoolConversionError> {
+        match self {
+            Primality::Prime => Ok(true),
+            Primality::Composite => Ok(false),
+            Primality::ProbablyPrime if policy.accept_probable => Ok(true),
+            Primality::ZeroOrOne if policy.zero_or_one_is_false => Ok(false),
+            primality => Err(BoolConversionError { primality }),
+        }
+    }
+
+    // NOTE: ZeroOrOne 与 Composite 都确定不是素数，二者处于同一等级。
+    fn rank(self) -> u8 {
+        match self {
+            Primality::ZeroOrOne
+            | Primality::Composite => 0,
+            Primality::Unknown => 1,
+            Primality::ProbablyPrime => 2,
+            Primality::Prime => 3,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ProofLevel {
+    // Proven prime.
+    Proven,
+    // Passed a probabilistic test.
+    Probable,
+}
+
+// How `Primality::to_bool` treats the results that are neither a proof of
+// primality nor of compositeness.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct BoolPolicy {
+    // `ProbablyPrime` becomes `true`.
+    pub accept_probable: bool,
+    // `ZeroOrOne` becomes `false`.
+    pub zero_or_one_is_false: bool,
+}
+
+impl BoolPolicy {
+    // Only proofs convert.
+    pub const STRICT: BoolPolicy = BoolPolicy { accept_probable: false, zero_or_one_is_false: false };
+    // What `Into<bool>` does.
+    pub const LENIENT: BoolPolicy = BoolPolicy { accept_probable: true, zero_or_one_is_false: true };
+}
+
+// A result the policy in use does not map to a `bool`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct BoolConversionError {
+    pub primality: Primality,
+}
+
+impl core::fmt::Display for BoolConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.primality {
+            Primality::ProbablyPrime => write!(f, "a probable prime is not a proof of primality"),
+            Primality::ZeroOrOne => write!(f, "zero and one are neither prime nor composite"),
+            Primality::Unknown => write!(f, "primality could not be decided"),
+            primality => write!(f, "{:?} has no ambiguity", primality),
+        }
+    }
+}
+
+// Why a result does not count as (probably) prime.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum CompositeInfo {
+    ZeroOrOne,
+    Composite,
+    // NOTE: 没有得到结论的结果也不能当作素数使用。
+    Unknown,
+}
+
+impl core::fmt::Display for CompositeInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CompositeInfo::ZeroOrOne => write!(f, "zero or one is not prime"),
+            CompositeInfo::Composite => write!(f, "composite"),
+            CompositeInfo::Unknown => write!(f, "primality could not be decided"),
+        }
+    }
+}
+
+// Ordered by how strongly a result says "prime":
+//
+//      Prime > ProbablyPrime > Unknown > Composite
+//
+// ZeroOrOne and Composite are both certainly not prime and compare as
+// neither greater nor less than each other.
+impl PartialOrd for Primality {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self != other && self.rank() == other.rank() {
+            return None;
+        }
+
+        Some(self.rank().cmp(&other.rank()))
+    }
+}
+
+// NOTE: 这两个转换会把 ProbablyPrime 当作素数、把任意 true 当作已证明的素数，
+//       启用 `strict-bool` 后将其移除，调用方需要改用上面的显式方法。
+#[cfg(not(feature = "strict-bool"))]
 impl Into<bool> for Primality {
     fn into(self) -> bool {
         match self {
@@ -42,6 +478,18 @@ impl Into<bool> for Primality {
     }
 }
 
+// NOTE: 不启用 `strict-bool` 时，标准库会根据上面的 Into<bool> 自动提供一个
+//       永远成功的 TryFrom，因此这个严格的版本只能在 `strict-bool` 下提供。
+#[cfg(feature = "strict-bool")]
+impl core::convert::TryFrom<Primality> for bool {
+    type Error = BoolConversionError;
+
+    fn try_from(primality: Primality) -> Result<bool, BoolConversionError> {
+        primality.to_bool(BoolPolicy::STRICT)
+    }
+}
+
+#[cfg(not(feature = "strict-bool"))]
 impl From<bool> for Primality {
     fn from(v: bool) -> Primality {
         match v {
@@ -50,3 +498,95 @@ impl From<bool> for Primality {
         }
     }
 }
+
+
+#[test]
+fn test_primality_conversions() {
+    assert_eq!(Primality::from_proof(true), Primality::Prime);
+    assert_eq!(Primality::from_proof(false), Primality::Composite);
+    assert_eq!(Primality::from_probable(true), Primality::PROBABLE);
+    assert_eq!(Primality::from_probable(false), Primality::Composite);
+
+    assert!(Primality::Prime.is_prime() && Primality::Prime.is_probably_prime());
+    assert!(!Primality::ProbablyPrime.is_prime() && Primality::ProbablyPrime.is_probably_prime());
+    for p in [Primality::ZeroOrOne, Primality::Composite, Primality::Unknown] {
+        assert!(!p.is_prime() && !p.is_probably_prime());
+        assert_eq!(p.into_option(), None);
+    }
+
+    assert_eq!(Primality::Prime.ok_or_composite(), Ok(ProofLevel::Proven));
+    assert_eq!(Primality::ProbablyPrime.into_option(), Some(ProofLevel::Probable));
+    assert_eq!(Primality::ZeroOrOne.ok_or_composite(), Err(CompositeInfo::ZeroOrOne));
+    assert_eq!(Primality::Composite.ok_or_composite(), Err(CompositeInfo::Composite));
+    assert_eq!(Primality::Unknown.ok_or_composite(), Err(CompositeInfo::Unknown));
+
+    // Composes with `?`.
+    let check = |n: u64| -> Result<ProofLevel, CompositeInfo> {
+        let level = miller_rabin_primality_test_u64(n).ok_or_composite()?;
+        Ok(level)
+    };
+    assert_eq!(check(65537), Ok(ProofLevel::Proven));
+    assert_eq!(check(65535).unwrap_err().to_string(), "composite");
+}
+
+#[test]
+fn test_primality_to_bool() {
+    for policy in [BoolPolicy::STRICT, BoolPolicy::LENIENT] {
+        assert_eq!(Primality::Prime.to_bool(policy), Ok(true));
+        assert_eq!(Primality::Composite.to_bool(policy), Ok(false));
+        assert!(Primality::Unknown.to_bool(policy).is_err());
+    }
+
+    let err = Primality::ProbablyPrime.to_bool(BoolPolicy::STRICT).unwrap_err();
+    assert_eq!(err, BoolConversionError { primality: Primality::ProbablyPrime });
+    assert_eq!(err.to_string(), "a probable prime is not a proof of primality");
+    assert!(Primality::ZeroOrOne.to_bool(BoolPolicy::STRICT).is_err());
+
+    assert_eq!(Primality::ProbablyPrime.to_bool(BoolPolicy::LENIENT), Ok(true));
+    assert_eq!(Primality::ZeroOrOne.to_bool(BoolPolicy::LENIENT), Ok(false));
+
+    let policy = BoolPolicy { accept_probable: true, ..BoolPolicy::STRICT };
+    assert_eq!(Primality::ProbablyPrime.to_bool(policy), Ok(true));
+    assert!(Primality::ZeroOrOne.to_bool(policy).is_err());
+
+    #[cfg(feature = "strict-bool")]
+    {
+        use core::convert::TryFrom;
+
+        assert_eq!(bool::try_from(Primality::Prime), Ok(true));
+        assert!(bool::try_from(Primality::ProbablyPrime).is_err());
+        assert!(bool::try_from(Primality::ZeroOrOne).is_err());
+    }
+
+    #[cfg(not(feature = "strict-bool"))]
+    for p in [Primality::ProbablyPrime, Primality::ZeroOrOne, Primality::Unknown] {
+        let b: bool = p.into();
+        assert_eq!(p.to_bool(BoolPolicy::LENIENT).unwrap_or(false), b);
+    }
+}
+
+#[test]
+fn test_primality_probable() {
+    use num_bigint::BigUint;
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let answers = [
+        Primality::from_probable(true),
+        miller_rabin_primality_test_biguint(&m127, 4),
+        solovay_strassen_primality_test_biguint(&m127, 4),
+        fermat_primality_test_biguint(&m127, 4),
+        baillie_psw_primality_test_biguint(&m127),
+        is_prime_biguint_auto(&m127),
+        check::<Primality>(&m127),
+    ];
+
+    #[cfg(not(feature = "proven-only"))]
+    assert!(answers.iter().all(|p| *p == Primality::ProbablyPrime), "{:?}", answers);
+    #[cfg(feature = "proven-only")]
+    assert!(answers.iter().all(|p| *p == Primality::Unknown), "{:?}", answers);
+
+    // Proofs and composites are unaffected either way.
+    assert_eq!(miller_rabin_primality_test_u64(1_000_000_007), Primality::Prime);
+    assert_eq!(is_prime_biguint_auto(&(&m127 * 3u8)), Primality::Composite);
+    assert_eq!(fermat_fibonacci_test_u64(1_000_000_007), Primality::Prime);
+}
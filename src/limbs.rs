@@ -0,0 +1,380 @@
+// Primality tests on raw little-endian `u64` limb slices
+//
+// Numbers handed over from other bignum libraries, FFI or hardware RNGs usually
+// arrive as limbs. The cheap cases (zero-extended single limbs, even numbers)
+// are answered straight from the slice, odd candidates of several limbs are
+// trial-divided by the primes below 2 ^ 10, and those of up to 4 limbs are
+// screened by BPSW on a `U256`. The k random rounds then run on the borrowed
+// slice with `MontgomeryLimbs`, whose buffers are allocated once per call, and
+// keep their error bound of 4 ^ −k (2 ^ −k for Solovay–Strassen) whatever
+// BPSW said.
+//
+// NOTE: 启用 rng-fallback 且没有熵时，派生底数要由 n 的 BigUint 计算，
+//       这时会复制成 BigUint，走 BigUint 版本。
+use crate::{Primality, U256};
+use crate::baillie_psw_primality_test_u256;
+use crate::miller_rabin_primality_test_u64;
+#[cfg(feature = "rng-fallback")]
+use crate::miller_rabin_primality_test_biguint;
+use crate::solovay_strassen_primality_test_u64;
+#[cfg(feature = "rng-fallback")]
+use crate::solovay_strassen_primality_test_biguint;
+use crate::montgomery::MontgomeryLimbs;
+
+use rand::Rng;
+
+
+// Strip the zero limbs at the most significant end.
+fn trim(limbs: &[u64]) -> &[u64] {
+    let len = limbs.iter().rposition(|limb| *limb != 0).map(|i| i + 1).unwrap_or(0);
+    &limbs[..len]
+}
+
+// Whether a < b, for slices of the same length.
+pub(crate) fn less_limbs(a: &[u64], b: &[u64]) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+// a ← a − b, wrapping around, for slices of the same length.
+pub(crate) fn sub_limbs(a: &mut [u64], b: &[u64]) {
+    let mut borrow = false;
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        let (d, b1) = x.overflowing_sub(*y);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        *x = d;
+        borrow = b1 || b2;
+    }
+}
+
+// a ← a >> shift, for shift < 64
+fn shr_limbs(a: &mut [u64], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+
+    let mut carry = 0;
+    for limb in a.iter_mut().rev() {
+        let next = *limb << (64 - shift);
+        *limb = *limb >> shift | carry;
+        carry = next;
+    }
+}
+
+fn bit(limbs: &[u64], i: usize) -> bool {
+    limbs[i / 64] >> (i % 64) & 1 == 1
+}
+
+fn bits(limbs: &[u64]) -> usize {
+    64 * limbs.len() - limbs.last().map(|limb| limb.leading_zeros() as usize).unwrap_or(64)
+}
+
+// Answer the inputs the probabilistic tests are not defined for.
+fn small_or_even(limbs: &[u64]) -> Option<Primality> {
+    match limbs {
+        [] | [1] => Some(Primality::ZeroOrOne),
+        [2] | [3] => Some(Primality::Prime),
+        _ if limbs[0] & 1 == 0 => Some(Primality::Composite),
+        _ => None,
+    }
+}
+
+// A definite `Composite` for the odd multi-limb candidates that fit in a
+// `U256` and fail BPSW.
+fn screen_u256(limbs: &[u64]) -> Option<Primality> {
+    if limbs.len() > 4 {
+        return None;
+    }
+
+    let mut padded = [0u64; 4];
+    padded[..limbs.len()].copy_from_slice(limbs);
+    match baillie_psw_primality_test_u256(&U256::from_limbs(padded)) {
+        Primality::Composite => Some(Primality::Composite),
+        _ => None,
+    }
+}
+
+// Whether the multi-limb n is divisible by an odd prime below 2 ^ 10.
+fn has_small_factor(limbs: &[u64]) -> bool {
+    let primes = crate::global().small_primes();
+    let end = primes.partition_point(|p| *p < 1 << 10);
+    let primes = &primes[1..end];
+
+    let mut i = 0;
+    while i < primes.len() {
+        // NOTE: 若干素数之积 g 不超过 2^32，r·(2^64 mod g) 就不会溢出 u64。
+        let mut g = 1u64;
+        let mut j = i;
+        while j < primes.len() && g * primes[j] as u64 <= u32::MAX as u64 {
+            g *= primes[j] as u64;
+            j += 1;
+        }
+
+        let shift = ((1u128 << 64) % g as u128) as u64;
+        let r = limbs.iter().rev().fold(0u64, |r, limb| (r * shift + limb % g) % g);
+        if primes[i..j].iter().any(|p| r % *p as u64 == 0) {
+            return true;
+        }
+
+        i = j;
+    }
+
+    false
+}
+
+// A uniformly random a in [2, n − 2], written into `a`.
+fn random_base(rng: &mut impl Rng, n: &[u64], a: &mut [u64]) {
+    let top = n.len() - 1;
+    loop {
+        a.iter_mut().for_each(|limb| *limb = rng.gen());
+        a[top] = match n[top] {
+            u64::MAX => rng.gen(),
+            hi => rng.gen_range(0, hi + 1),
+        };
+
+        // NOTE: n 是奇数，n − 1 只是把最低位清零。
+        let too_small = a[1..].iter().all(|limb| *limb == 0) && a[0] < 2;
+        let too_large = !less_limbs(a, n) || (a[1..] == n[1..] && a[0] == n[0] - 1);
+        if !too_small && !too_large {
+            return;
+        }
+    }
+}
+
+// The Jacobi symbol (a | n) for an odd n, by the binary algorithm; both
+// buffers are used as scratch.
+fn jacobi_limbs(a: &mut Vec<u64>, n: &mut Vec<u64>) -> i8 {
+    let mut t = 1;
+    loop {
+        if a.iter().all(|limb| *limb == 0) {
+            let one = n[0] == 1 && n[1..].iter().all(|limb| *limb == 0);
+            return if one { t } else { 0 };
+        }
+
+        // NOTE: (2 | n) = −1 当且仅当 n ≡ 3, 5 (mod 8)；整个 limb 的 0 是偶数个 2，不改变符号。
+        let zeros = a.iter().position(|limb| *limb != 0).unwrap();
+        if zeros > 0 {
+            a.copy_within(zeros.., 0);
+            let len = a.len();
+            a[len - zeros..].iter_mut().for_each(|limb| *limb = 0);
+        }
+        let shift = a[0].trailing_zeros();
+        if shift % 2 == 1 && (n[0] % 8 == 3 || n[0] % 8 == 5) {
+            t = -t;
+        }
+        shr_limbs(a, shift);
+
+        if less_limbs(a, n) {
+            core::mem::swap(a, n);
+            if a[0] % 4 == 3 && n[0] % 4 == 3 {
+                t = -t;
+            }
+        }
+        sub_limbs(a, n);
+    }
+}
+
+// The k random Miller–Rabin rounds on the odd multi-limb n.
+fn miller_rabin_rounds(n: &[u64], k: usize) -> Primality {
+    let s = n.len();
+    let mut ctx = MontgomeryLimbs::new(n);
+
+    let mut minus_one = n.to_vec();
+    sub_limbs(&mut minus_one, ctx.one());
+
+    // n − 1 = 2 ^ r · d, whose bits above bit 0 are those of n
+    let r = (1..bits(n)).find(|i| bit(n, *i)).unwrap();
+    let d_bits = || (r..bits(n)).rev().map(|i| bit(n, i));
+
+    let mut rng = crate::global().rng();
+    let (mut a, mut base, mut x) = (vec![0; s], vec![0; s], vec![0; s]);
+
+    'WitnessLoop: for _i in 0..k {
+        random_base(&mut rng, n, &mut a);
+        base.copy_from_slice(&a);
+        ctx.encode(&mut base);
+        ctx.pow(&base, d_bits(), &mut x);
+
+        if x == ctx.one() || x == minus_one {
+            continue 'WitnessLoop;
+        }
+
+        for _ in 1..r {
+            ctx.square(&mut x);
+            if x == minus_one {
+                continue 'WitnessLoop;
+            }
+        }
+
+        #[cfg(feature = "witness-stats")]
+        crate::stats::record(Some((_i, &crate::AsBigUint::as_biguint(&a[..]))));
+
+        return Primality::Composite;
+    }
+
+    #[cfg(feature = "witness-stats")]
+    crate::stats::record(None);
+
+    Primality::PROBABLE
+}
+
+// The k random Solovay–Strassen rounds on the odd multi-limb n.
+fn solovay_strassen_rounds(n: &[u64], k: usize) -> Primality {
+    let s = n.len();
+    let mut ctx = MontgomeryLimbs::new(n);
+
+    let mut minus_one = n.to_vec();
+    sub_limbs(&mut minus_one, ctx.one());
+
+    // (n − 1) / 2
+    let exp_bits = || (1..bits(n)).rev().map(|i| bit(n, i));
+
+    let mut rng = crate::global().rng();
+    let (mut a, mut base, mut x) = (vec![0; s], vec![0; s], vec![0; s]);
+    let (mut scratch_a, mut scratch_n) = (vec![0; s], vec![0; s]);
+
+    for _ in 0..k {
+        random_base(&mut rng, n, &mut a);
+
+        scratch_a.copy_from_slice(&a);
+        scratch_n.copy_from_slice(n);
+        let minus = match jacobi_limbs(&mut scratch_a, &mut scratch_n) {
+            0 => return Primality::Composite,
+            1 => false,
+            _ => true,
+        };
+
+        base.copy_from_slice(&a);
+        ctx.encode(&mut base);
+        ctx.pow(&base, exp_bits(), &mut x);
+        let expected = if minus { &minus_one[..] } else { ctx.one() };
+        if x != expected {
+            return Primality::Composite;
+        }
+    }
+
+    Primality::PROBABLE
+}
+
+// Whether the derived bases of `rng-fallback` are in use, which need n as a
+// BigUint.
+#[cfg(feature = "rng-fallback")]
+fn derived_bases() -> bool {
+    !crate::global().entropy_available()
+}
+
+pub fn miller_rabin_primality_test_limbs(limbs: &[u64], k: usize) -> Primality {
+    let limbs = trim(limbs);
+    if let Some(ret) = small_or_even(limbs) {
+        return ret;
+    }
+
+    match limbs {
+        // NOTE: 单个 limb 的情况直接走确定性的 u64 版本，不需要分配内存。
+        [n] => miller_rabin_primality_test_u64(*n),
+        _ if has_small_factor(limbs) => Primality::Composite,
+        _ => match screen_u256(limbs) {
+            Some(ret) => ret,
+            #[cfg(feature = "rng-fallback")]
+            None if derived_bases() => miller_rabin_primality_test_biguint(limbs, k),
+            None => miller_rabin_rounds(limbs, k),
+        },
+    }
+}
+
+pub fn solovay_strassen_primality_test_limbs(limbs: &[u64], k: usize) -> Primality {
+    let limbs = trim(limbs);
+    if let Some(ret) = small_or_even(limbs) {
+        return ret;
+    }
+
+    match limbs {
+        [n] => solovay_strassen_primality_test_u64(*n, k),
+        _ if has_small_factor(limbs) => Primality::Composite,
+        _ => match screen_u256(limbs) {
+            Some(ret) => ret,
+            #[cfg(feature = "rng-fallback")]
+            None if derived_bases() => solovay_strassen_primality_test_biguint(limbs, k),
+            None => solovay_strassen_rounds(limbs, k),
+        },
+    }
+}
+
+
+#[test]
+fn test_miller_rabin_primality_test_limbs() {
+    use crate::table_query_u16;
+
+    for n in 0..u16::MAX {
+        let a = miller_rabin_primality_test_limbs(&[n as u64, 0], 8).is_accepted();
+        let b = table_query_u16(n).is_prime();
+        assert_eq!(a, b, "N={}", n);
+    }
+
+    assert_eq!(miller_rabin_primality_test_limbs(&[], 8), Primality::ZeroOrOne);
+    assert_eq!(miller_rabin_primality_test_limbs(&[0, 0], 8), Primality::ZeroOrOne);
+    assert_eq!(miller_rabin_primality_test_limbs(&[2], 8), Primality::Prime);
+
+    // 2 ^ 64 + 13 (prime), 2 ^ 64 + 15 (= 31 * 107 * 5561273462077043), 2 ^ 64
+    assert_eq!(miller_rabin_primality_test_limbs(&[13, 1], 8), Primality::PROBABLE);
+    assert_eq!(miller_rabin_primality_test_limbs(&[15, 1], 8), Primality::Composite);
+    assert_eq!(miller_rabin_primality_test_limbs(&[0, 1], 8), Primality::Composite);
+
+    // 2 ^ 127 - 1
+    assert_eq!(miller_rabin_primality_test_limbs(&[u64::MAX, u64::MAX >> 1], 8), Primality::PROBABLE);
+
+    // 2 ^ 255 - 19 (prime) and 2 ^ 255 - 21 (divisible by 11 * 13) in 4 limbs,
+    // 2 ^ 256 + 1 (divisible by 1238926361552897) in 5
+    let p25519 = [u64::MAX - 18, u64::MAX, u64::MAX, u64::MAX >> 1];
+    assert_eq!(miller_rabin_primality_test_limbs(&p25519, 8), Primality::PROBABLE);
+    assert_eq!(miller_rabin_primality_test_limbs(&[u64::MAX - 20, u64::MAX, u64::MAX, u64::MAX >> 1], 8), Primality::Composite);
+    assert_eq!(miller_rabin_primality_test_limbs(&[1, 0, 0, 0, 1], 8), Primality::Composite);
+
+    // The 4-limb composites are rejected before any BigUint is built.
+    for limbs in [[15u64, 1, 0, 0], [u64::MAX - 20, u64::MAX, u64::MAX, u64::MAX >> 1]] {
+        assert_eq!(screen_u256(&limbs), Some(Primality::Composite));
+    }
+    assert_eq!(screen_u256(&p25519), None);
+
+    // Wider than a U256: 2 ^ 521 − 1 (prime), and its product with 2 ^ 127 − 1,
+    // which has no factor below 2 ^ 10 and goes through the borrowed rounds.
+    let m521: Vec<u64> = (0..9).map(|i| if i == 8 { (1 << 9) - 1 } else { u64::MAX }).collect();
+    assert_eq!(miller_rabin_primality_test_limbs(&m521, 8), Primality::PROBABLE);
+    let product = (crate::AsBigUint::as_biguint(&m521[..]).into_owned() * ((num_bigint::BigUint::from(1u8) << 127usize) - 1u8)).to_u64_digits();
+    assert!(!has_small_factor(&product));
+    assert_eq!(miller_rabin_primality_test_limbs(&product, 8), Primality::Composite);
+    assert_eq!(solovay_strassen_primality_test_limbs(&product, 8), Primality::Composite);
+    // 2 ^ 64 + 15 and 2 ^ 65 − 1 (divisible by 31), but not 2 ^ 256 + 1
+    assert!(has_small_factor(&[15, 1]) && has_small_factor(&[u64::MAX, 1]));
+    assert!(!has_small_factor(&[1, 0, 0, 0, 1]));
+}
+
+#[test]
+fn test_jacobi_limbs() {
+    use crate::AsBigUint;
+    use crate::jacobi_symbol_biguint;
+
+    let n = [u64::MAX - 18, u64::MAX, u64::MAX, u64::MAX >> 1];
+    let mut rng = crate::global().rng();
+    let mut a = [0u64; 4];
+    for _ in 0..200 {
+        random_base(&mut rng, &n, &mut a);
+        let expected = jacobi_symbol_biguint(&a.as_biguint(), &n.as_biguint());
+        assert_eq!(jacobi_limbs(&mut a.to_vec(), &mut n.to_vec()), expected, "a={:?}", a);
+    }
+
+    // (a | n) = 0 when they share a factor: 3 · 5 and 2 ^ 64 · 3 + 9
+    assert_eq!(jacobi_limbs(&mut vec![15, 0], &mut vec![9, 3]), 0);
+    assert_eq!(jacobi_limbs(&mut vec![0, 1], &mut vec![13, 1]), jacobi_symbol_biguint(&[0u64, 1].as_biguint(), &[13u64, 1].as_biguint()));
+}
+
+#[test]
+fn test_solovay_strassen_primality_test_limbs() {
+    assert_eq!(solovay_strassen_primality_test_limbs(&[3, 0], 8), Primality::Prime);
+    assert_eq!(solovay_strassen_primality_test_limbs(&[97], 8), Primality::Prime);
+    assert_eq!(solovay_strassen_primality_test_limbs(&[91], 8), Primality::Composite);
+    assert_eq!(solovay_strassen_primality_test_limbs(&[13, 1], 8), Primality::PROBABLE);
+    assert_eq!(solovay_strassen_primality_test_limbs(&[u64::MAX, u64::MAX >> 1], 8), Primality::PROBABLE);
+
+    let m521: Vec<u64> = (0..9).map(|i| if i == 8 { (1 << 9) - 1 } else { u64::MAX }).collect();
+    assert_eq!(solovay_strassen_primality_test_limbs(&m521, 8), Primality::PROBABLE);
+}
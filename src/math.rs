@@ -0,0 +1,169 @@
+// Number-theoretic building blocks
+//
+// Chinese remainder theorem, batched
+// https://en.wikipedia.org/wiki/Chinese_remainder_theorem
+//
+// D. J. Bernstein, "Scaled remainder trees" / "Fast multiplication and its
+// applications" (2008), section 23.
+//
+// x ≡ r_i (mod m_i) for pairwise coprime m_i has the solution
+//
+//      x = Σ r_i · s_i · (M / m_i)  (mod M),   s_i = (M / m_i) ^ −1 (mod m_i),
+//
+// with M = Π m_i. Folding one congruence in after the other costs O(k ^ 2)
+// multiplications of growing size. A product tree of the m_i gives M, a
+// remainder tree gives every M mod m_i ^ 2 and thereby M / m_i mod m_i, and the
+// sum is assembled bottom-up along the same tree, so that every level costs
+// about one multiplication of numbers the size of M.
+//
+// A `CrtTree` keeps the tree and the s_i, for the residue-number-system use of
+// reconstructing many numbers over the same moduli.
+use crate::AsBigUint;
+
+use num_integer::Integer;
+use num_bigint::{BigInt, BigUint, Sign};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CrtTree {
+    // levels[0] are the moduli, every next level the products of pairs of the
+    // previous one (an odd one out is carried up unchanged), the last [M].
+    levels: Vec<Vec<BigUint>>,
+    // (M / m_i) ^ −1 (mod m_i)
+    inverses: Vec<BigUint>,
+}
+
+impl CrtTree {
+    // `None` when a modulus is 0 or two of them share a factor.
+    pub fn new<M: AsBigUint>(moduli: &[M]) -> Option<Self> {
+        let leaves: Vec<BigUint> = moduli.iter().map(|m| m.as_biguint().into_owned()).collect();
+        if leaves.iter().any(|m| m.bits() == 0) {
+            return None;
+        }
+
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let level = levels.last().unwrap();
+            let next = level.chunks(2)
+                .map(|pair| pair.iter().product())
+                .collect();
+            levels.push(next);
+        }
+
+        // NOTE: 自顶向下求 M mod m^2，叶子处 (M mod m^2) / m 即 (M / m) mod m。
+        let mut remainders = vec![levels.last().and_then(|root| root.first()).cloned().unwrap_or_else(|| BigUint::from(1u8))];
+        for level in levels.iter().rev().skip(1) {
+            remainders = level.iter()
+                .enumerate()
+                .map(|(i, m)| &remainders[i / 2] % (m * m))
+                .collect();
+        }
+
+        let inverses = levels[0].iter()
+            .zip(remainders.iter())
+            .map(|(m, r)| inverse(&(r / m), m))
+            .collect::<Option<Vec<BigUint>>>()?;
+
+        Some(CrtTree { levels, inverses })
+    }
+
+    pub fn moduli(&self) -> &[BigUint] {
+        &self.levels[0]
+    }
+
+    // The product M of the moduli.
+    pub fn modulus(&self) -> BigUint {
+        self.levels.last().and_then(|root| root.first()).cloned().unwrap_or_else(|| BigUint::from(1u8))
+    }
+
+    // The x in [0, M) with x ≡ residues[i] (mod moduli[i]).
+    pub fn reconstruct<R: AsBigUint>(&self, residues: &[R]) -> BigUint {
+        assert_eq!(residues.len(), self.levels[0].len(), "one residue per modulus");
+
+        let mut values: Vec<BigUint> = residues.iter()
+            .zip(self.levels[0].iter().zip(self.inverses.iter()))
+            .map(|(r, (m, s))| r.as_biguint().as_ref() % m * s % m)
+            .collect();
+
+        // NOTE: 每层把相邻两项合并为 v_L · P_R + v_R · P_L，最终得到 Σ v_i · (M / m_i)。
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            values = values.chunks(2)
+                .zip(level.chunks(2))
+                .map(|(v, p)| match (v, p) {
+                    ([vl, vr], [pl, pr]) => vl * pr + vr * pl,
+                    _ => v[0].clone(),
+                })
+                .collect();
+        }
+
+        match values.first() {
+            Some(x) => x % self.modulus(),
+            None => BigUint::from(0u8),
+        }
+    }
+}
+
+// The x in [0, Π m_i) with x ≡ r_i (mod m_i) for every (r_i, m_i) in `pairs`,
+// or `None` when the moduli are not pairwise coprime or one of them is 0.
+pub fn chinese_pairwise<R: AsBigUint, M: AsBigUint>(pairs: &[(R, M)]) -> Option<BigUint> {
+    let moduli: Vec<BigUint> = pairs.iter().map(|(_, m)| m.as_biguint().into_owned()).collect();
+    let residues: Vec<BigUint> = pairs.iter().map(|(r, _)| r.as_biguint().into_owned()).collect();
+
+    CrtTree::new(&moduli).map(|tree| tree.reconstruct(&residues))
+}
+
+// a ^ −1 (mod m), or `None` when they share a factor.
+fn inverse(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    let m_signed = BigInt::from_biguint(Sign::Plus, m.clone());
+    let e = BigInt::from_biguint(Sign::Plus, a % m).extended_gcd(&m_signed);
+    if e.gcd != BigInt::from(1u8) {
+        return None;
+    }
+
+    e.x.mod_floor(&m_signed).to_biguint()
+}
+
+
+#[test]
+fn test_chinese_pairwise() {
+    use crate::primes_in_range;
+
+    assert_eq!(chinese_pairwise(&[(2u8, 3u8), (3, 5), (2, 7)]), Some(BigUint::from(23u8)));
+    assert_eq!(chinese_pairwise::<u8, u8>(&[]), Some(BigUint::from(0u8)));
+    assert_eq!(chinese_pairwise(&[(5u8, 7u8)]), Some(BigUint::from(5u8)));
+    assert_eq!(chinese_pairwise(&[(0u8, 1u8), (4, 9)]), Some(BigUint::from(4u8)));
+    // Not coprime, or 0
+    assert_eq!(chinese_pairwise(&[(1u8, 6u8), (1, 9)]), None);
+    assert_eq!(chinese_pairwise(&[(1u8, 0u8)]), None);
+
+    // A 2000-bit number from its residues modulo 1000 word-size primes and
+    // modulo odd-sized trees
+    let primes = primes_in_range(1 << 40, (1 << 40) + 30_000);
+    let x = (BigUint::from(1u8) << 2000usize) / 3u8;
+    for k in [1, 2, 3, 7, 64, 100, primes.len()] {
+        let moduli = &primes[..k];
+        let tree = CrtTree::new(moduli).unwrap();
+        assert_eq!(tree.moduli().len(), k);
+
+        let expected = &x % tree.modulus();
+        let residues: Vec<BigUint> = moduli.iter().map(|m| &x % *m).collect();
+        assert_eq!(tree.reconstruct(&residues), expected, "k={}", k);
+
+        let pairs: Vec<(BigUint, u64)> = residues.into_iter().zip(moduli.iter().copied()).collect();
+        assert_eq!(chinese_pairwise(&pairs), Some(expected), "k={}", k);
+    }
+    assert!(primes.len() * 40 > 2000);
+}
+
+#[bench]
+fn bench_crt_tree_reconstruct(b: &mut test::Bencher) {
+    use crate::primes_in_range;
+
+    let primes = primes_in_range(1 << 40, (1 << 40) + 30_000);
+    let tree = CrtTree::new(&primes).unwrap();
+    let x = (BigUint::from(1u8) << 2000usize) / 3u8;
+    let residues: Vec<BigUint> = primes.iter().map(|m| &x % *m).collect();
+    b.iter(|| {
+        tree.reconstruct(&residues)
+    })
+}
@@ -1,13 +1,15 @@
 // Miller–Rabin primality test
 // https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test
 use crate::Primality;
+use crate::AsBigUint;
+use crate::PrimalityTester;
+use crate::table_query_u16;
 
 use num_bigint::BigUint;
-use num_bigint::RandBigInt;
 
 
 // a * b % m
-fn modmul_u64(a: u64, b: u64, m: u64) -> u64 {
+const fn modmul_u64(a: u64, b: u64, m: u64) -> u64 {
     match a.checked_mul(b) {
         Some(r) => r % m,
         None => {
@@ -18,11 +20,25 @@ fn modmul_u64(a: u64, b: u64, m: u64) -> u64 {
     }
 }
 
+// n − 1 = 2 ^ r · d with d odd, for odd n > 1.
+pub const fn decompose_n_minus_one(n: u64) -> (u64, u32) {
+    let r = (n - 1).trailing_zeros();
+    ((n - 1) >> r, r)
+}
+
+// n − 1 = 2 ^ r · d with d odd, for odd n > 1.
+pub fn decompose_n_minus_one_biguint(n: &BigUint) -> (BigUint, u64) {
+    let n_minus_one: BigUint = n - 1u8;
+    // NOTE: 移位一次完成，不需要逐位地 % 2、/ 2（每次都会分配新的 BigUint）。
+    let r = n_minus_one.trailing_zeros().unwrap_or(0);
+    (n_minus_one >> r as usize, r)
+}
+
 // Modular exponentiation
 // https://en.wikipedia.org/wiki/Modular_exponentiation
 // 
 // base ^ exponent % modulus
-pub(crate) fn modpow(base: u64, exponent: u64, modulus: u64) -> u64 {
+pub(crate) const fn modpow(base: u64, exponent: u64, modulus: u64) -> u64 {
     let mut base = base;
     let mut exponent = exponent;
     let modulus = modulus;
@@ -46,61 +62,118 @@ pub(crate) fn modpow(base: u64, exponent: u64, modulus: u64) -> u64 {
     return result;
 }
 
-pub fn miller_rabin_primality_test_u64(n: u64) -> Primality {
+// Testing against small sets of bases
+// https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test#Testing_against_small_sets_of_bases
+// 
+// Deterministic variants of the Miller-Rabin primality test:
+// https://miller-rabin.appspot.com/
+//
+// Each entry is (largest n, bases); the first entry whose bound is >= n applies.
+pub static MILLER_RABIN_WITNESSES_U64: &[(u64, &[u64])] = &[
+    (                        2_046, &[2]),
+    (                    1_373_652, &[2, 3]),
+    (                    9_080_190, &[31, 73]),
+    (                   25_326_000, &[2, 3, 5]),
+    (                3_215_031_750, &[2, 3, 5, 7]),
+    (                4_759_123_140, &[2, 7, 61]),
+    (            1_122_004_669_632, &[2, 13, 23, 1662803]),
+    (            2_152_302_898_746, &[2, 3, 5, 7, 11]),
+    (            3_474_749_660_382, &[2, 3, 5, 7, 11, 13]),
+    (          341_550_071_728_320, &[2, 3, 5, 7, 11, 13, 17]),
+    (    3_825_123_056_546_413_050, &[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+    // n < 18_446_744_073_709_551_616 = 22 <= 2 ^ 64 - 1
+    (                     u64::MAX, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]),
+];
+
+pub const fn miller_rabin_primality_test_u64(n: u64) -> Primality {
+    // NOTE: 65536 以内直接查表，省去全部的模幂运算。
+    if n <= u16::MAX as u64 {
+        return table_query_u16(n as u16);
+    }
+
+    if n.is_multiple_of(2) {
+        return Primality::Composite;
+    }
+
+    miller_rabin_u64_with(n, MILLER_RABIN_WITNESSES_U64)
+}
+
+// Deterministic Miller–Rabin with a caller-chosen set of bases, e.g. the
+// seven bases of Jim Sinclair that cover every n < 2 ^ 64,
+//
+//      miller_rabin_with_witnesses_u64(n, &[2, 325, 9375, 28178, 450775, 9780504, 1795265022])
+//
+// or a smaller set verified for a smaller range. n is prime when every base
+// passes, which is only as true as the set is for n: nothing here checks it,
+// and no table answers small n, so that a set with holes shows them. A base
+// that is a multiple of n is skipped, as such sets are published to be used.
+// Without any base there is nothing to decide an odd n > 3 with, and the
+// answer is `Unknown`; `try_miller_rabin_with_witnesses_u64` reports it as an
+// error instead.
+pub const fn miller_rabin_with_witnesses_u64(n: u64, witnesses: &[u64]) -> Primality {
+    match n {
+        0 | 1 => Primality::ZeroOrOne,
+        2 | 3 => Primality::Prime,
+        _ if n.is_multiple_of(2) => Primality::Composite,
+        _ if witnesses.is_empty() => Primality::Unknown,
+        _ => miller_rabin_u64_with(n, &[(u64::MAX, witnesses)]),
+    }
+}
+
+// NOTE: 输入超出 `ladder` 覆盖的范围时返回 Unknown。
+//       这里是 const fn，不能使用迭代器，所有的循环都写成 while。
+pub(crate) const fn miller_rabin_u64_with(n: u64, ladder: &[(u64, &[u64])]) -> Primality {
     // Deterministic Miller primality testing
     // https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test#Deterministic_variants
     // 
     // Input: n > 1, an odd integer to be tested for primality
     // Output: “composite” if n is composite, “prime” otherwise
-    debug_assert!(n > 1 && n % 2 != 0);
+    //
+    // NOTE: 调用者只传入大于 1 的奇数；其它输入同样给出正确的答案，在 debug 构建中也不会 panic。
+    match n {
+        0 | 1 => return Primality::ZeroOrOne,
+        2 => return Primality::Prime,
+        _ if n % 2 == 0 => return Primality::Composite,
+        _ => { },
+    }
+
+    let mut i = 0;
+    while i < ladder.len() && n > ladder[i].0 {
+        i += 1;
+    }
+    if i == ladder.len() {
+        return Primality::Unknown;
+    }
+    let witnesses: &[u64] = ladder[i].1;
 
     let n_minus_one = n - 1;
 
     // write n as 2r·d + 1 with d odd (by factoring out powers of 2 from n − 1)
-    let mut d = n_minus_one;
-    let mut r = 0u64;
-    while d % 2 == 0 {
-        d /= 2;
-        r += 1;
-    }
-
-    // Testing against small sets of bases
-    // https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test#Testing_against_small_sets_of_bases
-    // 
-    // Deterministic variants of the Miller-Rabin primality test:
-    // https://miller-rabin.appspot.com/
-    let witnesses: &[u64] = match n {
-                          0..=                        2 => unreachable!(),
-                          3..=                     2046 => &[2],
-                       2047..=                1_373_652 => &[2, 3],
-                  1_373_653..=                9_080_190 => &[31, 73],
-                  9_080_191..=               25_326_000 => &[2, 3, 5],
-                 25_326_001..=            3_215_031_750 => &[2, 3, 5, 7],
-              3_215_031_751..=            4_759_123_140 => &[2, 7, 61],
-              4_759_123_141..=        1_122_004_669_632 => &[2, 13, 23, 1662803],
-          1_122_004_669_633..=        2_152_302_898_746 => &[2, 3, 5, 7, 11],
-          2_152_302_898_747..=        3_474_749_660_382 => &[2, 3, 5, 7, 11, 13],
-          3_474_749_660_383..=      341_550_071_728_320 => &[2, 3, 5, 7, 11, 13, 17],
-        341_550_071_728_321..=3_825_123_056_546_413_050 => &[2, 3, 5, 7, 11, 13, 17, 19, 23],
-        // n < 18_446_744_073_709_551_616 = 22 <= 2 ^ 64 - 1
-        _ => &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37],
-    };
+    let (d, r) = decompose_n_minus_one(n);
+
+    let mut i = 0;
+    'WitnessLoop: while i < witnesses.len() {
+        // NOTE: 底数是 n 的倍数时不提供任何信息。
+        let a = witnesses[i] % n;
+        i += 1;
+        if a == 0 {
+            continue 'WitnessLoop;
+        }
 
-    let k = witnesses.len();
-    'WitnessLoop: for i in 0..k {
-        let a = witnesses[i];
         let mut x = modpow(a, d, n);
         if x == 1 || x == n_minus_one {
             continue 'WitnessLoop;
         }
 
         // repeat r − 1 times:
-        for _ in 0..r {
+        let mut j = 0;
+        while j < r {
             // x ← x2 mod n
             x = modpow(x, 2, n);
             if x == n_minus_one {
                 continue 'WitnessLoop;
             }
+            j += 1;
         }
 
         // composite
@@ -111,64 +184,331 @@ pub fn miller_rabin_primality_test_u64(n: u64) -> Primality {
     return Primality::Prime;
 }
 
-pub fn miller_rabin_primality_test_biguint(n: &BigUint, k: usize) -> Primality {
+// One Miller–Rabin round to the base a: whether n is a strong probable prime
+// to base a, that is, with n − 1 = 2 ^ r · d and d odd,
+//
+//      a ^ d ≡ 1  or  a ^ (2 ^ s · d) ≡ −1 (mod n) for some 0 ≤ s < r.
+//
+// Every odd prime passes for every a it does not divide; a composite that
+// passes is a strong pseudoprime to base a. Even n, n < 3 and a ≡ 0 (mod n)
+// never pass, and nothing is answered from a table, so that small
+// pseudoprimes stay visible.
+pub const fn is_strong_prp_u64(n: u64, a: u64) -> bool {
+    if n < 3 || n.is_multiple_of(2) || a.is_multiple_of(n) {
+        return false;
+    }
+
+    let bases: &[u64] = &[a];
+    let ladder: &[(u64, &[u64])] = &[(u64::MAX, bases)];
+    matches!(miller_rabin_u64_with(n, ladder), Primality::Prime)
+}
+
+pub fn is_strong_prp_biguint(n: &(impl AsBigUint + ?Sized), a: &(impl AsBigUint + ?Sized)) -> bool {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    // NOTE: 先排除 n < 3 和偶数，n = 0 时下面的取模会除以零。
+    if n.bits() < 2 || !n.bit(0) {
+        return false;
+    }
+
+    let a = a.as_biguint().as_ref() % n;
+    if let [n] = n.to_u64_digits().as_slice() {
+        return is_strong_prp_u64(*n, a.to_u64_digits().first().copied().unwrap_or(0));
+    }
+    if a.bits() == 0 {
+        return false;
+    }
+
+    find_witness_biguint(n, 1, |_| a.clone()).is_none()
+}
+
+// Deterministic primality of any u64, including the inputs
+// `miller_rabin_primality_test_u64` is not defined for.
+pub(crate) const fn is_prime_u64(n: u64) -> bool {
+    match n {
+        0 | 1 => false,
+        2 | 3 => true,
+        _ => !n.is_multiple_of(2) && matches!(miller_rabin_primality_test_u64(n), Primality::Prime),
+    }
+}
+
+// `is_prime_u64` for constant expressions, e.g. to check a hash table or NTT
+// modulus at compile time:
+//
+//      const _: () = assert!(is_prime_const(998_244_353));
+pub const fn is_prime_const(n: u64) -> bool {
+    is_prime_u64(n)
+}
+
+// Fail the build unless the constant expression `$n` is a prime below 2 ^ 64:
+//
+//      static_assert_prime!(1_000_000_007);
+#[macro_export]
+macro_rules! static_assert_prime {
+    ($n:expr) => {
+        const _: () = assert!($crate::is_prime_const($n), concat!(stringify!($n), " is not prime"));
+    };
+}
+
+pub fn miller_rabin_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
     // Miller–Rabin test
     // https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test#Miller%E2%80%93Rabin_test
     // 
-    // Input #1: n > 3, an odd integer to be tested for primality
+    // Input #1: n, any integer; n < 2 ^ 32 (2 and 3 included) and even n are answered exactly
     // Input #2: k, the number of rounds of testing to perform
     // Output: “composite” if n is found to be composite, “probably prime” otherwise
+    crate::global().tester().miller_rabin_biguint(n, k)
+}
+
+// Random rounds, after trial division by the primes below `tester`'s bound.
+pub(crate) fn miller_rabin_biguint_with(n: &BigUint, k: usize, tester: &PrimalityTester) -> Primality {
+    if tester.has_small_factor(n) {
+        return Primality::Composite;
+    }
+
+    let n_minus_two: BigUint = n - 2u8;
+
+    let mut witnesses = crate::global().witnesses(n);
+
+    // pick a random integer a in the range [2, n − 2]
+    match find_witness_biguint(n, k, |_| witnesses.next(&n_minus_two)) {
+        // composite
+        Some(_) => Primality::Composite,
+        // probably prime
+        None => Primality::PROBABLE,
+    }
+}
+
+// Miller's test
+// https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test#Miller_test
+//
+// E. Bach, "Explicit bounds for primality testing and related problems",
+// Math. Comp. 55 (1990): under the generalized Riemann hypothesis every odd
+// composite n has a strong witness below 2 · ln(n) ^ 2.
+//
+// The strong liars of n lie in a proper subgroup of (Z/nZ)*, and the least
+// element outside it is below the bound; since a subgroup is closed under
+// products, so is its least prime. Only the prime bases are therefore tried.
+// That is still about 79 000 modular exponentiations for a 1024-bit n, so the
+// test suits those who want a reproducible answer free of randomness more than
+// those who want a fast one.
+//
+// The answer is free of randomness but not a proof: a prime n comes back as
+// `ProbablyPrime`, and is prime if the GRH holds. `Composite` is always a
+// proof, and inputs up to 64 bits are answered exactly.
+pub fn miller_test_grh_biguint(n: &(impl AsBigUint + ?Sized)) -> Primality {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    if let Some(primality) = crate::tester::small(n) {
+        return primality;
+    }
+    if let [n] = n.to_u64_digits().as_slice() {
+        return Primality::from_proof(is_prime_u64(*n));
+    }
+
+    // NOTE: ln(n) < bits · ln 2，向上取整得到的上界只会多测几个底数。
+    let ln_n = n.bits() as f64 * core::f64::consts::LN_2;
+    let bound = (2.0 * ln_n * ln_n).ceil() as u64;
+    let bases = crate::primes_up_to(bound);
+
+    miller_rabin_rounds_biguint(n, bases.len(), |i| BigUint::from(bases[i]))
+}
+
+// The Miller–Rabin rounds themselves, with the base for round `i` given by `witness(i)`.
+pub(crate) fn miller_rabin_rounds_biguint<F>(n: &BigUint, k: usize, witness: F) -> Primality
+where
+    F: FnMut(usize) -> BigUint,
+{
+    if crate::global().has_small_factor(n) {
+        return Primality::Composite;
+    }
+
+    match find_witness_biguint(n, k, witness) {
+        // composite
+        Some(_) => Primality::Composite,
+        // probably prime
+        None => Primality::PROBABLE,
+    }
+}
+
+// Run `k` rounds with the bases `witness(0..k)`, returning the first base that
+// proves `n` composite.
+pub(crate) fn find_witness_biguint<F>(n: &BigUint, k: usize, mut witness: F) -> Option<BigUint>
+where
+    F: FnMut(usize) -> BigUint,
+{
     debug_assert!(k > 0);
     
-    let zero = BigUint::from(0u8);
     let one  = BigUint::from(1u8);
-    let two  = BigUint::from(2u8);
 
     if cfg!(debug_assertions) {
         let four = BigUint::from(4u8);
-        debug_assert!(n > &four && n % 2u8 != zero);
+        debug_assert!(n > &four && n.bit(0));
     }
 
     let n_minus_one: BigUint = n - 1u8;
-    let n_minus_two: BigUint = n - 2u8;
 
     // write n as 2r·d + 1 with d odd (by factoring out powers of 2 from n − 1)
-    let mut d = n_minus_one.clone();
-    let mut r = 0usize;
-    while &d % 2u8 == zero {
-        d /= 2u8;
-        r += 1;
-    }
-
-    let mut rng = rand::thread_rng();
+    let (d, r) = decompose_n_minus_one_biguint(n);
 
-    'WitnessLoop: for _ in 0..k {
-        // pick a random integer a in the range [2, n − 2]
-        let a = rng.gen_biguint_range(&two, &n_minus_two);
-        let mut x = a.modpow(&d, &n);
+    'WitnessLoop: for i in 0..k {
+        let a = witness(i);
+        let mut x = a.modpow(&d, n);
         
-        if &x == &one || &x == &n_minus_one {
+        if x == one || x == n_minus_one {
             continue 'WitnessLoop;
         }
 
         // repeat r − 1 times:
         for _ in 0..r {
             // x ← x ^ 2 mod n
-            x = x.modpow(&two, &n);
-            if &x == &n_minus_one {
+            x = &x * &x % n;
+            if x == n_minus_one {
                 continue 'WitnessLoop;
             }
         }
 
         // composite
-        return Primality::Composite;
+        #[cfg(feature = "witness-stats")]
+        crate::stats::record(Some((i, &a)));
+
+        return Some(a);
     }
-    
-    // probably prime
-    return Primality::ProbablyPrime;
+
+    #[cfg(feature = "witness-stats")]
+    crate::stats::record(None);
+
+    None
 }
 
 
+#[test]
+fn test_decompose_n_minus_one() {
+    assert_eq!(decompose_n_minus_one(3), (1, 1));
+    assert_eq!(decompose_n_minus_one(5), (1, 2));
+    assert_eq!(decompose_n_minus_one(7), (3, 1));
+    assert_eq!(decompose_n_minus_one(65537), (1, 16));
+    assert_eq!(decompose_n_minus_one(u64::MAX), (u64::MAX >> 1, 1));
+
+    for n in (3..10_000u64).step_by(2) {
+        let (d, r) = decompose_n_minus_one(n);
+        assert_eq!(d % 2, 1);
+        assert_eq!(d << r, n - 1);
+        assert_eq!(decompose_n_minus_one_biguint(&BigUint::from(n)), (BigUint::from(d), r as u64));
+    }
+
+    let n = (BigUint::from(3u8) << 200usize) + 1u8;
+    assert_eq!(decompose_n_minus_one_biguint(&n), (BigUint::from(3u8), 200));
+}
+
+#[test]
+fn test_is_prime_const() {
+    const P: bool = is_prime_const(1_000_000_007);
+    const NTT: bool = is_prime_const(998_244_353);
+    const _: () = assert!(!is_prime_const(3_825_123_056_546_413_051));
+    const M61: Primality = miller_rabin_primality_test_u64((1 << 61) - 1);
+    const _: () = assert!(P && NTT);
+    assert_eq!(P, is_prime_u64(1_000_000_007));
+    assert_eq!(NTT, is_prime_u64(998_244_353));
+    assert_eq!(M61, Primality::Prime);
+
+    assert!(!is_prime_const(0) && !is_prime_const(1) && is_prime_const(2));
+    assert!(is_prime_const(18446744073709551557) && !is_prime_const(u64::MAX));
+    static_assert_prime!(998_244_353);
+    static_assert_prime!((1 << 61) - 1);
+    static_assert_prime!(u64::MAX - 58);
+
+    const R: u64 = modpow(2, 10, 1000);
+    assert_eq!(R, 24);
+}
+
+#[test]
+fn test_is_strong_prp() {
+    // OEIS A001262 and A020229, the strong pseudoprimes to bases 2 and 3
+    let spsps = |a: u64| (3..20_000u64).filter(|n| !is_prime_u64(*n) && is_strong_prp_u64(*n, a)).collect::<Vec<u64>>();
+    assert_eq!(spsps(2), [2047, 3277, 4033, 4681, 8321, 15841]);
+    assert_eq!(spsps(3), [121, 703, 1891, 3281, 8401, 8911, 10585, 12403, 16531, 18721, 19345]);
+
+    assert!((3..20_000u64).filter(|n| is_prime_u64(*n)).all(|n| is_strong_prp_u64(n, 2) && is_strong_prp_u64(n, n + 2)));
+    assert!(!is_strong_prp_u64(1, 2) && !is_strong_prp_u64(2, 3) && !is_strong_prp_u64(7, 14) && !is_strong_prp_u64(9, 0));
+    assert!(is_strong_prp_u64(2047, 2) && !is_strong_prp_u64(2047, 3));
+
+    // The least strong pseudoprime to the first 9 prime bases, and to the
+    // first 11 too; 37 exposes it.
+    let n = 3_825_123_056_546_413_051u64;
+    assert!([2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31].iter().all(|a| is_strong_prp_u64(n, *a)));
+    assert!(!is_strong_prp_u64(n, 37));
+
+    // The least to the first 12, beyond 64 bits; 41 exposes it.
+    let n = BigUint::parse_bytes(b"318665857834031151167461", 10).unwrap();
+    assert!([2u8, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37].iter().all(|a| is_strong_prp_biguint(&n, a)));
+    assert!(!is_strong_prp_biguint(&n, &41u8));
+    assert!(!is_strong_prp_biguint(&(&n + 1u8), &2u8) && !is_strong_prp_biguint(&n, &n));
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    assert!(is_strong_prp_biguint(&m127, &(&m127 + 3u8)));
+    for n in [0u64, 1, 2, 2047, 3277, 121, 1_000_000_007, 3_825_123_056_546_413_051] {
+        for a in [0u64, 2, 3, 29, u64::MAX] {
+            assert_eq!(is_strong_prp_biguint(&n, &a), is_strong_prp_u64(n, a), "N={} a={}", n, a);
+        }
+    }
+}
+
+#[test]
+fn test_miller_rabin_with_witnesses_u64() {
+    use crate::audit_witness_ladder;
+
+    const SINCLAIR: &[u64] = &[2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+    for n in 0..100_000u64 {
+        assert_eq!(miller_rabin_with_witnesses_u64(n, SINCLAIR).is_prime(), is_prime_u64(n), "N={}", n);
+    }
+    for n in [3_825_123_056_546_413_051u64, 18446744073709551557, u64::MAX, (1 << 61) - 1, 4_759_123_141] {
+        assert_eq!(miller_rabin_with_witnesses_u64(n, SINCLAIR).is_prime(), is_prime_u64(n), "N={}", n);
+    }
+
+    // The first ladder entries, used past their bounds
+    assert_eq!(miller_rabin_with_witnesses_u64(2047, &[2]), Primality::Prime);
+    assert_eq!(miller_rabin_with_witnesses_u64(2047, &[2, 3]), Primality::Composite);
+    assert_eq!(miller_rabin_with_witnesses_u64(3_215_031_751, &[2, 3, 5, 7]), Primality::Prime);
+    assert_eq!(miller_rabin_with_witnesses_u64(3_215_031_751, &[2, 7, 61]), Primality::Composite);
+
+    assert_eq!(miller_rabin_with_witnesses_u64(2047, &[]), Primality::Unknown);
+    assert_eq!(miller_rabin_with_witnesses_u64(2048, &[]), Primality::Composite);
+
+    // The same set as a one-entry ladder passes the pseudoprime audit.
+    let spsps = (3..1_000_000u64).step_by(2).filter(|n| !is_prime_u64(*n) && is_strong_prp_u64(*n, 2));
+    assert!(audit_witness_ladder(&[(u64::MAX, SINCLAIR)], spsps).is_ok());
+}
+
+#[test]
+fn test_miller_test_grh_biguint() {
+    use crate::table_query_u16;
+
+    for n in 0..4096u16 {
+        assert_eq!(miller_test_grh_biguint(&n), table_query_u16(n), "N={}", n);
+    }
+    assert_eq!(miller_test_grh_biguint(&(u64::MAX - 58)), Primality::Prime);
+    assert_eq!(miller_test_grh_biguint(&3_825_123_056_546_413_051u64), Primality::Composite);
+
+    // 2 ^ 89 − 1 and 2 ^ 127 − 1, products of large primes, and the least
+    // strong pseudoprime to the first 13 prime bases
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    assert_eq!(miller_test_grh_biguint(&m89), Primality::PROBABLE);
+    assert_eq!(miller_test_grh_biguint(&m127), Primality::PROBABLE);
+    assert_eq!(miller_test_grh_biguint(&(BigUint::from(u64::MAX - 58) * (u64::MAX - 82))), Primality::Composite);
+    assert_eq!(miller_test_grh_biguint(&(&m89 * &m127)), Primality::Composite);
+    let spsp = BigUint::from(3317044064679887385961981u128);
+    assert_eq!(miller_rabin_rounds_biguint(&spsp, 13, |i| BigUint::from([2u8, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41][i])), Primality::PROBABLE);
+    assert_eq!(miller_test_grh_biguint(&spsp), Primality::Composite);
+
+    // Reproducible
+    assert_eq!(miller_test_grh_biguint(&m127), miller_test_grh_biguint(&m127));
+}
+
 #[bench]
 fn bench_miller_rabin_primality_test_biguint(b: &mut test::Bencher) {
     let n = test::black_box(BigUint::from(18446744073709551615u64));
@@ -186,13 +526,18 @@ fn bench_miller_rabin_primality_test_u64(b: &mut test::Bencher) {
     })
 }
 
+#[bench]
+fn bench_miller_rabin_primality_test_u64_small(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(65521u64);
+        miller_rabin_primality_test_u64(n)
+    })
+}
+
 #[bench]
 fn bench_modpow_u64(b: &mut test::Bencher) {
     let n = u64::MAX;
-    let mut d = n - 1;
-    while d % 2 == 0 {
-        d /= 2;
-    }
+    let (d, _) = decompose_n_minus_one(n);
 
     // 2 ... n - 2
     let a = n - 2;
@@ -0,0 +1,257 @@
+// Arithmetic modulo a fixed u64
+//
+//      let ctx = ModCtx::new(1_000_000_007);
+//      let a = ctx.elem(3);
+//      assert_eq!((a.pow(ctx.modulus() - 2) * a).value(), 1);
+//
+// Elements remember their context, so every operation reduces by the right
+// modulus and mixing elements of two moduli panics instead of silently
+// producing garbage. Odd moduli keep their elements in Montgomery form, so
+// products and powers cost no u128 division; even moduli fall back to plain
+// u128 arithmetic.
+use crate::montgomery::Montgomery;
+
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ModCtx {
+    n: u64,
+    montgomery: Option<Montgomery>,
+}
+
+impl ModCtx {
+    pub fn new(n: u64) -> Self {
+        assert!(n > 0, "modulus must be positive");
+
+        // NOTE: n = 1 时只有 0 一个元素，不需要 Montgomery 形式。
+        let montgomery = match n % 2 == 1 && n > 1 {
+            true => Some(Montgomery::new(n)),
+            false => None,
+        };
+
+        ModCtx { n, montgomery }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.n
+    }
+
+    // a mod n
+    pub fn elem(&self, a: u64) -> Mod<'_> {
+        let repr = match &self.montgomery {
+            Some(ctx) => ctx.encode(a),
+            None => a % self.n,
+        };
+
+        Mod { ctx: self, repr }
+    }
+
+    pub fn zero(&self) -> Mod<'_> {
+        Mod { ctx: self, repr: 0 }
+    }
+
+    pub fn one(&self) -> Mod<'_> {
+        self.elem(1)
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        match &self.montgomery {
+            Some(ctx) => ctx.mul(a, b),
+            None => (a as u128 * b as u128 % self.n as u128) as u64,
+        }
+    }
+}
+
+// An element of Z / nZ, tied to the `ModCtx` of n.
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Mod<'a> {
+    ctx: &'a ModCtx,
+    // Montgomery form for odd n, the value itself otherwise
+    repr: u64,
+}
+
+impl<'a> Mod<'a> {
+    pub fn ctx(&self) -> &'a ModCtx {
+        self.ctx
+    }
+
+    // The representative in [0, n).
+    pub fn value(&self) -> u64 {
+        match &self.ctx.montgomery {
+            Some(ctx) => ctx.decode(self.repr),
+            None => self.repr,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.repr == 0
+    }
+
+    pub fn pow(self, exponent: u64) -> Self {
+        let repr = match &self.ctx.montgomery {
+            Some(ctx) => ctx.pow(self.repr, exponent),
+            None => {
+                let mut base = self.repr;
+                let mut exponent = exponent;
+                let mut result = 1 % self.ctx.n;
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        result = self.ctx.mul(result, base);
+                    }
+                    base = self.ctx.mul(base, base);
+                    exponent >>= 1;
+                }
+                result
+            },
+        };
+
+        Mod { ctx: self.ctx, repr }
+    }
+
+    // The x with x · self = 1, or `None` when self shares a factor with n.
+    pub fn inv(self) -> Option<Self> {
+        // NOTE: 扩展欧几里得算法，系数在 i128 中不会溢出。
+        let (mut r0, mut r1) = (self.ctx.n as i128, self.value() as i128);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+
+        match r0 == 1 {
+            true => Some(self.ctx.elem(t0.rem_euclid(self.ctx.n as i128) as u64)),
+            false => None,
+        }
+    }
+
+    fn check(&self, rhs: &Self) {
+        assert!(self.ctx.n == rhs.ctx.n, "operands modulo {} and {}", self.ctx.n, rhs.ctx.n);
+    }
+}
+
+impl<'a> Add for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check(&rhs);
+
+        let n = self.ctx.n;
+        let (sum, carry) = self.repr.overflowing_add(rhs.repr);
+        let repr = if carry || sum >= n { sum.wrapping_sub(n) } else { sum };
+        Mod { ctx: self.ctx, repr }
+    }
+}
+
+impl<'a> Sub for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<'a> Neg for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn neg(self) -> Self::Output {
+        let repr = match self.repr {
+            0 => 0,
+            repr => self.ctx.n - repr,
+        };
+        Mod { ctx: self.ctx, repr }
+    }
+}
+
+impl<'a> Mul for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check(&rhs);
+        Mod { ctx: self.ctx, repr: self.ctx.mul(self.repr, rhs.repr) }
+    }
+}
+
+impl<'a> Add<u64> for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn add(self, rhs: u64) -> Self::Output {
+        self + self.ctx.elem(rhs)
+    }
+}
+
+impl<'a> Sub<u64> for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn sub(self, rhs: u64) -> Self::Output {
+        self - self.ctx.elem(rhs)
+    }
+}
+
+impl<'a> Mul<u64> for Mod<'a> {
+    type Output = Mod<'a>;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        self * self.ctx.elem(rhs)
+    }
+}
+
+impl fmt::Debug for Mod<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value(), self.ctx.n)
+    }
+}
+
+impl fmt::Display for Mod<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+
+
+#[test]
+fn test_mod_ctx() {
+    use crate::miller_rabin::modpow;
+
+    for n in [1u64, 2, 3, 10, 15, 1 << 32, 1_000_000_007, (1 << 61) - 1, 18446744073709551557, u64::MAX - 1, u64::MAX] {
+        let ctx = ModCtx::new(n);
+        assert_eq!(ctx.one().value(), 1 % n);
+        assert!(ctx.zero().is_zero() && ctx.elem(n).is_zero());
+
+        let values = [0u64, 1, 2, 12345, n / 2, n - 1, u64::MAX];
+        for a in values {
+            let x = ctx.elem(a);
+            assert_eq!(x.value(), a % n, "N={} a={}", n, a);
+            assert_eq!(x.pow(n - 1).value(), modpow(a, n - 1, n), "N={} a={}", n, a);
+            assert_eq!((-x + x).value(), 0, "N={} a={}", n, a);
+
+            for b in values {
+                let (a128, b128, n128) = ((a % n) as u128, (b % n) as u128, n as u128);
+                let y = ctx.elem(b);
+                assert_eq!((x + y).value() as u128, (a128 + b128) % n128, "N={} a={} b={}", n, a, b);
+                assert_eq!((x - y).value() as u128, (a128 + n128 - b128) % n128, "N={} a={} b={}", n, a, b);
+                assert_eq!((x * y).value() as u128, a128 * b128 % n128, "N={} a={} b={}", n, a, b);
+                assert_eq!(x * b, x * y);
+            }
+
+            match x.inv() {
+                Some(inverse) => assert_eq!((x * inverse).value(), 1 % n, "N={} a={}", n, a),
+                None => assert!(num_integer::Integer::gcd(&(a % n), &n) != 1, "N={} a={}", n, a),
+            }
+        }
+    }
+
+    let ctx = ModCtx::new(1_000_000_007);
+    assert_eq!(format!("{:?}", ctx.elem(5) - 7), "1000000005 (mod 1000000007)");
+    assert_eq!((ctx.elem(3).inv().unwrap() * 3u64).to_string(), "1");
+    assert_eq!(ModCtx::new(12).elem(4).inv(), None);
+}
+
+#[test]
+#[should_panic]
+fn test_mod_ctx_mixed() {
+    let (p, q) = (ModCtx::new(7), ModCtx::new(11));
+    let _ = p.elem(3) + q.elem(3);
+}
@@ -0,0 +1,403 @@
+// Montgomery modular multiplication
+// https://en.wikipedia.org/wiki/Montgomery_modular_multiplication
+//
+// Numbers are kept in Montgomery form aR mod n with R = 2 ^ 64, so that every
+// product is reduced with two multiplications and a shift instead of a u128
+// division. Worth it whenever many products share the same odd modulus.
+//
+// `Montgomery128` does the same for moduli up to 2 ^ 128 with R = 2 ^ 128,
+// building the 256-bit products from four 64 × 64-bit multiplications.
+//
+// `MontgomeryLimbs` borrows a modulus of s little-endian u64 limbs, with
+// R = 2 ^ (64·s), and multiplies in place through a scratch buffer it
+// allocates once, so that a whole exponentiation allocates nothing.
+use crate::limbs::{less_limbs, sub_limbs};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub(crate) struct Montgomery {
+    n: u64,
+    // −n ^ −1 mod R
+    n_neg_inv: u64,
+    // R mod n
+    r1: u64,
+    // R ^ 2 mod n
+    r2: u64,
+}
+
+impl Montgomery {
+    pub(crate) fn new(n: u64) -> Self {
+        assert!(n % 2 == 1);
+
+        // NOTE: 牛顿迭代，每一步把 n ^ −1 mod 2^k 的精度 k 加倍。
+        let mut inv = n;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(inv)));
+        }
+
+        let r1 = ((1u128 << 64) % n as u128) as u64;
+        let r2 = ((r1 as u128 * r1 as u128) % n as u128) as u64;
+
+        Montgomery { n, n_neg_inv: inv.wrapping_neg(), r1, r2 }
+    }
+
+    // t·R ^ −1 mod n, for t < n·R
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_neg_inv);
+        let (sum, carry) = t.overflowing_add(m as u128 * self.n as u128);
+        let r = (sum >> 64) as u64;
+
+        if carry || r >= self.n {
+            r.wrapping_sub(self.n)
+        } else {
+            r
+        }
+    }
+
+    pub(crate) fn encode(&self, a: u64) -> u64 {
+        self.reduce((a % self.n) as u128 * self.r2 as u128)
+    }
+
+    pub(crate) fn decode(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    // The Montgomery form of 1
+    pub(crate) fn one(&self) -> u64 {
+        self.r1
+    }
+
+    pub(crate) fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    // base ^ exponent, both base and result in Montgomery form
+    pub(crate) fn pow(&self, base: u64, exponent: u64) -> u64 {
+        let mut base = base;
+        let mut exponent = exponent;
+        let mut result = self.r1;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exponent >>= 1;
+        }
+
+        result
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub(crate) struct Montgomery128 {
+    n: u128,
+    // −n ^ −1 mod R
+    n_neg_inv: u128,
+    // R mod n
+    r1: u128,
+    // R ^ 2 mod n
+    r2: u128,
+}
+
+// a · b as (low, high) halves
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let (a0, a1) = (a as u64 as u128, a >> 64);
+    let (b0, b1) = (b as u64 as u128, b >> 64);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    // NOTE: 三个不超过 2^64 − 1 的数相加，不会溢出 u128。
+    let mid = (p00 >> 64) + (p01 as u64 as u128) + (p10 as u64 as u128);
+    let lo = (p00 as u64 as u128) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+
+    (lo, hi)
+}
+
+impl Montgomery128 {
+    pub(crate) fn new(n: u128) -> Self {
+        assert!(n % 2 == 1);
+
+        let mut inv = n;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u128.wrapping_sub(n.wrapping_mul(inv)));
+        }
+
+        let r1 = (u128::MAX % n + 1) % n;
+        let mut ctx = Montgomery128 { n, n_neg_inv: inv.wrapping_neg(), r1, r2: r1 };
+        // NOTE: R ^ 2 mod n 超出 u128，由 R mod n 连续加倍 128 次得到。
+        for _ in 0..128 {
+            ctx.r2 = ctx.add(ctx.r2, ctx.r2);
+        }
+
+        ctx
+    }
+
+    // (lo + hi · R)·R ^ −1 mod n, for lo + hi · R < n·R
+    fn reduce(&self, lo: u128, hi: u128) -> u128 {
+        let m = lo.wrapping_mul(self.n_neg_inv);
+        let (m_lo, m_hi) = mul_wide(m, self.n);
+
+        // NOTE: lo + m_lo ≡ 0 (mod R)，只有 lo ≠ 0 时才向高位进位。
+        let carry = (lo.overflowing_add(m_lo).1) as u128;
+        let (sum, c1) = hi.overflowing_add(m_hi);
+        let (r, c2) = sum.overflowing_add(carry);
+
+        if c1 || c2 || r >= self.n {
+            r.wrapping_sub(self.n)
+        } else {
+            r
+        }
+    }
+
+    pub(crate) fn encode(&self, a: u128) -> u128 {
+        self.mul(a % self.n, self.r2)
+    }
+
+    pub(crate) fn decode(&self, a: u128) -> u128 {
+        self.reduce(a, 0)
+    }
+
+    // The Montgomery form of 1
+    pub(crate) fn one(&self) -> u128 {
+        self.r1
+    }
+
+    pub(crate) fn mul(&self, a: u128, b: u128) -> u128 {
+        let (lo, hi) = mul_wide(a, b);
+        self.reduce(lo, hi)
+    }
+
+    // a + b (mod n), for a, b < n
+    pub(crate) fn add(&self, a: u128, b: u128) -> u128 {
+        if a >= self.n - b {
+            a - (self.n - b)
+        } else {
+            a + b
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub(crate) struct MontgomeryLimbs<'a> {
+    n: &'a [u64],
+    // −n ^ −1 mod 2 ^ 64
+    n_neg_inv: u64,
+    // R mod n
+    r1: Vec<u64>,
+    // R ^ 2 mod n
+    r2: Vec<u64>,
+    // The s + 2 limbs of a product being reduced.
+    t: Vec<u64>,
+}
+
+impl<'a> MontgomeryLimbs<'a> {
+    // `n` must be odd and have a non-zero most significant limb.
+    pub(crate) fn new(n: &'a [u64]) -> Self {
+        assert!(n.first().map(|limb| limb % 2 == 1).unwrap_or(false) && n.last() != Some(&0));
+
+        let mut inv = n[0];
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let s = n.len();
+        let mut ctx = MontgomeryLimbs { n, n_neg_inv: inv.wrapping_neg(), r1: vec![0; s], r2: vec![0; s], t: vec![0; s + 2] };
+        // NOTE: 与 Montgomery128 相同，R 和 R ^ 2 mod n 都由 1 连续加倍得到。
+        ctx.r1[0] = 1;
+        for _ in 0..64 * s {
+            double_limbs(&mut ctx.r1, n);
+        }
+        ctx.r2.copy_from_slice(&ctx.r1);
+        for _ in 0..64 * s {
+            double_limbs(&mut ctx.r2, n);
+        }
+
+        ctx
+    }
+
+    // The Montgomery form of 1
+    pub(crate) fn one(&self) -> &[u64] {
+        &self.r1
+    }
+
+    // a ← aR mod n, for a < n
+    pub(crate) fn encode(&mut self, a: &mut [u64]) {
+        reduce_product(&mut self.t, self.n, self.n_neg_inv, a, &self.r2);
+        a.copy_from_slice(&self.t[..self.n.len()]);
+    }
+
+    // a ← a·b·R ^ −1 mod n
+    pub(crate) fn mul(&mut self, a: &mut [u64], b: &[u64]) {
+        reduce_product(&mut self.t, self.n, self.n_neg_inv, a, b);
+        a.copy_from_slice(&self.t[..self.n.len()]);
+    }
+
+    // a ← a ^ 2·R ^ −1 mod n
+    pub(crate) fn square(&mut self, a: &mut [u64]) {
+        reduce_product(&mut self.t, self.n, self.n_neg_inv, a, a);
+        a.copy_from_slice(&self.t[..self.n.len()]);
+    }
+
+    // out ← base ^ e, both in Montgomery form, for the exponent e given by its
+    // bits from the most significant one down.
+    pub(crate) fn pow(&mut self, base: &[u64], bits: impl Iterator<Item = bool>, out: &mut [u64]) {
+        out.copy_from_slice(&self.r1);
+        for bit in bits {
+            self.square(out);
+            if bit {
+                self.mul(out, base);
+            }
+        }
+    }
+}
+
+// a ← 2a (mod n), for a < n
+fn double_limbs(a: &mut [u64], n: &[u64]) {
+    let mut carry = 0;
+    for limb in a.iter_mut() {
+        let next = *limb >> 63;
+        *limb = *limb << 1 | carry;
+        carry = next;
+    }
+
+    if carry == 1 || !less_limbs(a, n) {
+        sub_limbs(a, n);
+    }
+}
+
+// t[..s] ← a·b·R ^ −1 mod n, by interleaving the product with the reduction
+// one limb of a at a time (CIOS).
+fn reduce_product(t: &mut [u64], n: &[u64], n_neg_inv: u64, a: &[u64], b: &[u64]) {
+    let s = n.len();
+    t.iter_mut().for_each(|limb| *limb = 0);
+
+    for ai in a.iter() {
+        // t ← t + ai·b
+        let mut carry = 0u64;
+        for j in 0..s {
+            let v = t[j] as u128 + *ai as u128 * b[j] as u128 + carry as u128;
+            t[j] = v as u64;
+            carry = (v >> 64) as u64;
+        }
+        let v = t[s] as u128 + carry as u128;
+        t[s] = v as u64;
+        t[s + 1] = (v >> 64) as u64;
+
+        // t ← (t + m·n) / 2 ^ 64, where m makes the lowest limb vanish
+        let m = t[0].wrapping_mul(n_neg_inv);
+        let v = t[0] as u128 + m as u128 * n[0] as u128;
+        let mut carry = (v >> 64) as u64;
+        for j in 1..s {
+            let v = t[j] as u128 + m as u128 * n[j] as u128 + carry as u128;
+            t[j - 1] = v as u64;
+            carry = (v >> 64) as u64;
+        }
+        let v = t[s] as u128 + carry as u128;
+        t[s - 1] = v as u64;
+        t[s] = t[s + 1] + (v >> 64) as u64;
+    }
+
+    // NOTE: 结果小于 2n，最多减一次 n。
+    if t[s] != 0 || !less_limbs(&t[..s], n) {
+        sub_limbs(&mut t[..s], n);
+    }
+}
+
+
+#[test]
+fn test_montgomery() {
+    use crate::miller_rabin::modpow;
+
+    for n in [3u64, 5, 15, 1_000_000_007, (1 << 61) - 1, 18446744073709551557, u64::MAX] {
+        let ctx = Montgomery::new(n);
+        assert_eq!(n.wrapping_mul(ctx.n_neg_inv), u64::MAX);
+        assert_eq!(ctx.decode(ctx.one()), 1 % n);
+
+        for a in [0u64, 1, 2, 12345, n - 1, u64::MAX] {
+            let am = ctx.encode(a);
+            assert_eq!(ctx.decode(am), a % n, "N={} a={}", n, a);
+
+            for b in [1u64, 7, n / 2, n - 2] {
+                let bm = ctx.encode(b);
+                let expected = ((a % n) as u128 * (b % n) as u128 % n as u128) as u64;
+                assert_eq!(ctx.decode(ctx.mul(am, bm)), expected, "N={} a={} b={}", n, a, b);
+            }
+
+            assert_eq!(ctx.decode(ctx.pow(am, n - 1)), modpow(a, n - 1, n), "N={} a={}", n, a);
+        }
+    }
+}
+
+#[test]
+fn test_montgomery_128() {
+    use num_bigint::BigUint;
+
+    let big = |v: u128| BigUint::from(v);
+    for n in [3u128, 15, 1_000_000_007, u64::MAX as u128, (1 << 89) - 1, (1 << 127) - 1, u128::MAX - 158, u128::MAX] {
+        let ctx = Montgomery128::new(n);
+        assert_eq!(n.wrapping_mul(ctx.n_neg_inv), u128::MAX);
+        assert_eq!(ctx.decode(ctx.one()), 1 % n);
+
+        for a in [0u128, 1, 2, 12345, u64::MAX as u128, n / 3, n - 1, u128::MAX] {
+            let am = ctx.encode(a);
+            assert_eq!(ctx.decode(am), a % n, "N={} a={}", n, a);
+
+            for b in [1u128, 7, n / 2, n - 2, u128::MAX] {
+                let bm = ctx.encode(b);
+                let expected = big(a) * big(b) % big(n);
+                assert_eq!(big(ctx.decode(ctx.mul(am, bm))), expected, "N={} a={} b={}", n, a, b);
+                assert_eq!(big(ctx.decode(ctx.add(am, bm))), (big(a) + big(b)) % big(n), "N={} a={} b={}", n, a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_montgomery_limbs() {
+    use crate::AsBigUint;
+    use num_bigint::BigUint;
+
+    let limbs = |v: &BigUint, s: usize| {
+        let mut limbs = v.to_u64_digits();
+        limbs.resize(s, 0);
+        limbs
+    };
+
+    // 2 ^ 127 − 1, 2 ^ 255 − 19, and a 5-limb modulus with a top limb of 1
+    let moduli = [
+        vec![u64::MAX, u64::MAX >> 1],
+        vec![u64::MAX - 18, u64::MAX, u64::MAX, u64::MAX >> 1],
+        vec![15, 0, 0, 0, 1],
+    ];
+    for n in moduli.iter() {
+        let (s, big_n) = (n.len(), n.as_biguint().into_owned());
+        let r = BigUint::from(1u8) << (64 * s);
+        let mut ctx = MontgomeryLimbs::new(n);
+        assert_eq!(ctx.one(), &limbs(&(&r % &big_n), s)[..]);
+
+        let values = [BigUint::from(0u8), BigUint::from(2u8), &big_n / 3u8, &big_n - 1u8];
+        for a in values.iter() {
+            let mut am = limbs(a, s);
+            ctx.encode(&mut am);
+            assert_eq!(am, limbs(&(a * &r % &big_n), s), "N={} a={}", big_n, a);
+
+            for b in values.iter() {
+                let mut bm = limbs(b, s);
+                ctx.encode(&mut bm);
+                let mut product = am.clone();
+                ctx.mul(&mut product, &bm);
+                assert_eq!(product, limbs(&(a * b * &r % &big_n), s), "N={} a={} b={}", big_n, a, b);
+            }
+
+            // a ^ 1000003 by its bits
+            let e = 1_000_003u64;
+            let mut out = vec![0; s];
+            ctx.pow(&am, (0..64 - e.leading_zeros()).rev().map(|i| e >> i & 1 == 1), &mut out);
+            assert_eq!(out, limbs(&(a.modpow(&BigUint::from(e), &big_n) * &r % &big_n), s), "N={} a={}", big_n, a);
+        }
+    }
+}
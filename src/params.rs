@@ -0,0 +1,226 @@
+// Validated parameters of the generation functions
+//
+// A bit length or a range passed as plain integers is easily swapped with
+// another integer argument or left empty: `sample_primes(hi..lo, ...)`
+// compiled, and so did asking for a 1-bit prime. `Bits` and `PrimeRange`
+// check their values once, when they are built, and the generation functions
+// take them instead of bare integers.
+//
+//      const RSA_HALF: Bits = match Bits::new(1024) { Ok(bits) => bits, Err(_) => panic!() };
+//      let p = gen_prime(RSA_HALF, &mut rng);
+//
+// Shapes that need more than that have their own constructors:
+// `Bits::safe_prime` (at least 3 bits) and `Bits::rsa_modulus` (even, at
+// least 16), whose results are what `gen_safe_prime` and `gen_rsa_primes`
+// take.
+use crate::TryError;
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Range;
+
+
+// The length in bits of a prime to generate, at least 2.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Bits(u32);
+
+impl Bits {
+    pub const fn new(bits: u32) -> Result<Self, TryError> {
+        match bits {
+            0 | 1 => Err(TryError::TooFewBits { bits: bits as usize, min: 2 }),
+            _ => Ok(Bits(bits)),
+        }
+    }
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    pub const fn safe_prime(bits: u32) -> Result<SafePrimeBits, TryError> {
+        match bits {
+            0..=2 => Err(TryError::TooFewBits { bits: bits as usize, min: 3 }),
+            _ => Ok(SafePrimeBits(Bits(bits))),
+        }
+    }
+
+    pub const fn rsa_modulus(bits: u32) -> Result<RsaModulusBits, TryError> {
+        match bits {
+            0..=15 => Err(TryError::TooFewBits { bits: bits as usize, min: 16 }),
+            _ if bits % 2 == 1 => Err(TryError::OddBits { bits: bits as usize }),
+            _ => Ok(RsaModulusBits(Bits(bits))),
+        }
+    }
+}
+
+impl TryFrom<u32> for Bits {
+    type Error = TryError;
+
+    fn try_from(bits: u32) -> Result<Self, Self::Error> {
+        Bits::new(bits)
+    }
+}
+
+impl From<Bits> for u32 {
+    fn from(bits: Bits) -> Self {
+        bits.0
+    }
+}
+
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bits", self.0)
+    }
+}
+
+// The length in bits of a safe prime p = 2q + 1, at least 3 (p = 5, 7).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct SafePrimeBits(Bits);
+
+impl SafePrimeBits {
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+}
+
+impl TryFrom<Bits> for SafePrimeBits {
+    type Error = TryError;
+
+    fn try_from(bits: Bits) -> Result<Self, Self::Error> {
+        Bits::safe_prime(bits.get())
+    }
+}
+
+impl From<SafePrimeBits> for Bits {
+    fn from(bits: SafePrimeBits) -> Self {
+        bits.0
+    }
+}
+
+// The length in bits of an RSA modulus p · q: even, so that p and q have the
+// same length, and at least 16.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct RsaModulusBits(Bits);
+
+impl RsaModulusBits {
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+
+    // The length of each of the two primes.
+    pub const fn half(self) -> Bits {
+        Bits(self.0.get() / 2)
+    }
+}
+
+impl TryFrom<Bits> for RsaModulusBits {
+    type Error = TryError;
+
+    fn try_from(bits: Bits) -> Result<Self, Self::Error> {
+        Bits::rsa_modulus(bits.get())
+    }
+}
+
+impl From<RsaModulusBits> for Bits {
+    fn from(bits: RsaModulusBits) -> Self {
+        bits.0
+    }
+}
+
+// The numbers lo <= n < hi to draw primes from: not empty, and holding an odd
+// number or 2, so that a prime is at least possible.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct PrimeRange {
+    lo: u64,
+    hi: u64,
+}
+
+impl PrimeRange {
+    pub const fn new(lo: u64, hi: u64) -> Result<Self, TryError> {
+        if lo >= hi {
+            return Err(TryError::EmptyRange { lo, hi });
+        }
+        // NOTE: 只有 [lo, lo + 1) 且 lo 为偶数时不含奇数。
+        if hi - lo == 1 && lo.is_multiple_of(2) && lo != 2 {
+            return Err(TryError::NoOddNumber { lo, hi });
+        }
+
+        Ok(PrimeRange { lo, hi })
+    }
+
+    pub const fn lo(&self) -> u64 {
+        self.lo
+    }
+
+    pub const fn hi(&self) -> u64 {
+        self.hi
+    }
+
+    pub const fn width(&self) -> u64 {
+        self.hi - self.lo
+    }
+
+    pub const fn contains(&self, n: u64) -> bool {
+        self.lo <= n && n < self.hi
+    }
+
+    pub fn range(&self) -> Range<u64> {
+        self.lo..self.hi
+    }
+}
+
+impl TryFrom<Range<u64>> for PrimeRange {
+    type Error = TryError;
+
+    fn try_from(range: Range<u64>) -> Result<Self, Self::Error> {
+        PrimeRange::new(range.start, range.end)
+    }
+}
+
+impl From<PrimeRange> for Range<u64> {
+    fn from(range: PrimeRange) -> Self {
+        range.range()
+    }
+}
+
+impl fmt::Display for PrimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.lo, self.hi)
+    }
+}
+
+
+#[test]
+fn test_bits() {
+    const B: Bits = match Bits::new(1024) { Ok(bits) => bits, Err(_) => panic!() };
+    assert_eq!(B.get(), 1024);
+    assert_eq!(B.to_string(), "1024 bits");
+
+    assert_eq!(Bits::new(0), Err(TryError::TooFewBits { bits: 0, min: 2 }));
+    assert_eq!(Bits::try_from(1), Err(TryError::TooFewBits { bits: 1, min: 2 }));
+    assert_eq!(Bits::try_from(2).map(u32::from), Ok(2));
+    assert!(Bits::new(2).unwrap() < Bits::new(3).unwrap());
+
+    assert_eq!(Bits::safe_prime(2), Err(TryError::TooFewBits { bits: 2, min: 3 }));
+    assert_eq!(Bits::safe_prime(3).map(SafePrimeBits::get), Ok(3));
+    assert_eq!(SafePrimeBits::try_from(Bits::new(2).unwrap()), Err(TryError::TooFewBits { bits: 2, min: 3 }));
+
+    assert_eq!(Bits::rsa_modulus(14), Err(TryError::TooFewBits { bits: 14, min: 16 }));
+    assert_eq!(Bits::rsa_modulus(2049), Err(TryError::OddBits { bits: 2049 }));
+    assert_eq!(Bits::rsa_modulus(2048).map(|bits| bits.half().get()), Ok(1024));
+    assert_eq!(RsaModulusBits::try_from(Bits::new(16).unwrap()).map(Bits::from), Ok(Bits::new(16).unwrap()));
+}
+
+#[test]
+fn test_prime_range() {
+    let range = PrimeRange::new(10, 20).unwrap();
+    assert_eq!((range.lo(), range.hi(), range.width()), (10, 20, 10));
+    assert!(range.contains(10) && range.contains(19) && !range.contains(20));
+    assert_eq!(Range::from(range), 10..20);
+    assert_eq!(range.to_string(), "[10, 20)");
+
+    assert_eq!(PrimeRange::new(20, 10), Err(TryError::EmptyRange { lo: 20, hi: 10 }));
+    assert_eq!(PrimeRange::try_from(7..7), Err(TryError::EmptyRange { lo: 7, hi: 7 }));
+    assert_eq!(PrimeRange::new(8, 9), Err(TryError::NoOddNumber { lo: 8, hi: 9 }));
+    assert!(PrimeRange::new(7, 8).is_ok() && PrimeRange::new(2, 3).is_ok() && PrimeRange::new(8, 10).is_ok());
+    assert!(PrimeRange::new(u64::MAX - 1, u64::MAX).is_err() && PrimeRange::new(0, u64::MAX).is_ok());
+}
@@ -0,0 +1,124 @@
+// Pratt certificate
+// https://en.wikipedia.org/wiki/Pratt_certificate
+//
+// p is prime iff there is a witness a with
+//
+//      a ^ (p − 1) ≡ 1 (mod p)
+//      a ^ ((p − 1) / q) ≢ 1 (mod p), for every prime q dividing p − 1
+//
+// and every such q is itself certified the same way.
+use crate::Primality;
+use crate::factor::factorize_u64_inline;
+use crate::miller_rabin_primality_test_u64;
+use crate::miller_rabin::modpow;
+use crate::small_vec::SmallVec;
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct PrattCertificate {
+    pub p: u64,
+    pub witness: u64,
+    // One certificate per distinct prime factor of p − 1, in ascending order.
+    pub factors: Vec<PrattCertificate>,
+}
+
+impl PrattCertificate {
+    pub fn verify(&self) -> bool {
+        let p = self.p;
+        if p == 2 {
+            return self.factors.is_empty();
+        }
+
+        if p < 2 || self.witness < 2 || self.witness >= p {
+            return false;
+        }
+
+        let p_minus_one = p - 1;
+        if modpow(self.witness, p_minus_one, p) != 1 {
+            return false;
+        }
+
+        // NOTE: 所有的 q 必须恰好把 p − 1 完全分解。
+        let mut rest = p_minus_one;
+        for cert in self.factors.iter() {
+            let q = cert.p;
+            if q < 2 || !rest.is_multiple_of(q) {
+                return false;
+            }
+
+            while rest.is_multiple_of(q) {
+                rest /= q;
+            }
+
+            if modpow(self.witness, p_minus_one / q, p) == 1 || !cert.verify() {
+                return false;
+            }
+        }
+
+        rest == 1
+    }
+}
+
+// Build a Pratt certificate for `p`, or `None` if `p` is not prime.
+pub fn pratt_certificate_u64(p: u64) -> Option<PrattCertificate> {
+    match p {
+        0 | 1 => return None,
+        2 => return Some(PrattCertificate { p, witness: 1, factors: Vec::new() }),
+        _ => {
+            if p.is_multiple_of(2) || miller_rabin_primality_test_u64(p) != Primality::Prime {
+                return None;
+            }
+        }
+    }
+
+    let p_minus_one = p - 1;
+    let primes: SmallVec<u64, 16> = factorize_u64_inline(p_minus_one).iter().map(|(q, _)| *q).collect();
+
+    // NOTE: 寻找模 p 的原根，因为 p 是素数，所以一定存在，并且通常很小。
+    let witness = (2..p)
+        .find(|a| primes.iter().all(|q| modpow(*a, p_minus_one / q, p) != 1))?;
+
+    let factors = primes.iter()
+        .map(|q| pratt_certificate_u64(*q))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(PrattCertificate { p, witness, factors })
+}
+
+
+#[test]
+fn test_pratt_certificate_u64() {
+    use crate::table_query_u16;
+
+    for n in 0..4096u16 {
+        let cert = pratt_certificate_u64(n as u64);
+        assert_eq!(cert.is_some(), table_query_u16(n) == Primality::Prime, "N={}", n);
+        if let Some(cert) = cert {
+            assert!(cert.verify(), "N={}", n);
+        }
+    }
+
+    let cert = pratt_certificate_u64(18446744073709551557).unwrap();
+    assert!(cert.verify());
+    assert_eq!(pratt_certificate_u64(18446744073709551555), None);
+
+    // 3 is not a primitive root of 11 (3 ^ 5 ≡ 1).
+    let eleven = crate::ModCtx::new(11);
+    assert_eq!(eleven.elem(3).pow(5), eleven.one());
+    let mut cert = pratt_certificate_u64(11).unwrap();
+    cert.witness = 3;
+    assert!(!cert.verify());
+
+    // p − 1 is not fully covered.
+    let mut cert = pratt_certificate_u64(1_000_000_007).unwrap();
+    cert.factors.pop();
+    assert!(!cert.verify());
+
+    // A composite disguised as prime: 561 = 3 · 11 · 17, 560 = 2 ^ 4 · 5 · 7
+    let fake = PrattCertificate {
+        p: 561,
+        witness: 2,
+        factors: vec![2u64, 5, 7].into_iter().map(|q| pratt_certificate_u64(q).unwrap()).collect(),
+    };
+    assert!(!fake.verify());
+}
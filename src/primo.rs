@@ -0,0 +1,399 @@
+// Primo certificate import
+// http://www.ellipsa.eu/
+//
+// Reads the `.out` certificates written by Primo (format 4) into the crate's
+// `Certificate` chain, which can then be checked with `Certificate::verify`.
+//
+// A certificate is an INI file. `[Candidate]` holds N, and the numbered
+// sections `[1]`, `[2]`, ... each reduce the current N to a smaller R:
+//
+//      Type=1  N − 1 test        S, B            R = (N − 1) / S
+//      Type=2  N + 1 test        S, Q            R = (N + 1) / S
+//      Type=3  ECPP              S, W, A, B, T   R = (N + 1 − W) / S
+//      Type=4  ECPP              S, W, J, T      R = (N + 1 − W) / S
+//
+// For Type=4 the curve is A = 3J(1728 − J), B = 2J(1728 − J) ^ 2. For both
+// ECPP types the point is found from T as
+//
+//      L = T ^ 3 + AT + B,  y ^ 2 = x ^ 3 + AL ^ 2·x + BL ^ 3,  P = (TL, L ^ 2)
+//
+// The N + 1 test uses the Lucas sequence U(P, Q) with P = 1 unless a `P` is
+// given, and becomes a `Certificate::Bls` with D = P ^ 2 − 4Q and R as its
+// only factor of N + 1.
+//
+// The last R must be below 2 ^ 64 and ends the chain with a Pratt certificate.
+// Numbers are decimal, or hexadecimal when prefixed by `$`.
+use crate::Certificate;
+use crate::PocklingtonFactor;
+use crate::LucasFactor;
+use crate::EcppStep;
+use crate::pratt_certificate_u64;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+
+use core::convert::TryFrom;
+use std::fmt;
+
+
+// The line of a Primo certificate that could not be understood, counting from 1.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ParsePrimoError {
+    pub line: usize,
+}
+
+impl fmt::Display for ParsePrimoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Primo certificate at line {}", self.line)
+    }
+}
+
+struct Section<'a> {
+    line: usize,
+    name: &'a str,
+    entries: Vec<(usize, &'a str, &'a str)>,
+}
+
+impl<'a> Section<'a> {
+    fn get(&self, key: &str) -> Option<(usize, &'a str)> {
+        self.entries.iter().find(|(_, k, _)| *k == key).map(|(line, _, value)| (*line, *value))
+    }
+
+    fn number(&self, key: &str) -> Result<BigInt, ParsePrimoError> {
+        let (line, value) = self.get(key).ok_or(ParsePrimoError { line: self.line })?;
+        parse_number(value).ok_or(ParsePrimoError { line })
+    }
+
+    // A number in [0, n)
+    fn residue(&self, key: &str, n: &BigUint) -> Result<BigUint, ParsePrimoError> {
+        let v = self.number(key)?;
+        let line = self.get(key).map(|(line, _)| line).unwrap_or(self.line);
+        match v.to_biguint() {
+            Some(v) if &v < n => Ok(v),
+            _ => Err(ParsePrimoError { line }),
+        }
+    }
+}
+
+// `123`, `$7B` or `-$7B`
+fn parse_number(s: &str) -> Option<BigInt> {
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (Sign::Minus, s),
+        None => (Sign::Plus, s),
+    };
+
+    let magnitude = match s.strip_prefix('$') {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16)?,
+        None => BigUint::parse_bytes(s.as_bytes(), 10)?,
+    };
+
+    Some(BigInt::from_biguint(sign, magnitude))
+}
+
+fn sections(s: &str) -> Result<Vec<Section<'_>>, ParsePrimoError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (i, text) in s.lines().enumerate() {
+        let line = i + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = text.strip_prefix('[').and_then(|text| text.strip_suffix(']')) {
+            sections.push(Section { line, name, entries: Vec::new() });
+            continue;
+        }
+
+        let (key, value) = text.split_once('=').ok_or(ParsePrimoError { line })?;
+        match sections.last_mut() {
+            Some(section) => section.entries.push((line, key.trim(), value.trim())),
+            None => return Err(ParsePrimoError { line }),
+        }
+    }
+
+    Ok(sections)
+}
+
+// Whatever a numbered section does to the current N.
+enum Step {
+    NMinusOne { witness: BigUint },
+    NPlusOne { d: i64, p: u64 },
+    Ecpp(EcppStep),
+}
+
+pub fn parse_primo_certificate(s: &str) -> Result<Certificate, ParsePrimoError> {
+    let sections = sections(s)?;
+
+    let header = match sections.first() {
+        Some(header) if header.name == "PRIMO - Primality Certificate" => header,
+        Some(header) => return Err(ParsePrimoError { line: header.line }),
+        None => return Err(ParsePrimoError { line: 1 }),
+    };
+    match header.get("Format") {
+        Some((_, "4")) => {},
+        Some((line, _)) => return Err(ParsePrimoError { line }),
+        None => return Err(ParsePrimoError { line: header.line }),
+    }
+
+    let candidate = sections.iter()
+        .find(|section| section.name == "Candidate")
+        .ok_or(ParsePrimoError { line: header.line })?;
+    let mut n = candidate.number("N")?
+        .to_biguint()
+        .ok_or(ParsePrimoError { line: candidate.line })?;
+
+    let zero = BigUint::from(0u8);
+
+    let mut steps: Vec<(BigUint, Step)> = Vec::new();
+    let numbered = sections.iter().filter(|section| section.name.parse::<usize>().is_ok());
+    for (i, section) in numbered.enumerate() {
+        let invalid = ParsePrimoError { line: section.line };
+        if section.name.parse::<usize>() != Ok(i + 1) || n.bits() < 3 {
+            return Err(invalid);
+        }
+
+        let kind = match section.get("Type") {
+            Some((line, kind)) => kind.parse::<u8>().map_err(|_| ParsePrimoError { line })?,
+            // NOTE: 没有 Type 的时候，按照出现的字段推断。
+            None if section.get("J").is_some() => 4,
+            None if section.get("A").is_some() => 3,
+            None if section.get("Q").is_some() => 2,
+            None => 1,
+        };
+
+        let s = section.number("S")?.to_biguint().filter(|s| s > &zero).ok_or(invalid)?;
+
+        let (r, step) = match kind {
+            1 => {
+                let (r, rem) = (&n - 1u8).div_rem(&s);
+                if rem != zero {
+                    return Err(invalid);
+                }
+                (r, Step::NMinusOne { witness: section.residue("B", &n)? })
+            },
+            2 => {
+                let (r, rem) = (&n + 1u8).div_rem(&s);
+                if rem != zero {
+                    return Err(invalid);
+                }
+
+                let p = match section.get("P") {
+                    Some(_) => section.number("P")?,
+                    None => BigInt::from(1u8),
+                };
+                let q = section.number("Q")?;
+                // NOTE: Certificate::Bls 要求 P 为奇数（即 D ≡ 1 (mod 4)），且 D 能放进 i64。
+                let d = i64::try_from(&p * &p - q * 4u8).map_err(|_| invalid)?;
+                let p = u64::try_from(&p).ok().filter(|p| p % 2 == 1).ok_or(invalid)?;
+                (r, Step::NPlusOne { d, p })
+            },
+            3 | 4 => {
+                let (a, b) = if kind == 3 {
+                    (section.residue("A", &n)?, section.residue("B", &n)?)
+                } else {
+                    let j = section.residue("J", &n)?;
+                    let c = (BigUint::from(1728u16) + &n - &j) % &n;
+                    (&j * &c * 3u8 % &n, &j * &c * &c * 2u8 % &n)
+                };
+                let t = section.residue("T", &n)?;
+
+                // m = N + 1 − W
+                let m = (BigInt::from_biguint(Sign::Plus, &n + 1u8) - section.number("W")?)
+                    .to_biguint()
+                    .ok_or(invalid)?;
+                let (r, rem) = m.div_rem(&s);
+                if rem != zero {
+                    return Err(invalid);
+                }
+
+                let l = (&t * &t * &t + &a * &t + &b) % &n;
+                let l2 = &l * &l % &n;
+                let step = EcppStep {
+                    n: n.clone(),
+                    a: &a * &l2 % &n,
+                    b: &b * &l2 * &l % &n,
+                    m,
+                    q: r.clone(),
+                    x: &t * &l % &n,
+                    y: l2,
+                };
+                (r, Step::Ecpp(step))
+            },
+            _ => return Err(invalid),
+        };
+
+        steps.push((n, step));
+        n = r;
+    }
+
+    let last = sections.iter().rev().find(|section| section.name.parse::<usize>().is_ok()).unwrap_or(candidate);
+    let tail = match n.to_u64_digits().as_slice() {
+        [] => None,
+        [n] => pratt_certificate_u64(*n),
+        _ => None,
+    };
+    let mut cert = Certificate::Pratt(tail.ok_or(ParsePrimoError { line: last.line })?);
+
+    for (n, step) in steps.into_iter().rev() {
+        cert = match step {
+            Step::NMinusOne { witness } => Certificate::Pocklington {
+                n,
+                factors: vec![PocklingtonFactor { witness, certificate: cert }],
+            },
+            Step::NPlusOne { d, p } => Certificate::Bls {
+                n,
+                d,
+                minus: Vec::new(),
+                plus: vec![LucasFactor { p, certificate: cert }],
+            },
+            Step::Ecpp(step) => Certificate::Ecpp { step, next: Box::new(cert) },
+        };
+    }
+
+    Ok(cert)
+}
+
+
+#[test]
+fn test_parse_primo_certificate() {
+    // N = 2k·(2 ^ 127 − 1) + 1: one N − 1 step down to 2 ^ 127 − 1, then
+    // ECPP steps with CM discriminants −3, −4, −7 and −8.
+    const CERTIFICATE: &str = "\
+[PRIMO - Primality Certificate]
+Version=4.3.3 - LX64
+WebSite=http://www.ellipsa.eu/
+Format=4
+TestCount=7
+Status=Candidate certified prime
+
+[Candidate]
+N=$38FFFFFFFFFFFFFFFFFFFFFFFFFFFFFF8F
+HexadecimalSize=34
+DecimalSize=41
+BinarySize=134
+
+[1]
+Type=1
+S=$72
+B=$2
+
+[2]
+Type=3
+S=$925
+W=-$15C9B758DDEFAA1E7
+A=$0
+B=$1DAF9EC3268ECC45DC6BF1E1A399F82B
+T=$32D54E4179F248B08CB4A0D7D6225675
+
+[3]
+Type=4
+S=$620
+W=$77B5B6F0DFED63C
+J=$DFF5807DFA1846DF0F9B5270681EC
+T=$3E0ABDC2AE9963D2E49085EF3430
+
+[4]
+Type=3
+S=$7434
+W=-$2DFFD37C763D3C
+A=$0
+B=$11DFB0AF1E3EC007B1BE1830295
+T=$23454B9693C961CADBCB7EBB70C
+
+[5]
+Type=4
+S=$44214C
+W=$2BD91C7BB7E
+J=$1F40
+T=$ED0094F4689770938233C
+
+[6]
+Type=4
+S=$2
+W=-$868DD3812
+J=$1F40
+T=$DF8AF8C793287D050
+
+[7]
+Type=4
+S=$246
+W=$192AEF82E
+J=$1F40
+T=$49C4792DA4AA71C38
+
+[Signature]
+1=0000000000000000
+";
+
+    let cert = parse_primo_certificate(CERTIFICATE).unwrap();
+    assert_eq!(cert.n(), "19396094914493492417412352623610788052879".parse().unwrap());
+    assert!(cert.verify());
+
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    match &cert {
+        Certificate::Pocklington { factors, .. } => {
+            assert_eq!(factors.len(), 1);
+            assert_eq!(factors[0].certificate.n(), m127);
+            assert!(matches!(factors[0].certificate, Certificate::Ecpp { .. }));
+        },
+        cert => panic!("{:?}", cert),
+    }
+
+    // Hexadecimal, negative and decimal numbers
+    assert_eq!(parse_number("$7B"), Some(BigInt::from(123)));
+    assert_eq!(parse_number("-$7B"), Some(BigInt::from(-123)));
+    assert_eq!(parse_number("123"), Some(BigInt::from(123)));
+    assert_eq!(parse_number("$"), None);
+
+    // Another curve, on which m is not the order of the point.
+    let tampered = CERTIFICATE.replace("B=$1DAF9EC3268ECC45DC6BF1E1A399F82B", "B=$1DAF9EC3268ECC45DC6BF1E1A399F82C");
+    assert!(!parse_primo_certificate(&tampered).unwrap().verify());
+
+    // S must divide N − 1.
+    let line = CERTIFICATE.lines().position(|line| line == "[1]").unwrap() + 1;
+    let bad = CERTIFICATE.replace("S=$72", "S=$71");
+    assert_eq!(parse_primo_certificate(&bad), Err(ParsePrimoError { line }));
+
+    let bad = CERTIFICATE.replace("Type=1", "Type=5");
+    assert_eq!(parse_primo_certificate(&bad), Err(ParsePrimoError { line }));
+
+    // An N + 1 step down to 2 ^ 127 − 1 in place of the N − 1 step:
+    // N = S·(2 ^ 127 − 1) − 1 with a Q for which (1 − 4Q | N) = −1.
+    let (n, s) = (2u32..).step_by(2)
+        .map(|s| (&m127 * s - 1u8, s))
+        .find(|(n, _)| crate::baillie_psw_primality_test_biguint(n) == crate::Primality::PROBABLE)
+        .unwrap();
+    let plus_one = |q: i64| {
+        let first = CERTIFICATE.find("[2]").unwrap();
+        let rest = &CERTIFICATE[first..];
+        let head = &CERTIFICATE[..CERTIFICATE.find("[Candidate]").unwrap()];
+        format!("{}[Candidate]\nN=${:X}\n\n[1]\nType=2\nS=${:X}\nQ={}\n\n{}", head, n, s, q, rest)
+    };
+    let q = (2i64..)
+        .find(|q| crate::jacobi_symbol_bigint(&BigInt::from(1 - 4 * q), &n) == -1)
+        .unwrap();
+
+    let cert = parse_primo_certificate(&plus_one(q)).unwrap();
+    assert_eq!(cert.n(), n);
+    assert!(cert.verify());
+    match &cert {
+        Certificate::Bls { d, minus, plus, .. } => {
+            assert_eq!(*d, 1 - 4 * q);
+            assert!(minus.is_empty());
+            assert_eq!(plus.len(), 1);
+            assert_eq!(plus[0].p, 1);
+            assert_eq!(plus[0].certificate.n(), m127);
+        },
+        cert => panic!("{:?}", cert),
+    }
+
+    // S must divide N + 1.
+    let bad = plus_one(q).replace(&format!("S=${:X}", s), &format!("S=${:X}", s + 2));
+    let line = bad.lines().position(|line| line == "[1]").unwrap() + 1;
+    assert_eq!(parse_primo_certificate(&bad), Err(ParsePrimoError { line }));
+
+    let bad = CERTIFICATE.replace("Format=4", "Format=3");
+    assert_eq!(parse_primo_certificate(&bad), Err(ParsePrimoError { line: 4 }));
+
+    assert_eq!(parse_primo_certificate(""), Err(ParsePrimoError { line: 1 }));
+}
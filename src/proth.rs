@@ -0,0 +1,158 @@
+// Proth's theorem
+// https://en.wikipedia.org/wiki/Proth%27s_theorem
+//
+// Let N = k · 2 ^ e + 1 with k odd and k < 2 ^ e. N is prime if and only if
+// some a has
+//
+//      a ^ ((N − 1) / 2) ≡ −1 (mod N).
+//
+// For prime N every quadratic non-residue a is such a witness (Euler's
+// criterion), so the search only tries bases with Jacobi symbol (a | N) = −1:
+// for those, a ^ ((N − 1) / 2) ≢ −1 proves N composite, and a single success
+// proves it prime. A symbol of 0 exposes a factor. Only a square N has no base
+// with symbol −1, so squares are ruled out first.
+//
+// This is the test behind most of the largest known non-Mersenne primes; with
+// k = 1 it is Pépin's test for Fermat numbers (a = 3).
+use crate::Primality;
+use crate::AsBigUint;
+use crate::jacobi_symbol_biguint;
+use crate::is_perfect_square_biguint;
+use crate::miller_rabin::is_prime_u64;
+
+use num_bigint::BigUint;
+
+
+// k · 2 ^ e + 1 as (k, e) with k odd, if that is a Proth number.
+pub fn proth_decompose(n: &(impl AsBigUint + ?Sized)) -> Option<(BigUint, u64)> {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+    if n.bits() == 0 {
+        return None;
+    }
+
+    let n_minus_one: BigUint = n - 1u8;
+    let e = n_minus_one.trailing_zeros()?;
+    let k = n_minus_one >> e as usize;
+
+    // k < 2 ^ e
+    if k.bits() <= e {
+        Some((k, e))
+    } else {
+        None
+    }
+}
+
+// Whether k · 2 ^ e + 1 is prime. The answer is a proof either way, except for
+// `Unknown` when the number is not a Proth number (k ≥ 2 ^ e once the powers
+// of 2 are moved out of k) or, in theory, when no small base has symbol −1.
+pub fn proth_primality_test(k: &(impl AsBigUint + ?Sized), e: u64) -> Primality {
+    match search(&k.as_biguint(), e) {
+        Ok(_) => Primality::Prime,
+        Err(primality) => primality,
+    }
+}
+
+// The base a with a ^ ((N − 1) / 2) ≡ −1 (mod N) that proves k · 2 ^ e + 1
+// prime, or `None` when it is not proven prime.
+pub fn proth_witness(k: &(impl AsBigUint + ?Sized), e: u64) -> Option<u32> {
+    search(&k.as_biguint(), e).ok()
+}
+
+fn search(k: &BigUint, e: u64) -> Result<u32, Primality> {
+    let n: BigUint = (k << e as usize) + 1u8;
+
+    // NOTE: 把 k 中的 2 的幂移到指数上，得到 k 为奇数的标准形式。
+    let (k, e) = match proth_decompose(&n) {
+        Some(ke) => ke,
+        None if k.bits() == 0 => return Err(Primality::ZeroOrOne),
+        None => return Err(Primality::Unknown),
+    };
+
+    // NOTE: 64 bits 以内直接给出确定的结果，此时仍然寻找一个见证。
+    let small = match n.to_u64_digits().as_slice() {
+        [n] => Some(is_prime_u64(*n)),
+        _ => None,
+    };
+    if small == Some(false) || is_perfect_square_biguint(&n) {
+        return Err(Primality::Composite);
+    }
+
+    let n_minus_one: BigUint = &n - 1u8;
+    // (N − 1) / 2 = k · 2 ^ (e − 1)
+    let exponent: BigUint = &k << (e - 1) as usize;
+
+    for a in crate::global().small_primes().iter().map(|p| *p as u32) {
+        match jacobi_symbol_biguint(&BigUint::from(a), &n) {
+            1 => continue,
+            // NOTE: a < N 时符号为 0 说明 a 是 N 的真因子。
+            0 if n == BigUint::from(a) => return Err(Primality::Prime),
+            0 => return Err(Primality::Composite),
+            _ => {},
+        }
+
+        return match BigUint::from(a).modpow(&exponent, &n) == n_minus_one {
+            true => Ok(a),
+            false => Err(Primality::Composite),
+        };
+    }
+
+    Err(small.map(Primality::from_proof).unwrap_or(Primality::Unknown))
+}
+
+
+#[test]
+fn test_proth_decompose() {
+    assert_eq!(proth_decompose(&3u8), Some((BigUint::from(1u8), 1)));
+    assert_eq!(proth_decompose(&13u8), Some((BigUint::from(3u8), 2)));
+    assert_eq!(proth_decompose(&97u8), Some((BigUint::from(3u8), 5)));
+    // 7 = 3 · 2 + 1, 3 > 2
+    assert_eq!(proth_decompose(&7u8), None);
+    assert_eq!(proth_decompose(&2u8), None);
+    assert_eq!(proth_decompose(&1u8), None);
+    assert_eq!(proth_decompose(&0u8), None);
+}
+
+#[test]
+fn test_proth_primality_test() {
+    use crate::table_query_u16;
+
+    // Every Proth number below 2 ^ 16, against the table
+    for e in 1..16u64 {
+        for k in (1..1u64 << e).step_by(2) {
+            let n = (k << e) + 1;
+            if n > u16::MAX as u64 {
+                break;
+            }
+            let expected = table_query_u16(n as u16);
+            assert_eq!(proth_primality_test(&k, e), expected, "N={}", n);
+            assert_eq!(proth_witness(&k, e).is_some(), expected == Primality::Prime, "N={}", n);
+        }
+    }
+
+    // Even k is moved into the exponent; 6 · 2 ^ 3 + 1 = 3 · 2 ^ 4 + 1 = 49
+    assert_eq!(proth_primality_test(&6u8, 3), Primality::Composite);
+    assert_eq!(proth_primality_test(&12u8, 3), Primality::Prime);
+    // 7 · 2 + 1 is not a Proth number
+    assert_eq!(proth_primality_test(&7u8, 1), Primality::Unknown);
+    assert_eq!(proth_primality_test(&0u8, 5), Primality::ZeroOrOne);
+
+    // 3 · 2 ^ n + 1 is prime for n = 189, 201, 209 and composite in between
+    for e in 185..=210 {
+        let expected = [189, 201, 209].contains(&e);
+        assert_eq!(proth_primality_test(&3u8, e) == Primality::Prime, expected, "e={}", e);
+    }
+
+    // Pépin's test: F4 is prime, F5 to F8 are not.
+    assert_eq!(proth_witness(&1u8, 16), Some(3));
+    for e in [32, 64, 128, 256] {
+        assert_eq!(proth_primality_test(&1u8, e), Primality::Composite, "e={}", e);
+    }
+}
+
+#[bench]
+fn bench_proth_primality_test(b: &mut test::Bencher) {
+    b.iter(|| {
+        proth_primality_test(&3u8, 2208)
+    })
+}
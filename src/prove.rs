@@ -0,0 +1,667 @@
+// Primality proving within a time budget
+//
+// `prove_prime` screens n with Baillie–PSW and then tries to certify it with
+// Pocklington's theorem, and failing that with the combined n − 1 / n + 1
+// test of `prove_prime_bls`. When the budget runs out first, the answer is
+// downgraded to `ProbablyPrime` with the BPSW evidence, which lists the
+// proofs that were tried, instead of failing.
+//
+// Pocklington's theorem
+// https://en.wikipedia.org/wiki/Pocklington_primality_test
+//
+// Let n − 1 = F·R with F > √n and the prime factors of F known. If for every
+// prime q dividing F there is a witness a with
+//
+//      a ^ (n − 1) ≡ 1 (mod n)
+//      gcd(a ^ ((n − 1) / q) − 1, n) = 1
+//
+// then n is prime.
+//
+// NOTE: 目前只实现了 n − 1 和 n − 1 / n + 1 方法，APR-CL 和 ECPP 尚未实现；
+//       两者都无法在预算内分解到足够大的数，结果会降级为 BPSW。
+use crate::Primality;
+use crate::AsBigUint;
+use crate::factor::factorize_u64_inline;
+use crate::baillie_psw_primality_test_biguint;
+use crate::factor::pollard_brent_rho_biguint;
+use crate::{pratt_certificate_u64, PrattCertificate};
+use crate::EcppStep;
+use crate::LucasFactor;
+use crate::Provenance;
+
+use num_integer::Integer;
+use num_bigint::BigUint;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Certificate {
+    // n < 2 ^ 64
+    Pratt(PrattCertificate),
+    // One entry per distinct prime factor of the factored part F of n − 1, in
+    // ascending order.
+    Pocklington { n: BigUint, factors: Vec<PocklingtonFactor> },
+    // One elliptic curve step for n, followed by the certificate for its q.
+    Ecpp { step: EcppStep, next: Box<Certificate> },
+    // The combined n − 1 / n + 1 test of `prove_prime_bls`: Pocklington
+    // witnesses for the factored part F1 of n − 1 and Lucas sequences with
+    // discriminant `d` for the factored part F2 of n + 1, both in ascending
+    // order of their prime.
+    Bls { n: BigUint, d: i64, minus: Vec<PocklingtonFactor>, plus: Vec<LucasFactor> },
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct PocklingtonFactor {
+    pub witness: BigUint,
+    // Certificate for the prime factor q itself.
+    pub certificate: Certificate,
+}
+
+impl Certificate {
+    // The number this certificate proves prime.
+    pub fn n(&self) -> BigUint {
+        match self {
+            Certificate::Pratt(cert) => BigUint::from(cert.p),
+            Certificate::Pocklington { n, .. } => n.clone(),
+            Certificate::Ecpp { step, .. } => step.n.clone(),
+            Certificate::Bls { n, .. } => n.clone(),
+        }
+    }
+
+    // "pratt", "pocklington", "ecpp" or "bls".
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Certificate::Pratt(_) => "pratt",
+            Certificate::Pocklington { .. } => "pocklington",
+            Certificate::Ecpp { .. } => "ecpp",
+            Certificate::Bls { .. } => "bls",
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        match self {
+            Certificate::Pratt(cert) => cert.verify(),
+            Certificate::Pocklington { n, factors } => pocklington_part(n, factors).is_some_and(|f| &f * &f > *n),
+            Certificate::Ecpp { step, next } => next.n() == step.q && step.verify() && next.verify(),
+            Certificate::Bls { n, d, minus, plus } => crate::bls::verify(n, *d, minus, plus),
+        }
+    }
+}
+
+// The part F of n − 1 that `factors` account for, if n is odd and every
+// factor checks out.
+pub(crate) fn pocklington_part(n: &BigUint, factors: &[PocklingtonFactor]) -> Option<BigUint> {
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+    let two  = BigUint::from(2u8);
+
+    if n <= &two || n % 2u8 == zero {
+        return None;
+    }
+
+    let n_minus_one: BigUint = n - 1u8;
+    let mut f = one.clone();
+    let mut last = one.clone();
+    for factor in factors.iter() {
+        let q = factor.certificate.n();
+        let a = &factor.witness;
+
+        // NOTE: q 必须严格递增，否则同一个因子会被重复计入 F。
+        if q <= last || &n_minus_one % &q != zero || a < &two || a >= &n_minus_one {
+            return None;
+        }
+
+        let mut rest = n_minus_one.clone();
+        while &rest % &q == zero {
+            rest /= &q;
+            f *= &q;
+        }
+
+        if a.modpow(&n_minus_one, n) != one {
+            return None;
+        }
+
+        let y = a.modpow(&(&n_minus_one / &q), n);
+        if y == zero || (y - 1u8).gcd(n) != one || !factor.certificate.verify() {
+            return None;
+        }
+
+        last = q;
+    }
+
+    Some(f)
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Evidence {
+    // n < 2, or n is composite.
+    None,
+    // n passed Baillie–PSW, but no certificate was found within the budget
+    // by the proofs `tried`, given as their `Certificate::kind`s in order.
+    BailliePsw { tried: Vec<&'static str> },
+    Certificate(Certificate),
+}
+
+// The strongest statement `prove_prime` could make about n: `Prime` always
+// comes with a certificate and `ProbablyPrime` with the BPSW evidence.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Proof {
+    pub primality: Primality,
+    pub evidence: Evidence,
+}
+
+impl Proof {
+    // How the answer was reached, or `None` when there is no evidence to
+    // speak of (n < 2 or composite).
+    pub fn provenance(&self) -> Option<Provenance> {
+        match &self.evidence {
+            Evidence::None => None,
+            Evidence::BailliePsw { .. } => Some(Provenance::BailliePsw),
+            Evidence::Certificate(cert) => Some(Provenance::Certificate(cert.kind())),
+        }
+    }
+}
+
+pub fn prove_prime(n: &(impl AsBigUint + ?Sized), budget: Duration) -> Proof {
+    let start = Instant::now();
+
+    // NOTE: n − 1 先用一半的预算；BLS 同时分解 n − 1 和 n + 1，用剩下的时间。
+    if let Ok(proof) = ProofState::new(n).resume(budget / 2) {
+        return proof;
+    }
+
+    let proof = crate::prove_prime_bls(n, budget.saturating_sub(start.elapsed()));
+    match proof.evidence {
+        Evidence::BailliePsw { .. } => Proof {
+            primality: proof.primality,
+            evidence: Evidence::BailliePsw { tried: vec!["pocklington", "bls"] },
+        },
+        _ => proof,
+    }
+}
+
+// Progress of a proof, returned by `ProofState::resume` when the budget runs
+// out so that long proofs can be continued later, possibly by another process.
+//
+// The state records how far n − 1 has been factored, which is where the time
+// goes, and the certificates already finished for its prime factors. Its
+// `Display` form is a line-based text format accepted by `FromStr`:
+//
+//      prime-proof-state 2
+//      n <decimal>
+//      prime <decimal>         (one per prime factor of n − 1 found so far)
+//      pending <decimal>       (one per cofactor of n − 1 not yet split)
+//      rho-seed <decimal>
+//      factor <witness>        (one per prime factor q already certified,
+//                               followed by the certificate of q)
+//
+// A certificate is either a line `pratt <q>` or a line `pocklington <q> <k>`
+// followed by k `factor` entries of its own, indented one level deeper.
+// Version 1 states, which have no `factor` lines, are still accepted.
+//
+// NOTE: pratt 证明只记录 q，读取时重新计算（q < 2 ^ 64，只需几微秒）；
+//       读入的每个 factor 都会重新验证，损坏的状态文件不会产生错误的证明。
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct ProofState {
+    n: BigUint,
+    primes: Vec<BigUint>,
+    pending: Vec<BigUint>,
+    rho_seed: u64,
+    // Finished certificates for primes of `primes`, in ascending order of q.
+    certified: Vec<PocklingtonFactor>,
+}
+
+impl ProofState {
+    pub fn new(n: &(impl AsBigUint + ?Sized)) -> Self {
+        let n = n.as_biguint().into_owned();
+        let mut state = ProofState { n, primes: Vec::new(), pending: Vec::new(), rho_seed: 1, certified: Vec::new() };
+        if state.n.bits() <= 64 {
+            return state;
+        }
+
+        let zero = BigUint::from(0u8);
+        let mut rest: BigUint = &state.n - 1u8;
+        for p in crate::global().small_primes().iter() {
+            if &rest % *p == zero {
+                state.take(BigUint::from(*p), &mut rest);
+            }
+        }
+
+        state.pending.push(rest);
+        state
+    }
+
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    // Distinct prime factors of n − 1 found so far, in ascending order.
+    pub fn primes(&self) -> &[BigUint] {
+        &self.primes
+    }
+
+    // Continue the proof for at most `budget`.
+    pub fn resume(mut self, budget: Duration) -> Result<Proof, ProofState> {
+        let deadline = Instant::now() + budget;
+
+        let primality = baillie_psw_primality_test_biguint(&self.n);
+        if primality == Primality::ZeroOrOne || primality == Primality::Composite {
+            return Ok(Proof { primality, evidence: Evidence::None });
+        }
+
+        match self.certify(deadline) {
+            Some(cert) => Ok(Proof { primality: Primality::Prime, evidence: Evidence::Certificate(cert) }),
+            None => Err(self),
+        }
+    }
+
+    // Record the prime factor q of n − 1, dividing every power of it out of `rest`.
+    fn take(&mut self, q: BigUint, rest: &mut BigUint) {
+        let zero = BigUint::from(0u8);
+        if let Err(i) = self.primes.binary_search(&q) {
+            while &*rest % &q == zero {
+                *rest /= &q;
+            }
+            self.primes.insert(i, q);
+        }
+    }
+
+    // Certify the probable prime n, or give up at `deadline`.
+    fn certify(&mut self, deadline: Instant) -> Option<Certificate> {
+        let n = self.n.clone();
+        if n.bits() <= 64 {
+            return pratt_certificate_u64(n.to_u64_digits()[0]).map(Certificate::Pratt);
+        }
+
+        let zero = BigUint::from(0u8);
+        let one  = BigUint::from(1u8);
+
+        let n_minus_one: BigUint = &n - 1u8;
+
+        // F = (n − 1) / rest
+        let mut rest = n_minus_one.clone();
+        for q in self.primes.iter() {
+            while &rest % q == zero {
+                rest /= q;
+            }
+        }
+
+        loop {
+            let f = &n_minus_one / &rest;
+            if &f * &f > n {
+                break;
+            }
+
+            if Instant::now() >= deadline {
+                return None;
+            }
+
+            // NOTE: 优先分解较小的余因子。
+            self.pending.sort_unstable_by(|a, b| b.cmp(a));
+            let m = self.pending.pop()?;
+            // NOTE: m 的素因子已经全部计入 F。
+            if m.gcd(&rest) == one {
+                continue;
+            }
+
+            if m.bits() <= 64 {
+                for (q, _) in factorize_u64_inline(m.to_u64_digits()[0]).iter() {
+                    self.take(BigUint::from(*q), &mut rest);
+                }
+                continue;
+            }
+
+            if baillie_psw_primality_test_biguint(&m) == Primality::PROBABLE {
+                self.take(m, &mut rest);
+                continue;
+            }
+
+            match pollard_brent_rho_biguint(&m, self.rho_seed, deadline) {
+                Some(d) => {
+                    self.pending.push(&m / &d);
+                    self.pending.push(d);
+                },
+                None => {
+                    // NOTE: 超时中断的 walk 下次还要从头开始，所以只有走完时才换多项式。
+                    if Instant::now() < deadline {
+                        self.rho_seed += 1;
+                    }
+                    self.pending.push(m);
+                },
+            }
+        }
+
+        for q in self.primes.iter() {
+            // NOTE: 已经完成的证明直接复用；新完成的立即存入状态，超时也不会丢失。
+            let i = match self.certified.binary_search_by(|factor| factor.certificate.n().cmp(q)) {
+                Ok(_) => continue,
+                Err(i) => i,
+            };
+
+            let exp = &n_minus_one / q;
+
+            let mut a = BigUint::from(2u8);
+            let witness = loop {
+                if Instant::now() >= deadline {
+                    return None;
+                }
+
+                if a.modpow(&n_minus_one, &n) != one {
+                    // NOTE: n 通过了 BPSW，却不满足 Fermat 小定理。
+                    return None;
+                }
+
+                let y = a.modpow(&exp, &n);
+                if y != zero && (y - 1u8).gcd(&n) == one {
+                    break a;
+                }
+
+                a += 1u8;
+            };
+
+            let certificate = ProofState::new(q).certify(deadline)?;
+            self.certified.insert(i, PocklingtonFactor { witness, certificate });
+        }
+
+        Some(Certificate::Pocklington { n, factors: self.certified.clone() })
+    }
+}
+
+impl fmt::Display for ProofState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "prime-proof-state 2")?;
+        writeln!(f, "n {}", self.n)?;
+        for q in self.primes.iter() {
+            writeln!(f, "prime {}", q)?;
+        }
+        for m in self.pending.iter() {
+            writeln!(f, "pending {}", m)?;
+        }
+        writeln!(f, "rho-seed {}", self.rho_seed)?;
+        for factor in self.certified.iter() {
+            write_factor(f, factor, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_factor(f: &mut fmt::Formatter<'_>, factor: &PocklingtonFactor, depth: usize) -> fmt::Result {
+    let indent = 2 * depth;
+    writeln!(f, "{:indent$}factor {}", "", factor.witness, indent = indent)?;
+    match &factor.certificate {
+        Certificate::Pratt(cert) => writeln!(f, "{:indent$}pratt {}", "", cert.p, indent = indent + 2),
+        Certificate::Pocklington { n, factors } => {
+            writeln!(f, "{:indent$}pocklington {} {}", "", n, factors.len(), indent = indent + 2)?;
+            for factor in factors.iter() {
+                write_factor(f, factor, depth + 2)?;
+            }
+            Ok(())
+        },
+        // NOTE: certify 只会产生 pratt 和 pocklington 证明。
+        cert => unreachable!("{} certificate in a proof state", cert.kind()),
+    }
+}
+
+// The line of a saved `ProofState` that could not be understood, counting from 1.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ParseProofStateError {
+    pub line: usize,
+}
+
+impl fmt::Display for ParseProofStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid proof state at line {}", self.line)
+    }
+}
+
+impl FromStr for ProofState {
+    type Err = ParseProofStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let zero = BigUint::from(0u8);
+
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        match lines.next() {
+            Some((_, "prime-proof-state 1")) | Some((_, "prime-proof-state 2")) => {},
+            _ => return Err(ParseProofStateError { line: 1 }),
+        }
+
+        let mut state = ProofState { n: zero.clone(), primes: Vec::new(), pending: Vec::new(), rho_seed: 1, certified: Vec::new() };
+        let mut n_minus_one: Option<BigUint> = None;
+        let mut lines = lines.filter(|(_, text)| !text.is_empty());
+        while let Some((line, text)) = lines.next() {
+            let invalid = ParseProofStateError { line };
+
+            let (key, value) = text.split_once(' ').ok_or(invalid)?;
+            let value = value.trim();
+
+            // NOTE: 除了 n 本身，所有的数都必须整除 n − 1，否则状态文件与 n 不符。
+            let divisor = |n_minus_one: &Option<BigUint>| -> Result<BigUint, ParseProofStateError> {
+                let m: BigUint = value.parse().map_err(|_| invalid)?;
+                match n_minus_one {
+                    Some(n_minus_one) if m > zero && n_minus_one % &m == zero => Ok(m),
+                    _ => Err(invalid),
+                }
+            };
+
+            match key {
+                "n" if n_minus_one.is_none() => {
+                    state.n = value.parse().map_err(|_| invalid)?;
+                    if state.n <= BigUint::from(1u8) {
+                        return Err(invalid);
+                    }
+                    n_minus_one = Some(&state.n - 1u8);
+                },
+                "prime" => {
+                    let q = divisor(&n_minus_one)?;
+                    if state.primes.last().map(|last| *last >= q).unwrap_or(false) {
+                        return Err(invalid);
+                    }
+                    state.primes.push(q);
+                },
+                "pending" => state.pending.push(divisor(&n_minus_one)?),
+                "rho-seed" => state.rho_seed = value.parse().map_err(|_| invalid)?,
+                "factor" if n_minus_one.is_some() => {
+                    let witness = value.parse().map_err(|_| invalid)?;
+                    let certificate = parse_certificate(&mut lines, line)?;
+                    let q = certificate.n();
+                    let factor = PocklingtonFactor { witness, certificate };
+
+                    let ascending = state.certified.last().map(|last| last.certificate.n() < q).unwrap_or(true);
+                    if !ascending || state.primes.binary_search(&q).is_err()
+                        || pocklington_part(&state.n, std::slice::from_ref(&factor)).is_none() {
+                        return Err(invalid);
+                    }
+                    state.certified.push(factor);
+                },
+                _ => return Err(invalid),
+            }
+        }
+
+        if n_minus_one.is_none() {
+            return Err(ParseProofStateError { line: 2 });
+        }
+
+        Ok(state)
+    }
+}
+
+// The certificate following the `factor` entry at `line`.
+fn parse_certificate<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>, line: usize) -> Result<Certificate, ParseProofStateError> {
+    let (line, text) = lines.next().ok_or(ParseProofStateError { line: line + 1 })?;
+    let invalid = ParseProofStateError { line };
+
+    let mut words = text.split_whitespace();
+    match (words.next(), words.next(), words.next(), words.next()) {
+        (Some("pratt"), Some(p), None, None) => {
+            let p = p.parse().map_err(|_| invalid)?;
+            pratt_certificate_u64(p).map(Certificate::Pratt).ok_or(invalid)
+        },
+        (Some("pocklington"), Some(n), Some(k), None) => {
+            let n = n.parse().map_err(|_| invalid)?;
+            let k: usize = k.parse().map_err(|_| invalid)?;
+
+            let mut factors = Vec::new();
+            let mut last = line;
+            for _ in 0..k {
+                let (line, text) = lines.next().ok_or(ParseProofStateError { line: last + 1 })?;
+                let witness = match text.split_once(' ') {
+                    Some(("factor", witness)) => witness.trim().parse().map_err(|_| ParseProofStateError { line })?,
+                    _ => return Err(ParseProofStateError { line }),
+                };
+                let certificate = parse_certificate(lines, line)?;
+                factors.push(PocklingtonFactor { witness, certificate });
+                last = line;
+            }
+
+            Ok(Certificate::Pocklington { n, factors })
+        },
+        _ => Err(invalid),
+    }
+}
+
+
+
+#[test]
+fn test_prove_prime() {
+    let budget = Duration::from_secs(60);
+
+    assert_eq!(prove_prime(&1u8, budget), Proof { primality: Primality::ZeroOrOne, evidence: Evidence::None });
+    assert_eq!(prove_prime(&91u8, budget), Proof { primality: Primality::Composite, evidence: Evidence::None });
+
+    let proof = prove_prime(&97u8, budget);
+    assert_eq!(proof.primality, Primality::Prime);
+    assert_eq!(proof.evidence, Evidence::Certificate(Certificate::Pratt(pratt_certificate_u64(97).unwrap())));
+    assert_eq!(proof.provenance(), Some(Provenance::Certificate("pratt")));
+    assert_eq!(prove_prime(&91u8, budget).provenance(), None);
+
+    // 2 ^ 127 − 1, whose n − 1 splits into primes below 2 ^ 64
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let proof = prove_prime(&m127, budget);
+    assert_eq!(proof.primality, Primality::Prime);
+    match proof.evidence {
+        Evidence::Certificate(cert) => {
+            assert_eq!(cert.n(), m127);
+            assert!(cert.verify());
+        },
+        evidence => panic!("{:?}", evidence),
+    }
+
+    // n = 2k·(2 ^ 127 − 1) + 1, which needs 2 ^ 127 − 1 certified in turn.
+    let n = (1u32..)
+        .map(|k| &m127 * (2 * k) + 1u8)
+        .find(|n| baillie_psw_primality_test_biguint(n) == Primality::PROBABLE)
+        .unwrap();
+    let proof = prove_prime(&n, budget);
+    assert_eq!(proof.primality, Primality::Prime);
+    match proof.evidence {
+        Evidence::Certificate(Certificate::Pocklington { factors, .. }) => {
+            assert!(factors.iter().any(|factor| factor.certificate.n() == m127));
+            let cert = Certificate::Pocklington { n: n.clone(), factors };
+            assert!(cert.verify());
+        },
+        evidence => panic!("{:?}", evidence),
+    }
+
+    // No time at all: downgraded rather than failed, after both proofs.
+    assert_eq!(
+        prove_prime(&m127, Duration::from_secs(0)),
+        Proof { primality: Primality::PROBABLE, evidence: Evidence::BailliePsw { tried: vec!["pocklington", "bls"] } }
+    );
+
+    let m61 = (1u64 << 61) - 1;
+    assert_eq!(prove_prime(&(&m127 * m61), budget).primality, Primality::Composite);
+}
+
+#[test]
+fn test_certificate_verify() {
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let cert = match prove_prime(&m127, Duration::from_secs(60)).evidence {
+        Evidence::Certificate(cert) => cert,
+        evidence => panic!("{:?}", evidence),
+    };
+
+    let factors = match cert {
+        Certificate::Pocklington { factors, .. } => factors,
+        cert => panic!("{:?}", cert),
+    };
+
+    // F no longer exceeds √n.
+    let mut fewer = factors.clone();
+    fewer.truncate(3);
+    assert!(!Certificate::Pocklington { n: m127.clone(), factors: fewer }.verify());
+
+    // A certificate for a composite: 2 ^ 127 + 1 = 3 · ...
+    let fake = Certificate::Pocklington { n: &m127 + 2u8, factors: factors.clone() };
+    assert!(!fake.verify());
+
+    // Repeating a factor does not count twice.
+    let mut repeated = factors.clone();
+    let last = repeated.last().unwrap().clone();
+    repeated.push(last);
+    assert!(!Certificate::Pocklington { n: m127.clone(), factors: repeated }.verify());
+}
+
+#[test]
+fn test_proof_state_resume() {
+    // n = 2k·(2 ^ 127 − 1) + 1
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let n = (1u32..)
+        .map(|k| &m127 * (2 * k) + 1u8)
+        .find(|n| baillie_psw_primality_test_biguint(n) == Primality::PROBABLE)
+        .unwrap();
+
+    let state = ProofState::new(&n).resume(Duration::from_secs(0)).unwrap_err();
+    assert_eq!(state.n(), &n);
+
+    // Round trip through the text format, as a restarted process would.
+    let saved = state.to_string();
+    assert!(saved.starts_with("prime-proof-state 2\n"));
+    let state: ProofState = saved.parse().unwrap();
+    assert_eq!(state.to_string(), saved);
+
+    let proof = state.resume(Duration::from_secs(60)).unwrap();
+    assert_eq!(proof.primality, Primality::Prime);
+    let factors = match proof.evidence {
+        Evidence::Certificate(Certificate::Pocklington { factors, .. }) => factors,
+        evidence => panic!("{:?}", evidence),
+    };
+
+    // A state interrupted after certifying every q but the smallest, among
+    // them 2 ^ 127 − 1 with its own Pocklington certificate.
+    let mut state = ProofState::new(&n);
+    state.primes = factors.iter().map(|factor| factor.certificate.n()).collect();
+    state.pending.clear();
+    state.certified = factors[1..].to_vec();
+
+    let saved = state.to_string();
+    assert!(saved.contains(&format!("  pocklington {} ", m127)));
+    let parsed: ProofState = saved.parse().unwrap();
+    assert_eq!(parsed, state);
+    assert_eq!(parsed.to_string(), saved);
+
+    let proof = parsed.resume(Duration::from_secs(60)).unwrap();
+    assert_eq!(proof.evidence, Evidence::Certificate(Certificate::Pocklington { n: n.clone(), factors: factors.clone() }));
+
+    // A witness that does not check out is rejected, not trusted.
+    let line = saved.lines().position(|text| text.starts_with("factor ")).unwrap();
+    let corrupt: Vec<String> = saved.lines().enumerate()
+        .map(|(i, text)| if i == line { "factor 1".to_string() } else { text.to_string() })
+        .collect();
+    assert_eq!(corrupt.join("\n").parse::<ProofState>(), Err(ParseProofStateError { line: line + 1 }));
+
+    // Version 1 states are still read.
+    assert!("prime-proof-state 1\nn 23\nprime 2\npending 11\n".parse::<ProofState>().is_ok());
+
+    assert_eq!(ProofState::new(&91u8).resume(Duration::from_secs(0)).unwrap().primality, Primality::Composite);
+
+    assert_eq!("".parse::<ProofState>(), Err(ParseProofStateError { line: 1 }));
+    assert_eq!("prime-proof-state 1\nn 23\nprime 5\n".parse::<ProofState>(), Err(ParseProofStateError { line: 3 }));
+    assert_eq!("prime-proof-state 1\nn 23\nprime 11\nprime 2\n".parse::<ProofState>(), Err(ParseProofStateError { line: 4 }));
+    assert_eq!("prime-proof-state 1\nprime 2\n".parse::<ProofState>(), Err(ParseProofStateError { line: 2 }));
+    assert_eq!("prime-proof-state 1\n".parse::<ProofState>(), Err(ParseProofStateError { line: 2 }));
+}
@@ -0,0 +1,203 @@
+// Quality checks for RSA primes
+//
+// NIST FIPS 186-4, appendix B.3 and table B.1, and FIPS 186-5, appendix A.1:
+//
+//   * p has exactly nlen / 2 bits and p ≥ √2 · 2 ^ (nlen / 2 − 1), so that
+//     p · q has exactly nlen bits;
+//   * p − 1 and p + 1 each have a prime factor of more than 100, 140 or 170
+//     bits for nlen = 1024, 2048 or 3072 (the "auxiliary primes");
+//   * gcd(e, p − 1) = 1;
+//   * |p − q| > 2 ^ (nlen / 2 − 100).
+//
+// p ± 1 are only partially factored: trial division by the primes below
+// 2 ^ 16, then Pollard's rho until the time budget runs out. A prime factor
+// that was not found counts as missing, so a report can fail for a prime
+// that would pass with a larger budget, but never the other way round.
+use crate::Primality;
+use crate::Bits;
+use crate::factor::factorize_u64_inline;
+use crate::is_prime_biguint_auto;
+use crate::baillie_psw_primality_test_biguint;
+use crate::factor::pollard_brent_rho_biguint;
+
+use num_integer::Integer;
+use num_bigint::BigUint;
+
+use std::time::{Duration, Instant};
+
+
+// The partial factorization of p − 1 or p + 1.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct FactorCheck {
+    // Bits of the largest prime factor found.
+    pub largest_prime_factor_bits: u64,
+    // Bits of the part left unfactored when the budget ran out; 0 once the
+    // factorization is complete.
+    pub unfactored_bits: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct RsaPrimeReport {
+    // p has exactly `bits` bits and p ≥ √2 · 2 ^ (bits − 1).
+    pub bit_length: bool,
+    pub probable_prime: bool,
+    pub p_minus_one: FactorCheck,
+    pub p_plus_one: FactorCheck,
+    // The size an auxiliary prime must reach for a modulus of 2 · `bits` bits.
+    pub min_auxiliary_bits: u64,
+    // gcd(e, p − 1) = 1
+    pub e_coprime: bool,
+    // |p − q| > 2 ^ (bits − 100), when q was given.
+    pub distance: Option<bool>,
+}
+
+impl RsaPrimeReport {
+    pub fn passed(&self) -> bool {
+        self.bit_length
+            && self.probable_prime
+            && self.p_minus_one.largest_prime_factor_bits >= self.min_auxiliary_bits
+            && self.p_plus_one.largest_prime_factor_bits >= self.min_auxiliary_bits
+            && self.e_coprime
+            && self.distance != Some(false)
+    }
+}
+
+// Check p, one of the two `bits`-bit primes of an RSA modulus with public
+// exponent `e`, and its distance to the other prime `q` if given. `budget` is
+// shared by the factorizations of p − 1 and p + 1.
+pub fn check_rsa_prime_quality(p: &BigUint, q: Option<&BigUint>, e: &BigUint, bits: Bits, budget: Duration) -> RsaPrimeReport {
+    let bits = bits.get() as usize;
+    let one = BigUint::from(1u8);
+    let deadline = Instant::now() + budget;
+
+    // p ≥ √2 · 2 ^ (bits − 1)  ⟺  p² ≥ 2 ^ (2 · bits − 1)
+    let bit_length = p.bits() as usize == bits
+        && p * p >= &one << (2 * bits - 1);
+
+    let probable_prime = is_prime_biguint_auto(p).is_accepted();
+
+    let (p_minus_one, p_plus_one) = if p > &one {
+        let half = deadline.saturating_duration_since(Instant::now()) / 2;
+        let p_minus_one = partial_factor(p - 1u8, Instant::now() + half);
+        let p_plus_one = partial_factor(p + 1u8, deadline);
+        (p_minus_one, p_plus_one)
+    } else {
+        let none = FactorCheck { largest_prime_factor_bits: 0, unfactored_bits: 0 };
+        (none, none)
+    };
+
+    let e_coprime = p > &one && e.gcd(&(p - 1u8)) == one;
+
+    let distance = q.map(|q| {
+        let diff = if p > q { p - q } else { q - p };
+        diff > &one << bits.saturating_sub(100)
+    });
+
+    RsaPrimeReport {
+        bit_length,
+        probable_prime,
+        p_minus_one,
+        p_plus_one,
+        min_auxiliary_bits: min_auxiliary_bits(2 * bits),
+        e_coprime,
+        distance,
+    }
+}
+
+// FIPS 186-4, table B.1: the auxiliary primes must have more than 100, 140
+// or 170 bits.
+fn min_auxiliary_bits(nlen: usize) -> u64 {
+    match nlen {
+        0..=1024 => 101,
+        1025..=2048 => 141,
+        _ => 171,
+    }
+}
+
+fn partial_factor(n: BigUint, deadline: Instant) -> FactorCheck {
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+
+    let mut largest = 0u64;
+    let mut rest = n;
+    for p in crate::global().small_primes().iter() {
+        if &rest % *p == zero {
+            largest = largest.max(BigUint::from(*p).bits());
+            while &rest % *p == zero {
+                rest /= *p;
+            }
+        }
+    }
+
+    let mut pending: Vec<BigUint> = if rest > one { vec![rest] } else { Vec::new() };
+    let mut unfactored_bits = 0u64;
+    let mut seed = 1u64;
+    while let Some(m) = pending.pop() {
+        if m.bits() <= 64 {
+            for (q, _) in factorize_u64_inline(m.to_u64_digits()[0]).iter() {
+                largest = largest.max(BigUint::from(*q).bits());
+            }
+            continue;
+        }
+
+        if baillie_psw_primality_test_biguint(&m) == Primality::PROBABLE {
+            largest = largest.max(m.bits());
+            continue;
+        }
+
+        if Instant::now() >= deadline {
+            unfactored_bits += m.bits();
+            continue;
+        }
+
+        match pollard_brent_rho_biguint(&m, seed, deadline) {
+            Some(d) => {
+                pending.push(&m / &d);
+                pending.push(d);
+            },
+            None => {
+                // NOTE: 这条 walk 走完也没有找到因子，换一个多项式重试。
+                seed += 1;
+                pending.push(m);
+            },
+        }
+    }
+
+    FactorCheck { largest_prime_factor_bits: largest, unfactored_bits }
+}
+
+
+#[test]
+fn test_check_rsa_prime_quality() {
+    use crate::gen_safe_prime;
+
+    let e = BigUint::from(65537u32);
+    let budget = Duration::from_secs(1);
+    let bits = |bits: u32| Bits::new(bits).unwrap();
+
+    // 2 ^ 127 − 1: p + 1 = 2 ^ 128 and p − 1 splits into primes below 2 ^ 37.
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let report = check_rsa_prime_quality(&m127, None, &e, bits(127), budget);
+    assert!(report.bit_length && report.probable_prime && report.e_coprime);
+    assert_eq!(report.p_minus_one, FactorCheck { largest_prime_factor_bits: 37, unfactored_bits: 0 });
+    assert_eq!(report.p_plus_one, FactorCheck { largest_prime_factor_bits: 2, unfactored_bits: 0 });
+    assert_eq!(report.min_auxiliary_bits, 101);
+    assert_eq!(report.distance, None);
+    assert!(!report.passed());
+
+    // 3 | p − 1
+    assert!(!check_rsa_prime_quality(&m127, None, &BigUint::from(3u8), bits(127), budget).e_coprime);
+    // 2 ^ 127 − 1 has only 127 bits.
+    assert!(!check_rsa_prime_quality(&m127, None, &e, bits(128), budget).bit_length);
+
+    // A safe prime p = 2r + 1 has the largest possible factor of p − 1.
+    let p = gen_safe_prime(crate::Bits::safe_prime(128).unwrap(), &mut crate::global().rng());
+    let q = &p + (BigUint::from(1u8) << 60usize);
+    let report = check_rsa_prime_quality(&p, Some(&q), &e, bits(128), budget);
+    assert_eq!(report.p_minus_one.largest_prime_factor_bits, 127);
+    assert_eq!(report.distance, Some(true));
+    assert_eq!(check_rsa_prime_quality(&p, Some(&(&p + 2u8)), &e, bits(128), budget).distance, Some(false));
+
+    let composite = &m127 * 3u8;
+    assert!(!check_rsa_prime_quality(&composite, None, &e, bits(129), budget).probable_prime);
+}
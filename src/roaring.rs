@@ -0,0 +1,414 @@
+// Compressed sets of u64 in the layout of roaring bitmaps
+// https://roaringbitmap.org/
+//
+// The numbers are split by their high 48 bits into chunks of 2 ^ 16, and each
+// chunk holds its low 16 bits either as a sorted array, while it has at most
+// 4096 of them, or as a bitmap of 8 KiB beyond that. The primes below 2 ^ 16
+// fill a bitmap; from about 2 ^ 28 on a chunk holds fewer than 4096 primes and
+// stays an array, two bytes a prime instead of eight in a `Vec<u64>`.
+//
+//      let primes = primes_roaring(0, 1 << 32);
+//      let ids: RoaringSet = my_ids.iter().copied().collect();
+//      let prime_ids = primes.intersection(&ids);
+//
+// Membership is a binary search over the chunks and then over the array or a
+// bit test; intersection and union go chunk by chunk, bitmap against bitmap
+// a word at a time.
+//
+// A `RoaringSet` converts to and from `roaring::RoaringTreemap`, which can be
+// combined with other roaring bitmaps and written in their portable format:
+//
+//      let treemap = RoaringTreemap::from(&primes);
+//      treemap.serialize_into(&mut file)?;
+//
+// NOTE: 两种结构的分块方式不同（高 48 位对高 32 位），转换时按升序逐个插入。
+use crate::sieve::for_each_segment;
+
+use ::roaring::RoaringTreemap;
+
+use core::iter::FromIterator;
+
+
+// Chunks with more values than this are bitmaps.
+const ARRAY_MAX: usize = 4096;
+const BITMAP_WORDS: usize = (1 << 16) / 64;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Container {
+    // Sorted, without duplicates
+    Array(Vec<u16>),
+    Bitmap(Box<[u64; BITMAP_WORDS]>, u32),
+}
+
+impl Container {
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bitmap(_, len) => *len as usize,
+        }
+    }
+
+    fn contains(&self, low: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&low).is_ok(),
+            Container::Bitmap(words, _) => words[low as usize / 64] >> (low % 64) & 1 == 1,
+        }
+    }
+
+    fn insert(&mut self, low: u16) -> bool {
+        match self {
+            Container::Array(values) => {
+                let i = match values.binary_search(&low) {
+                    Ok(_) => return false,
+                    Err(i) => i,
+                };
+                values.insert(i, low);
+                if values.len() > ARRAY_MAX {
+                    *self = Container::from_words(to_words(values));
+                }
+                true
+            },
+            Container::Bitmap(words, len) => {
+                let (word, bit) = (&mut words[low as usize / 64], 1u64 << (low % 64));
+                if *word & bit != 0 {
+                    return false;
+                }
+                *word |= bit;
+                *len += 1;
+                true
+            },
+        }
+    }
+
+    // A bitmap, or an array when the words hold few enough values.
+    fn from_words(words: Box<[u64; BITMAP_WORDS]>) -> Self {
+        let len = words.iter().map(|word| word.count_ones()).sum::<u32>();
+        match len as usize > ARRAY_MAX {
+            true => Container::Bitmap(words, len),
+            false => Container::Array(from_words(&words)),
+        }
+    }
+
+    fn words(&self) -> Box<[u64; BITMAP_WORDS]> {
+        match self {
+            Container::Array(values) => to_words(values),
+            Container::Bitmap(words, _) => words.clone(),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            Container::Array(values) => Box::new(values.iter().copied()),
+            Container::Bitmap(words, _) => Box::new(
+                (0..1u32 << 16).filter(move |i| words[*i as usize / 64] >> (i % 64) & 1 == 1).map(|i| i as u16)
+            ),
+        }
+    }
+
+    fn intersection(&self, other: &Container) -> Option<Container> {
+        let ret = match (self, other) {
+            (Container::Array(a), Container::Array(b)) => {
+                // NOTE: 两个有序数组的归并。
+                let (mut i, mut j) = (0, 0);
+                let mut values = Vec::new();
+                while i < a.len() && j < b.len() {
+                    match a[i].cmp(&b[j]) {
+                        core::cmp::Ordering::Less => i += 1,
+                        core::cmp::Ordering::Greater => j += 1,
+                        core::cmp::Ordering::Equal => { values.push(a[i]); i += 1; j += 1; },
+                    }
+                }
+                Container::Array(values)
+            },
+            (Container::Array(values), bitmap @ Container::Bitmap(..))
+            | (bitmap @ Container::Bitmap(..), Container::Array(values)) => {
+                Container::Array(values.iter().copied().filter(|low| bitmap.contains(*low)).collect())
+            },
+            (Container::Bitmap(a, _), Container::Bitmap(b, _)) => {
+                let mut words = a.clone();
+                words.iter_mut().zip(b.iter()).for_each(|(x, y)| *x &= y);
+                Container::from_words(words)
+            },
+        };
+
+        match ret.len() {
+            0 => None,
+            _ => Some(ret),
+        }
+    }
+
+    fn union(&self, other: &Container) -> Container {
+        if let (Container::Array(a), Container::Array(b)) = (self, other) {
+            if a.len() + b.len() <= ARRAY_MAX {
+                let mut values: Vec<u16> = a.iter().chain(b.iter()).copied().collect();
+                values.sort_unstable();
+                values.dedup();
+                return Container::Array(values);
+            }
+        }
+
+        let mut words = self.words();
+        match other {
+            Container::Array(values) => values.iter().for_each(|low| words[*low as usize / 64] |= 1 << (low % 64)),
+            Container::Bitmap(b, _) => words.iter_mut().zip(b.iter()).for_each(|(x, y)| *x |= y),
+        }
+        Container::from_words(words)
+    }
+}
+
+fn to_words(values: &[u16]) -> Box<[u64; BITMAP_WORDS]> {
+    let mut words = Box::new([0u64; BITMAP_WORDS]);
+    for low in values {
+        words[*low as usize / 64] |= 1 << (low % 64);
+    }
+
+    words
+}
+
+fn from_words(words: &[u64; BITMAP_WORDS]) -> Vec<u16> {
+    let mut values = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        let mut word = *word;
+        while word != 0 {
+            values.push((i * 64) as u16 + word.trailing_zeros() as u16);
+            word &= word - 1;
+        }
+    }
+
+    values
+}
+
+// A set of u64, compressed chunk by chunk as in a roaring bitmap.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct RoaringSet {
+    // (n >> 16, the low 16 bits of the values in that chunk), sorted by key;
+    // no container is empty.
+    chunks: Vec<(u64, Container)>,
+}
+
+impl RoaringSet {
+    pub fn new() -> Self {
+        RoaringSet::default()
+    }
+
+    // Whether n was not in the set before.
+    pub fn insert(&mut self, n: u64) -> bool {
+        let (key, low) = (n >> 16, n as u16);
+        // NOTE: 按升序插入时总是落在最后一个分块，先检查它，避免二分查找。
+        let i = match self.chunks.last() {
+            Some((last, _)) if *last == key => self.chunks.len() - 1,
+            Some((last, _)) if *last < key => {
+                self.chunks.push((key, Container::Array(Vec::new())));
+                self.chunks.len() - 1
+            },
+            _ => match self.chunks.binary_search_by_key(&key, |(key, _)| *key) {
+                Ok(i) => i,
+                Err(i) => {
+                    self.chunks.insert(i, (key, Container::Array(Vec::new())));
+                    i
+                },
+            },
+        };
+
+        self.chunks[i].1.insert(low)
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        match self.chunks.binary_search_by_key(&(n >> 16), |(key, _)| *key) {
+            Ok(i) => self.chunks[i].1.contains(n as u16),
+            Err(_) => false,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.chunks.iter().map(|(_, container)| container.len() as u64).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    // The values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.chunks.iter().flat_map(|(key, container)| container.iter().map(move |low| key << 16 | low as u64))
+    }
+
+    pub fn intersection(&self, other: &RoaringSet) -> RoaringSet {
+        let mut chunks = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.chunks.len() && j < other.chunks.len() {
+            let ((a, x), (b, y)) = (&self.chunks[i], &other.chunks[j]);
+            match a.cmp(b) {
+                core::cmp::Ordering::Less => i += 1,
+                core::cmp::Ordering::Greater => j += 1,
+                core::cmp::Ordering::Equal => {
+                    if let Some(container) = x.intersection(y) {
+                        chunks.push((*a, container));
+                    }
+                    i += 1;
+                    j += 1;
+                },
+            }
+        }
+
+        RoaringSet { chunks }
+    }
+
+    pub fn union(&self, other: &RoaringSet) -> RoaringSet {
+        let mut chunks = Vec::with_capacity(self.chunks.len().max(other.chunks.len()));
+        let (mut i, mut j) = (0, 0);
+        while i < self.chunks.len() || j < other.chunks.len() {
+            let ordering = match (self.chunks.get(i), other.chunks.get(j)) {
+                (Some((a, _)), Some((b, _))) => a.cmp(b),
+                (Some(_), None) => core::cmp::Ordering::Less,
+                _ => core::cmp::Ordering::Greater,
+            };
+            match ordering {
+                core::cmp::Ordering::Less => { chunks.push(self.chunks[i].clone()); i += 1; },
+                core::cmp::Ordering::Greater => { chunks.push(other.chunks[j].clone()); j += 1; },
+                core::cmp::Ordering::Equal => {
+                    let ((key, x), (_, y)) = (&self.chunks[i], &other.chunks[j]);
+                    chunks.push((*key, x.union(y)));
+                    i += 1;
+                    j += 1;
+                },
+            }
+        }
+
+        RoaringSet { chunks }
+    }
+
+    // Approximate heap usage in bytes: two per array value, 8 KiB per bitmap.
+    pub fn size_in_bytes(&self) -> usize {
+        self.chunks.iter()
+            .map(|(_, container)| match container {
+                Container::Array(values) => values.len() * 2,
+                Container::Bitmap(..) => BITMAP_WORDS * 8,
+            })
+            .sum::<usize>()
+            + self.chunks.len() * core::mem::size_of::<(u64, Container)>()
+    }
+}
+
+impl FromIterator<u64> for RoaringSet {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut set = RoaringSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u64> for RoaringSet {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for n in iter {
+            self.insert(n);
+        }
+    }
+}
+
+impl From<&RoaringSet> for RoaringTreemap {
+    fn from(set: &RoaringSet) -> Self {
+        RoaringTreemap::from_sorted_iter(set.iter()).expect("RoaringSet iterates in ascending order")
+    }
+}
+
+impl From<RoaringSet> for RoaringTreemap {
+    fn from(set: RoaringSet) -> Self {
+        RoaringTreemap::from(&set)
+    }
+}
+
+impl From<&RoaringTreemap> for RoaringSet {
+    fn from(treemap: &RoaringTreemap) -> Self {
+        treemap.iter().collect()
+    }
+}
+
+impl From<RoaringTreemap> for RoaringSet {
+    fn from(treemap: RoaringTreemap) -> Self {
+        RoaringSet::from(&treemap)
+    }
+}
+
+// Every prime p with lo <= p < hi, straight from the sieve segments into the
+// compressed set, without a `Vec<u64>` of them in between.
+pub fn primes_roaring(lo: u64, hi: u64) -> RoaringSet {
+    let mut set = RoaringSet::new();
+    for_each_segment(lo, hi, |base, composite| {
+        for (i, is_composite) in composite.iter().enumerate() {
+            if !is_composite {
+                set.insert(base + i as u64);
+            }
+        }
+
+        true
+    });
+
+    set
+}
+
+
+#[test]
+fn test_roaring_set() {
+    use crate::primes_in_range;
+
+    // Dense below 2 ^ 16, sparse above; both kinds of container, and a range
+    // across a chunk boundary.
+    for (lo, hi) in [(0u64, 200_000u64), (1 << 32, (1 << 32) + 300_000), ((1 << 40) - 100_000, (1 << 40) + 100_000)] {
+        let primes = primes_in_range(lo, hi);
+        let set = primes_roaring(lo, hi);
+        assert_eq!(set.len(), primes.len() as u64);
+        assert_eq!(set.iter().collect::<Vec<u64>>(), primes);
+        assert!(primes.iter().all(|p| set.contains(*p)));
+        assert!(!set.contains(lo.saturating_sub(1)) && !set.contains(hi) && !set.contains(lo + 1 + lo % 2));
+        assert_eq!(set, primes.iter().rev().copied().collect::<RoaringSet>());
+    }
+    assert!(matches!(primes_roaring(0, 1 << 16).chunks[..], [(0, Container::Bitmap(_, 6542))]));
+    assert!(matches!(primes_roaring(1 << 40, (1 << 40) + (1 << 16)).chunks[..], [(_, Container::Array(_))]));
+
+    // The primes that are also ≡ 1 (mod 4), and those or the squares.
+    let primes = primes_roaring(0, 1 << 20);
+    let ids: RoaringSet = (1..1u64 << 20).step_by(4).collect();
+    let both = primes.intersection(&ids);
+    assert_eq!(both.iter().collect::<Vec<u64>>(), primes.iter().filter(|p| p % 4 == 1).collect::<Vec<u64>>());
+    assert_eq!(both, ids.intersection(&primes));
+    assert!(primes.intersection(&RoaringSet::new()).is_empty());
+
+    let squares: RoaringSet = (0..1u64 << 10).map(|i| i * i).collect();
+    let either = primes.union(&squares);
+    assert_eq!(either.len(), primes.len() + squares.len());
+    assert_eq!(either, squares.union(&primes));
+    assert!(either.iter().zip(either.iter().skip(1)).all(|(a, b)| a < b));
+    assert_eq!(either.intersection(&squares), squares);
+
+    // A chunk outgrows its array and shrinks back to one.
+    let mut set = RoaringSet::new();
+    assert!(set.insert(5) && !set.insert(5));
+    set.extend(0..5000);
+    assert!(matches!(set.chunks[..], [(0, Container::Bitmap(_, 5000))]));
+    assert!(matches!(set.intersection(&(0..100).collect()).chunks[..], [(0, Container::Array(_))]));
+    assert!(primes.size_in_bytes() < primes.len() as usize * 8 / 3);
+}
+
+#[test]
+fn test_roaring_treemap() {
+    let primes = primes_roaring((1 << 32) - 100_000, (1 << 32) + 100_000);
+    let treemap = RoaringTreemap::from(&primes);
+    assert_eq!(treemap.len(), primes.len());
+    assert!(treemap.iter().eq(primes.iter()));
+    assert_eq!(RoaringSet::from(&treemap), primes);
+
+    // Through the portable serialization of the roaring crate and back.
+    let mut bytes = Vec::new();
+    treemap.serialize_into(&mut bytes).unwrap();
+    let read = RoaringTreemap::deserialize_from(&bytes[..]).unwrap();
+    assert_eq!(RoaringSet::from(read), primes);
+
+    // Combined with a treemap built elsewhere.
+    let odd: RoaringTreemap = (1u64 << 32..(1 << 32) + 100_000).step_by(2).collect();
+    let both = RoaringSet::from(&treemap & &odd);
+    assert_eq!(both, primes.intersection(&RoaringSet::from(odd)));
+
+    assert_eq!(RoaringTreemap::from(RoaringSet::new()), RoaringTreemap::new());
+    assert!(RoaringSet::from(RoaringTreemap::new()).is_empty());
+}
@@ -0,0 +1,244 @@
+// Integer roots
+// https://en.wikipedia.org/wiki/Integer_square_root#Algorithm_using_Newton's_method
+// https://en.wikipedia.org/wiki/Nth_root#Computing_principal_roots
+//
+// ⌊n ^ (1/k)⌋ by Newton's iteration
+//
+//      x ← ((k − 1) · x + ⌊n / x ^ (k − 1)⌋) / k
+//
+// started above the root, which decreases monotonically to the root and stops
+// as soon as it would increase again. No floating point is involved, so the
+// results are exact for every input, including those near u64::MAX and
+// u128::MAX where an f64 estimate is off by more than one.
+//
+// Perfect squares are first screened by their residues modulo 64, 63 and 65,
+// and perfect powers b ^ e are found by trying every prime exponent e.
+use crate::Primality;
+use crate::table_query_u16;
+
+use num_bigint::BigUint;
+
+
+macro_rules! iroot_impl {
+    ($name:ident, $t:ty) => {
+        // ⌊n ^ (1/k)⌋, for k >= 1.
+        pub fn $name(n: $t, k: u32) -> $t {
+            assert!(k > 0);
+
+            if k == 1 || n < 2 {
+                return n;
+            }
+
+            let bits = <$t>::BITS - n.leading_zeros();
+            if k >= bits {
+                // NOTE: 2 ^ k > n，根只能是 1。
+                return 1;
+            }
+
+            // 2 ^ ⌈bits / k⌉ > n ^ (1/k)
+            let mut x: $t = 1 << bits.div_ceil(k);
+            let k1 = (k - 1) as $t;
+            loop {
+                // NOTE: x ^ (k − 1) 溢出时它一定大于 n，商为 0。
+                let q = x.checked_pow(k - 1).map(|p| n / p).unwrap_or(0);
+                let y = (k1 * x + q) / k as $t;
+                if y >= x {
+                    return x;
+                }
+                x = y;
+            }
+        }
+    };
+}
+
+iroot_impl!(iroot_u64, u64);
+iroot_impl!(iroot_u128, u128);
+
+// ⌊√n⌋
+pub fn isqrt_u64(n: u64) -> u64 {
+    iroot_u64(n, 2)
+}
+
+pub fn isqrt_u128(n: u128) -> u128 {
+    iroot_u128(n, 2)
+}
+
+// ⌊∛n⌋
+pub fn icbrt_u64(n: u64) -> u64 {
+    iroot_u64(n, 3)
+}
+
+pub fn icbrt_u128(n: u128) -> u128 {
+    iroot_u128(n, 3)
+}
+
+// NOTE: num-bigint 的 nth_root 同样使用从上方开始的 Newton 迭代。
+pub fn iroot_biguint(n: &BigUint, k: u32) -> BigUint {
+    assert!(k > 0);
+    n.nth_root(k)
+}
+
+pub fn isqrt_biguint(n: &BigUint) -> BigUint {
+    n.sqrt()
+}
+
+pub fn icbrt_biguint(n: &BigUint) -> BigUint {
+    n.cbrt()
+}
+
+// NOTE: 平方数模 64、63、65 的余数分别只有 12、16、21 种，三个过滤器合起来
+//       在计算平方根之前排除掉 99% 以上的非平方数。
+const fn squares_mod<const M: usize>() -> [bool; M] {
+    let mut table = [false; M];
+    let mut i = 0;
+    while i < M {
+        table[i * i % M] = true;
+        i += 1;
+    }
+    table
+}
+
+static SQUARES_MOD_64: [bool; 64] = squares_mod::<64>();
+static SQUARES_MOD_63: [bool; 63] = squares_mod::<63>();
+static SQUARES_MOD_65: [bool; 65] = squares_mod::<65>();
+
+// Whether r = n mod 64 · 63 · 65 is a square residue modulo 64, 63 and 65.
+pub(crate) fn may_be_square(r: u64) -> bool {
+    SQUARES_MOD_64[(r % 64) as usize]
+        && SQUARES_MOD_63[(r % 63) as usize]
+        && SQUARES_MOD_65[(r % 65) as usize]
+}
+
+pub fn is_perfect_square_u64(n: u64) -> bool {
+    if !may_be_square(n % (64 * 63 * 65)) {
+        return false;
+    }
+
+    let r = isqrt_u64(n);
+    r * r == n
+}
+
+pub fn is_perfect_square_biguint(n: &BigUint) -> bool {
+    let r = n % (64u32 * 63 * 65);
+    if !may_be_square(r.to_u64_digits().first().copied().unwrap_or(0)) {
+        return false;
+    }
+
+    let r = n.sqrt();
+    &(&r * &r) == n
+}
+
+// (b, e) with n = b ^ e, e >= 2 as large as possible, or `None` when n is
+// not a perfect power. 0 and 1 are powers of themselves for every e and
+// also give `None`.
+pub fn perfect_power_u64(n: u64) -> Option<(u64, u32)> {
+    if n < 4 {
+        return None;
+    }
+
+    let bits = u64::BITS - n.leading_zeros();
+    // NOTE: 只需要尝试素数指数：n = b^(pq) 时，先求出 b^q 再递归。
+    for p in (2..bits).filter(|p| table_query_u16(*p as u16) == Primality::Prime) {
+        if p == 2 && !is_perfect_square_u64(n) {
+            continue;
+        }
+
+        let r = iroot_u64(n, p);
+        if r.checked_pow(p) == Some(n) {
+            return match perfect_power_u64(r) {
+                Some((b, e)) => Some((b, e * p)),
+                None => Some((r, p)),
+            };
+        }
+    }
+
+    None
+}
+
+pub fn perfect_power_biguint(n: &BigUint) -> Option<(BigUint, u32)> {
+    if n.bits() <= 64 {
+        let n = n.to_u64_digits().first().copied().unwrap_or(0);
+        return perfect_power_u64(n).map(|(b, e)| (BigUint::from(b), e));
+    }
+
+    let bits = n.bits() as u32;
+    for p in (2..bits).filter(|p| *p > u16::MAX as u32 || table_query_u16(*p as u16) == Primality::Prime) {
+        if p == 2 && !is_perfect_square_biguint(n) {
+            continue;
+        }
+
+        let r = n.nth_root(p);
+        if &r.pow(p) == n {
+            return match perfect_power_biguint(&r) {
+                Some((b, e)) => Some((b, e * p)),
+                None => Some((r, p)),
+            };
+        }
+    }
+
+    None
+}
+
+
+#[test]
+fn test_perfect_power() {
+    for n in 0..10_000u64 {
+        let r = isqrt_u64(n);
+        assert_eq!(is_perfect_square_u64(n), r * r == n, "N={}", n);
+        assert_eq!(is_perfect_square_biguint(&BigUint::from(n)), r * r == n, "N={}", n);
+    }
+    assert!(is_perfect_square_u64(0xffff_fffe_0000_0001));
+    assert!(!is_perfect_square_u64(u64::MAX));
+
+    assert_eq!(perfect_power_u64(1), None);
+    assert_eq!(perfect_power_u64(4), Some((2, 2)));
+    assert_eq!(perfect_power_u64(64), Some((2, 6)));
+    assert_eq!(perfect_power_u64(36), Some((6, 2)));
+    assert_eq!(perfect_power_u64(72), None);
+    assert_eq!(perfect_power_u64(3u64.pow(40)), Some((3, 40)));
+    assert_eq!(perfect_power_u64(1 << 63), Some((2, 63)));
+    assert_eq!(perfect_power_u64(u64::MAX), None);
+    assert_eq!(perfect_power_u64(4294967291 * 4294967291), Some((4294967291, 2)));
+
+    let b = BigUint::from(1_000_000_007u64);
+    assert_eq!(perfect_power_biguint(&b.pow(6u32)), Some((b.clone(), 6)));
+    assert_eq!(perfect_power_biguint(&(b.pow(6u32) + 1u8)), None);
+    assert!(is_perfect_square_biguint(&b.pow(6u32)));
+    assert_eq!(perfect_power_biguint(&(BigUint::from(1u8) << 127usize)), Some((BigUint::from(2u8), 127)));
+}
+
+#[test]
+fn test_iroot() {
+    for n in 0..10_000u64 {
+        let r = isqrt_u64(n);
+        assert!(r * r <= n && (r + 1) * (r + 1) > n, "N={}", n);
+        let r = icbrt_u64(n);
+        assert!(r.pow(3) <= n && (r + 1).pow(3) > n, "N={}", n);
+    }
+
+    assert_eq!(isqrt_u64(u64::MAX), u32::MAX as u64);
+    assert_eq!(isqrt_u64((1 << 52) + 1), 1 << 26);
+    // (2 ^ 32 − 1) ^ 2 and one below it, where an f64 estimate rounds up.
+    assert_eq!(isqrt_u64(0xffff_fffe_0000_0001), 0xffff_ffff);
+    assert_eq!(isqrt_u64(0xffff_fffe_0000_0000), 0xffff_fffe);
+    assert_eq!(icbrt_u64(u64::MAX), 2642245);
+    assert_eq!(iroot_u64(u64::MAX, 63), 2);
+    assert_eq!(iroot_u64(u64::MAX, 64), 1);
+    assert_eq!(iroot_u64(u64::MAX, 1), u64::MAX);
+
+    assert_eq!(isqrt_u128(u128::MAX), u64::MAX as u128);
+    assert_eq!(isqrt_u128((u64::MAX as u128).pow(2) - 1), u64::MAX as u128 - 1);
+    assert_eq!(icbrt_u128(u128::MAX), 6981463658331);
+    assert_eq!(iroot_u128(1 << 120, 5), 1 << 24);
+    assert_eq!(iroot_u128((1 << 120) - 1, 5), (1 << 24) - 1);
+
+    for (n, k) in [(u64::MAX, 2u32), (u64::MAX, 3), (1u64 << 62, 7), (123456789012345, 4)] {
+        let r = iroot_u64(n, k);
+        assert_eq!(iroot_u128(n as u128, k), r as u128);
+        assert_eq!(iroot_biguint(&BigUint::from(n), k), BigUint::from(r));
+    }
+
+    let n = (BigUint::from(1u8) << 300usize) + 1u8;
+    assert_eq!(isqrt_biguint(&n), BigUint::from(1u8) << 150usize);
+    assert_eq!(icbrt_biguint(&n), BigUint::from(1u8) << 100usize);
+}
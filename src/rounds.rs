@@ -0,0 +1,87 @@
+// Number of Miller–Rabin rounds for a given security level
+//
+// Average case error estimates for the strong probable prime test
+// (Damgård, Landrock, Pomerance, 1993)
+// https://math.dartmouth.edu/~carlp/PDF/paper88.pdf
+//
+// Handbook of Applied Cryptography, Table 4.4
+// https://cacr.uwaterloo.ca/hac/about/chap4.pdf
+//
+// FIPS 186-5, Appendix B.3 (Probabilistic Primality Tests)
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SecurityLevel {
+    // Error probability below 2^-80 for a uniformly random odd candidate.
+    Bits80,
+    // Error probability below 2^-128 for a uniformly random odd candidate.
+    Bits128,
+    // Error probability below 2^-128 for ANY candidate, including numbers
+    // chosen by an attacker. Only Rabin's worst-case bound (1/4 per round)
+    // applies here, so the bit length does not help.
+    Adversarial,
+}
+
+// Recommended number of rounds `k` for `miller_rabin_primality_test_biguint`.
+//
+// NOTE: 表中的数据只适用于随机选取的候选数（例如素数生成），
+//       如果待测数字来自不可信的输入，请使用 `SecurityLevel::Adversarial`。
+pub fn recommended_rounds(bits: usize, security: SecurityLevel) -> u32 {
+    match security {
+        // HAC Table 4.4 (derived from the Damgård–Landrock–Pomerance bounds),
+        // below 100 bits we fall back to the worst-case bound: 4 ^ -40 = 2 ^ -80.
+        SecurityLevel::Bits80 => match bits {
+                0..=  99 => 40,
+              100..= 149 => 27,
+              150..= 199 => 18,
+              200..= 249 => 15,
+              250..= 299 => 12,
+              300..= 349 =>  9,
+              350..= 399 =>  8,
+              400..= 449 =>  7,
+              450..= 549 =>  6,
+              550..= 649 =>  5,
+              650..= 849 =>  4,
+              850..=1299 =>  3,
+            _ => 2,
+        },
+        // Damgård–Landrock–Pomerance bounds for 2 ^ -128
+        // (the same table OpenSSL uses in `BN_prime_checks_for_size`).
+        SecurityLevel::Bits128 => match bits {
+                0..=  54 => 34,
+               55..= 307 => 27,
+              308..= 346 =>  8,
+              347..= 399 =>  7,
+              400..= 475 =>  6,
+              476..=1344 =>  5,
+             1345..=3746 =>  4,
+            _ => 3,
+        },
+        // 4 ^ -64 = 2 ^ -128
+        SecurityLevel::Adversarial => 64,
+    }
+}
+
+
+#[test]
+fn test_recommended_rounds() {
+    assert_eq!(recommended_rounds(64, SecurityLevel::Bits80), 40);
+    assert_eq!(recommended_rounds(512, SecurityLevel::Bits80), 6);
+    assert_eq!(recommended_rounds(1024, SecurityLevel::Bits80), 3);
+    assert_eq!(recommended_rounds(2048, SecurityLevel::Bits80), 2);
+
+    assert_eq!(recommended_rounds(512, SecurityLevel::Bits128), 5);
+    assert_eq!(recommended_rounds(1024, SecurityLevel::Bits128), 5);
+    assert_eq!(recommended_rounds(2048, SecurityLevel::Bits128), 4);
+    assert_eq!(recommended_rounds(4096, SecurityLevel::Bits128), 3);
+
+    assert_eq!(recommended_rounds(4096, SecurityLevel::Adversarial), 64);
+
+    // More bits never need more rounds.
+    for level in [SecurityLevel::Bits80, SecurityLevel::Bits128, SecurityLevel::Adversarial].iter() {
+        for bits in 1..8192 {
+            assert!(recommended_rounds(bits, *level) <= recommended_rounds(bits - 1, *level));
+        }
+    }
+}
@@ -0,0 +1,147 @@
+// Safe prime testing
+//
+// p is a safe prime when both p and q = (p − 1) / 2 are prime. Rather than
+// running two independent tests, the checks on p and q are interleaved from
+// cheapest to most expensive, so that most composites are rejected by a
+// single-word operation:
+//
+//   1. p ≡ 3 (mod 4), since q must be odd;
+//   2. one pass over the small primes s: with r = q mod s, s | q when r = 0
+//      and s | p = 2q + 1 when 2r + 1 ≡ 0 (mod s), so a single remainder
+//      per prime serves both numbers;
+//   3. a strong test to base 2 on q, then on p;
+//   4. the remaining random rounds on q, then on p.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::SecurityLevel;
+use crate::recommended_rounds;
+use crate::miller_rabin::{find_witness_biguint, is_prime_u64};
+
+use num_bigint::{BigUint, RandBigInt};
+
+
+// NOTE: 与素数生成的预筛选使用相同的上界。
+const SIEVE_BOUND: u16 = 1 << 12;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SafePrimeTester {
+    security: SecurityLevel,
+}
+
+impl Default for SafePrimeTester {
+    fn default() -> Self {
+        // NOTE: 待测的 p 通常来自外部（例如 DH 参数），默认按最坏情况选取轮数。
+        SafePrimeTester { security: SecurityLevel::Adversarial }
+    }
+}
+
+impl SafePrimeTester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Choose the number of random rounds on p and on q by `recommended_rounds`.
+    pub fn with_security_level(mut self, security: SecurityLevel) -> Self {
+        self.security = security;
+        self
+    }
+
+    // `Prime` or `ProbablyPrime` when both p and (p − 1) / 2 are (probably)
+    // prime, `Composite` when either of them is not.
+    pub fn test(&self, p: &(impl AsBigUint + ?Sized)) -> Primality {
+        let p = p.as_biguint();
+        let p: &BigUint = &p;
+
+        if p.bits() <= 64 {
+            let p = p.to_u64_digits().first().copied().unwrap_or(0);
+            return match p {
+                0 | 1 => Primality::ZeroOrOne,
+                _ => Primality::from_proof(is_prime_u64(p) && p >= 5 && is_prime_u64(p / 2)),
+            };
+        }
+
+        // 1. q = (p − 1) / 2 is odd
+        if p % 4u8 != BigUint::from(3u8) {
+            return Primality::Composite;
+        }
+
+        let q: BigUint = p >> 1usize;
+
+        // 2. shared trial division
+        for s in crate::global().small_primes().iter().skip(1) {
+            let s = *s as u32;
+            if s >= SIEVE_BOUND as u32 {
+                break;
+            }
+
+            let r = (&q % s).to_u32_digits().first().copied().unwrap_or(0);
+            if r == 0 || (2 * r + 1) % s == 0 {
+                return Primality::Composite;
+            }
+        }
+
+        // 3. base 2, 4. random bases
+        let two = BigUint::from(2u8);
+        let k = recommended_rounds(p.bits() as usize, self.security) as usize;
+        let mut rng = crate::global().rng();
+        for n in [&q, p] {
+            if find_witness_biguint(n, 1, |_| two.clone()).is_some() {
+                return Primality::Composite;
+            }
+        }
+        for n in [&q, p] {
+            let n_minus_two: BigUint = n - 2u8;
+            if k > 1 && find_witness_biguint(n, k - 1, |_| rng.gen_biguint_range(&two, &n_minus_two)).is_some() {
+                return Primality::Composite;
+            }
+        }
+
+        Primality::PROBABLE
+    }
+}
+
+
+#[test]
+fn test_safe_prime_tester() {
+    use crate::gen_safe_prime;
+    use crate::miller_rabin_primality_test_biguint;
+
+    let tester = SafePrimeTester::new();
+
+    // OEIS A005385
+    let safe = [5u64, 7, 11, 23, 47, 59, 83, 107, 167, 179, 227, 263, 347, 359, 383, 467, 479, 503, 563, 587];
+    for p in 0..600u64 {
+        let expected = if safe.contains(&p) { Primality::Prime } else if p < 2 { Primality::ZeroOrOne } else { Primality::Composite };
+        assert_eq!(tester.test(&p), expected, "P={}", p);
+    }
+
+    let mut rng = crate::global().rng();
+    let p = gen_safe_prime(crate::Bits::safe_prime(256).unwrap(), &mut rng);
+    assert_eq!(tester.test(&p), Primality::PROBABLE);
+    assert_eq!(tester.with_security_level(SecurityLevel::Bits80).test(&p), Primality::PROBABLE);
+
+    // 2 ^ 127 − 1 is prime, but (2 ^ 127 − 2) / 2 = 2 ^ 126 − 1 is not.
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    assert_eq!(tester.test(&m127), Primality::Composite);
+    // q = 2 ^ 127 − 1 is prime, but p = 2 ^ 128 − 1 is not.
+    assert_eq!(tester.test(&(&m127 * 2u8 + 1u8)), Primality::Composite);
+
+    // For a random prime p the answer is the primality of q.
+    for _ in 0..5 {
+        let p = crate::gen_prime(crate::Bits::new(128).unwrap(), &mut rng);
+        let q: BigUint = &p >> 1usize;
+        let expected = miller_rabin_primality_test_biguint(&q, 20).is_accepted();
+        assert_eq!(tester.test(&p).is_accepted(), expected, "P={}", p);
+    }
+}
+
+#[bench]
+fn bench_safe_prime_tester_random_odd(b: &mut test::Bencher) {
+    let mut rng = crate::global().rng();
+    let tester = SafePrimeTester::new();
+
+    b.iter(|| {
+        let n = rng.gen_biguint(1024) | BigUint::from(1u8);
+        tester.test(&n)
+    })
+}
@@ -0,0 +1,98 @@
+// Uniform samples of primes
+//
+// "The next prime after a random x" favours primes that follow long gaps:
+// p is picked with probability proportional to p − prevprime(p). Instead,
+// every prime of the range is equally likely here, and every set of `count`
+// distinct primes is an equally likely result.
+//
+// Wide ranges use rejection sampling: draw x uniformly and keep it if it is a
+// prime not drawn before. Narrow ranges, and requests for a large share of
+// the primes in a range, sieve the whole range and sample from the list.
+use crate::primes_in_range;
+use crate::PrimeRange;
+use crate::miller_rabin::is_prime_u64;
+
+use rand::Rng;
+
+use std::collections::HashSet;
+
+
+// NOTE: 不超过这个宽度的区间直接筛出全部素数（约 4M 个数，一次分段筛）。
+const SIEVE_WIDTH: u64 = 1 << 22;
+
+// `count` distinct primes from `range`, each drawn uniformly at random, in
+// random order. When the range holds fewer than `count` primes, all of them.
+pub fn sample_primes<R: Rng + ?Sized>(range: PrimeRange, count: usize, rng: &mut R) -> Vec<u64> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let width = range.width();
+
+    // NOTE: 2^64 以内素数的密度不低于 1/45，count <= width / 128 时所需的素数
+    //       不到区间内素数的三分之一，重复抽中的概率很低。
+    if width <= SIEVE_WIDTH || count as u64 > width / 128 {
+        let primes = primes_in_range(range.lo(), range.hi());
+        let amount = count.min(primes.len());
+        return rand::seq::index::sample(rng, primes.len(), amount)
+            .into_iter()
+            .map(|i| primes[i])
+            .collect();
+    }
+
+    let mut seen: HashSet<u64> = HashSet::with_capacity(count);
+    let mut ret: Vec<u64> = Vec::with_capacity(count);
+    while ret.len() < count {
+        let x = rng.gen_range(range.lo(), range.hi());
+        if is_prime_u64(x) && seen.insert(x) {
+            ret.push(x);
+        }
+    }
+
+    ret
+}
+
+
+#[test]
+fn test_sample_primes() {
+    let mut rng = crate::global().rng();
+    let range = |lo: u64, hi: u64| PrimeRange::new(lo, hi).unwrap();
+
+    assert!(sample_primes(range(0, 100), 0, &mut rng).is_empty());
+    assert!(sample_primes(range(24, 29), 5, &mut rng).is_empty());
+
+    // Fewer primes than requested: all of them.
+    let mut all = sample_primes(range(0, 30), 100, &mut rng);
+    all.sort_unstable();
+    assert_eq!(all, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+    // Both paths return distinct primes from the range.
+    for (range, count) in [(range(1000, 2000), 20), (range(1 << 40, (1 << 40) + (1 << 30)), 200), (range(u64::MAX - (1 << 32), u64::MAX), 50)] {
+        let sample = sample_primes(range, count, &mut rng);
+        assert_eq!(sample.len(), count);
+        assert!(sample.iter().all(|p| range.contains(*p) && is_prime_u64(*p)));
+        assert_eq!(sample.iter().collect::<HashSet<_>>().len(), count);
+    }
+
+    // Every prime below 30 is drawn about equally often; "next prime after x"
+    // would pick 29 six times as often as 3.
+    let mut hits = [0u32; 30];
+    for _ in 0..10_000 {
+        for p in sample_primes(range(0, 30), 1, &mut rng) {
+            hits[p as usize] += 1;
+        }
+    }
+    for p in [2usize, 3, 5, 7, 11, 13, 17, 19, 23, 29] {
+        assert!(hits[p] > 700 && hits[p] < 1300, "P={} {}", p, hits[p]);
+    }
+}
+
+#[bench]
+fn bench_sample_primes(b: &mut test::Bencher) {
+    let mut rng = crate::global().rng();
+    let range = PrimeRange::new(1 << 62, 1 << 63).unwrap();
+
+    b.iter(|| {
+        sample_primes(range, 16, &mut rng)
+    })
+}
@@ -0,0 +1,985 @@
+// Sieve of Eratosthenes
+// https://en.wikipedia.org/wiki/Sieve_of_Eratosthenes#Segmented_sieve
+//
+// Prime-counting function
+// https://en.wikipedia.org/wiki/Prime-counting_function
+use crate::Primality;
+use crate::table_query_u16;
+use crate::isqrt_u64;
+use crate::maximal_prime_gaps;
+use crate::miller_rabin::is_prime_u64;
+
+use core::cell::OnceCell;
+use core::num::NonZeroU64;
+use std::collections::VecDeque;
+
+
+// Every prime p <= limit, in ascending order.
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    if limit <= u16::MAX as u64 {
+        // NOTE: 小范围直接查表。
+        return (0..=limit as u16)
+            .filter(|n| table_query_u16(*n) == Primality::Prime)
+            .map(|n| n as u64)
+            .collect();
+    }
+
+    primes_in_range(0, limit.saturating_add(1))
+}
+
+// Every prime p with lo <= p < hi, in ascending order.
+pub fn primes_in_range(lo: u64, hi: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    for_each_segment(lo, hi, |base, composite| {
+        for (i, is_composite) in composite.iter().enumerate() {
+            if !is_composite {
+                primes.push(base + i as u64);
+            }
+        }
+
+        true
+    });
+
+    primes
+}
+
+// Every prime p with lo <= p < hi as (k, p) pairs, walkable from both ends.
+//
+// k counts from 1 at the first prime of the range, so it is the global index
+// (p = nth_prime(k)) whenever lo <= 2. The range is sieved one chunk at a time
+// from whichever end is walked; the number of primes in it, which `len` and
+// `next_back` need, is counted the first time it is asked for.
+pub fn primes(lo: u64, hi: u64) -> Primes {
+    Primes {
+        lo,
+        hi: hi.max(lo),
+        chunk: crate::thresholds().segment_size,
+        base: Vec::new(),
+        front: VecDeque::new(),
+        back: VecDeque::new(),
+        taken_front: 0,
+        taken_back: 0,
+        count: OnceCell::new(),
+        range: (lo, hi),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Primes {
+    // [lo, hi) is the part of the range not sieved yet.
+    lo: u64,
+    hi: u64,
+    chunk: u64,
+    // The sieving primes found so far, extended as needed.
+    base: Vec<u64>,
+    // Primes sieved from either end and not handed out yet.
+    front: VecDeque<u64>,
+    back: VecDeque<u64>,
+    taken_front: u64,
+    taken_back: u64,
+    // The number of primes in `range`.
+    count: OnceCell<u64>,
+    range: (u64, u64),
+}
+
+impl Primes {
+    fn total(&self) -> u64 {
+        *self.count.get_or_init(|| count_primes_in_range(self.range.0, self.range.1))
+    }
+}
+
+impl Iterator for Primes {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // NOTE: 一个块里也可能一个素数都没有，所以要循环到找到素数或范围筛完为止。
+        let p = loop {
+            if let Some(p) = self.front.pop_front() {
+                break p;
+            }
+
+            if self.lo == self.hi {
+                break self.back.pop_front()?;
+            }
+
+            let end = self.hi.min(self.lo.saturating_add(self.chunk));
+            sieve_chunk(self.lo, end, &mut self.base, &mut self.front);
+            self.lo = end;
+        };
+
+        self.taken_front += 1;
+        Some((self.taken_front, p))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.total() - self.taken_front - self.taken_back) as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Primes {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let p = loop {
+            if let Some(p) = self.back.pop_back() {
+                break p;
+            }
+
+            if self.lo == self.hi {
+                break self.front.pop_back()?;
+            }
+
+            let start = self.lo.max(self.hi.saturating_sub(self.chunk));
+            sieve_chunk(start, self.hi, &mut self.base, &mut self.back);
+            self.hi = start;
+        };
+
+        let k = self.total() - self.taken_back;
+        self.taken_back += 1;
+        Some((k, p))
+    }
+}
+
+impl ExactSizeIterator for Primes { }
+
+impl core::iter::FusedIterator for Primes { }
+
+// The number of primes p with lo <= p < hi: π(hi − 1) − π(lo − 1), or one
+// pass of the sieve when the range is narrow next to hi ^ (3/4), about what
+// `prime_pi` costs there.
+fn count_primes_in_range(lo: u64, hi: u64) -> u64 {
+    if lo >= hi {
+        return 0;
+    }
+
+    let r = isqrt_u64(hi);
+    if hi - lo >= r * isqrt_u64(r) {
+        return prime_pi(hi - 1) - prime_pi(lo.saturating_sub(1));
+    }
+
+    let mut count = 0;
+    for_each_segment(lo, hi, |_, composite| {
+        count += composite.iter().filter(|c| !**c).count() as u64;
+        true
+    });
+
+    count
+}
+
+// Append the primes in [lo, end) to `out`, in ascending order. `base` holds
+// the sieving primes found so far and is extended up to √(end − 1) when it
+// falls short, so that walking a range chunk by chunk finds each of them once.
+pub(crate) fn sieve_chunk(lo: u64, end: u64, base: &mut Vec<u64>, out: &mut VecDeque<u64>) {
+    if lo >= end {
+        return;
+    }
+
+    let limit = isqrt_u64(end - 1);
+    let known = base.last().map_or(0, |p| *p + 1);
+    if limit >= known {
+        base.extend(primes_in_range(known, limit + 1));
+    }
+
+    let mut composite = vec![false; (end - lo) as usize];
+    for p in base.iter().take_while(|p| **p <= limit) {
+        let first = match lo.div_ceil(*p).checked_mul(*p) {
+            Some(m) => m.max(p * p),
+            None => continue,
+        };
+        let mut m = first;
+        while m < end {
+            composite[(m - lo) as usize] = true;
+            m = match m.checked_add(*p) {
+                Some(m) => m,
+                None => break,
+            };
+        }
+    }
+
+    for (i, is_composite) in composite.iter().enumerate() {
+        let n = lo + i as u64;
+        if !is_composite && n >= 2 {
+            out.push_back(n);
+        }
+    }
+}
+
+// Sieve [lo, hi) segment by segment. `f(base, composite)` receives one segment
+// at a time, where `composite[i]` tells whether `base + i` is NOT prime, and
+// returns whether to continue with the next segment.
+pub(crate) fn for_each_segment<F>(lo: u64, hi: u64, f: F)
+where
+    F: FnMut(u64, &[bool]) -> bool,
+{
+    // NOTE: 每个分段的大小（字节），默认与 L2 cache 的大小相当，可由 `calibrate` 调整。
+    for_each_segment_with(lo, hi, crate::thresholds().segment_size, f)
+}
+
+// NOTE: `gpu` 特性的 `GpuSieve` 在 GPU 上做同样的分段标记，测试时以这里的结果为准。
+pub(crate) fn for_each_segment_with<F>(lo: u64, hi: u64, segment_size: u64, mut f: F)
+where
+    F: FnMut(u64, &[bool]) -> bool,
+{
+    if lo >= hi {
+        return;
+    }
+
+    // NOTE: 2^32 以下的分段只需要 2^16 以下的素数，直接使用全局的素数表，不再分配。
+    let limit = isqrt_u64(hi - 1);
+    let large_primes = match limit {
+        0..=0xffff => Vec::new(),
+        _ => primes_in_range(1 << 16, limit + 1),
+    };
+    let small_primes = crate::global().small_primes().iter()
+        .map(|p| *p as u64)
+        .take_while(|p| *p <= limit);
+    let base_primes = || small_primes.clone().chain(large_primes.iter().copied());
+
+    // Bucket sieve: a prime p >= segment_size hits each segment at most once,
+    // so instead of visiting every such prime in every segment, (next
+    // multiple, p) waits in the bucket of the segment that multiple falls in.
+    // The next multiple is never more than p / segment_size + 1 segments
+    // ahead, so a ring of buckets is enough.
+    let segment_of = |m: u64| ((m - lo) / segment_size) as usize;
+    let ring = (limit / segment_size) as usize + 2;
+    let mut buckets: Vec<Vec<(u64, u64)>> = vec![Vec::new(); ring];
+
+    // NOTE: p² 在 lo 之后的素数，第一个倍数 p² 可能远在环之外，等到它所在的分段再放进桶里。
+    let mut later: Vec<u64> = Vec::new();
+    for p in base_primes().filter(|p| *p >= segment_size) {
+        if p * p >= lo {
+            later.push(p);
+        } else if let Some(m) = lo.div_ceil(p).checked_mul(p).filter(|m| *m < hi) {
+            buckets[segment_of(m) % ring].push((m, p));
+        }
+    }
+    let mut later = later.into_iter().peekable();
+
+    let mut composite: Vec<bool> = Vec::with_capacity(segment_size as usize);
+    let mut start = lo;
+    let mut segment = 0usize;
+    while start < hi {
+        let end = hi.min(start.saturating_add(segment_size));
+
+        composite.clear();
+        composite.resize((end - start) as usize, false);
+
+        for n in start..end.min(2) {
+            composite[(n - start) as usize] = true;
+        }
+
+        for p in base_primes().take_while(|p| *p < segment_size) {
+            // NOTE: 从 max(p², ⌈start / p⌉·p) 开始划掉 p 的倍数；靠近 u64::MAX 时倍数可能溢出，
+            //       此时分段里已经没有 p 的倍数了。
+            let mut m = match start.div_ceil(p).checked_mul(p) {
+                Some(m) => m.max(p * p),
+                None => continue,
+            };
+            while m < end {
+                composite[(m - start) as usize] = true;
+                m = match m.checked_add(p) {
+                    Some(m) => m,
+                    None => break,
+                };
+            }
+        }
+
+        // NOTE: 取出当前分段的桶之后再放回去，保留它已经分配的空间。
+        let mut bucket = core::mem::take(&mut buckets[segment % ring]);
+        while let Some(p) = later.next_if(|p| p * p < end) {
+            bucket.push((p * p, p));
+        }
+        for (m, p) in bucket.drain(..) {
+            composite[(m - start) as usize] = true;
+            if let Some(next) = m.checked_add(p).filter(|m| *m < hi) {
+                buckets[segment_of(next) % ring].push((next, p));
+            }
+        }
+        buckets[segment % ring] = bucket;
+
+        if !f(start, &composite) {
+            break;
+        }
+
+        start = end;
+        segment += 1;
+    }
+}
+
+// lpf[n] is the least prime factor of n, for every n < limit; lpf[0] and
+// lpf[1] are 0.
+//
+// Linear sieve, O(limit) time: every composite is crossed out exactly once, by
+// its least prime factor.
+// https://cp-algorithms.com/algebra/prime-sieve-linear.html
+pub fn least_prime_factor_table(limit: u32) -> Vec<u32> {
+    let mut lpf = vec![0u32; limit as usize];
+    let mut primes: Vec<u32> = Vec::new();
+
+    for i in 2..limit {
+        if lpf[i as usize] == 0 {
+            lpf[i as usize] = i;
+            primes.push(i);
+        }
+
+        let li = lpf[i as usize];
+        for p in primes.iter().take_while(|p| **p <= li) {
+            match p.checked_mul(i) {
+                Some(m) if m < limit => lpf[m as usize] = *p,
+                _ => break,
+            }
+        }
+    }
+
+    lpf
+}
+
+// lpf[i] is the least prime factor of lo + i, for every lo <= n < hi; primes
+// are their own least prime factor and 0 and 1 map to 0.
+pub fn least_prime_factors_in_range(lo: u64, hi: u64) -> Vec<u64> {
+    if lo >= hi {
+        return Vec::new();
+    }
+
+    let mut lpf = Vec::new();
+    fill_least_prime_factors(lo, hi, &primes_up_to(isqrt_u64(hi - 1)), &mut lpf);
+
+    lpf
+}
+
+// lpf[i] = the least prime factor of start + i, given every prime <= √(end − 1).
+fn fill_least_prime_factors(start: u64, end: u64, base_primes: &[u64], lpf: &mut Vec<u64>) {
+    lpf.clear();
+    lpf.resize((end - start) as usize, 0);
+
+    // NOTE: 从小到大处理素数，只填写尚未确定的位置，即可得到最小素因子。
+    for p in base_primes.iter() {
+        let p = *p;
+        let mut m = match start.div_ceil(p).checked_mul(p) {
+            Some(m) => m.max(p * p),
+            None => continue,
+        };
+        while m < end {
+            let slot = &mut lpf[(m - start) as usize];
+            if *slot == 0 {
+                *slot = p;
+            }
+            m = match m.checked_add(p) {
+                Some(m) => m,
+                None => break,
+            };
+        }
+    }
+
+    for (i, slot) in lpf.iter_mut().enumerate() {
+        let n = start + i as u64;
+        if *slot == 0 && n >= 2 {
+            *slot = n;
+        }
+    }
+}
+
+// (n, least prime factor of n) for every composite lo <= n < hi, in order.
+//
+// The window is sieved one segment at a time, so that wide ranges need only
+// O(√hi) memory besides the segment.
+pub fn composites_in_range(lo: u64, hi: u64) -> Composites {
+    let base_primes = match hi.saturating_sub(1) {
+        0..=3 => Vec::new(),
+        max => primes_up_to(isqrt_u64(max)),
+    };
+
+    Composites { base_primes, next: lo, hi, start: lo, lpf: Vec::new(), pos: 0 }
+}
+
+const LPF_SEGMENT_SIZE: u64 = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub struct Composites {
+    base_primes: Vec<u64>,
+    // The next segment starts here.
+    next: u64,
+    hi: u64,
+    // The current segment: lpf[i] for start + i, read up to `pos`.
+    start: u64,
+    lpf: Vec<u64>,
+    pos: usize,
+}
+
+impl Iterator for Composites {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.pos < self.lpf.len() {
+                let n = self.start + self.pos as u64;
+                let p = self.lpf[self.pos];
+                self.pos += 1;
+
+                // NOTE: 0 和 1 的 lpf 为 0，素数的 lpf 等于它自身，都不是合数。
+                if p != 0 && p != n {
+                    return Some((n, p));
+                }
+            }
+
+            if self.next >= self.hi {
+                return None;
+            }
+
+            self.start = self.next;
+            self.next = self.hi.min(self.start.saturating_add(LPF_SEGMENT_SIZE));
+            self.pos = 0;
+            fill_least_prime_factors(self.start, self.next, &self.base_primes, &mut self.lpf);
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Composites { }
+
+// Prime factorization of n < lpf.len() as (prime, exponent) pairs in
+// ascending order, read off a `least_prime_factor_table`.
+pub fn factorize_with_table(n: u32, lpf: &[u32]) -> Vec<(u64, u32)> {
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    let mut n = n;
+    while n > 1 {
+        let p = lpf[n as usize];
+        match factors.last_mut() {
+            Some((q, e)) if *q == p as u64 => *e += 1,
+            _ => factors.push((p as u64, 1)),
+        }
+        n /= p;
+    }
+
+    factors
+}
+
+// (n, factorization of n) for every lo <= n < hi, in order, with each
+// factorization as (prime, exponent) pairs in ascending order; 0 and 1 have
+// no factors.
+//
+// The window is sieved one segment at a time: every base prime p <= √hi is
+// divided out of its multiples, and whatever remains above 1 is a prime.
+pub fn factorize_range(lo: u64, hi: u64) -> FactorizeRange {
+    let base_primes = match hi.saturating_sub(1) {
+        0..=3 => Vec::new(),
+        max => primes_up_to(isqrt_u64(max)),
+    };
+
+    FactorizeRange { base_primes, next: lo, hi, segment: Vec::new().into_iter() }
+}
+
+// NOTE: 每个数都要分配一个 Vec，所以分段比筛法本身小得多。
+const FACTOR_SEGMENT_SIZE: u64 = 1 << 14;
+
+#[derive(Debug, Clone)]
+pub struct FactorizeRange {
+    base_primes: Vec<u64>,
+    next: u64,
+    hi: u64,
+    segment: std::vec::IntoIter<(u64, Vec<(u64, u32)>)>,
+}
+
+impl FactorizeRange {
+    fn fill(&mut self) {
+        let start = self.next;
+        let end = self.hi.min(start.saturating_add(FACTOR_SEGMENT_SIZE));
+        self.next = end;
+
+        let mut rest: Vec<u64> = (start..end).collect();
+        let mut factors: Vec<Vec<(u64, u32)>> = vec![Vec::new(); rest.len()];
+
+        for p in self.base_primes.iter() {
+            let p = *p;
+            let mut m = match start.div_ceil(p).checked_mul(p) {
+                Some(m) => m,
+                None => continue,
+            };
+            while m < end {
+                let i = (m - start) as usize;
+                if m != 0 {
+                    let mut e = 0u32;
+                    while rest[i].is_multiple_of(p) {
+                        rest[i] /= p;
+                        e += 1;
+                    }
+                    factors[i].push((p, e));
+                }
+
+                m = match m.checked_add(p) {
+                    Some(m) => m,
+                    None => break,
+                };
+            }
+        }
+
+        for (i, r) in rest.iter().enumerate() {
+            if *r > 1 && start + i as u64 != 0 {
+                factors[i].push((*r, 1));
+            }
+        }
+
+        self.segment = (start..end).zip(factors).collect::<Vec<_>>().into_iter();
+    }
+}
+
+impl Iterator for FactorizeRange {
+    type Item = (u64, Vec<(u64, u32)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.segment.len() == 0 && self.next < self.hi {
+            self.fill();
+        }
+
+        self.segment.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.segment.len() as u64 + (self.hi - self.next);
+        (len as usize, Some(len as usize))
+    }
+}
+
+impl ExactSizeIterator for FactorizeRange { }
+
+// π(n), the number of primes <= n.
+//
+// Lucy_Hedgehog's algorithm, O(n ^ (3/4)) time and O(n ^ (1/2)) space.
+// https://projecteuler.net/thread=10;page=5#111677
+pub fn prime_pi(n: u64) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+
+    let r = isqrt_u64(n);
+
+    // small[v] = S(v)      for v <= r
+    // large[i] = S(n / i)  for i <= r
+    //
+    // S(v) starts as the number of integers in [2, v] and ends as π(v).
+    let mut small: Vec<u64> = (0..=r).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = (0..=r).map(|i| n.checked_div(i).map(|v| v - 1).unwrap_or(0)).collect();
+
+    for p in 2..=r {
+        if small[p as usize] == small[p as usize - 1] {
+            // p is not prime
+            continue;
+        }
+
+        let sp = small[p as usize - 1];
+        let p2 = p * p;
+
+        for i in 1..=r.min(n / p2) {
+            let v = n / i;
+            // NOTE: i·p <= r 时，v / p = n / (i·p) 存放在 large 里面。
+            let s = if i * p <= r { large[(i * p) as usize] } else { small[(v / p) as usize] };
+            large[i as usize] -= s - sp;
+        }
+
+        let mut v = r;
+        while v >= p2 {
+            small[v as usize] -= small[(v / p) as usize] - sp;
+            v -= 1;
+        }
+    }
+
+    large[1]
+}
+
+// The n-th prime, counting from nth_prime(1) = 2.
+pub fn nth_prime(n: u64) -> u64 {
+    nth_prime_nonzero(NonZeroU64::new(n).expect("the index of the first prime is 1"))
+}
+
+pub fn nth_prime_nonzero(n: NonZeroU64) -> u64 {
+    let n = n.get();
+
+    // Rosser's theorem and its refinements (Dusart, 1999)
+    // https://en.wikipedia.org/wiki/Prime_number_theorem#Approximations_for_the_nth_prime_number
+    //
+    //      n (ln n + ln ln n − 1) <= p(n) <= n (ln n + ln ln n),  n >= 6
+    if n < 6 {
+        return [2, 3, 5, 7, 11][n as usize - 1];
+    }
+
+    let x = n as f64;
+    let lower = (x * (x.ln() + x.ln().ln() - 1.0)) as u64;
+    let upper = (x * (x.ln() + x.ln().ln())).ceil() as u64;
+
+    let mut count = prime_pi(lower);
+    let mut ret = 0u64;
+    // NOTE: n 接近 π(2^64 − 1) 时上界会超过 u64::MAX（转换时截断为 u64::MAX），+1 不能溢出。
+    for_each_segment(lower + 1, upper.saturating_add(1), |base, composite| {
+        for (i, is_composite) in composite.iter().enumerate() {
+            if !is_composite {
+                count += 1;
+                if count == n {
+                    ret = base + i as u64;
+                    return false;
+                }
+            }
+        }
+
+        true
+    });
+
+    debug_assert!(ret != 0);
+    ret
+}
+
+
+// The least prime p >= n for every n in `ns`, in the same order.
+//
+// The inputs are answered in ascending order, so that the ones less than a
+// prime gap apart share their sieve segments: rounding every capacity of a
+// hash table up to a prime sieves each stretch once instead of searching from
+// every capacity. Every gap below 2 ^ 64 is known, so a stretch never needs to
+// reach further than the longest of them past its last input. Above 2 ^ 32 a
+// segment would need base primes of its own; there the candidates of a
+// stretch are tested one by one instead, still once for all its inputs.
+//
+// Panics if an n is above the largest prime below 2 ^ 64.
+pub fn next_primes(ns: &[u64]) -> Vec<u64> {
+    const LARGEST_PRIME: u64 = 18446744073709551557;
+    let max_gap = maximal_prime_gaps().last().map_or(0, |record| record.gap as u64);
+
+    let mut order: Vec<usize> = (0..ns.len()).collect();
+    order.sort_unstable_by_key(|i| ns[*i]);
+    if let Some(n) = order.last().map(|i| ns[*i]).filter(|n| *n > LARGEST_PRIME) {
+        panic!("no prime >= {} below 2 ^ 64", n);
+    }
+
+    let mut ret = vec![0u64; ns.len()];
+    let mut rest = &order[..];
+    while let Some(first) = rest.first() {
+        let lo = ns[*first];
+        let mut hi = lo.saturating_add(max_gap);
+        let mut len = 1;
+        while let Some(i) = rest.get(len).filter(|i| ns[**i] < hi) {
+            hi = ns[*i].saturating_add(max_gap);
+            len += 1;
+        }
+        let (stretch, tail) = rest.split_at(len);
+        rest = tail;
+
+        // Answer every pending input up to the prime p; whether any is left.
+        let mut pending = stretch.iter().peekable();
+        let mut answer = |p: u64| {
+            while let Some(i) = pending.next_if(|i| ns[**i] <= p) {
+                ret[*i] = p;
+            }
+            pending.peek().is_some()
+        };
+
+        if hi <= 1 << 32 {
+            for_each_segment(lo, hi, |base, composite| {
+                composite.iter()
+                    .enumerate()
+                    .filter(|(_, is_composite)| !**is_composite)
+                    .all(|(i, _)| answer(base + i as u64))
+            });
+        } else {
+            for p in (lo..hi).filter(|n| is_prime_u64(*n)) {
+                if !answer(p) {
+                    break;
+                }
+            }
+        }
+    }
+
+    ret
+}
+
+
+#[test]
+fn test_primes_in_range() {
+    assert_eq!(primes_up_to(1), Vec::<u64>::new());
+    assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert_eq!(primes_in_range(0, 30), primes_up_to(29));
+    assert_eq!(primes_in_range(90, 110), vec![97, 101, 103, 107, 109]);
+    assert_eq!(primes_in_range(7, 7), Vec::<u64>::new());
+
+    assert_eq!(primes_up_to(1_000_000).len(), 78498);
+    assert_eq!(primes_in_range(1_000_000_000, 1_000_000_100), vec![
+        1000000007, 1000000009, 1000000021, 1000000033, 1000000087, 1000000093, 1000000097,
+    ]);
+    assert_eq!(primes_in_range(1_000_000_000_000 - 100, 1_000_000_000_000), vec![
+        999999999937, 999999999959, 999999999961, 999999999989,
+    ]);
+}
+
+// The sieve needs every prime below 2 ^ 32 here, which takes minutes in a
+// debug build:
+//
+//      cargo test --release near_u64_max
+#[test]
+#[cfg_attr(debug_assertions, ignore)]
+fn test_primes_in_range_near_u64_max() {
+    use crate::miller_rabin::is_prime_u64;
+
+    // The multiples of the small primes run past u64::MAX in the last segment.
+    assert_eq!(primes_in_range(u64::MAX - 3, u64::MAX), Vec::<u64>::new());
+    let lo = u64::MAX - 1000;
+    let expected: Vec<u64> = (lo..u64::MAX).filter(|n| is_prime_u64(*n)).collect();
+    assert_eq!(primes_in_range(lo, u64::MAX), expected);
+    assert_eq!(expected.last(), Some(&18446744073709551557));
+}
+
+#[test]
+fn test_bucket_sieve() {
+    use crate::miller_rabin::is_prime_u64;
+
+    let collect = |lo: u64, hi: u64, segment_size: u64| {
+        let mut primes = Vec::new();
+        for_each_segment_with(lo, hi, segment_size, |base, composite| {
+            primes.extend(composite.iter().enumerate().filter(|(_, c)| !**c).map(|(i, _)| base + i as u64));
+            true
+        });
+        primes
+    };
+
+    // Segments much smaller than the base primes put nearly all of them in
+    // buckets, including segment sizes that are not powers of two.
+    let (lo, hi) = (1_000_000_000, 1_000_100_000);
+    let expected: Vec<u64> = (lo..hi).filter(|n| is_prime_u64(*n)).collect();
+    for segment_size in [1 << 7, 1000, 1 << 12, 1 << 18] {
+        assert_eq!(collect(lo, hi, segment_size), expected, "segment_size={}", segment_size);
+    }
+    assert_eq!(collect(0, 10_000, 1 << 5), primes_up_to(9_999));
+
+    // Near 10 ^ 15 the base primes go up to 3.2 · 10 ^ 7.
+    let (lo, hi) = (1_000_000_000_000_000 - 20_000, 1_000_000_000_000_000);
+    let expected: Vec<u64> = (lo..hi).filter(|n| is_prime_u64(*n)).collect();
+    assert_eq!(expected.last(), Some(&999999999999989));
+    assert_eq!(collect(lo, hi, 1 << 12), expected);
+    assert_eq!(primes_in_range(lo, hi), expected);
+}
+
+#[test]
+fn test_primes_iter() {
+    let mut iter = primes(0, 100);
+    assert_eq!(iter.len(), 25);
+    assert_eq!(iter.next(), Some((1, 2)));
+    assert_eq!(iter.next_back(), Some((25, 97)));
+    assert_eq!(iter.next_back(), Some((24, 89)));
+    assert_eq!(iter.len(), 22);
+    assert_eq!(iter.next(), Some((2, 3)));
+
+    // Meeting in the middle
+    let rest: Vec<(u64, u64)> = iter.by_ref().collect();
+    assert_eq!(rest.len(), 21);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    // The largest prime below 10 ^ 9
+    assert_eq!(primes(1_000_000_000 - 100, 1_000_000_000).next_back(), Some((2, 999999937)));
+
+    let all: Vec<(u64, u64)> = primes(0, 10_000).rev().collect();
+    assert_eq!(all.len(), 1229);
+    assert!(all.iter().all(|(k, p)| nth_prime(*k) == *p));
+
+    assert_eq!(primes(90, 110).map(|(k, _)| k).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(primes(24, 28).len(), 0);
+    assert_eq!(primes(28, 24).next_back(), None);
+
+    // Only the chunks walked are sieved, from either end.
+    let mut iter = primes(0, 1 << 40);
+    assert_eq!(iter.next(), Some((1, 2)));
+    assert_eq!(iter.lo, iter.chunk);
+    assert_eq!(iter.hi, 1 << 40);
+    assert!(iter.count.get().is_none());
+
+    let lo = 1u64 << 44;
+    let mut iter = primes(lo, lo + 10_000_000);
+    let expected = primes_in_range(lo + 10_000_000 - 2000, lo + 10_000_000);
+    let last = iter.next_back().unwrap();
+    assert_eq!(last.1, *expected.last().unwrap());
+    assert_eq!(iter.lo, lo);
+    assert_eq!(last.0 as usize, iter.len() + 1);
+    assert_eq!(last.0, count_primes_in_range(lo, lo + 10_000_000));
+
+    // Both ends meeting within one chunk and across several
+    for chunk in [1, 7, 64, 1 << 16] {
+        let mut iter = primes(0, 2000);
+        iter.chunk = chunk;
+        let mut seen = Vec::new();
+        while let Some(a) = iter.next() {
+            seen.push(a);
+            seen.extend(iter.next_back());
+        }
+        seen.sort_unstable();
+        let expected: Vec<(u64, u64)> = primes_up_to(1999).into_iter().enumerate().map(|(i, p)| (i as u64 + 1, p)).collect();
+        assert_eq!(seen, expected, "chunk={}", chunk);
+    }
+}
+
+#[test]
+fn test_count_primes_in_range() {
+    assert_eq!(count_primes_in_range(0, 0), 0);
+    assert_eq!(count_primes_in_range(0, 100), 25);
+    assert_eq!(count_primes_in_range(0, 1_000_000), 78498);
+    // Narrow ranges are sieved, wide ones go through prime_pi.
+    for (lo, hi) in [(1_000_000u64, 1_001_000), (1 << 40, (1 << 40) + 1000), (999_000, 2_000_000)] {
+        assert_eq!(count_primes_in_range(lo, hi), primes_in_range(lo, hi).len() as u64, "[{}, {})", lo, hi);
+    }
+}
+
+#[test]
+fn test_least_prime_factor_table() {
+    use crate::factorize_u64;
+
+    let lpf = least_prime_factor_table(100_000);
+    assert_eq!(lpf.len(), 100_000);
+    assert_eq!(lpf[..10], [0, 0, 2, 3, 2, 5, 2, 7, 2, 3]);
+
+    for n in 2..100_000u32 {
+        let factors = factorize_with_table(n, &lpf);
+        assert_eq!(factors, factorize_u64(n as u64), "N={}", n);
+        assert_eq!(lpf[n as usize] as u64, factors[0].0, "N={}", n);
+    }
+    assert_eq!(factorize_with_table(1, &lpf), vec![]);
+
+    let range = least_prime_factors_in_range(0, 100_000);
+    assert!(range.iter().zip(lpf.iter()).all(|(a, b)| *a == *b as u64));
+
+    // 10 ^ 12 − 11 and 10 ^ 12 − 1 = 3 ^ 3 · 7 · 11 · 13 · 37 · 101 · 9901
+    let range = least_prime_factors_in_range(1_000_000_000_000 - 11, 1_000_000_000_000);
+    assert_eq!(range[0], 999999999989);
+    assert_eq!(range[10], 3);
+    for (i, p) in range.iter().enumerate() {
+        let n = 1_000_000_000_000 - 11 + i as u64;
+        assert_eq!(*p, factorize_u64(n)[0].0, "N={}", n);
+    }
+
+    assert_eq!(least_prime_factors_in_range(7, 7), Vec::<u64>::new());
+}
+
+#[test]
+fn test_composites_in_range() {
+    let lpf = least_prime_factor_table(300_000);
+    let expected: Vec<(u64, u64)> = (0..300_000u64)
+        .filter(|n| *n >= 4 && lpf[*n as usize] as u64 != *n)
+        .map(|n| (n, lpf[n as usize] as u64))
+        .collect();
+    assert_eq!(composites_in_range(0, 300_000).collect::<Vec<_>>(), expected);
+    assert_eq!(composites_in_range(0, 10).collect::<Vec<_>>(), [(4, 2), (6, 2), (8, 2), (9, 3)]);
+
+    // Crosses a segment boundary.
+    let lo = LPF_SEGMENT_SIZE - 100;
+    let window: Vec<_> = composites_in_range(lo, lo + 200).collect();
+    assert_eq!(window, expected.iter().copied().filter(|(n, _)| (lo..lo + 200).contains(n)).collect::<Vec<_>>());
+
+    // 10 ^ 12 − 11 is prime and 10 ^ 12 − 1 = 3 ^ 3 · 7 · 11 · 13 · 37 · 101 · 9901
+    let lo = 1_000_000_000_000 - 11;
+    let top: Vec<_> = composites_in_range(lo, lo + 11).collect();
+    assert_eq!(top.len(), 10);
+    assert_eq!(top.last(), Some(&(1_000_000_000_000 - 1, 3)));
+    let expected = least_prime_factors_in_range(lo, lo + 11);
+    assert!(top.iter().all(|(n, p)| expected[(n - lo) as usize] == *p));
+
+    assert_eq!(composites_in_range(24, 29).count(), 5);
+    assert_eq!(composites_in_range(7, 7).count(), 0);
+}
+
+#[test]
+fn test_factorize_range() {
+    use crate::factorize_u64;
+
+    let mut count = 0u64;
+    for (n, factors) in factorize_range(0, 100_000) {
+        assert_eq!(n, count);
+        if n > 0 {
+            assert_eq!(factors, factorize_u64(n), "N={}", n);
+        } else {
+            assert!(factors.is_empty());
+        }
+        count += 1;
+    }
+    assert_eq!(count, 100_000);
+
+    let lo = 1_000_000_000_000 - 1000;
+    let all: Vec<_> = factorize_range(lo, 1_000_000_000_000).collect();
+    assert_eq!(all.len(), 1000);
+    for (n, factors) in all {
+        assert_eq!(factors, factorize_u64(n), "N={}", n);
+    }
+
+    assert_eq!(factorize_range(5, 5).count(), 0);
+    assert_eq!(factorize_range(10, 50_000).len(), 49_990);
+}
+
+#[test]
+fn test_prime_pi() {
+    let expected = [0u64, 4, 25, 168, 1229, 9592, 78498, 664579, 5761455, 50847534];
+    for (k, pi) in expected.iter().enumerate() {
+        assert_eq!(prime_pi(10u64.pow(k as u32)), *pi, "10^{}", k);
+    }
+
+    for n in 0..2000u64 {
+        assert_eq!(prime_pi(n), primes_up_to(n).len() as u64, "N={}", n);
+    }
+}
+
+#[test]
+fn test_nth_prime() {
+    let primes = primes_up_to(200_000);
+    for (i, p) in primes.iter().enumerate().take(2000) {
+        assert_eq!(nth_prime(i as u64 + 1), *p);
+    }
+
+    assert_eq!(nth_prime(10_000), 104729);
+    assert_eq!(nth_prime(1_000_000), 15485863);
+    assert_eq!(nth_prime(10_000_000), 179424673);
+
+    assert_eq!(nth_prime_nonzero(NonZeroU64::new(1).unwrap()), 2);
+    assert_eq!(nth_prime_nonzero(NonZeroU64::new(10_000).unwrap()), 104729);
+}
+
+
+#[test]
+fn test_next_primes() {
+    let next_prime = |n: u64| (n..).find(|n| is_prime_u64(*n)).unwrap();
+
+    assert_eq!(next_primes(&[]), Vec::<u64>::new());
+    assert_eq!(next_primes(&[0, 1, 2, 3, 4, 14, 13, 14, 90]), vec![2, 2, 2, 3, 5, 17, 13, 17, 97]);
+
+    // Hash table capacities, and stretches on both sides of 2 ^ 32
+    let mut ns: Vec<u64> = (0..64).map(|k| 1u64 << k).collect();
+    ns.extend((0..3000).map(|i| 1_000_000 - 3 * i));
+    ns.extend(((1 << 32) - 2000..(1 << 32) + 2000).step_by(7));
+    ns.extend([18361375334787046697 + 1, 18446744073709551557, 18446744073709551557 - 58]);
+    let expected: Vec<u64> = ns.iter().map(|n| next_prime(*n)).collect();
+    assert_eq!(next_primes(&ns), expected);
+
+    // The maximal gap below 2 ^ 64 is bridged.
+    assert_eq!(next_primes(&[18361375334787046698]), vec![18361375334787046697 + 1550]);
+}
+
+#[test]
+#[should_panic]
+fn test_next_primes_overflow() {
+    next_primes(&[5, u64::MAX]);
+}
+
+
+#[bench]
+fn bench_prime_pi(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(1_000_000_000u64);
+        prime_pi(n)
+    })
+}
+
+#[bench]
+fn bench_factorize_range(b: &mut test::Bencher) {
+    b.iter(|| {
+        let lo = test::black_box(1_000_000_000_000u64);
+        factorize_range(lo, lo + 10_000).count()
+    })
+}
+
+#[bench]
+fn bench_next_primes(b: &mut test::Bencher) {
+    let ns: Vec<u64> = (0..10_000).map(|i| 1_000_000_000 + 97 * i).collect();
+    b.iter(|| {
+        next_primes(test::black_box(&ns))
+    })
+}
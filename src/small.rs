@@ -0,0 +1,146 @@
+// Exact primality of the small unsigned integer types
+//
+// Every answer is `ZeroOrOne`, `Prime` or `Composite`, never a probable one,
+// and all three functions are `const fn`, so they also work in constant
+// expressions:
+//
+//      const _: () = assert!(matches!(is_prime_u32(65537), Primality::Prime));
+use crate::Primality;
+use crate::table_query_u16;
+use crate::miller_rabin::decompose_n_minus_one;
+
+
+pub const fn is_prime_u8(n: u8) -> Primality {
+    table_query_u16(n as u16)
+}
+
+pub const fn is_prime_u16(n: u16) -> Primality {
+    table_query_u16(n)
+}
+
+// The table below 2 ^ 16 and a single strong test above it.
+//
+// Forišek and Jančina, "Fast Primality Testing for Integers That Fit into a
+// Machine Word" (2015): n is hashed into one of 256 buckets, and the base of
+// every bucket is chosen so that no composite n < 2 ^ 32 in the bucket and
+// free of 2, 3, 5 and 7 is a strong pseudoprime to it. One modular
+// exponentiation decides, where the bases 2, 7 and 61 take up to three.
+pub const fn is_prime_u32(n: u32) -> Primality {
+    if n <= u16::MAX as u32 {
+        return table_query_u16(n as u16);
+    }
+
+    if n.is_multiple_of(2) || n.is_multiple_of(3) || n.is_multiple_of(5) || n.is_multiple_of(7) {
+        return Primality::Composite;
+    }
+
+    let a = HASHED_BASES[hash_u32(n)] as u64;
+    Primality::from_proof(is_strong_probable_prime_u32(n as u64, a))
+}
+
+// NOTE: 该表取自 Forišek 与 Jančina 的论文，已对 2^32 以内的所有整数验证。
+static HASHED_BASES: [u16; 256] = [
+    15591, 2018, 166, 7429, 8064, 16045, 10503, 4399, 1949, 1295, 2776, 3620, 560, 3128, 5212, 2657,
+    2300, 2021, 4652, 1471, 9336, 4018, 2398, 20462, 10277, 8028, 2213, 6219, 620, 3763, 4852, 5012,
+    3185, 1333, 6227, 5298, 1074, 2391, 5113, 7061, 803, 1269, 3875, 422, 751, 580, 4729, 10239,
+    746, 2951, 556, 2206, 3778, 481, 1522, 3476, 481, 2487, 3266, 5633, 488, 3373, 6441, 3344,
+    17, 15105, 1490, 4154, 2036, 1882, 1813, 467, 3307, 14042, 6371, 658, 1005, 903, 737, 1887,
+    7447, 1888, 2848, 1784, 7559, 3400, 951, 13969, 4304, 177, 41, 19875, 3110, 13221, 8726, 571,
+    7043, 6943, 1199, 352, 6435, 165, 1169, 3315, 978, 233, 3003, 2562, 2994, 10587, 10030, 2377,
+    1902, 5354, 4447, 1555, 263, 27027, 2283, 305, 669, 1912, 601, 6186, 429, 1930, 14873, 1784,
+    1661, 524, 3577, 236, 2360, 6146, 2850, 55637, 1753, 4178, 8466, 222, 2579, 2743, 2031, 2226,
+    2276, 374, 2132, 813, 23788, 1610, 4422, 5159, 1725, 3597, 3366, 14336, 579, 165, 1375, 10018,
+    12616, 9816, 1371, 536, 1867, 10864, 857, 2206, 5788, 434, 8085, 17618, 727, 3639, 1595, 4944,
+    2129, 2029, 8195, 8344, 6232, 9183, 8126, 1870, 3296, 7455, 8947, 25017, 541, 19115, 368, 566,
+    5674, 411, 522, 1027, 8215, 2050, 6544, 10049, 614, 774, 2333, 3007, 35201, 4706, 1152, 1785,
+    1028, 1540, 3743, 493, 4474, 2521, 26845, 8354, 864, 18915, 5465, 2447, 42, 4511, 1660, 166,
+    1249, 6259, 2553, 304, 272, 7286, 73, 6554, 899, 2816, 5197, 13330, 7054, 2818, 3199, 811,
+    922, 350, 7514, 4452, 3449, 2663, 4708, 418, 1621, 1171, 3471, 88, 11345, 412, 1559, 194,
+];
+
+const fn hash_u32(n: u32) -> usize {
+    let mut h = n as u64;
+    h = ((h >> 16) ^ h).wrapping_mul(0x45d9f3b);
+    h = ((h >> 16) ^ h).wrapping_mul(0x45d9f3b);
+    (((h >> 16) ^ h) & 255) as usize
+}
+
+// One strong test of the odd n < 2 ^ 32 to the base a.
+const fn is_strong_probable_prime_u32(n: u64, a: u64) -> bool {
+    let (d, r) = decompose_n_minus_one(n);
+
+    let mut x = modpow_u32(a, d, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+
+    let mut j = 1;
+    while j < r {
+        x = x * x % n;
+        if x == n - 1 {
+            return true;
+        }
+        j += 1;
+    }
+
+    false
+}
+
+// base ^ exponent % modulus, for modulus < 2 ^ 32 so that no product overflows.
+const fn modpow_u32(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let mut base = base % modulus;
+    let mut exponent = exponent;
+    let mut result = 1;
+    while exponent > 0 {
+        if !exponent.is_multiple_of(2) {
+            result = result * base % modulus;
+        }
+        exponent >>= 1;
+        base = base * base % modulus;
+    }
+
+    result
+}
+
+
+#[test]
+fn test_is_prime_small() {
+    use crate::miller_rabin::is_prime_u64;
+
+    const M31: Primality = is_prime_u32(2147483647);
+    const _: () = assert!(matches!(is_prime_u32(65537), Primality::Prime));
+    assert_eq!(M31, Primality::Prime);
+
+    for n in 0..=u8::MAX {
+        assert_eq!(is_prime_u8(n), table_query_u16(n as u16));
+    }
+
+    assert_eq!(is_prime_u16(0), Primality::ZeroOrOne);
+    assert_eq!(is_prime_u16(65521), Primality::Prime);
+
+    for n in (0..=u32::MAX).step_by(9973).chain(u32::MAX - 1000..=u32::MAX) {
+        assert_eq!(is_prime_u32(n).is_prime(), is_prime_u64(n as u64), "N={}", n);
+    }
+
+    // Strong pseudoprimes to base 2 and to the bases 2, 3, 5 (and 7).
+    for n in [2047u32, 3277, 4033, 25326001, 3215031751] {
+        assert_eq!(is_prime_u32(n), Primality::Composite, "N={}", n);
+    }
+
+    // Every bucket, primes and composites alike
+    let mut buckets = [0u32; 256];
+    for n in (1u32 << 31..).step_by(2).take(100_000) {
+        buckets[hash_u32(n)] += 1;
+        assert_eq!(is_prime_u32(n).is_prime(), is_prime_u64(n as u64), "N={}", n);
+    }
+    assert!(buckets.iter().all(|count| *count > 0));
+}
+
+
+#[bench]
+fn bench_is_prime_u32(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(4_294_967_291u32);
+        is_prime_u32(n)
+    })
+}
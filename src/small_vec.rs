@@ -0,0 +1,170 @@
+// A vector that keeps up to N elements inline and spills to the heap beyond
+//
+// The short-lived lists of the hot paths, such as the prime factors of p − 1
+// in a Pratt certificate, almost never hold more than a handful of elements,
+// so keeping them on the stack saves an allocation per call. Unused inline
+// slots hold `T::default()`, which keeps the implementation free of `unsafe`.
+//
+// The Miller–Rabin witness sets never needed one: the u64 sets are static
+// tables and the BigUint bases are drawn one at a time.
+use core::fmt;
+use core::iter::FromIterator;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
+
+
+#[derive(Clone)]
+pub(crate) enum SmallVec<T: Default, const N: usize> {
+    Inline([T; N], usize),
+    Heap(Vec<T>),
+}
+
+impl<T: Default, const N: usize> SmallVec<T, N> {
+    pub(crate) fn new() -> Self {
+        SmallVec::Inline(core::array::from_fn(|_| T::default()), 0)
+    }
+
+    pub(crate) fn push(&mut self, value: T) {
+        match self {
+            SmallVec::Inline(buf, len) if *len < N => {
+                buf[*len] = value;
+                *len += 1;
+            },
+            SmallVec::Inline(buf, len) => {
+                // NOTE: 超出 N 之后一次性搬到堆上，之后和 Vec 的行为相同。
+                let mut heap = Vec::with_capacity(2 * N.max(1));
+                heap.extend(buf[..*len].iter_mut().map(core::mem::take));
+                heap.push(value);
+                *self = SmallVec::Heap(heap);
+            },
+            SmallVec::Heap(heap) => heap.push(value),
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        match self {
+            SmallVec::Inline(_, 0) => None,
+            SmallVec::Inline(buf, len) => {
+                *len -= 1;
+                Some(core::mem::take(&mut buf[*len]))
+            },
+            SmallVec::Heap(heap) => heap.pop(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn spilled(&self) -> bool {
+        matches!(self, SmallVec::Heap(_))
+    }
+}
+
+impl<T: Default, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Default, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            SmallVec::Inline(buf, len) => &buf[..*len],
+            SmallVec::Heap(heap) => heap,
+        }
+    }
+}
+
+impl<T: Default, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            SmallVec::Inline(buf, len) => &mut buf[..*len],
+            SmallVec::Heap(heap) => heap,
+        }
+    }
+}
+
+impl<T: Default, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Default, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<'a, T: Default, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// NOTE: 比较和哈希只看元素，与元素是否已经搬到堆上无关。
+impl<T: Default + PartialEq, const N: usize> PartialEq for SmallVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Default + Eq, const N: usize> Eq for SmallVec<T, N> {}
+
+impl<T: Default + Hash, const N: usize> Hash for SmallVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: Default + fmt::Debug, const N: usize> fmt::Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+
+#[test]
+fn test_small_vec() {
+    let mut v: SmallVec<u64, 4> = SmallVec::new();
+    assert!(v.is_empty() && !v.spilled());
+
+    v.extend(1..=4);
+    assert_eq!(*v, [1, 2, 3, 4]);
+    assert!(!v.spilled());
+
+    v.push(5);
+    assert_eq!(*v, [1, 2, 3, 4, 5]);
+    assert!(v.spilled());
+
+    let inline: SmallVec<u64, 8> = (1..=5).collect();
+    assert_eq!(*inline, *v);
+
+    assert_eq!(v.pop(), Some(5));
+    let other: SmallVec<u64, 4> = (1..=4).collect();
+    assert_eq!(v, other);
+    assert_eq!(format!("{:?}", v), "[1, 2, 3, 4]");
+
+    v[0] = 7;
+    v.sort_unstable();
+    assert_eq!(*v, [2, 3, 4, 7]);
+
+    // Elements need not be `Copy`.
+    let mut strings: SmallVec<String, 1> = SmallVec::new();
+    strings.push("a".to_string());
+    strings.push("b".to_string());
+    assert!(strings.spilled());
+    assert_eq!(strings.pop().as_deref(), Some("b"));
+
+    let mut empty: SmallVec<u64, 0> = SmallVec::new();
+    assert_eq!(empty.pop(), None);
+    empty.push(1);
+    assert_eq!(*empty, [1]);
+}
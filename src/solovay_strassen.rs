@@ -1,89 +1,127 @@
 // Solovay–Strassen primality test
 // https://en.wikipedia.org/wiki/Solovay%E2%80%93Strassen_primality_test
 use crate::Primality;
+use crate::AsBigUint;
+use crate::PrimalityTester;
+use crate::table::small_primality;
 use crate::miller_rabin::modpow;
+use crate::montgomery::Montgomery;
 
 use rand::Rng;
-use num_integer::Integer;
-use num_bigint::{BigUint, BigInt, RandBigInt};
+use num_bigint::{BigUint, BigInt, Sign};
 
 
-// a | n or (a/n)
+// Jacobi symbol (a | n), for odd n
+// https://en.wikipedia.org/wiki/Jacobi_symbol#Calculating_the_Jacobi_symbol
 // 
-// Legendre symbol
-// https://en.wikipedia.org/wiki/Legendre_symbol
-fn legendre_symbol_biguint(
-    a: &BigUint, 
-    n: &BigUint, 
-    zero: &BigUint, 
-    one: &BigUint, 
-    three: &BigUint, 
-    five: &BigUint
-) -> i8 {
-    let mut a = a.clone();
+// The factors of 2 are removed from a with a single shift, and n mod 8 and
+// n mod 4 are read from the lowest digit, so that the only BigUint allocation
+// per step is the reduction a mod n.
+pub fn jacobi_symbol_biguint(a: &BigUint, n: &BigUint) -> i8 {
+    assert!(n.bit(0));
+
+    let mut a = a % n;
     let mut n = n.clone();
 
     let mut res = 1i8;
 
-    while &a != zero {
-        while &(&a % 2u8) == zero {
-            a /= 2u8;
-            let r = &n % 8u8;
-            if &r == three || &r == five {
-                res = -res;
-            }
+    // NOTE: a = 0 时 trailing_zeros 返回 None，循环结束。
+    while let Some(s) = a.trailing_zeros() {
+        a >>= s as usize;
+        // (2 | n) = −1 for n ≡ 3, 5 (mod 8)
+        let r = low_u64(&n) & 7;
+        if s % 2 == 1 && (r == 3 || r == 5) {
+            res = -res;
         }
 
         core::mem::swap(&mut a, &mut n);
 
-        let r1 = &a % 4u8;
-        let r2 = &n % 4u8;
-        if &r1 == three && &r2 == three {
+        if low_u64(&a) & 3 == 3 && low_u64(&n) & 3 == 3 {
             res = -res;
         }
-        a = &a % &n;
+        a %= &n;
     }
 
-    if &n == one {
+    if n == BigUint::from(1u8) {
         res
     } else {
         0i8
     } 
 }
 
+// n mod 2 ^ 64
+fn low_u64(n: &BigUint) -> u64 {
+    n.iter_u64_digits().next().unwrap_or(0)
+}
+
+
+// (a | p) for every a in `values`, by Euler's criterion
+//
+//      (a | p) ≡ a ^ ((p − 1) / 2) (mod p)
+//
+// `p` must be prime. All the exponentiations share one Montgomery context, so
+// that the set-up cost is paid once per p rather than once per a.
+pub fn quadratic_residues_mod_p(p: u64, values: &[u64]) -> Vec<i8> {
+    assert!(p >= 2);
+
+    if p == 2 {
+        return values.iter().map(|a| (a % 2) as i8).collect();
+    }
+
+    let ctx = Montgomery::new(p);
+    let exp = (p - 1) / 2;
+    let one = ctx.one();
+
+    values.iter()
+        .map(|a| {
+            let a = ctx.encode(*a);
+            if a == 0 {
+                return 0;
+            }
+
+            if ctx.pow(a, exp) == one { 1 } else { -1 }
+        })
+        .collect()
+}
 
 // Algorithm and running time
 // https://en.wikipedia.org/wiki/Solovay%E2%80%93Strassen_primality_test#Algorithm_and_running_time
-pub fn solovay_strassen_primality_test_biguint(n: &BigUint, k: usize) -> Primality {
-    // Input #1: n > 3, an odd integer to be tested for primality
+pub fn solovay_strassen_primality_test_biguint(n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+    // Input #1: n, any integer; n < 2 ^ 32 (2 and 3 included) and even n are answered exactly
     // Input #2: k, the number of rounds of testing to perform
+    crate::global().tester().solovay_strassen_biguint(n, k)
+}
+
+// Random rounds, after trial division by the primes below `tester`'s bound.
+pub(crate) fn solovay_strassen_biguint_with(n: &BigUint, k: usize, tester: &PrimalityTester) -> Primality {
     debug_assert!(k > 0);
 
-    let zero  = BigUint::from(0u8);
     let one   = BigUint::from(1u8);
-    let two   = BigUint::from(2u8);
     let three = BigUint::from(3u8);
-    let five  = BigUint::from(5u8);
     
     if cfg!(debug_assertions) {
-        debug_assert!(n > &three && n % 2u8 != zero);
+        debug_assert!(n > &three && n.bit(0));
     }
     
 
+    if tester.has_small_factor(n) {
+        return Primality::Composite;
+    }
+
     let n_minus_one = n - 1u8;
     // (n - 1) / 2
-    let exp = &n_minus_one / 2u8;
+    let exp = &n_minus_one >> 1usize;
     // NOTE: 此处，因为 num 库没用提供内部方法，所以开销较大。
     // let n1 = BigInt::from(n.clone());
     
-    let mut rng = rand::thread_rng();
+    let mut witnesses = crate::global().witnesses(n);
 
     // repeat k times
     for _ in 0..k {
         // choose a randomly in the range [2, n − 1]
-        let a: BigUint = rng.gen_biguint_range(&two, &n_minus_one);
+        let a: BigUint = witnesses.next(&n_minus_one);
 
-        let x: i8 = legendre_symbol_biguint(&a, n, &zero, &one, &three, &five);
+        let x: i8 = jacobi_symbol_biguint(&a, n);
 
         match x {
             -1 => {
@@ -138,7 +176,7 @@ pub fn solovay_strassen_primality_test_biguint(n: &BigUint, k: usize) -> Primali
     }
 
     // probably prime
-    return Primality::ProbablyPrime;
+    return Primality::PROBABLE;
 }
 
 
@@ -162,8 +200,11 @@ fn legendre_symbol_u64(a: u64, n: u64) -> i8 {
     // 
     //      x² = a (mod p)
     // 
-    debug_assert!(n > 1 && a >= 2 && a < n - 1);
+    jacobi_u64(a % n, n)
+}
 
+// (a | n) for a < n, n odd
+fn jacobi_u64(a: u64, n: u64) -> i8 {
     let mut a = a as u128;
     let mut n = n as u128;
     
@@ -196,6 +237,37 @@ fn legendre_symbol_u64(a: u64, n: u64) -> i8 {
     }
 }
 
+// (a | n), the Jacobi symbol, for any integer a and odd n > 0
+// https://en.wikipedia.org/wiki/Jacobi_symbol
+//
+// Negative a is reduced with the first supplementary law
+//
+//      (−1 | n) = (−1) ^ ((n − 1) / 2)
+//
+// so that callers can pass Lucas parameters such as D = 5, −7, 9, −11, ...
+// directly.
+pub fn jacobi_symbol_i64(a: i64, n: u64) -> i8 {
+    assert!(n % 2 == 1);
+
+    let s = jacobi_u64(a.unsigned_abs() % n, n);
+    if a < 0 && n % 4 == 3 {
+        -s
+    } else {
+        s
+    }
+}
+
+pub fn jacobi_symbol_bigint(a: &BigInt, n: &BigUint) -> i8 {
+    assert!(n.bit(0));
+
+    let s = jacobi_symbol_biguint(a.magnitude(), n);
+    if a.sign() == Sign::Minus && low_u64(n) & 3 == 3 {
+        -s
+    } else {
+        s
+    }
+}
+
 
 
 pub fn solovay_strassen_primality_test_u64(n: u64, k: usize) -> Primality {
@@ -205,64 +277,105 @@ pub fn solovay_strassen_primality_test_u64(n: u64, k: usize) -> Primality {
     // 
     // NOTE: 因为 随机数 a 的取值范围为 [2, n - 1], 并且勒让德符号中的分母只限奇质数，
     //       所以这里送入判断的数字 需 > 3, 以及 为奇数。
-    debug_assert!(n > 3 && n % 2 != 0);
-    debug_assert!(k > 0);
-    match n {
-        0 | 1 => Primality::ZeroOrOne,
-        2 | 3 | 5 => Primality::ProbablyPrime,
-        // 2 | 3 => Primality::Prime,
-        _ => {
-            let mut rng = rand::thread_rng();
-
-            let n_minus_one = n - 1;
-            let exp = n_minus_one / 2;
-
-            for _ in 0..k {
-                // choose a randomly in the range [2, n − 1]
-                let a: u64 = rng.gen_range(2, n_minus_one);
-
-                // x ← ( a \ n ), Legendre symbol
-                let x: i8 = legendre_symbol_u64(a, n);
-
-                // if x = 0 or a ^ ((n - 1) / 2) != x (mod n) then return composite
-                match x {
-                    -1 => {
-                        // NOTE: 当 n > 1 时，-1 ModFloor n = n - 1
-                        debug_assert!(n > 1);
-
-                        // x (mod n)
-                        let r1 = n_minus_one;
-                        // a ^ ((n - 1) / 2) % n
-                        let r2 = modpow(a, exp, n);
-                        if r1 != r2 {
-                            // composite
-                            return Primality::Composite;
-                        }
-                    },
-                    0 => {
-                        return Primality::Composite;
-                    },
-                    1 => {
-                        // NOTE: 当 n > 1 时，+1 ModFloor n = 1
-                        debug_assert!(n > 1);
-
-                        // x (mod n)
-                        let r1 = 1;
-                        // a ^ ((n - 1) / 2) % n
-                        let r2 = modpow(a, exp, n);
-                        if r1 != r2 {
-                            // composite
-                            return Primality::Composite;
-                        }
-                    },
-                    _ => unreachable!(),
+    //       小于 2^32 的数字直接给出确定的结果。
+    if let Some(ret) = small_primality(n) {
+        return ret;
+    }
+
+    if n.is_multiple_of(2) {
+        return Primality::Composite;
+    }
+
+    // NOTE: 一轮都不做就没有任何证据，只能回答 Unknown。
+    if k == 0 {
+        return Primality::Unknown;
+    }
+
+    let mut rng = rand::thread_rng();
+
+    let n_minus_one = n - 1;
+    let exp = n_minus_one / 2;
+
+    for _ in 0..k {
+        // choose a randomly in the range [2, n − 1]
+        let a: u64 = rng.gen_range(2, n_minus_one);
+
+        // x ← ( a \ n ), Legendre symbol
+        let x: i8 = legendre_symbol_u64(a, n);
+
+        // if x = 0 or a ^ ((n - 1) / 2) != x (mod n) then return composite
+        match x {
+            -1 => {
+                // NOTE: 当 n > 1 时，-1 ModFloor n = n - 1
+
+                // x (mod n)
+                let r1 = n_minus_one;
+                // a ^ ((n - 1) / 2) % n
+                let r2 = modpow(a, exp, n);
+                if r1 != r2 {
+                    // composite
+                    return Primality::Composite;
                 }
-            }
+            },
+            0 => {
+                return Primality::Composite;
+            },
+            1 => {
+                // NOTE: 当 n > 1 时，+1 ModFloor n = 1
 
-            // probably prime
-            return Primality::ProbablyPrime;
+                // x (mod n)
+                let r1 = 1;
+                // a ^ ((n - 1) / 2) % n
+                let r2 = modpow(a, exp, n);
+                if r1 != r2 {
+                    // composite
+                    return Primality::Composite;
+                }
+            },
+            _ => unreachable!(),
         }
     }
+
+    // probably prime
+    return Primality::PROBABLE;
+}
+
+
+// One Solovay–Strassen round to the base a: whether n is an Euler–Jacobi
+// probable prime to base a,
+//
+//      a ^ ((n − 1) / 2) ≡ (a | n) ≢ 0 (mod n).
+//
+// Every odd prime n passes for every a it does not divide; a composite that
+// passes is an Euler–Jacobi pseudoprime to base a. Even n and n < 3 never
+// pass, and nothing is answered from a table, so that small pseudoprimes stay
+// visible.
+pub fn is_euler_jacobi_prp(n: &(impl AsBigUint + ?Sized), a: &(impl AsBigUint + ?Sized)) -> bool {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    if n.bits() < 2 || !n.bit(0) {
+        return false;
+    }
+
+    if n.bits() <= 64 {
+        let n = n.to_u64_digits()[0];
+        let a = (a.as_biguint().as_ref() % n).to_u64_digits().first().copied().unwrap_or(0);
+        return match jacobi_u64(a, n) {
+            0 => false,
+            1 => modpow(a, (n - 1) / 2, n) == 1,
+            _ => modpow(a, (n - 1) / 2, n) == n - 1,
+        };
+    }
+
+    let a = a.as_biguint().as_ref() % n;
+    let n_minus_one: BigUint = n - 1u8;
+    let x = a.modpow(&(&n_minus_one >> 1usize), n);
+    match jacobi_symbol_biguint(&a, n) {
+        0 => false,
+        1 => x == BigUint::from(1u8),
+        _ => x == n_minus_one,
+    }
 }
 
 
@@ -294,24 +407,28 @@ fn test_solovay_strassen_primality_test_biguint() {
     for n in sets.iter() {
         let n = BigUint::from(*n);
         let ret = solovay_strassen_primality_test_biguint(&n, 3);
-        assert!(ret == Primality::ProbablyPrime || ret == Primality::Prime);
+        assert!(ret == Primality::PROBABLE || ret == Primality::Prime);
     }
 
     use crate::table_query_u16;
+    use crate::{recommended_rounds, SecurityLevel};
 
+    let k = recommended_rounds(16, SecurityLevel::Bits128) as usize;
     for n in 5..u16::MAX {
         if n % 2 != 0 {
-            let b: bool = table_query_u16(n).into();
+            let b = table_query_u16(n).is_prime();
             let n = BigUint::from(n);
-            let a: bool = solovay_strassen_primality_test_biguint(&n, 4).into();
+            let a = solovay_strassen_primality_test_biguint(&n, k).is_accepted();
             assert_eq!(a, b, "N={} a={} b={}", n, a, b);
         }
     }
     
-    assert!(solovay_strassen_primality_test_biguint(&BigUint::from(7u8), 3) == Primality::ProbablyPrime);
-    assert!(solovay_strassen_primality_test_biguint(&BigUint::from(9u8), 3) == Primality::Composite);
-    assert!(solovay_strassen_primality_test_biguint(&BigUint::from(11u8), 3) == Primality::ProbablyPrime);
-    assert!(solovay_strassen_primality_test_biguint(&BigUint::from(15u8), 3) == Primality::Composite);
+    // Inputs below 2 ^ 32 are answered exactly.
+    assert!(solovay_strassen_primality_test_biguint(&7u8, 3) == Primality::Prime);
+    assert!(solovay_strassen_primality_test_biguint(&9u16, 3) == Primality::Composite);
+    assert!(solovay_strassen_primality_test_biguint(&11u64, 3) == Primality::Prime);
+    assert!(solovay_strassen_primality_test_biguint(&15u128, 3) == Primality::Composite);
+    assert!(solovay_strassen_primality_test_biguint(&4294967311u64, 3) == Primality::PROBABLE);
 }
 
 #[test]
@@ -324,20 +441,115 @@ fn test_solovay_strassen_primality_test_u64() {
 
     for n in sets.iter() {
         let ret = solovay_strassen_primality_test_u64(*n, 8);
-        assert!(ret == Primality::ProbablyPrime || ret == Primality::Prime, "N={:?} RET={:?}", n, ret);
+        assert!(ret == Primality::PROBABLE || ret == Primality::Prime, "N={:?} RET={:?}", n, ret);
     }
 
     use crate::table_query_u16;
+    use crate::{recommended_rounds, SecurityLevel};
+
+    let k = recommended_rounds(16, SecurityLevel::Bits128) as usize;
     for n in 5..u16::MAX {
         if n % 2 != 0 {
-            let b: bool = table_query_u16(n).into();
-            let a: bool = solovay_strassen_primality_test_u64(n as u64, 5).into();
+            let b = table_query_u16(n).is_prime();
+            let a = solovay_strassen_primality_test_u64(n as u64, k).is_accepted();
             assert_eq!(a, b, "N={} a={} b={}", n, a, b);
         }
     }
     
-    assert!(solovay_strassen_primality_test_u64(7, 3) == Primality::ProbablyPrime);
+    assert!(solovay_strassen_primality_test_u64(7, 3) == Primality::Prime);
     assert!(solovay_strassen_primality_test_u64(9, 3) == Primality::Composite);
-    assert!(solovay_strassen_primality_test_u64(11, 3) == Primality::ProbablyPrime);
+    assert!(solovay_strassen_primality_test_u64(11, 3) == Primality::Prime);
     assert!(solovay_strassen_primality_test_u64(15, 3) == Primality::Composite);
+    assert!(solovay_strassen_primality_test_u64(4294967311, 3) == Primality::PROBABLE);
+    assert!(solovay_strassen_primality_test_u64(4294967311 * 65537, 8) == Primality::Composite);
+}
+
+#[test]
+fn test_is_euler_jacobi_prp() {
+    use crate::miller_rabin::is_prime_u64;
+
+    // OEIS A047713, the Euler–Jacobi pseudoprimes to base 2
+    let pseudoprimes: Vec<u64> = (3..70_000u64)
+        .filter(|n| !is_prime_u64(*n) && is_euler_jacobi_prp(n, &2u8))
+        .collect();
+    assert_eq!(pseudoprimes, [
+        561, 1105, 1729, 1905, 2047, 2465, 3277, 4033, 4681, 6601, 8321, 8481, 10585, 12801, 15841,
+        16705, 18705, 25761, 29341, 30121, 33153, 34945, 41041, 42799, 46657, 49141, 52633, 62745,
+        65281,
+    ]);
+    assert!((3..70_000u64).filter(|n| is_prime_u64(*n)).all(|n| is_euler_jacobi_prp(&n, &2u8)));
+
+    // 1905 is not one to base 3, and no base divisible by n counts.
+    assert!(!is_euler_jacobi_prp(&1905u16, &3u8));
+    assert!(!is_euler_jacobi_prp(&97u8, &0u8) && !is_euler_jacobi_prp(&97u8, &194u8));
+    assert!(is_euler_jacobi_prp(&97u8, &195u8));
+    assert!(!is_euler_jacobi_prp(&0u8, &2u8) && !is_euler_jacobi_prp(&1u8, &2u8) && !is_euler_jacobi_prp(&2u8, &3u8));
+
+    // Beyond 64 bits
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    let m89 = (BigUint::from(1u8) << 89usize) - 1u8;
+    for a in [2u8, 3, 5, 7, 10] {
+        assert!(is_euler_jacobi_prp(&m127, &a), "a={}", a);
+        assert!(!is_euler_jacobi_prp(&(&m127 * &m89), &a), "a={}", a);
+    }
+    assert!(!is_euler_jacobi_prp(&m127, &m127));
+    assert!(is_euler_jacobi_prp(&18446744073709551557u64, &u64::MAX));
+}
+
+
+#[test]
+fn test_jacobi_symbol() {
+    // (a | 15) for a = 0, 1, ..., 14
+    let expected = [0i8, 1, 1, 0, 1, 0, 0, -1, 1, 0, 0, -1, 0, -1, -1];
+    for (a, s) in expected.iter().enumerate() {
+        assert_eq!(jacobi_symbol_i64(a as i64, 15), *s, "a={}", a);
+        assert_eq!(jacobi_symbol_i64(a as i64 + 15, 15), *s, "a={}", a);
+        assert_eq!(jacobi_symbol_bigint(&BigInt::from(a), &BigUint::from(15u8)), *s, "a={}", a);
+    }
+
+    // (−1 | n) = −1 exactly when n ≡ 3 (mod 4)
+    for n in (1..200u64).step_by(2) {
+        let s = if n % 4 == 3 { -1 } else { 1 };
+        assert_eq!(jacobi_symbol_i64(-1, n), s, "N={}", n);
+        assert_eq!(jacobi_symbol_bigint(&BigInt::from(-1), &BigUint::from(n)), s, "N={}", n);
+    }
+
+    // (−a | n) = (−1 | n)·(a | n)
+    for n in (3..300u64).step_by(2) {
+        for a in 0..50i64 {
+            let s = jacobi_symbol_i64(-1, n) * jacobi_symbol_i64(a, n);
+            assert_eq!(jacobi_symbol_i64(-a, n), s, "a={} N={}", -a, n);
+            assert_eq!(jacobi_symbol_bigint(&BigInt::from(-a), &BigUint::from(n)), s, "a={} N={}", -a, n);
+        }
+    }
+
+    // Multi-digit moduli agree with the u64 version, with a reduced first.
+    for n in [u64::MAX, (1 << 61) - 1, 18446744073709551557] {
+        for a in [0u64, 1, 2, 1 << 40, 1 << 63, 12345678987654321] {
+            let s = jacobi_symbol_i64(a as i64 & i64::MAX, n);
+            let a = BigUint::from(a & i64::MAX as u64);
+            assert_eq!(jacobi_symbol_biguint(&a, &BigUint::from(n)), s, "a={} N={}", a, n);
+            assert_eq!(jacobi_symbol_biguint(&(a.clone() + n), &BigUint::from(n)), s, "a={} N={}", a, n);
+        }
+    }
+    let n = (BigUint::from(1u8) << 127usize) - 1u8;
+    // 2 ^ 127 ≡ 1, so 2 is a square mod 2 ^ 127 − 1.
+    assert_eq!(jacobi_symbol_biguint(&BigUint::from(2u8), &n), 1);
+    assert_eq!(jacobi_symbol_biguint(&(&n - 1u8), &n), -1);
+    assert_eq!(jacobi_symbol_biguint(&(&n * 3u8), &n), 0);
+
+    assert_eq!(jacobi_symbol_i64(-7, 1), 1);
+    assert_eq!(jacobi_symbol_i64(i64::MIN, u64::MAX), jacobi_symbol_i64(-1, u64::MAX) * jacobi_symbol_i64(1 << 62, u64::MAX) * jacobi_symbol_i64(2, u64::MAX));
+}
+
+#[test]
+fn test_quadratic_residues_mod_p() {
+    for p in [3u64, 5, 7, 11, 13, 65521, 1_000_000_007, 18446744073709551557] {
+        let values: Vec<u64> = (0..200u64).chain([p - 1, p, p + 1, u64::MAX]).collect();
+        let expected: Vec<i8> = values.iter().map(|a| jacobi_symbol_bigint(&BigInt::from(*a), &BigUint::from(p))).collect();
+        assert_eq!(quadratic_residues_mod_p(p, &values), expected, "P={}", p);
+    }
+
+    assert_eq!(quadratic_residues_mod_p(2, &[0, 1, 2, 3]), vec![0, 1, 0, 1]);
+    assert_eq!(quadratic_residues_mod_p(7, &[]), vec![]);
 }
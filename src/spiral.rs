@@ -0,0 +1,73 @@
+// Ulam spiral
+// https://en.wikipedia.org/wiki/Ulam_spiral
+//
+// 1 sits at the origin and the numbers wind counterclockwise around it, x to
+// the right and y up:
+//
+//      17 16 15 14 13
+//      18  5  4  3 12
+//      19  6  1  2 11
+//      20  7  8  9 10
+//      21 22 23 24 25
+//
+// Ring k holds (2k − 1) ^ 2 < n <= (2k + 1) ^ 2 and ends at (k, −k) with the
+// odd square (2k + 1) ^ 2; the primes crowd onto diagonals, which a plot of
+// the coordinates below shows.
+use crate::isqrt_u64;
+
+
+// The position of n >= 1 on the spiral.
+pub fn ulam_spiral_coords(n: u64) -> (i64, i64) {
+    assert!(n > 0, "the spiral starts at 1");
+
+    // NOTE: 最小的 k 使 (2k + 1)^2 >= n；n 接近 u64::MAX 时平方会溢出 u64，这里用 i128。
+    let k = (isqrt_u64(n - 1) as i128 + 1) / 2;
+    let side = 2 * k;
+    let end = (side + 1) * (side + 1);
+    let n = n as i128;
+
+    let (x, y) = match end - n {
+        // bottom edge, right to left from (k, −k) backwards
+        d if d < side => (k - d, -k),
+        // left edge
+        d if d < 2 * side => (-k, -k + (d - side)),
+        // top edge
+        d if d < 3 * side => (-k + (d - 2 * side), k),
+        // right edge
+        d => (k, k - (d - 3 * side)),
+    };
+
+    (x as i64, y as i64)
+}
+
+
+#[test]
+fn test_ulam_spiral_coords() {
+    use std::collections::HashSet;
+
+    let grid = [
+        [17, 16, 15, 14, 13],
+        [18, 5, 4, 3, 12],
+        [19, 6, 1, 2, 11],
+        [20, 7, 8, 9, 10],
+        [21, 22, 23, 24, 25],
+    ];
+    for (row, values) in grid.iter().enumerate() {
+        for (col, n) in values.iter().enumerate() {
+            assert_eq!(ulam_spiral_coords(*n), (col as i64 - 2, 2 - row as i64), "N={}", n);
+        }
+    }
+
+    // The first (2k + 1) ^ 2 numbers fill the square of side 2k + 1 exactly,
+    // and consecutive numbers are neighbours.
+    let side = 101u64;
+    let coords: Vec<(i64, i64)> = (1..=side * side).map(ulam_spiral_coords).collect();
+    assert!(coords.iter().all(|(x, y)| x.abs() <= 50 && y.abs() <= 50));
+    assert_eq!(coords.iter().collect::<HashSet<_>>().len(), coords.len());
+    assert!(coords.windows(2).all(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() == 1));
+
+    // The largest ring below 2 ^ 64 still fits.
+    let k = (isqrt_u64(u64::MAX) as i64 + 1) / 2;
+    let (x, y) = ulam_spiral_coords(u64::MAX);
+    assert!(x.abs() <= k && y.abs() <= k);
+}
@@ -0,0 +1,210 @@
+// Base-2 pseudoprimes below 2 ^ 64
+// http://www.cecm.sfu.ca/Pseudoprimes/index-2-to-64.html
+//
+// J. Feitsma and W. Galway enumerated every base-2 Fermat pseudoprime below
+// 2 ^ 64 (118,968,378 numbers, 31,894,014 of them strong) and published them
+// as a plain text list, one decimal number per line in ascending order. Every
+// composite that gets past the base 2 round of a Miller–Rabin ladder is on it,
+// so running a ladder over the whole list checks it for every n below 2 ^ 64:
+//
+//      let file = BufReader::new(File::open("psps-below-2-to-64.txt")?);
+//      let audit = audit_witness_ladder(MILLER_RABIN_WITNESSES_U64, read_spsp_list(file).map(Result::unwrap));
+//      assert!(audit.is_ok() && audit.strong == 31_894_014, "{:?}", audit);
+//
+// A list of only the strong ones does as well. Lines may carry more fields
+// after the number (a factorization, say), which are ignored, as are blank
+// lines and lines starting with `#`.
+use crate::Primality;
+use crate::miller_rabin::{is_prime_u64, is_strong_prp_u64, miller_rabin_u64_with, modpow};
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+
+// The line of a pseudoprime list that is not a number, counting from 1.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ParseSpspError {
+    pub line: usize,
+}
+
+impl fmt::Display for ParseSpspError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pseudoprime list at line {}", self.line)
+    }
+}
+
+impl std::error::Error for ParseSpspError {}
+
+// The number on a line, `None` for blank and comment lines.
+fn parse_line(s: &str) -> Result<Option<u64>, ()> {
+    match s.split_whitespace().next() {
+        None => Ok(None),
+        Some(field) if field.starts_with('#') => Ok(None),
+        Some(field) => field.parse::<u64>().map(Some).map_err(|_| ()),
+    }
+}
+
+pub fn parse_spsp_list(s: &str) -> Result<Vec<u64>, ParseSpspError> {
+    let mut ret = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        if let Some(n) = parse_line(line).map_err(|_| ParseSpspError { line: i + 1 })? {
+            ret.push(n);
+        }
+    }
+
+    Ok(ret)
+}
+
+// The numbers of a list read line by line, for lists too large to hold in
+// memory. A malformed line comes back as an `InvalidData` error wrapping a
+// `ParseSpspError`.
+pub fn read_spsp_list<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<u64>> {
+    reader.lines()
+        .enumerate()
+        .filter_map(|(i, line)| match line.map(|line| parse_line(&line)) {
+            Ok(Ok(n)) => n.map(Ok),
+            Ok(Err(_)) => Some(Err(io::Error::new(io::ErrorKind::InvalidData, ParseSpspError { line: i + 1 }))),
+            Err(e) => Some(Err(e)),
+        })
+}
+
+// What `audit_witness_ladder` found.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct LadderAudit {
+    // Entries looked at
+    pub checked: u64,
+    // Entries that are strong pseudoprimes to base 2, the ones the ladder
+    // has to reject with its other bases
+    pub strong: u64,
+    // Strong pseudoprimes the ladder does not call composite: the ones it gets
+    // wrong, or leaves `Unknown` because they are above its last bound.
+    pub missed: Vec<u64>,
+    // Entries that are not base-2 pseudoprimes at all (primes, even numbers,
+    // composites failing the Fermat test); the list is corrupt.
+    pub invalid: Vec<u64>,
+    // Entries not above the one before them; the list is corrupt or truncated
+    // and pasted together.
+    pub unordered: Vec<u64>,
+}
+
+impl LadderAudit {
+    pub fn is_ok(&self) -> bool {
+        self.missed.is_empty() && self.invalid.is_empty() && self.unordered.is_empty()
+    }
+}
+
+// Run the (largest n, bases) `ladder`, as in `MILLER_RABIN_WITNESSES_U64`,
+// over a list of base-2 (strong) pseudoprimes and collect every entry it
+// fails to reject.
+//
+// NOTE: 这里只能说明列表中的数都被判为合数。列表是否完整，需要对照发布的总数。
+pub fn audit_witness_ladder<I>(ladder: &[(u64, &[u64])], spsps: I) -> LadderAudit
+where
+    I: IntoIterator<Item = u64>,
+{
+    let mut audit = LadderAudit::default();
+    let mut previous = 0u64;
+    for n in spsps {
+        audit.checked += 1;
+
+        if n <= previous {
+            audit.unordered.push(n);
+        }
+        previous = previous.max(n);
+
+        if n < 3 || n.is_multiple_of(2) || is_prime_u64(n) || modpow(2, n - 1, n) != 1 {
+            audit.invalid.push(n);
+            continue;
+        }
+        if !is_strong_prp_u64(n, 2) {
+            continue;
+        }
+        audit.strong += 1;
+
+        if miller_rabin_u64_with(n, ladder) != Primality::Composite {
+            audit.missed.push(n);
+        }
+    }
+
+    audit
+}
+
+
+#[cfg(test)]
+fn spsps_below(limit: u64) -> Vec<u64> {
+    (3..limit)
+        .step_by(2)
+        .filter(|n| !is_prime_u64(*n) && is_strong_prp_u64(*n, 2))
+        .collect()
+}
+
+#[test]
+fn test_parse_spsp_list() {
+    const LIST: &str = "\
+# base-2 strong pseudoprimes
+2047
+3277 29 113
+
+4033\t37 109
+";
+    assert_eq!(parse_spsp_list(LIST), Ok(vec![2047, 3277, 4033]));
+    assert_eq!(parse_spsp_list(""), Ok(vec![]));
+    assert_eq!(parse_spsp_list("2047\n3277\n-1\n"), Err(ParseSpspError { line: 3 }));
+    assert_eq!(parse_spsp_list("18446744073709551616"), Err(ParseSpspError { line: 1 }));
+
+    let read: Vec<u64> = read_spsp_list(LIST.as_bytes()).map(Result::unwrap).collect();
+    assert_eq!(read, [2047, 3277, 4033]);
+
+    let err = read_spsp_list("2047\nx\n".as_bytes()).find_map(Result::err).unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "invalid pseudoprime list at line 2");
+}
+
+#[test]
+fn test_audit_witness_ladder() {
+    use crate::MILLER_RABIN_WITNESSES_U64;
+
+    // OEIS A001262
+    let spsps = spsps_below(1_000_000);
+    assert_eq!(spsps[..12], [2047, 3277, 4033, 4681, 8321, 15841, 29341, 42799, 49141, 52633, 65281, 74665]);
+
+    // The exhaustive list starts the same way; beyond 2 ^ 32, the least
+    // strong pseudoprimes to the first 5, 6, 7 and 9 prime bases. 341 is a
+    // Fermat pseudoprime that is not strong.
+    let mut list = spsps.clone();
+    list.extend([341, 2_152_302_898_747, 3_474_749_660_383, 341_550_071_728_321, 3_825_123_056_546_413_051]);
+    list.sort_unstable();
+    let audit = audit_witness_ladder(MILLER_RABIN_WITNESSES_U64, list.iter().copied());
+    assert!(audit.is_ok(), "{:?}", audit);
+    assert_eq!((audit.checked, audit.strong), (list.len() as u64, list.len() as u64 - 1));
+
+    // Base 2 alone misses every one of them. Primes, even numbers and
+    // composites failing the Fermat test are flagged, and so is disorder.
+    let audit = audit_witness_ladder(&[(u64::MAX, &[2])], spsps.iter().copied());
+    assert_eq!(audit.missed, spsps);
+    let audit = audit_witness_ladder(MILLER_RABIN_WITNESSES_U64, [2047, 2053, 3000, 3277, 3277, 9].iter().copied());
+    assert_eq!(audit.invalid, [2053, 3000, 9]);
+    assert_eq!(audit.unordered, [3277, 9]);
+    assert!(audit.missed.is_empty() && !audit.is_ok());
+
+    // A ladder that stops short leaves the rest `Unknown`, hence missed.
+    let audit = audit_witness_ladder(&[(100_000, &[2, 3])], spsps.iter().copied());
+    assert_eq!(audit.missed, spsps.iter().copied().filter(|n| *n > 100_000).collect::<Vec<u64>>());
+}
+
+// The full Feitsma–Galway list, from the path in `PRIME_SPSP_LIST`:
+//
+//      PRIME_SPSP_LIST=psps-below-2-to-64.txt cargo test --release spsp_list_below_2_to_64 -- --ignored
+#[test]
+#[ignore]
+fn test_audit_spsp_list_below_2_to_64() {
+    use crate::MILLER_RABIN_WITNESSES_U64;
+
+    let path = std::env::var("PRIME_SPSP_LIST").expect("set PRIME_SPSP_LIST to the path of the list");
+    let file = std::fs::File::open(&path).expect("cannot open the list");
+    let spsps = read_spsp_list(io::BufReader::new(file)).map(|n| n.expect("cannot read the list"));
+
+    let audit = audit_witness_ladder(MILLER_RABIN_WITNESSES_U64, spsps);
+    assert!(audit.is_ok(), "{:?}", audit);
+    assert_eq!(audit.strong, 31_894_014);
+}
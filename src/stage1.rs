@@ -0,0 +1,69 @@
+// Stage-1 exponents for Pollard's p − 1 and ECM
+// https://en.wikipedia.org/wiki/Pollard%27s_p_%E2%88%92_1_algorithm
+//
+// Stage 1 raises a group element to
+//
+//      E = lcm(1, 2, ..., B1) = ∏ q ^ ⌊log_q B1⌋, over the primes q <= B1,
+//
+// so that it reaches the identity modulo every prime factor p of n whose
+// group order (p − 1, or the order of the curve) is B1-smooth.
+use crate::primes_up_to;
+use crate::wilson::product;
+
+use num_bigint::BigUint;
+
+
+// q ^ e for every prime q <= b1, with e the largest exponent such that
+// q ^ e <= b1, in ascending order of q.
+pub fn primes_powers_up_to(b1: u64) -> Vec<u64> {
+    primes_up_to(b1).into_iter()
+        .map(|q| {
+            let mut power = q;
+            // NOTE: power <= b1 / q 等价于 power * q <= b1，且不会溢出。
+            while power <= b1 / q {
+                power *= q;
+            }
+            power
+        })
+        .collect()
+}
+
+// lcm(1, 2, ..., b1), the product of `primes_powers_up_to(b1)`, multiplied
+// with a product tree. 1 for b1 < 2.
+pub fn lcm_of_primes_powers_up_to(b1: u64) -> BigUint {
+    let powers: Vec<BigUint> = primes_powers_up_to(b1).into_iter()
+        .map(BigUint::from)
+        .collect();
+
+    product(&powers)
+}
+
+
+#[test]
+fn test_lcm_of_primes_powers_up_to() {
+    use num_integer::Integer;
+
+    assert_eq!(primes_powers_up_to(1), Vec::<u64>::new());
+    assert_eq!(primes_powers_up_to(10), vec![8, 9, 5, 7]);
+    assert_eq!(primes_powers_up_to(32), vec![32, 27, 25, 7, 11, 13, 17, 19, 23, 29, 31]);
+
+    assert_eq!(lcm_of_primes_powers_up_to(0), BigUint::from(1u8));
+    assert_eq!(lcm_of_primes_powers_up_to(10), BigUint::from(2520u32));
+
+    let mut expected = BigUint::from(1u8);
+    for i in 1..=300u32 {
+        expected = expected.lcm(&BigUint::from(i));
+        assert_eq!(lcm_of_primes_powers_up_to(i as u64), expected, "B1={}", i);
+    }
+
+    // The largest prime power stays below the bound.
+    let powers = primes_powers_up_to(u64::MAX >> 40);
+    assert_eq!(powers[0], 1 << 23);
+}
+
+#[bench]
+fn bench_lcm_of_primes_powers_up_to(b: &mut test::Bencher) {
+    b.iter(|| {
+        lcm_of_primes_powers_up_to(100_000)
+    })
+}
@@ -0,0 +1,121 @@
+// Statistics on the Miller–Rabin witnesses
+//
+// With the `witness-stats` feature every strong test on a BigUint, whatever
+// its bases (random, fixed or derandomized), is recorded in a process-wide
+// sink: which base exposed the composite, and after how many rounds. Users
+// tuning a custom base order can run their workload and read the result back
+// with `witness_stats`. Without the feature nothing is recorded and none of
+// this is compiled.
+//
+// NOTE: u64 的确定性测试是 const fn，不经过这里。
+use num_bigint::BigUint;
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+
+static STATS: Mutex<WitnessStats> = Mutex::new(WitnessStats::new());
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct WitnessStats {
+    // Strong tests run, i.e. calls with at least one round.
+    pub calls: u64,
+    // Tests in which every base passed.
+    pub passed: u64,
+    // rounds_to_composite[i] counts the tests decided by their (i + 1)-th base.
+    pub rounds_to_composite: Vec<u64>,
+    // How often each base below 2 ^ 64 exposed a composite.
+    pub by_witness: BTreeMap<u64, u64>,
+    // Composites exposed by a base of 64 bits or more.
+    pub large_witnesses: u64,
+}
+
+impl WitnessStats {
+    pub const fn new() -> Self {
+        WitnessStats {
+            calls: 0,
+            passed: 0,
+            rounds_to_composite: Vec::new(),
+            by_witness: BTreeMap::new(),
+            large_witnesses: 0,
+        }
+    }
+
+    // The `count` bases that exposed the most composites, most effective first.
+    pub fn top_witnesses(&self, count: usize) -> Vec<(u64, u64)> {
+        let mut witnesses: Vec<(u64, u64)> = self.by_witness.iter().map(|(a, hits)| (*a, *hits)).collect();
+        witnesses.sort_by(|x, y| y.1.cmp(&x.1).then(x.0.cmp(&y.0)));
+        witnesses.truncate(count);
+        witnesses
+    }
+}
+
+fn lock() -> MutexGuard<'static, WitnessStats> {
+    // NOTE: 统计数据只是计数，某个线程 panic 之后继续使用也没有问题。
+    STATS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// A snapshot of everything recorded since the start or the last reset.
+pub fn witness_stats() -> WitnessStats {
+    lock().clone()
+}
+
+pub fn reset_witness_stats() {
+    *lock() = WitnessStats::new();
+}
+
+// The outcome of one strong test: the round index and base of the witness, or
+// `None` when every base passed.
+pub(crate) fn record(witness: Option<(usize, &BigUint)>) {
+    let mut stats = lock();
+    stats.calls += 1;
+
+    match witness {
+        None => stats.passed += 1,
+        Some((round, a)) => {
+            if stats.rounds_to_composite.len() <= round {
+                stats.rounds_to_composite.resize(round + 1, 0);
+            }
+            stats.rounds_to_composite[round] += 1;
+
+            if a.bits() <= 64 {
+                let a = a.to_u64_digits().first().copied().unwrap_or(0);
+                *stats.by_witness.entry(a).or_insert(0) += 1;
+            } else {
+                stats.large_witnesses += 1;
+            }
+        },
+    }
+}
+
+
+#[test]
+fn test_witness_stats() {
+    use crate::miller_rabin::find_witness_biguint;
+
+    let bases = [2u64, 3, 5, 7];
+    let base = |i: usize| BigUint::from(bases[i]);
+
+    // NOTE: 其它测试会并发地调用强测试，这里只检查下界。
+    reset_witness_stats();
+
+    // 2047 = 23·89 is a strong pseudoprime to base 2 and is exposed by 3.
+    for _ in 0..10 {
+        assert_eq!(find_witness_biguint(&BigUint::from(2047u32), 4, base), Some(BigUint::from(3u8)));
+    }
+    // 1_000_000_007 is prime.
+    assert_eq!(find_witness_biguint(&BigUint::from(1_000_000_007u32), 4, base), None);
+
+    let stats = witness_stats();
+    assert!(stats.calls >= 11);
+    assert!(stats.passed >= 1);
+    assert!(stats.rounds_to_composite.len() >= 2 && stats.rounds_to_composite[1] >= 10);
+    assert!(stats.by_witness.get(&3).copied().unwrap_or(0) >= 10);
+    assert!(stats.top_witnesses(usize::MAX).iter().any(|(a, _)| *a == 3));
+
+    let mut stats = WitnessStats::new();
+    stats.by_witness.insert(2, 5);
+    stats.by_witness.insert(3, 7);
+    stats.by_witness.insert(5, 7);
+    assert_eq!(stats.top_witnesses(2), vec![(3, 7), (5, 7)]);
+}
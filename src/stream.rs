@@ -0,0 +1,273 @@
+// Primes as asynchronous streams
+//
+// `primes` sieves [lo, hi) one chunk at a time, only when the consumer has
+// drained the previous one, so a slow consumer never makes the sieve run
+// ahead of it; `gen_primes` draws one random prime per item. Both implement
+// `futures_core::Stream`, for the combinators of the futures ecosystem, and
+// the nightly `core::async_iter::AsyncIterator`, and offer an inherent `next`
+// for use without combinators.
+//
+// Sieving a chunk and generating a prime are CPU-bound. After each, the
+// stream wakes its task and returns `Pending` once, so that other tasks on the
+// same executor get a turn between chunks instead of waiting for the whole
+// range.
+use crate::sieve::sieve_chunk;
+use crate::gen_prime;
+use crate::Bits;
+
+use rand::Rng;
+use num_bigint::BigUint;
+
+use core::pin::Pin;
+use core::future::Future;
+use core::async_iter::AsyncIterator;
+use core::task::{Context, Poll};
+
+use std::collections::VecDeque;
+
+
+// NOTE: 默认每次筛 2^16 个数，远小于一个分段，消费者不需要时不会多筛。
+const DEFAULT_CHUNK: u64 = 1 << 16;
+
+// Every prime p with lo <= p < hi, in ascending order.
+pub fn primes(lo: u64, hi: u64) -> PrimeStream {
+    PrimeStream { next: lo, hi, chunk: DEFAULT_CHUNK, base: Vec::new(), buffer: VecDeque::new(), yielded: false }
+}
+
+// An endless stream of random primes of exactly `bits` bits.
+pub fn gen_primes<R: Rng>(bits: Bits, rng: R) -> GenPrimes<R> {
+    GenPrimes { bits, rng, ready: None }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrimeStream {
+    // The start of the part of the range not sieved yet.
+    next: u64,
+    hi: u64,
+    chunk: u64,
+    // The sieving primes found so far, extended as the chunks move up.
+    base: Vec<u64>,
+    buffer: VecDeque<u64>,
+    // Whether the task was already yielded for the chunk in `buffer`.
+    yielded: bool,
+}
+
+impl PrimeStream {
+    // Numbers sieved per chunk.
+    pub fn with_chunk_size(mut self, chunk: u64) -> Self {
+        assert!(chunk > 0, "chunk size must be positive");
+
+        self.chunk = chunk;
+        self
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> impl Future<Output = Option<u64>> + '_ {
+        core::future::poll_fn(move |cx| Pin::new(&mut *self).poll_next(cx))
+    }
+}
+
+impl AsyncIterator for PrimeStream {
+    type Item = u64;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u64>> {
+        let this = &mut *self;
+
+        // NOTE: 一个块里也可能一个素数都没有，所以要循环到找到素数或范围结束为止。
+        while this.buffer.is_empty() {
+            if this.next >= this.hi {
+                return Poll::Ready(None);
+            }
+
+            let end = this.next.saturating_add(this.chunk).min(this.hi);
+            sieve_chunk(this.next, end, &mut this.base, &mut this.buffer);
+            this.next = end;
+            this.yielded = false;
+        }
+
+        if !this.yielded {
+            this.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        Poll::Ready(this.buffer.pop_front())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = (self.hi - self.next.min(self.hi)) as usize;
+        (self.buffer.len(), Some(self.buffer.len().saturating_add(rest)))
+    }
+}
+
+// NOTE: 两者的 `poll_next` 签名相同，直接转发。
+impl futures_core::Stream for PrimeStream {
+    type Item = u64;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u64>> {
+        AsyncIterator::poll_next(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        AsyncIterator::size_hint(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GenPrimes<R> {
+    bits: Bits,
+    rng: R,
+    // A prime generated by the previous poll, returned after yielding once.
+    ready: Option<BigUint>,
+}
+
+impl<R: Rng + Unpin> GenPrimes<R> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> impl Future<Output = Option<BigUint>> + '_ {
+        core::future::poll_fn(move |cx| Pin::new(&mut *self).poll_next(cx))
+    }
+}
+
+impl<R: Rng + Unpin> AsyncIterator for GenPrimes<R> {
+    type Item = BigUint;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<BigUint>> {
+        let this = &mut *self;
+
+        match this.ready.take() {
+            Some(p) => Poll::Ready(Some(p)),
+            None => {
+                this.ready = Some(gen_prime(this.bits, &mut this.rng));
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            },
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R: Rng + Unpin> futures_core::Stream for GenPrimes<R> {
+    type Item = BigUint;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<BigUint>> {
+        AsyncIterator::poll_next(self, cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        AsyncIterator::size_hint(self)
+    }
+}
+
+
+#[cfg(test)]
+fn block_on<F: Future>(future: F) -> F::Output {
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct Unpark(std::thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = core::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn test_prime_stream() {
+    let collect = |mut stream: PrimeStream| block_on(async move {
+        let mut primes = Vec::new();
+        while let Some(p) = stream.next().await {
+            primes.push(p);
+        }
+        primes
+    });
+
+    assert_eq!(collect(primes(0, 30)), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert_eq!(collect(primes(30, 30)), []);
+    assert_eq!(collect(primes(24, 29)), []);
+
+    let lo = 1u64 << 40;
+    let expected = crate::primes_in_range(lo, lo + 100_000);
+    for chunk in [1000, 4099, 1 << 16, 1 << 20] {
+        assert_eq!(collect(primes(lo, lo + 100_000).with_chunk_size(chunk)), expected, "chunk={}", chunk);
+    }
+
+    // Sieving primes beyond those of the first chunk
+    assert_eq!(collect(primes(0, 100_000).with_chunk_size(1000)), crate::primes_in_range(0, 100_000));
+    for chunk in [1, 7] {
+        assert_eq!(collect(primes(0, 2000).with_chunk_size(chunk)), crate::primes_in_range(0, 2000), "chunk={}", chunk);
+    }
+}
+
+#[test]
+fn test_prime_stream_backpressure() {
+    let noop = core::task::Waker::noop();
+    let mut cx = Context::from_waker(noop);
+
+    // Only the first chunk is sieved until the consumer drains it.
+    let mut stream = primes(0, 1_000_000).with_chunk_size(100);
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    assert_eq!(stream.next, 100);
+    assert_eq!(stream.buffer.len(), 25);
+    for _ in 0..25 {
+        assert!(matches!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(Some(_))));
+    }
+    assert_eq!(stream.next, 100);
+    assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+    assert_eq!(stream.next, 200);
+}
+
+#[test]
+fn test_gen_primes() {
+    use crate::baillie_psw_primality_test_biguint;
+    use crate::Primality;
+
+    let mut stream = gen_primes(Bits::new(128).unwrap(), rand::thread_rng());
+    let generated = block_on(async {
+        let mut generated = Vec::new();
+        for _ in 0..4 {
+            generated.push(stream.next().await.unwrap());
+        }
+        generated
+    });
+
+    for p in generated.iter() {
+        assert_eq!(p.bits(), 128);
+        assert_eq!(baillie_psw_primality_test_biguint(p), Primality::PROBABLE);
+    }
+}
+
+#[test]
+fn test_futures_stream() {
+    use futures_core::Stream;
+
+    let mut stream = primes(0, 30);
+    assert_eq!(Stream::size_hint(&stream), (0, Some(30)));
+
+    let collected = block_on(async {
+        let mut collected = Vec::new();
+        while let Some(p) = core::future::poll_fn(|cx| Stream::poll_next(Pin::new(&mut stream), cx)).await {
+            collected.push(p);
+        }
+        collected
+    });
+    assert_eq!(collected, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+    let mut stream = gen_primes(Bits::new(64).unwrap(), rand::thread_rng());
+    let p = block_on(core::future::poll_fn(|cx| Stream::poll_next(Pin::new(&mut stream), cx))).unwrap();
+    assert_eq!(p.bits(), 64);
+}
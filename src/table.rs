@@ -1,6 +1,7 @@
 // Lists of Primes at the Prime Pages
 // https://primes.utm.edu/lists/
 use crate::Primality;
+use crate::miller_rabin::is_prime_u64;
 
 // NOTE: 已确定的 从 1 至 65535（含）奇数序列范围内的素数表。
 //       512 * 8 / 1024 = 4K 大小
@@ -73,10 +74,11 @@ static SMALL_PRIME_TABLE: [u64; 512] = [
 ];
 
 
-fn query(n: u16) -> bool {
-    // NOTE: SMALL_PRIME_TABLE 里面存储的是奇数序列，
-    //       所以查询的时候需要确保查询的数字是奇数。
-    debug_assert!(n % 2 != 0);
+const fn query(n: u16) -> bool {
+    // NOTE: SMALL_PRIME_TABLE 里面存储的是奇数序列，偶数在查表之前直接回答。
+    if n % 2 == 0 {
+        return n == 2;
+    }
     
     let pos = (n as usize - 1) / 2;
     let i = pos / 64;
@@ -87,7 +89,9 @@ fn query(n: u16) -> bool {
     (mask << r >> 63) == 1
 }
 
-pub fn table_query_u16(n: u16) -> Primality {
+// 0 and 1 are `ZeroOrOne`, 2 is the only even `Prime` and every other
+// answer is a proven `Prime` or `Composite`.
+pub const fn table_query_u16(n: u16) -> Primality {
     match n {
         0 | 1 => Primality::ZeroOrOne,
         2 => Primality::Prime,
@@ -96,12 +100,140 @@ pub fn table_query_u16(n: u16) -> Primality {
                 // NOTE: 偶数当中，除了 2 之外都不是质数。
                 Primality::Composite
             } else {
-                Primality::from(query(n))
+                Primality::from_proof(query(n))
             }
         }
     }
 }
 
+// Trial division by the primes in the table, which settles every
+// n < 65536 ^ 2 = 2 ^ 32. Larger inputs get `None`.
+pub fn table_query_u32_partial(n: u64) -> Option<Primality> {
+    if n <= u16::MAX as u64 {
+        return Some(table_query_u16(n as u16));
+    }
+
+    if n > u32::MAX as u64 {
+        return None;
+    }
+
+    if n.is_multiple_of(2) {
+        return Some(Primality::Composite);
+    }
+
+    let composite = crate::global().small_prime_divisors().iter()
+        .take_while(|d| d.p * d.p <= n)
+        .any(|d| d.divides(n));
+
+    Some(Primality::from_proof(!composite))
+}
+
+// Counts of the primes in the table.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TableStats {
+    pub count: u32,
+    pub largest: u16,
+    // per_decade[k] counts the primes in [10 ^ k, 10 ^ (k + 1)).
+    pub per_decade: [u32; 5],
+    // per_bit_size[b] counts the primes of exactly b bits.
+    pub per_bit_size: [u32; 17],
+    // The smallest and largest differences between consecutive primes, and
+    // the prime that starts the first largest gap.
+    pub min_gap: u16,
+    pub max_gap: u16,
+    pub max_gap_start: u16,
+}
+
+pub fn table_stats() -> TableStats {
+    let mut stats = TableStats {
+        count: 0,
+        largest: 0,
+        per_decade: [0; 5],
+        per_bit_size: [0; 17],
+        min_gap: u16::MAX,
+        max_gap: 0,
+        max_gap_start: 0,
+    };
+
+    let primes = (2..=u16::MAX).filter(|n| table_query_u16(*n) == Primality::Prime);
+    for p in primes {
+        stats.count += 1;
+        stats.per_decade[(p as f64).log10() as usize] += 1;
+        stats.per_bit_size[16 - p.leading_zeros() as usize] += 1;
+
+        if stats.largest > 0 {
+            let gap = p - stats.largest;
+            stats.min_gap = stats.min_gap.min(gap);
+            if gap > stats.max_gap {
+                stats.max_gap = gap;
+                stats.max_gap_start = stats.largest;
+            }
+        }
+        stats.largest = p;
+    }
+
+    stats
+}
+
+// An exact answer for every n < 2 ^ 32: the table below 2 ^ 16 and the
+// deterministic Miller–Rabin bases above it.
+pub(crate) fn small_primality(n: u64) -> Option<Primality> {
+    if n <= u16::MAX as u64 {
+        Some(table_query_u16(n as u16))
+    } else if n <= u32::MAX as u64 {
+        Some(Primality::from_proof(is_prime_u64(n)))
+    } else {
+        None
+    }
+}
+
+
+#[test]
+fn test_small_primality() {
+    assert_eq!(small_primality(0), Some(Primality::ZeroOrOne));
+    assert_eq!(small_primality(2), Some(Primality::Prime));
+    assert_eq!(small_primality(65521), Some(Primality::Prime));
+    assert_eq!(small_primality(65537), Some(Primality::Prime));
+    assert_eq!(small_primality(65537 * 65521), Some(Primality::Composite));
+    assert_eq!(small_primality(4294967291), Some(Primality::Prime));
+    assert_eq!(small_primality(1 << 32), None);
+}
+
+#[test]
+fn test_table_stats() {
+    let stats = table_stats();
+    assert_eq!(stats.count, 6542);
+    assert_eq!(stats.largest, 65521);
+    assert_eq!(stats.per_decade, [4, 21, 143, 1061, 5313]);
+    assert_eq!(stats.per_bit_size[..8], [0, 0, 2, 2, 2, 5, 7, 13]);
+    assert_eq!(stats.per_bit_size.iter().sum::<u32>(), stats.count);
+    assert_eq!((stats.min_gap, stats.max_gap, stats.max_gap_start), (1, 72, 31397));
+}
+
+#[test]
+fn test_table_query_u16() {
+    assert_eq!(table_query_u16(0), Primality::ZeroOrOne);
+    assert_eq!(table_query_u16(1), Primality::ZeroOrOne);
+    assert_eq!(table_query_u16(2), Primality::Prime);
+    assert_eq!(table_query_u16(3), Primality::Prime);
+    assert_eq!(table_query_u16(4), Primality::Composite);
+    assert_eq!(table_query_u16(65534), Primality::Composite);
+    assert_eq!(table_query_u16(65521), Primality::Prime);
+}
+
+#[test]
+fn test_table_query_u32_partial() {
+    assert_eq!(table_query_u32_partial(1), Some(Primality::ZeroOrOne));
+    assert_eq!(table_query_u32_partial(65537), Some(Primality::Prime));
+    assert_eq!(table_query_u32_partial(65521 * 65521), Some(Primality::Composite));
+    assert_eq!(table_query_u32_partial(4294967291), Some(Primality::Prime));
+    assert_eq!(table_query_u32_partial(u32::MAX as u64), Some(Primality::Composite));
+    assert_eq!(table_query_u32_partial(1 << 32), None);
+
+    for n in (1u64 << 32) - 10_000..1 << 32 {
+        assert_eq!(table_query_u32_partial(n), small_primality(n), "N={}", n);
+    }
+}
 
 #[bench]
 fn bench_table_query_u16(b: &mut test::Bencher) {
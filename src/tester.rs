@@ -0,0 +1,370 @@
+// Configurable front end for the probabilistic BigUint tests
+//
+// Before its random rounds, each test tries the primes below a bound, so that
+// a random composite is usually rejected without a single modular
+// exponentiation. The free functions use `DEFAULT_PREFILTER_BOUND`, or the
+// bound measured by `calibrate` if it ran first; build a `PrimalityTester` to
+// choose another one.
+//
+// The primes are tried all at once, by reducing their product modulo n. That
+// costs about as much as one modular exponentiation when the product has
+// bits(n) ^ 2 bits, so shorter inputs only use the primes up to a power of two
+// whose product stays within that length.
+use crate::Primality;
+use crate::AsBigUint;
+use crate::primes_up_to;
+use crate::table::small_primality;
+use crate::miller_rabin::{miller_rabin_biguint_with, miller_rabin_u64_with};
+use crate::miller_rabin::MILLER_RABIN_WITNESSES_U64;
+use crate::solovay_strassen::solovay_strassen_biguint_with;
+use crate::euler_strong::euler_plus_strong_with;
+#[cfg(feature = "cache")]
+use crate::cache::{ResultCache, CacheStats};
+
+use num_integer::Integer;
+use num_bigint::BigUint;
+
+use core::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+
+// NOTE: 2^20 以下的素数之积约有 1.5M bits，约 1200 bits 以上的输入才会用到全部的素数。
+pub const DEFAULT_PREFILTER_BOUND: u32 = 1 << 20;
+
+// NOTE: 最短的一级是 2^10 以下的素数之积，约 1400 bits。
+const FIRST_LEVEL: u32 = 1 << 10;
+
+#[derive(Debug, Clone)]
+pub struct PrimalityTester {
+    prefilter_bound: u32,
+    // Products of every prime below 2 ^ 10, 2 ^ 11, ... and finally below
+    // `prefilter_bound`, shared by the clones of this tester.
+    primorials: Arc<Vec<BigUint>>,
+    // Bases for the deterministic u64 test, as in `MILLER_RABIN_WITNESSES_U64`.
+    witnesses: &'static [(u64, &'static [u64])],
+    // Shared by the clones of this tester.
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<ResultCache>>,
+}
+
+// The tests whose results a cache tells apart.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub(crate) enum Test {
+    MillerRabin,
+    SolovayStrassen,
+    EulerPlusStrong,
+}
+
+// NOTE: 素数积由 prefilter_bound 决定，比较和哈希时不必再看。
+impl PartialEq for PrimalityTester {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "cache")]
+        if self.cache != other.cache {
+            return false;
+        }
+
+        self.prefilter_bound == other.prefilter_bound && self.witnesses == other.witnesses
+    }
+}
+
+impl Eq for PrimalityTester {}
+
+impl Hash for PrimalityTester {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.prefilter_bound.hash(state);
+        self.witnesses.hash(state);
+        #[cfg(feature = "cache")]
+        self.cache.hash(state);
+    }
+}
+
+impl Default for PrimalityTester {
+    fn default() -> Self {
+        crate::global().tester().clone()
+    }
+}
+
+impl PrimalityTester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_bound(bound: u32) -> Self {
+        let primes = primes_up_to(bound.saturating_sub(1) as u64);
+
+        let mut primorials = Vec::new();
+        let mut primorial = BigUint::from(1u8);
+        let mut start = 0;
+        let mut level = FIRST_LEVEL as u64;
+        loop {
+            let end = primes.partition_point(|p| *p < level);
+            primorial *= product(&primes[start..end]);
+            primorials.push(primorial.clone());
+
+            if end == primes.len() {
+                break;
+            }
+            start = end;
+            level *= 2;
+        }
+
+        PrimalityTester {
+            prefilter_bound: bound,
+            primorials: Arc::new(primorials),
+            witnesses: MILLER_RABIN_WITNESSES_U64,
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
+    // Trial-divide by the primes below `bound` before the random rounds, as
+    // many of them as pay off for the length of n; 0 turns the prefilter off.
+    pub fn with_prefilter_bound(self, bound: u32) -> Self {
+        if bound == self.prefilter_bound {
+            return self;
+        }
+
+        PrimalityTester {
+            witnesses: self.witnesses,
+            #[cfg(feature = "cache")]
+            cache: self.cache,
+            ..Self::with_bound(bound)
+        }
+    }
+
+    // Replace the (largest n, bases) ladder of `miller_rabin_u64`. Inputs above
+    // the last bound come back as `Unknown`.
+    pub fn with_witness_ladder(mut self, ladder: &'static [(u64, &'static [u64])]) -> Self {
+        assert!(ladder.windows(2).all(|w| w[0].0 < w[1].0), "bounds must be ascending");
+
+        self.witnesses = ladder;
+        self
+    }
+
+    // Remember the results of the last `capacity` BigUint tests above 2 ^ 32;
+    // 0 turns the cache off. Clones made afterwards share the cache.
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = match capacity {
+            0 => None,
+            _ => Some(Arc::new(ResultCache::new(capacity))),
+        };
+        self
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    pub fn prefilter_bound(&self) -> u32 {
+        self.prefilter_bound
+    }
+
+    pub fn witness_ladder(&self) -> &'static [(u64, &'static [u64])] {
+        self.witnesses
+    }
+
+    // Deterministic Miller–Rabin with the bases of the witness ladder.
+    pub fn miller_rabin_u64(&self, n: u64) -> Primality {
+        match n {
+            0 | 1 => Primality::ZeroOrOne,
+            2 | 3 => Primality::Prime,
+            _ if n.is_multiple_of(2) => Primality::Composite,
+            _ => miller_rabin_u64_with(n, self.witnesses),
+        }
+    }
+
+    // NOTE: 小于 2^32 的输入总是直接给出确定的结果（Prime 而不是 ProbablyPrime）。
+    pub fn miller_rabin_biguint(&self, n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+        let n = n.as_biguint();
+        small(&n).unwrap_or_else(|| self.cached(Test::MillerRabin, &n, k, || miller_rabin_biguint_with(&n, k, self)))
+    }
+
+    pub fn solovay_strassen_biguint(&self, n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+        let n = n.as_biguint();
+        small(&n).unwrap_or_else(|| self.cached(Test::SolovayStrassen, &n, k, || solovay_strassen_biguint_with(&n, k, self)))
+    }
+
+    pub fn euler_plus_strong_biguint(&self, n: &(impl AsBigUint + ?Sized), k: usize) -> Primality {
+        let n = n.as_biguint();
+        small(&n).unwrap_or_else(|| self.cached(Test::EulerPlusStrong, &n, k, || euler_plus_strong_with(&n, k, self)))
+    }
+
+    #[cfg(feature = "cache")]
+    fn cached<F: FnOnce() -> Primality>(&self, test: Test, n: &BigUint, k: usize, f: F) -> Primality {
+        match &self.cache {
+            Some(cache) => cache.get_or_insert_with(test, n, k, f),
+            None => f(),
+        }
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn cached<F: FnOnce() -> Primality>(&self, _test: Test, _n: &BigUint, _k: usize, f: F) -> Primality {
+        f()
+    }
+
+    // Whether `n` is a proper multiple of a prime below the prefilter bound,
+    // among the primes `primorial_for(n)` covers.
+    pub(crate) fn has_small_factor(&self, n: &BigUint) -> bool {
+        // NOTE: 32 bits 以内的数直接试除到 √n 即可，比对整个素数积取模快得多。
+        if n.bits() <= 32 {
+            let n = n.to_u32_digits().first().copied().unwrap_or(0) as u64;
+            if self.prefilter_bound > 2 && n > 2 && n.is_multiple_of(2) {
+                return true;
+            }
+
+            return crate::global().small_prime_divisors().iter()
+                .take_while(|d| d.p < self.prefilter_bound as u64 && d.p * d.p <= n)
+                .any(|d| d.divides(n));
+        }
+
+        // NOTE: n ≥ 2^32 大于所有参与乘积的素数，所以任何公因子都是真因子。
+        let primorial = self.primorial_for(n);
+        let g = if n > primorial {
+            (n % primorial).gcd(primorial)
+        } else {
+            (primorial % n).gcd(n)
+        };
+
+        g != BigUint::from(1u8)
+    }
+
+    // The longest product of the prefilter with at most bits(n) ^ 2 bits, and
+    // at least the shortest one.
+    fn primorial_for(&self, n: &BigUint) -> &BigUint {
+        let limit = n.bits().saturating_mul(n.bits());
+        let fits = self.primorials.iter().take_while(|p| p.bits() <= limit).count();
+
+        &self.primorials[fits.saturating_sub(1)]
+    }
+}
+
+// The answer for inputs below 2 ^ 32 and for even ones, which the random
+// rounds are not defined for.
+pub(crate) fn small(n: &BigUint) -> Option<Primality> {
+    match n.to_u64_digits().as_slice() {
+        [] => Some(Primality::ZeroOrOne),
+        [n] if *n <= u32::MAX as u64 => small_primality(*n),
+        [n, ..] if u64::is_multiple_of(*n, 2) => Some(Primality::Composite),
+        _ => None,
+    }
+}
+
+// Product of `values`, multiplied pairwise so that both operands of every
+// multiplication have about the same size.
+fn product(values: &[u64]) -> BigUint {
+    if values.len() <= 16 {
+        return values.iter().fold(BigUint::from(1u8), |acc, v| acc * *v);
+    }
+
+    let (lo, hi) = values.split_at(values.len() / 2);
+    product(lo) * product(hi)
+}
+
+
+#[test]
+fn test_primality_tester() {
+    let tester = PrimalityTester::new();
+    assert_eq!(tester.prefilter_bound(), DEFAULT_PREFILTER_BOUND);
+
+    // Missed by the global 2 ^ 10 primorial, caught below 2 ^ 20 once n is
+    // long enough for the whole product to pay off.
+    let m1279 = (BigUint::from(1u8) << 1279usize) - 1u8;
+    let n = &m1279 * 1000003u32;
+    assert!(!crate::global().has_small_factor(&n));
+    assert!(tester.has_small_factor(&n));
+    assert!(!tester.clone().with_prefilter_bound(1000003).has_small_factor(&n));
+    assert!(tester.clone().with_prefilter_bound(1000004).has_small_factor(&n));
+
+    // A shorter n reduces fewer primes, down to those below 2 ^ 10.
+    let m127 = (BigUint::from(1u8) << 127usize) - 1u8;
+    assert!(!tester.has_small_factor(&(&m127 * 1000003u32)));
+    assert!(tester.has_small_factor(&(&m127 * 1021u32)));
+    assert!(tester.primorial_for(&m127).bits() <= 127 * 127);
+    assert_eq!(tester.primorial_for(&BigUint::from(1u64 << 40)), crate::global().primorial());
+    assert_eq!(tester.primorial_for(&n), tester.primorials.last().unwrap());
+    assert_eq!(tester.primorials.len(), 11);
+
+    // Both factors lie just above 2 ^ 20.
+    assert!(!tester.has_small_factor(&BigUint::from(1048583u64 * 1048589)));
+    assert!(!tester.has_small_factor(&(&m1279 * 1048583u32)));
+
+    // Clones share the products, which are left out of comparisons.
+    assert!(Arc::ptr_eq(&tester.primorials, &tester.clone().primorials));
+    assert_eq!(tester, PrimalityTester::new().with_prefilter_bound(100).with_prefilter_bound(DEFAULT_PREFILTER_BOUND));
+
+    let tester = PrimalityTester::new().with_prefilter_bound(0);
+    assert!(!tester.has_small_factor(&BigUint::from(15u8)));
+    assert_eq!(tester.miller_rabin_biguint(&15u8, 10), Primality::Composite);
+    assert_eq!(tester.solovay_strassen_biguint(&15u8, 10), Primality::Composite);
+
+    for tester in [PrimalityTester::new(), PrimalityTester::new().with_prefilter_bound(100)] {
+        // Inputs below 2 ^ 32 are answered exactly.
+        assert_eq!(tester.miller_rabin_biguint(&97u8, 10), Primality::Prime);
+        assert_eq!(tester.solovay_strassen_biguint(&97u8, 10), Primality::Prime);
+        assert_eq!(tester.euler_plus_strong_biguint(&4294967291u32, 10), Primality::Prime);
+        assert_eq!(tester.euler_plus_strong_biguint(&(65537u32 * 65521), 10), Primality::Composite);
+
+        let p = BigUint::from(u64::MAX - 58);
+        assert_eq!(tester.miller_rabin_biguint(&p, 10), Primality::PROBABLE);
+        assert_eq!(tester.solovay_strassen_biguint(&p, 10), Primality::PROBABLE);
+        assert_eq!(tester.miller_rabin_biguint(&(&p * 1000003u32), 10), Primality::Composite);
+        assert_eq!(tester.solovay_strassen_biguint(&(&p * 1000003u32), 10), Primality::Composite);
+    }
+}
+
+#[test]
+fn test_witness_ladder() {
+    let tester = PrimalityTester::new();
+    assert_eq!(tester.witness_ladder(), MILLER_RABIN_WITNESSES_U64);
+    assert_eq!(tester.witness_ladder().last().unwrap().0, u64::MAX);
+
+    for n in 0..4096u16 {
+        assert_eq!(tester.miller_rabin_u64(n as u64), crate::table_query_u16(n), "N={}", n);
+    }
+
+    // Base 2 alone is only good up to 2046; 2047 = 23 · 89 fools it.
+    static BASE_2: &[(u64, &[u64])] = &[(1 << 32, &[2])];
+    let weak = PrimalityTester::new().with_witness_ladder(BASE_2);
+    assert_eq!(weak.miller_rabin_u64(2047), Primality::Prime);
+    assert_eq!(tester.miller_rabin_u64(2047), Primality::Composite);
+    assert_eq!(weak.miller_rabin_u64((1 << 32) + 15), Primality::Unknown);
+
+    // The ladder survives a change of the prefilter bound.
+    assert_eq!(weak.with_prefilter_bound(100).witness_ladder(), BASE_2);
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn test_tester_cache() {
+    let p = BigUint::from(u64::MAX - 58);
+    let tester = PrimalityTester::new().with_cache(16);
+    assert_eq!(tester.cache_stats().map(|stats| stats.capacity), Some(16));
+    assert_eq!(PrimalityTester::new().cache_stats(), None);
+
+    let shared = tester.clone().with_prefilter_bound(100);
+    assert_eq!(tester.miller_rabin_biguint(&p, 10), Primality::PROBABLE);
+    assert_eq!(shared.miller_rabin_biguint(&p, 10), Primality::PROBABLE);
+    assert_eq!(tester.solovay_strassen_biguint(&p, 10), Primality::PROBABLE);
+    // Answered exactly, without the cache
+    assert_eq!(tester.miller_rabin_biguint(&97u8, 10), Primality::Prime);
+
+    let stats = tester.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 2));
+    assert_eq!(tester, shared.clone().with_prefilter_bound(DEFAULT_PREFILTER_BOUND));
+    assert_ne!(tester, PrimalityTester::new().with_cache(16));
+    assert_eq!(tester.with_cache(0).cache_stats(), None);
+}
+
+#[bench]
+fn bench_prefilter_random_composite(b: &mut test::Bencher) {
+    use num_bigint::RandBigInt;
+
+    let tester = PrimalityTester::new();
+    let mut rng = crate::global().rng();
+    b.iter(|| {
+        let n = rng.gen_biguint(1024) | BigUint::from(1u8);
+        tester.miller_rabin_biguint(&n, 1)
+    })
+}
@@ -0,0 +1,129 @@
+// Summatory totient function
+// https://en.wikipedia.org/wiki/Euler%27s_totient_function#Other_formulae
+//
+// Φ(n) = Σ φ(k) for 1 <= k <= n. Every fraction a / b in lowest terms with
+// 1 <= a <= b <= n is counted once, so Φ(n) is the number of coprime pairs
+// below n and the Farey sequence of order n has Φ(n) + 1 terms.
+//
+// Sorting the n(n + 1) / 2 pairs 1 <= a <= b <= n by their gcd g gives
+//
+//      Φ(n) = n(n + 1) / 2 − Σ Φ(n / g),  2 <= g <= n,
+//
+// where n / g takes O(√n) distinct values. Φ is sieved up to L ≈ n ^ (2/3)
+// and the recursion runs over the values n / i > L only, O(n ^ (2/3)) time in
+// all.
+use crate::least_prime_factor_table;
+
+
+// NOTE: 筛表最多 2^22 项，n 更大时 L 不再增长，以免占用过多内存。
+const MAX_SIEVE_LIMIT: u64 = 1 << 22;
+
+// Φ(n) = φ(1) + φ(2) + ... + φ(n)
+pub fn totient_sum(n: u64) -> u128 {
+    let limit = ((n as f64).cbrt().powi(2) as u64).clamp(2, MAX_SIEVE_LIMIT).min(n);
+    let small = totient_sums_up_to(limit);
+    if n <= limit {
+        return small[n as usize] as u128;
+    }
+
+    // large[i] = Φ(n / i) for every n / i > limit, computed from the smallest up.
+    let count = (n / (limit + 1)) as usize;
+    let mut large = vec![0u128; count + 1];
+    for i in (1..=count).rev() {
+        let m = n / i as u64;
+        let mut sum = m as u128 * (m as u128 + 1) / 2;
+
+        // NOTE: 对 m / g 相同的一段 g 一起减去。
+        let mut g = 2;
+        loop {
+            let q = m / g;
+            let last = m / q;
+            let phi = match q <= limit {
+                true => small[q as usize] as u128,
+                false => large[i * g as usize],
+            };
+            sum -= (last - g + 1) as u128 * phi;
+
+            if last == m {
+                break;
+            }
+            g = last + 1;
+        }
+
+        large[i] = sum;
+    }
+
+    large[1]
+}
+
+// The ordered pairs (a, b) with 1 <= a, b <= limit and gcd(a, b) = 1.
+pub fn count_coprime_pairs(limit: u64) -> u128 {
+    // NOTE: (1, 1) 在 a <= b 与 a >= b 两边各计一次。
+    match limit {
+        0 => 0,
+        _ => 2 * totient_sum(limit) - 1,
+    }
+}
+
+// sums[k] = Φ(k) for every k <= limit.
+fn totient_sums_up_to(limit: u64) -> Vec<u64> {
+    let lpf = least_prime_factor_table(limit as u32 + 1);
+
+    // φ(k) = φ(k / p)·p when p ^ 2 | k, φ(k / p)·(p − 1) otherwise, for the
+    // least prime factor p of k.
+    let mut sums = vec![0u64; limit as usize + 1];
+    if limit >= 1 {
+        sums[1] = 1;
+    }
+    for k in 2..=limit as usize {
+        let p = lpf[k] as usize;
+        let rest = k / p;
+        sums[k] = match lpf[rest] as usize == p {
+            true => sums[rest] * p as u64,
+            false => sums[rest] * (p as u64 - 1),
+        };
+    }
+
+    for k in 1..sums.len() {
+        sums[k] += sums[k - 1];
+    }
+
+    sums
+}
+
+
+#[test]
+fn test_totient_sum() {
+    use num_integer::Integer;
+
+    let mut expected = 0u128;
+    for n in 0..3000u64 {
+        expected += (1..=n).filter(|k| k.gcd(&n) == 1).count() as u128;
+        assert_eq!(totient_sum(n), expected, "N={}", n);
+    }
+
+    // OEIS A064018
+    assert_eq!(totient_sum(12345), 46326398);
+    assert_eq!(totient_sum(1_000_000), 303963552392);
+    assert_eq!(totient_sum(10_000_000), 30396356427242);
+    assert_eq!(totient_sum(1_000_000_000), 303963551173008414);
+
+    assert_eq!(count_coprime_pairs(0), 0);
+    assert_eq!(count_coprime_pairs(1), 1);
+    // (1, 1), (1, 2), (2, 1), (1, 3), (3, 1), (2, 3), (3, 2)
+    assert_eq!(count_coprime_pairs(3), 7);
+    for limit in [10u64, 57, 100] {
+        let pairs = (1..=limit).flat_map(|a| (1..=limit).map(move |b| (a, b)))
+            .filter(|(a, b)| a.gcd(b) == 1)
+            .count();
+        assert_eq!(count_coprime_pairs(limit), pairs as u128, "limit={}", limit);
+    }
+}
+
+#[bench]
+fn bench_totient_sum(b: &mut test::Bencher) {
+    b.iter(|| {
+        let n = test::black_box(1_000_000_000u64);
+        totient_sum(n)
+    })
+}
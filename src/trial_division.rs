@@ -2,6 +2,9 @@
 // https://en.wikipedia.org/wiki/Trial_division
 // https://en.wikipedia.org/wiki/Primality_test#Simple_methods
 use crate::Primality;
+use crate::AsBigUint;
+use crate::table_query_u16;
+use crate::{isqrt_u64, isqrt_u128, isqrt_biguint};
 
 use num_bigint::BigUint;
 
@@ -11,31 +14,17 @@ pub fn trial_division_u64(n: u64) -> Primality {
     // https://en.wikipedia.org/wiki/Primality_test#Python_code
     match n {
         0 | 1 => Primality::ZeroOrOne,
-        // Lists of Primes at the Prime Pages
-        // https://primes.utm.edu/lists/
-        // 
-        // [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97]
-        2 | 3 | 5 | 7 
-        | 11 | 13 | 17 | 19 
-        | 23 | 29 
-        | 31 | 37 
-        | 41 | 43 | 47 
-        | 53 | 59 
-        | 61 | 67 
-        | 71 | 73 | 79 
-        | 83 | 89 
-        | 97 => Primality::Prime,
+        // NOTE: 65536 以内直接查表。
+        _ if n <= u16::MAX as u64 => table_query_u16(n as u16),
         _ => {
             if n % 2 == 0 || n % 3 == 0 {
                 return Primality::Composite;
             }
 
+            // NOTE: 先求出 ⌊√n⌋，循环里不再需要 i > n / i 这样的除法。
+            let limit = isqrt_u64(n);
             let mut i = 5u64;
-            loop {
-                if i.pow(2) > n {
-                    break;
-                }
-
+            while i <= limit {
                 if n % i == 0 || n % ( i + 2 ) == 0 {
                     return Primality::Composite;
                 }
@@ -56,12 +45,9 @@ pub fn trial_division_u128(n: u128) -> Primality {
             return Primality::Composite;
         }
 
+        let limit = isqrt_u128(n);
         let mut i = 5u128;
-        loop {
-            if i.pow(2) > n {
-                break;
-            }
-
+        while i <= limit {
             if n % i == 0 || n % ( i + 2 ) == 0 {
                 return Primality::Composite;
             }
@@ -73,9 +59,12 @@ pub fn trial_division_u128(n: u128) -> Primality {
     }
 }
 
-pub fn trial_division_biguint(n: &BigUint) -> Primality {
+pub fn trial_division_biguint(n: &(impl AsBigUint + ?Sized)) -> Primality {
     use core::convert::TryFrom;
-    
+
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
     if let Ok(small_uint) = u128::try_from(n) {
         return trial_division_u128(small_uint);
     }
@@ -86,13 +75,9 @@ pub fn trial_division_biguint(n: &BigUint) -> Primality {
         return Primality::Composite;
     }
 
+    let limit = isqrt_biguint(n);
     let mut i = BigUint::from(5u8);
-    loop {
-        let i_square = i.pow(2u32);
-        if &i_square > n {
-            break;
-        }
-        
+    while i <= limit {
         let i_plus_two = &i + 2u8;
         if n % &i == zero || n % i_plus_two == zero {
             return Primality::Composite;
@@ -105,6 +90,22 @@ pub fn trial_division_biguint(n: &BigUint) -> Primality {
 }
 
 
+#[test]
+fn test_trial_division_u64() {
+    use crate::miller_rabin::is_prime_u64;
+
+    assert_eq!(trial_division_u64(0), Primality::ZeroOrOne);
+    assert_eq!(trial_division_u64(97), Primality::Prime);
+    assert_eq!(trial_division_u64(65521), Primality::Prime);
+    assert_eq!(trial_division_u128(65535), Primality::Composite);
+    assert_eq!(trial_division_biguint(&BigUint::from(65521u32)), Primality::Prime);
+
+    // Both sides of the table boundary.
+    for n in 65000u64..70000 {
+        assert_eq!(trial_division_u64(n).is_prime(), is_prime_u64(n), "N={}", n);
+    }
+}
+
 #[bench]
 fn bench_trial_division_u64(b: &mut test::Bencher) {
     b.iter(|| {
@@ -0,0 +1,562 @@
+// Fixed-width 256-bit integers and their primality
+//
+// Elliptic-curve field and group orders, and moduli derived from 256-bit
+// hashes, are too wide for u128 but small enough that a heap-allocated
+// BigUint is mostly overhead. `U256` keeps four little-endian u64 limbs on the
+// stack. Products are formed limb by limb (schoolbook) and reduced either by
+// shift-and-subtract (`mul_mod`, any modulus) or by Montgomery reduction (odd
+// moduli, used by BPSW). Nothing here allocates.
+use crate::Primality;
+use crate::jacobi_symbol_i64;
+use crate::baillie_psw_primality_test_u64;
+use crate::roots::may_be_square;
+
+use num_bigint::BigUint;
+
+use core::cmp::Ordering;
+
+
+// NOTE: 与 safe_prime 的预筛选使用相同的上界。
+const SIEVE_BOUND: u16 = 1 << 12;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct U256 {
+    // little-endian
+    limbs: [u64; 4],
+}
+
+impl U256 {
+    pub const ZERO: U256 = U256 { limbs: [0; 4] };
+    pub const ONE: U256 = U256 { limbs: [1, 0, 0, 0] };
+    pub const MAX: U256 = U256 { limbs: [u64::MAX; 4] };
+
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        U256 { limbs }
+    }
+
+    pub const fn limbs(&self) -> [u64; 4] {
+        self.limbs
+    }
+
+    pub const fn from_u128(n: u128) -> Self {
+        U256 { limbs: [n as u64, (n >> 64) as u64, 0, 0] }
+    }
+
+    // `None` when n does not fit in 256 bits.
+    pub fn from_biguint(n: &BigUint) -> Option<Self> {
+        let digits = n.to_u64_digits();
+        if digits.len() > 4 {
+            return None;
+        }
+
+        let mut limbs = [0u64; 4];
+        limbs[..digits.len()].copy_from_slice(&digits);
+        Some(U256 { limbs })
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        let mut digits: Vec<u32> = Vec::with_capacity(8);
+        for limb in self.limbs.iter() {
+            digits.push(*limb as u32);
+            digits.push((*limb >> 32) as u32);
+        }
+
+        BigUint::new(digits)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs == [0; 4]
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self.limbs.iter().rposition(|limb| *limb != 0) {
+            Some(i) => 64 * i as u32 + 64 - self.limbs[i].leading_zeros(),
+            None => 0,
+        }
+    }
+
+    pub fn bit(&self, i: u32) -> bool {
+        i < 256 && (self.limbs[i as usize / 64] >> (i % 64)) & 1 == 1
+    }
+
+    // 256 for zero
+    pub fn trailing_zeros(&self) -> u32 {
+        match self.limbs.iter().position(|limb| *limb != 0) {
+            Some(i) => 64 * i as u32 + self.limbs[i].trailing_zeros(),
+            None => 256,
+        }
+    }
+
+    pub fn overflowing_add(&self, rhs: &U256) -> (U256, bool) {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+        for (limb, (a, b)) in limbs.iter_mut().zip(self.limbs.iter().zip(rhs.limbs.iter())) {
+            let (s, c1) = a.overflowing_add(*b);
+            let (s, c2) = s.overflowing_add(carry as u64);
+            *limb = s;
+            carry = c1 || c2;
+        }
+
+        (U256 { limbs }, carry)
+    }
+
+    pub fn overflowing_sub(&self, rhs: &U256) -> (U256, bool) {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+        for (limb, (a, b)) in limbs.iter_mut().zip(self.limbs.iter().zip(rhs.limbs.iter())) {
+            let (d, b1) = a.overflowing_sub(*b);
+            let (d, b2) = d.overflowing_sub(borrow as u64);
+            *limb = d;
+            borrow = b1 || b2;
+        }
+
+        (U256 { limbs }, borrow)
+    }
+
+    pub fn wrapping_add(&self, rhs: &U256) -> U256 {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(&self, rhs: &U256) -> U256 {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn shr(&self, k: u32) -> U256 {
+        if k >= 256 {
+            return U256::ZERO;
+        }
+
+        let (words, bits) = ((k / 64) as usize, k % 64);
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate().take(4 - words) {
+            *limb = self.limbs[i + words] >> bits;
+            if bits > 0 && i + words + 1 < 4 {
+                *limb |= self.limbs[i + words + 1] << (64 - bits);
+            }
+        }
+
+        U256 { limbs }
+    }
+
+    // self mod m, for a single-limb m
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        assert!(m != 0);
+
+        self.limbs.iter().rev().fold(0u64, |r, limb| ((((r as u128) << 64) | *limb as u128) % m as u128) as u64)
+    }
+
+    // self · rhs mod m, for any m > 0: the full 512-bit product, reduced one
+    // bit at a time.
+    pub fn mul_mod(&self, rhs: &U256, m: &U256) -> U256 {
+        assert!(!m.is_zero());
+
+        let product = mul_wide(&self.limbs, &rhs.limbs);
+
+        // NOTE: r < m，左移一位后 r < 2m，至多减一次 m；移出的最高位记在 carry 里。
+        let mut r = U256::ZERO;
+        for i in (0..512).rev() {
+            let carry = r.limbs[3] >> 63 == 1;
+            let mut limbs = [0u64; 4];
+            for j in (1..4).rev() {
+                limbs[j] = (r.limbs[j] << 1) | (r.limbs[j - 1] >> 63);
+            }
+            limbs[0] = (r.limbs[0] << 1) | ((product[i / 64] >> (i % 64)) & 1);
+            r = U256 { limbs };
+
+            if carry || r >= *m {
+                r = r.wrapping_sub(m);
+            }
+        }
+
+        r
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(n: u64) -> Self {
+        U256 { limbs: [n, 0, 0, 0] }
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(n: u128) -> Self {
+        U256::from_u128(n)
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Schoolbook product of two 4-limb numbers
+fn mul_wide(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let mut t = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u64;
+        for j in 0..4 {
+            let uv = t[i + j] as u128 + a[j] as u128 * b[i] as u128 + carry as u128;
+            t[i + j] = uv as u64;
+            carry = (uv >> 64) as u64;
+        }
+        t[i + 4] = carry;
+    }
+
+    t
+}
+
+// `crate::montgomery::Montgomery` with R = 2 ^ 256, multiplying by the
+// coarsely integrated operand scanning (CIOS) method.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Montgomery256 {
+    n: U256,
+    // −n ^ −1 mod 2 ^ 64
+    n_neg_inv: u64,
+    // R mod n
+    r1: U256,
+    // R ^ 2 mod n
+    r2: U256,
+}
+
+impl Montgomery256 {
+    fn new(n: &U256) -> Self {
+        assert!(n.bit(0));
+
+        let n0 = n.limbs[0];
+        let mut inv = n0;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n0.wrapping_mul(inv)));
+        }
+        debug_assert_eq!(n0.wrapping_mul(inv), 1);
+
+        // R mod n = (R − n) mod n
+        let r1 = U256::ZERO.wrapping_sub(n).mul_mod(&U256::ONE, n);
+        let r2 = r1.mul_mod(&r1, n);
+
+        Montgomery256 { n: *n, n_neg_inv: inv.wrapping_neg(), r1, r2 }
+    }
+
+    fn encode(&self, a: &U256) -> U256 {
+        self.mul(&a.mul_mod(&U256::ONE, &self.n), &self.r2)
+    }
+
+    // The Montgomery form of 1
+    fn one(&self) -> U256 {
+        self.r1
+    }
+
+    // a·b·R ^ −1 mod n, for a, b < n
+    fn mul(&self, a: &U256, b: &U256) -> U256 {
+        let n = &self.n.limbs;
+        let mut t = [0u64; 6];
+        for i in 0..4 {
+            // t += a·b[i]
+            let mut carry = 0u64;
+            for (tj, aj) in t.iter_mut().zip(a.limbs.iter()) {
+                let uv = *tj as u128 + *aj as u128 * b.limbs[i] as u128 + carry as u128;
+                *tj = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[4] as u128 + carry as u128;
+            t[4] = uv as u64;
+            t[5] = (uv >> 64) as u64;
+
+            // t = (t + m·n) / 2 ^ 64
+            let m = t[0].wrapping_mul(self.n_neg_inv);
+            let uv = t[0] as u128 + m as u128 * n[0] as u128;
+            let mut carry = (uv >> 64) as u64;
+            for j in 1..4 {
+                let uv = t[j] as u128 + m as u128 * n[j] as u128 + carry as u128;
+                t[j - 1] = uv as u64;
+                carry = (uv >> 64) as u64;
+            }
+            let uv = t[4] as u128 + carry as u128;
+            t[3] = uv as u64;
+            t[4] = t[5] + (uv >> 64) as u64;
+        }
+
+        let r = U256 { limbs: [t[0], t[1], t[2], t[3]] };
+        if t[4] != 0 || r >= self.n {
+            r.wrapping_sub(&self.n)
+        } else {
+            r
+        }
+    }
+
+    fn add(&self, a: &U256, b: &U256) -> U256 {
+        let (s, carry) = a.overflowing_add(b);
+        if carry || s >= self.n {
+            s.wrapping_sub(&self.n)
+        } else {
+            s
+        }
+    }
+
+    fn sub(&self, a: &U256, b: &U256) -> U256 {
+        let (d, borrow) = a.overflowing_sub(b);
+        if borrow {
+            d.wrapping_add(&self.n)
+        } else {
+            d
+        }
+    }
+
+    // a / 2 mod n
+    fn half(&self, a: &U256) -> U256 {
+        if !a.bit(0) {
+            return a.shr(1);
+        }
+
+        let (s, carry) = a.overflowing_add(&self.n);
+        let mut r = s.shr(1);
+        r.limbs[3] |= (carry as u64) << 63;
+        r
+    }
+
+    // base ^ exponent, both base and result in Montgomery form
+    fn pow(&self, base: &U256, exponent: &U256) -> U256 {
+        let mut result = self.r1;
+        for i in (0..exponent.bits()).rev() {
+            result = self.mul(&result, &result);
+            if exponent.bit(i) {
+                result = self.mul(&result, base);
+            }
+        }
+
+        result
+    }
+}
+
+// Digit-by-digit square root: only shifts, additions and comparisons.
+fn is_perfect_square(n: &U256) -> bool {
+    if !may_be_square(n.rem_u64(64 * 63 * 65)) {
+        return false;
+    }
+
+    let mut x = *n;
+    let mut c = U256::ZERO;
+    // d = the largest power of 4 not above n
+    let mut d = U256::ZERO;
+    let top = n.bits().saturating_sub(1) & !1;
+    d.limbs[top as usize / 64] = 1 << (top % 64);
+
+    while !d.is_zero() {
+        let (cd, _) = c.overflowing_add(&d);
+        if x >= cd {
+            x = x.wrapping_sub(&cd);
+            c = c.shr(1).wrapping_add(&d);
+        } else {
+            c = c.shr(1);
+        }
+        d = d.shr(2);
+    }
+
+    x.is_zero()
+}
+
+// `baillie_psw_primality_test_biguint` on a `U256`: exact below 2 ^ 64, a
+// probable prime above.
+pub fn baillie_psw_primality_test_u256(n: &U256) -> Primality {
+    if n.bits() <= 64 {
+        return baillie_psw_primality_test_u64(n.limbs[0]);
+    }
+
+    if !n.bit(0) {
+        return Primality::Composite;
+    }
+
+    for p in crate::global().small_primes().iter().skip(1) {
+        if *p >= SIEVE_BOUND {
+            break;
+        }
+        if n.rem_u64(*p as u64) == 0 {
+            return Primality::Composite;
+        }
+    }
+
+    let ctx = Montgomery256::new(n);
+    let one = ctx.one();
+    let minus_one = ctx.sub(&U256::ZERO, &one);
+
+    // strong probable prime to base 2
+    let n_minus_one = n.wrapping_sub(&U256::ONE);
+    let r = n_minus_one.trailing_zeros();
+    let d = n_minus_one.shr(r);
+    let mut x = ctx.pow(&ctx.encode(&U256::from(2u64)), &d);
+    if x != one && x != minus_one {
+        let mut j = 1;
+        while j < r && x != minus_one {
+            x = ctx.mul(&x, &x);
+            j += 1;
+        }
+        if x != minus_one {
+            return Primality::Composite;
+        }
+    }
+
+    if !strong_lucas_probable_prime(n, &ctx) {
+        return Primality::Composite;
+    }
+
+    Primality::PROBABLE
+}
+
+// `strong_lucas_probable_prime_biguint` in Montgomery form, for an odd n above
+// 2 ^ 64 without small factors.
+fn strong_lucas_probable_prime(n: &U256, ctx: &Montgomery256) -> bool {
+    if is_perfect_square(n) {
+        return false;
+    }
+
+    // NOTE: Selfridge 序列里的 D 都满足 D ≡ 1 (mod 4)，由二次互反律 (D | n) = (n mod |D| | |D|)。
+    let mut d: i64 = 5;
+    loop {
+        let m = d.unsigned_abs();
+        match jacobi_symbol_i64(n.rem_u64(m) as i64, m) {
+            -1 => break,
+            0 => return false,
+            _ => {},
+        }
+        d = if d > 0 { -(d + 2) } else { -d + 2 };
+    }
+
+    let residue = |v: i64| -> U256 {
+        let r = U256::from(v.unsigned_abs());
+        if v < 0 { ctx.encode(&n.wrapping_sub(&r)) } else { ctx.encode(&r) }
+    };
+    let d_mod = residue(d);
+    let q_mod = residue((1 - d) / 4);
+
+    // V_2m = V_m ^ 2 − 2·Q^m
+    let double_v = |v: &U256, qk: &U256| -> U256 { ctx.sub(&ctx.mul(v, v), &ctx.add(qk, qk)) };
+
+    // NOTE: 2^256 − 1 能被 3 整除，已被试除排除，所以 n + 1 不会溢出。
+    let n_plus_one = n.wrapping_add(&U256::ONE);
+    let s = n_plus_one.trailing_zeros();
+    let k = n_plus_one.shr(s);
+
+    let mut u = ctx.one();
+    let mut v = ctx.one();
+    let mut qk = q_mod;
+    for i in (0..k.bits() - 1).rev() {
+        u = ctx.mul(&u, &v);
+        v = double_v(&v, &qk);
+        qk = ctx.mul(&qk, &qk);
+
+        if k.bit(i) {
+            let u_next = ctx.half(&ctx.add(&u, &v));
+            let v_next = ctx.half(&ctx.add(&ctx.mul(&d_mod, &u), &v));
+            u = u_next;
+            v = v_next;
+            qk = ctx.mul(&qk, &q_mod);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = double_v(&v, &qk);
+        if v.is_zero() {
+            return true;
+        }
+        qk = ctx.mul(&qk, &qk);
+    }
+
+    false
+}
+
+
+#[test]
+fn test_u256_arithmetic() {
+    use rand::Rng;
+
+    let mut rng = crate::global().rng();
+
+    let n = U256::from_limbs([1, 2, 3, 4]);
+    assert_eq!(U256::from_biguint(&n.to_biguint()), Some(n));
+    assert_eq!(U256::from_biguint(&(BigUint::from(1u8) << 256usize)), None);
+    assert_eq!(n.bits(), 195);
+    assert_eq!(U256::ZERO.bits(), 0);
+    assert_eq!(U256::from(96u64).trailing_zeros(), 5);
+    assert!(U256::MAX > n && n > U256::from(u128::MAX));
+    assert_eq!(U256::MAX.overflowing_add(&U256::ONE), (U256::ZERO, true));
+    assert_eq!(U256::ZERO.overflowing_sub(&U256::ONE), (U256::MAX, true));
+
+    for _ in 0..200 {
+        let a = U256::from_limbs(rng.gen());
+        let b = U256::from_limbs(rng.gen());
+        let mut m = U256::from_limbs(rng.gen::<[u64; 4]>()).shr(rng.gen_range(0, 250)).limbs();
+        m[0] |= 1;
+        let m = U256::from_limbs(m);
+        let (ab, mb) = (a.to_biguint(), m.to_biguint());
+
+        assert_eq!(a.mul_mod(&b, &m).to_biguint(), &ab * b.to_biguint() % &mb);
+        assert_eq!(a.shr(77).to_biguint(), &ab >> 77usize);
+        assert_eq!(a.rem_u64(1_000_000_007), (&ab % 1_000_000_007u64).to_u64_digits().first().copied().unwrap_or(0));
+        assert_eq!(is_perfect_square(&a), ab.sqrt().pow(2) == ab);
+
+        let root = U256::from_limbs(rng.gen::<[u64; 4]>()).shr(128);
+        assert!(is_perfect_square(&root.mul_mod(&root, &U256::MAX)));
+
+        let ctx = Montgomery256::new(&m);
+        let (am, bm) = (ctx.encode(&a), ctx.encode(&b));
+        let decode = |x: &U256| ctx.mul(x, &U256::ONE).to_biguint();
+        assert_eq!(decode(&ctx.mul(&am, &bm)), &ab * b.to_biguint() % &mb);
+        assert_eq!(decode(&ctx.half(&am)) * 2u8 % &mb, &ab % &mb);
+        assert_eq!(decode(&ctx.pow(&am, &b)), ab.modpow(&b.to_biguint(), &mb));
+    }
+}
+
+#[test]
+fn test_baillie_psw_primality_test_u256() {
+    use crate::baillie_psw_primality_test_biguint;
+    use rand::Rng;
+
+    assert_eq!(baillie_psw_primality_test_u256(&U256::ZERO), Primality::ZeroOrOne);
+    assert_eq!(baillie_psw_primality_test_u256(&U256::from(65537u64)), Primality::Prime);
+    assert_eq!(baillie_psw_primality_test_u256(&U256::MAX), Primality::Composite);
+
+    let p = |s: &str| U256::from_biguint(&s.parse().unwrap()).unwrap();
+    // 2 ^ 255 − 19, the secp256k1 field prime and group order, 2 ^ 256 − 189
+    for n in [
+        "57896044618658097711785492504343953926634992332820282019728792003956564819949",
+        "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+        "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+        "115792089237316195423570985008687907853269984665640564039457584007913129639747",
+    ] {
+        assert_eq!(baillie_psw_primality_test_u256(&p(n)), Primality::PROBABLE, "N={}", n);
+    }
+
+    // (2 ^ 127 − 1) ^ 2, and a strong pseudoprime to bases 2 through 37 (Arnault)
+    let m127 = U256::from((1u128 << 127) - 1);
+    assert_eq!(baillie_psw_primality_test_u256(&m127.mul_mod(&m127, &U256::MAX)), Primality::Composite);
+    assert_eq!(baillie_psw_primality_test_u256(&p("3317044064679887385961981")), Primality::Composite);
+
+    let mut rng = crate::global().rng();
+    for _ in 0..300 {
+        let mut n = U256::from_limbs(rng.gen::<[u64; 4]>()).shr(rng.gen_range(0, 192)).limbs();
+        n[0] |= 1;
+        let n = U256::from_limbs(n);
+        let expected = baillie_psw_primality_test_biguint(&n.to_biguint());
+        assert_eq!(baillie_psw_primality_test_u256(&n), expected, "N={:?}", n);
+    }
+}
+
+#[bench]
+fn bench_baillie_psw_primality_test_u256(b: &mut test::Bencher) {
+    // 2 ^ 255 − 19
+    let n = U256::from_limbs([u64::MAX - 18, u64::MAX, u64::MAX, u64::MAX >> 1]);
+
+    b.iter(|| {
+        baillie_psw_primality_test_u256(&n)
+    })
+}
@@ -0,0 +1,183 @@
+// Labeled test vectors for downstream primality test suites
+//
+// Each vector is a number of exactly the requested size together with what it
+// is and, for composites, its prime factors:
+//
+//   * "prime" and "safe prime" come from `gen_prime` and `gen_safe_prime`;
+//   * "semiprime" is a balanced p · q from `gen_semiprime`;
+//   * "carmichael" is a Chernick number (6k + 1)(12k + 1)(18k + 1) with all
+//     three factors prime, which fools the Fermat test to every coprime base;
+//   * "sprp-base-2 pseudoprime" is an Arnault number from
+//     `gen_strong_pseudoprime`, which also fools the strong test to base 2.
+//
+// Only meant for testing: the composites are far from uniformly distributed.
+use crate::Primality;
+use crate::gen_prime;
+use crate::gen_safe_prime;
+use crate::gen_semiprime;
+use crate::Bits;
+use crate::gen_strong_pseudoprime;
+use crate::icbrt_biguint;
+use crate::baillie_psw_primality_test_biguint;
+
+use rand::Rng;
+use num_bigint::{BigUint, RandBigInt};
+
+use core::fmt;
+
+
+// NOTE: 以 2 为底的 Arnault 构造在 p1 较小时非常稀疏（61、62、67、75 bits ……），
+//       p1 超过 23 bits 以后才能覆盖每一个位数。
+pub const MIN_TEST_VECTOR_BITS: usize = 80;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum VectorKind {
+    Prime,
+    Semiprime,
+    Carmichael,
+    StrongPseudoprimeBase2,
+    SafePrime,
+}
+
+impl VectorKind {
+    pub const ALL: [VectorKind; 5] = [
+        VectorKind::Prime,
+        VectorKind::Semiprime,
+        VectorKind::Carmichael,
+        VectorKind::StrongPseudoprimeBase2,
+        VectorKind::SafePrime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match *self {
+            VectorKind::Prime => "prime",
+            VectorKind::Semiprime => "semiprime",
+            VectorKind::Carmichael => "carmichael",
+            VectorKind::StrongPseudoprimeBase2 => "sprp-base-2 pseudoprime",
+            VectorKind::SafePrime => "safe prime",
+        }
+    }
+
+    pub fn is_prime(&self) -> bool {
+        matches!(*self, VectorKind::Prime | VectorKind::SafePrime)
+    }
+}
+
+impl fmt::Display for VectorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct TestVector {
+    pub kind: VectorKind,
+    pub n: BigUint,
+    // The prime factors in increasing order, empty for a prime.
+    pub factors: Vec<BigUint>,
+}
+
+// One vector of exactly `bits` bits for every entry of `kinds`, in order.
+// `bits` must be at least `MIN_TEST_VECTOR_BITS`.
+pub fn gen_test_vectors<R: Rng + ?Sized>(bits: Bits, kinds: &[VectorKind], rng: &mut R) -> Vec<TestVector> {
+    assert!(bits.get() as usize >= MIN_TEST_VECTOR_BITS);
+
+    kinds.iter()
+        .map(|kind| {
+            let (n, factors) = match kind {
+                VectorKind::Prime => (gen_prime(bits, rng), Vec::new()),
+                // NOTE: bits >= MIN_TEST_VECTOR_BITS，安全素数的下限（3 bits）一定满足。
+                VectorKind::SafePrime => (gen_safe_prime(Bits::safe_prime(bits.get()).unwrap(), rng), Vec::new()),
+                VectorKind::Semiprime => {
+                    let s = gen_semiprime(bits, 0.5, rng);
+                    (s.n, vec![s.p, s.q])
+                },
+                VectorKind::Carmichael => gen_chernick(bits.get() as usize, rng),
+                VectorKind::StrongPseudoprimeBase2 => gen_sprp_base_2(bits.get() as usize, rng),
+            };
+
+            TestVector { kind: *kind, n, factors }
+        })
+        .collect()
+}
+
+fn gen_chernick<R: Rng + ?Sized>(bits: usize, rng: &mut R) -> (BigUint, Vec<BigUint>) {
+    let chernick = |k: &BigUint| -> [BigUint; 3] { [k * 6u8 + 1u8, k * 12u8 + 1u8, k * 18u8 + 1u8] };
+    let product = |k: &BigUint| -> BigUint { chernick(k).iter().product() };
+
+    // The smallest k with (6k + 1)(12k + 1)(18k + 1) >= bound
+    let first_k = |bound: &BigUint| -> BigUint {
+        let mut k = icbrt_biguint(&(bound / 1296u32));
+        while &product(&k) >= bound && k > BigUint::from(0u8) {
+            k -= 1u8;
+        }
+        while &product(&k) < bound {
+            k += 1u8;
+        }
+        k
+    };
+
+    let lo = first_k(&(BigUint::from(1u8) << (bits - 1)));
+    let hi = first_k(&(BigUint::from(1u8) << bits));
+    loop {
+        let k = rng.gen_biguint_range(&lo, &hi);
+        let factors = chernick(&k);
+        // NOTE: 开启 `proven-only` 时 BPSW 通过的数是 `Unknown`，只排除合数。
+        if factors.iter().all(|p| baillie_psw_primality_test_biguint(p) != Primality::Composite) {
+            return (factors.iter().product(), factors.to_vec());
+        }
+    }
+}
+
+fn gen_sprp_base_2<R: Rng + ?Sized>(bits: usize, rng: &mut R) -> (BigUint, Vec<BigUint>) {
+    // NOTE: n ≈ k2 · k3 · p1^3，以 2 为底时 k2 · k3 = 221，约 8 bits；p1 有 b bits 时
+    //       n 有 3b + 5 到 3b + 8 bits，相邻的 b 互相重叠，所以总能收敛。
+    let mut p1_bits = (bits as u32 - 8) / 3;
+    loop {
+        let spsp = gen_strong_pseudoprime(&[2], Bits::new(p1_bits).unwrap(), rng);
+        match (spsp.n.bits() as usize).cmp(&bits) {
+            core::cmp::Ordering::Equal => return (spsp.n, spsp.factors),
+            core::cmp::Ordering::Greater => p1_bits -= 1,
+            core::cmp::Ordering::Less => p1_bits += 1,
+        }
+    }
+}
+
+
+#[test]
+fn test_gen_test_vectors() {
+    use crate::miller_rabin::find_witness_biguint;
+
+    let mut rng = crate::global().rng();
+
+    for bits in [80, 81, 100, 128] {
+        let vectors = gen_test_vectors(Bits::new(bits).unwrap(), &VectorKind::ALL, &mut rng);
+        assert_eq!(vectors.iter().map(|v| v.kind).collect::<Vec<_>>(), VectorKind::ALL.to_vec());
+
+        for v in vectors.iter() {
+            assert_eq!(v.n.bits(), bits as u64, "{} N={}", v.kind, v.n);
+            assert_eq!(baillie_psw_primality_test_biguint(&v.n) != Primality::Composite, v.kind.is_prime(), "{} N={}", v.kind, v.n);
+
+            if !v.kind.is_prime() {
+                assert_eq!(v.factors.iter().product::<BigUint>(), v.n);
+                assert!(v.factors.windows(2).all(|w| w[0] < w[1]));
+            }
+
+            let one = BigUint::from(1u8);
+            let n_minus_one: BigUint = &v.n - 1u8;
+            match v.kind {
+                VectorKind::SafePrime => assert_ne!(baillie_psw_primality_test_biguint(&(&v.n >> 1usize)), Primality::Composite),
+                VectorKind::Semiprime => assert_eq!(v.factors.len(), 2),
+                // Korselt's criterion, and the Fermat test to base 2
+                VectorKind::Carmichael => {
+                    assert!(v.factors.iter().all(|p| (&n_minus_one % (p - 1u8)) == BigUint::from(0u8)));
+                    assert_eq!(BigUint::from(2u8).modpow(&n_minus_one, &v.n), one);
+                },
+                VectorKind::StrongPseudoprimeBase2 => assert_eq!(find_witness_biguint(&v.n, 1, |_| BigUint::from(2u8)), None),
+                VectorKind::Prime => {},
+            }
+        }
+    }
+
+    assert_eq!(VectorKind::StrongPseudoprimeBase2.to_string(), "sprp-base-2 pseudoprime");
+}
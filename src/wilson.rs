@@ -0,0 +1,84 @@
+// Wilson's theorem
+// https://en.wikipedia.org/wiki/Wilson%27s_theorem
+//
+// n > 1 is prime if and only if
+//
+//      (n − 1)! ≡ −1 (mod n)
+//
+// An exact test, but it needs n − 2 multiplications, i.e. time exponential in
+// the number of bits of n. Only meant for teaching and for checking other
+// tests on small inputs.
+use crate::Primality;
+use crate::AsBigUint;
+
+use num_bigint::BigUint;
+
+
+// NOTE: 每批 256 个因子先用乘积树相乘，再对 n 取一次模，减少取模的次数。
+const BATCH: usize = 256;
+
+pub fn wilson_test(n: &(impl AsBigUint + ?Sized)) -> Primality {
+    let n = n.as_biguint();
+    let n: &BigUint = &n;
+
+    let zero = BigUint::from(0u8);
+    let one  = BigUint::from(1u8);
+    if n <= &one {
+        return Primality::ZeroOrOne;
+    }
+
+    let mut acc = one;
+    let mut i = BigUint::from(2u8);
+    let mut batch: Vec<BigUint> = Vec::with_capacity(BATCH);
+    while &i < n {
+        batch.push(i.clone());
+        i += 1u8;
+
+        if batch.len() == BATCH || &i == n {
+            acc = acc * product(&batch) % n;
+            batch.clear();
+
+            // NOTE: 一旦乘进了 n 的全部因子，余数就一直是 0，可以提前结束。
+            if acc == zero {
+                return Primality::Composite;
+            }
+        }
+    }
+
+    Primality::from_proof(acc == n - 1u8)
+}
+
+// Product of `values`, multiplied pairwise so that both operands of every
+// multiplication have about the same size.
+pub(crate) fn product(values: &[BigUint]) -> BigUint {
+    match values {
+        [] => BigUint::from(1u8),
+        [v] => v.clone(),
+        _ => {
+            let (lo, hi) = values.split_at(values.len() / 2);
+            product(lo) * product(hi)
+        }
+    }
+}
+
+
+#[test]
+fn test_wilson_test() {
+    use crate::table_query_u16;
+
+    for n in 0..2000u16 {
+        assert_eq!(wilson_test(&n), table_query_u16(n), "N={}", n);
+    }
+
+    assert_eq!(wilson_test(&65521u32), Primality::Prime);
+    assert_eq!(wilson_test(&(65521u64 * 65521)), Primality::Composite);
+}
+
+#[bench]
+fn bench_wilson_test(b: &mut test::Bencher) {
+    let n = test::black_box(BigUint::from(65521u32));
+
+    b.iter(|| {
+        wilson_test(&n)
+    })
+}